@@ -0,0 +1,194 @@
+//! Benchmarks for the escrow's hot paths. Milestones live in a `Vec` inside each escrow, so
+//! creating, approving and listing all scale with the number of milestones per escrow.
+//!
+//! Run with `cargo bench`.
+
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{coin, coins, OwnedDeps};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+use cw20_escrow_milestones::contract::{execute, instantiate, query};
+use cw20_escrow_milestones::msg::{
+    CreateMilestoneMsg, CreateMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
+};
+use cw20_escrow_milestones::state::GenericBalance;
+
+type Deps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+const ARBITER: &str = "arbiter";
+const DENOM: &str = "tokens";
+const MILESTONE_AMOUNT: u128 = 10;
+
+/// Milestone counts per escrow
+const MILESTONES: &[usize] = &[1, 10, 50, 100];
+/// Number of escrows already in the store
+const ESCROWS: &[usize] = &[1, 10, 100];
+
+fn create_msg(id: &str, milestones: usize) -> CreateMsg {
+    CreateMsg {
+        id: id.to_string(),
+        arbiter: ARBITER.to_string(),
+        co_arbiters: None,
+        threshold: None,
+        arbiter_kind: None,
+        recipient: Some("recipient".to_string()),
+        title: id.to_string(),
+        description: id.to_string(),
+        cw20_whitelist: None,
+        strict_whitelist: None,
+        crowdfunded: None,
+        stake: None,
+        milestones: (0..milestones)
+            .map(|i| CreateMilestoneMsg {
+                escrow_id: id.to_string(),
+                title: format!("milestone_{}", i),
+                description: format!("milestone_{}", i),
+                amount: GenericBalance {
+                    native: vec![coin(MILESTONE_AMOUNT, DENOM)],
+                    cw20: vec![],
+                },
+                expires: None,
+                stream: None,
+            })
+            .collect(),
+    }
+}
+
+fn create(deps: &mut Deps, id: &str, milestones: usize) {
+    let funds = coins(MILESTONE_AMOUNT * milestones as u128, DENOM);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("source", &funds),
+        ExecuteMsg::Create(create_msg(id, milestones)),
+    )
+    .unwrap();
+}
+
+/// An instantiated contract holding `escrows` accepted escrows of `milestones` milestones each,
+/// all submitted for approval
+fn setup(escrows: usize, milestones: usize) -> Deps {
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ARBITER, &[]),
+        InstantiateMsg::default(),
+    )
+    .unwrap();
+    for i in 0..escrows {
+        let id = format!("escrow_{}", i);
+        create(&mut deps, &id, milestones);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("recipient", &[]),
+            ExecuteMsg::AcceptEscrow { id: id.clone() },
+        )
+        .unwrap();
+        for milestone in 1..=milestones {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("recipient", &[]),
+                ExecuteMsg::SubmitMilestone {
+                    id: id.clone(),
+                    milestone_id: milestone.to_string(),
+                },
+            )
+            .unwrap();
+        }
+    }
+    deps
+}
+
+fn bench_create(c: &mut Criterion) {
+    let mut group = c.benchmark_group("create");
+    for &milestones in MILESTONES {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(milestones),
+            &milestones,
+            |b, &milestones| {
+                b.iter_batched(
+                    || setup(0, 0),
+                    |mut deps| create(&mut deps, "escrow", milestones),
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_approve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("approve_milestone");
+    for &milestones in MILESTONES {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(milestones),
+            &milestones,
+            |b, &milestones| {
+                // approve the last milestone, which is the furthest into the Vec
+                let msg = ExecuteMsg::ApproveMilestone {
+                    id: "escrow_0".to_string(),
+                    milestone_id: milestones.to_string(),
+                };
+                b.iter_batched(
+                    || setup(1, milestones),
+                    |mut deps| {
+                        execute(
+                            deps.as_mut(),
+                            mock_env(),
+                            mock_info(ARBITER, &[]),
+                            msg.clone(),
+                        )
+                        .unwrap()
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_queries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("list");
+    for &escrows in ESCROWS {
+        let deps = setup(escrows, 10);
+        group.bench_with_input(BenchmarkId::from_parameter(escrows), &deps, |b, deps| {
+            b.iter(|| query(deps.as_ref(), mock_env(), QueryMsg::List { status: None }).unwrap())
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("list_milestones");
+    for &milestones in MILESTONES {
+        let deps = setup(10, milestones);
+        let msg = QueryMsg::ListMilestones {
+            id: "escrow_0".to_string(),
+            start_after: None,
+            limit: Some(30),
+        };
+        group.bench_with_input(BenchmarkId::from_parameter(milestones), &deps, |b, deps| {
+            b.iter(|| query(deps.as_ref(), mock_env(), msg.clone()).unwrap())
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("escrow_details");
+    for &milestones in MILESTONES {
+        let deps = setup(10, milestones);
+        let msg = QueryMsg::EscrowDetails {
+            id: "escrow_0".to_string(),
+        };
+        group.bench_with_input(BenchmarkId::from_parameter(milestones), &deps, |b, deps| {
+            b.iter(|| query(deps.as_ref(), mock_env(), msg.clone()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_create, bench_approve, bench_queries);
+criterion_main!(benches);
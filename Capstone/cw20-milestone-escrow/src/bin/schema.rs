@@ -0,0 +1,13 @@
+use cosmwasm_schema::write_api;
+
+use cw20_escrow_milestones::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
+
+fn main() {
+    write_api! {
+        instantiate: InstantiateMsg,
+        query: QueryMsg,
+        execute: ExecuteMsg,
+        migrate: MigrateMsg,
+        sudo: SudoMsg,
+    }
+}
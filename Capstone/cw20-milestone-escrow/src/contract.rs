@@ -1,164 +1,471 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response,
-    StdError, StdResult, SubMsg, WasmMsg,
+    coin, from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, Reply, Response, StakingMsg, StdError, StdResult, Storage, SubMsg,
+    SubMsgResult, Uint128, WasmMsg,
 };
+use cosmwatch_events::{attr, migrate_response, response};
 
+use cosmwatch_balances::send_tokens;
+use cosmwatch_migrate::Migration;
 use cw2::set_contract_version;
-use cw20::{Balance, Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw20::{Balance, Cw20Coin, Cw20CoinVerified, Cw20ReceiveMsg};
+use cw3::{Cw3QueryMsg, ProposalResponse, Status};
+use cw_storage_plus::{Bound, MultiIndex};
+use cw_utils::Expiration;
 
 use crate::error::ContractError;
+use crate::helpers::cw3_threshold;
 use crate::msg::{
-    CreateMilestoneMsg, CreateMsg, EscrowDetailsResponse, ExecuteMsg, InstantiateMsg,
-    ListEscrowsResponse, ListMilestonesResponse, QueryMsg, ReceiveMsg,
+    is_valid_name, ClaimsResponse, ConfigResponse, Contribution, ContributionsResponse,
+    CreateMilestoneMsg, CreateMsg, DisputesResponse, EscrowDetailsResponse, EscrowHistoryResponse,
+    EscrowStatus, EscrowSummary, ExecuteMsg, FundingStatusResponse, InstantiateMsg,
+    ListEscrowsResponse, ListMilestonesResponse, MigrateMsg, MilestoneApprovalsResponse, QueryMsg,
+    ReceiveMsg, StakeMsg, StatsResponse, SudoMsg, MAX_DESCRIPTION_LENGTH, MAX_FEE_BPS,
+    MAX_TITLE_LENGTH,
+};
+use crate::state::{
+    append_history, clear_history, count_created, escrows, get_escrow_by_id, init_stats, pro_rata,
+    remove_escrow, save_escrow, upgrade_escrows, ArbiterKind, BlockStamp, Config, Dispute,
+    DisputeResolution, Escrow, GenericBalance, Milestone, MilestoneStatus, Payout, Restore, Stake,
+    Stats, Stream, ValidatorStake, Vesting, CLAIMS, CONFIG, CONTRIBUTIONS, CO_ARBITER_ESCROWS,
+    DEFAULT_UNBONDING_PERIOD, DISPUTES, DISPUTE_COUNT, EXPIRY_CURSOR, HISTORY, OWNERSHIP, PAUSE,
+    PAYOUTS, PAYOUT_COUNT, RECEIVED_CREATE, STATS, USED_PROPOSALS, VALIDATOR_STAKES, VESTINGS,
+    VESTING_COUNT,
 };
-use crate::state::{all_escrow_ids, get_escrow_by_id, Escrow, GenericBalance, Milestone, ESCROWS};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:cw20-escrow-milestones";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+/// Escrows ProcessExpired reads at most per call, however few of them it can refund
+pub const MAX_EXPIRY_SCAN: usize = 100;
+
+/// Reply to the create a cw20 Receive hands off to the contract itself
+pub const CREATE_REPLY_ID: u64 = 1;
+/// Replies to failed payouts, numbered on from here in the order they were sent
+pub const FIRST_PAYOUT_REPLY_ID: u64 = 2;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
-    _msg: InstantiateMsg,
-) -> StdResult<Response> {
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    // No setup required aside from contract version
+    // The instantiator operates the contract unless it names an owner, escrows themselves are
+    // run by their arbiters
+    let owner = match msg.owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => info.sender,
+    };
+    OWNERSHIP.initialize(deps.storage, &owner)?;
+
+    let config = Config {
+        fee_bps: msg.fee_bps.unwrap_or_default(),
+        treasury: msg
+            .treasury
+            .map(|treasury| deps.api.addr_validate(&treasury))
+            .transpose()?,
+        unbonding_period: msg.unbonding_period.unwrap_or(DEFAULT_UNBONDING_PERIOD),
+    };
+    validate_config(&config)?;
+    CONFIG.save(deps.storage, &config)?;
+    STATS.save(deps.storage, &Stats::default())?;
     Ok(Response::default())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // Pausing stops new funds coming in and milestones paying out. Refunds and claims of what
+    // was already credited stay open, so escrowed funds can't get stuck
+    let is_halted = matches!(
+        msg,
+        ExecuteMsg::Create(_)
+            | ExecuteMsg::CreateMilestone(_)
+            | ExecuteMsg::TopUp { .. }
+            | ExecuteMsg::Receive(_)
+            | ExecuteMsg::ApproveMilestone { .. }
+            | ExecuteMsg::ApproveMilestones { .. }
+            | ExecuteMsg::ApproveByProposal { .. }
+            | ExecuteMsg::ResolveDispute { .. }
+    );
+    if is_halted {
+        PAUSE.assert_not_paused(deps.storage)?;
+    }
+
     match msg {
         ExecuteMsg::Create(msg) => {
-            execute_create(deps, msg, info.clone(), Balance::from(info.funds))
+            execute_create(deps, env, msg, info.clone(), Balance::from(info.funds))
         }
         ExecuteMsg::CreateMilestone(msg) => {
-            execute_create_milestone(deps, msg, info.clone(), Balance::from(info.funds))
+            execute_create_milestone(deps, env, msg, info.clone(), Balance::from(info.funds))
+        }
+        ExecuteMsg::UpdateMilestone {
+            id,
+            milestone_id,
+            title,
+            description,
+            amount,
+        } => execute_update_milestone(
+            deps,
+            env,
+            info,
+            id,
+            milestone_id,
+            title,
+            description,
+            amount,
+        ),
+        ExecuteMsg::RemoveMilestone { id, milestone_id } => {
+            execute_remove_milestone(deps, env, info, id, milestone_id)
         }
         ExecuteMsg::SetRecipient { id, recipient } => {
             execute_set_recipient(deps, env, info, id, recipient)
         }
+        ExecuteMsg::UpdateCw20Whitelist { id, add, remove } => {
+            execute_update_cw20_whitelist(deps, env, info, id, add, remove)
+        }
+        ExecuteMsg::AcceptEscrow { id } => execute_accept_escrow(deps, env, info, id),
+        ExecuteMsg::TransferArbiter { id, new_arbiter } => {
+            execute_transfer_arbiter(deps, env, info, id, new_arbiter)
+        }
+        ExecuteMsg::AcceptArbiter { id } => execute_accept_arbiter(deps, env, info, id),
+        ExecuteMsg::SubmitMilestone { id, milestone_id } => {
+            execute_submit_milestone(deps, env, info, id, milestone_id)
+        }
         ExecuteMsg::ApproveMilestone { id, milestone_id } => {
             execute_approve_milestone(deps, env, info, id, milestone_id)
         }
+        ExecuteMsg::ApproveMilestones { id, milestone_ids } => {
+            execute_approve_milestones(deps, env, info, id, milestone_ids)
+        }
         ExecuteMsg::ExtendMilestone {
             id,
             milestone_id,
-            end_height,
-            end_time,
-        } => execute_extend_milestone(deps, env, info, id, milestone_id, end_height, end_time),
+            expires,
+        } => execute_extend_milestone(deps, env, info, id, milestone_id, expires),
+        ExecuteMsg::RaiseDispute {
+            id,
+            milestone_id,
+            reason,
+        } => execute_raise_dispute(deps, env, info, id, milestone_id, reason),
+        ExecuteMsg::ResolveDispute {
+            id,
+            milestone_id,
+            recipient_share,
+        } => execute_resolve_dispute(deps, env, info, id, milestone_id, recipient_share),
+        ExecuteMsg::CancelMilestone { id, milestone_id } => {
+            execute_cancel_milestone(deps, env, info, id, milestone_id)
+        }
+        ExecuteMsg::ApproveByProposal {
+            id,
+            milestone_id,
+            proposal_id,
+        } => {
+            let msg = ExecuteMsg::ApproveMilestone {
+                id: id.clone(),
+                milestone_id: milestone_id.clone(),
+            };
+            let info = proposal_arbiter(deps.branch(), &env, &id, proposal_id, msg)?;
+            execute_approve_milestone(deps, env, info, id, milestone_id)
+        }
+        ExecuteMsg::RefundByProposal { id, proposal_id } => {
+            let msg = ExecuteMsg::Refund { id: id.clone() };
+            let info = proposal_arbiter(deps.branch(), &env, &id, proposal_id, msg)?;
+            execute_refund(deps, env, info, id)
+        }
         ExecuteMsg::Refund { id } => execute_refund(deps, env, info, id),
-        ExecuteMsg::Receive(msg) => execute_receive(deps, info, msg),
+        ExecuteMsg::RefundMilestone { id, milestone_id } => {
+            execute_refund_milestone(deps, env, info, id, milestone_id)
+        }
+        ExecuteMsg::ProcessExpired { limit } => process_expired(deps, env, limit),
+        ExecuteMsg::TopUp { id } => {
+            execute_top_up(deps, env, info.clone(), id, Balance::from(info.funds))
+        }
+        ExecuteMsg::Unstake { id } => execute_unstake(deps, env, info, id),
+        ExecuteMsg::Claim {} => execute_claim(deps, env, info),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::CreateReceived(msg) => execute_create_received(deps, env, info, msg),
+        ExecuteMsg::UpdateConfig {
+            fee_bps,
+            treasury,
+            unbonding_period,
+        } => execute_update_config(deps, info, fee_bps, treasury, unbonding_period),
+        ExecuteMsg::Pause {} => Ok(PAUSE.set_paused(deps.storage, &OWNERSHIP, &info, true)?),
+        ExecuteMsg::Unpause {} => Ok(PAUSE.set_paused(deps.storage, &OWNERSHIP, &info, false)?),
+        ExecuteMsg::TransferOwnership { new_owner } => {
+            Ok(OWNERSHIP.transfer(deps.storage, deps.api, &info, &new_owner)?)
+        }
+        ExecuteMsg::AcceptOwnership {} => Ok(OWNERSHIP.accept(deps.storage, &info)?),
+    }
+}
+
+/// Checks `proposal_id` of escrow `id`'s cw3 arbiter has passed and carries `msg` for this
+/// contract, then marks it used. Returns the arbiter as sender, to act on the escrow with
+fn proposal_arbiter(
+    deps: DepsMut,
+    env: &Env,
+    id: &str,
+    proposal_id: u64,
+    msg: ExecuteMsg,
+) -> Result<MessageInfo, ContractError> {
+    let escrow = get_escrow_by_id(&deps.as_ref(), id)?;
+    if escrow.arbiter_kind != ArbiterKind::Cw3 {
+        return Err(ContractError::NotCw3Arbiter {});
+    }
+
+    let proposal: ProposalResponse = deps
+        .querier
+        .query_wasm_smart(&escrow.arbiter, &Cw3QueryMsg::Proposal { proposal_id })?;
+    if proposal.status != Status::Passed {
+        return Err(ContractError::ProposalNotPassed { proposal_id });
+    }
+    let carries_msg = proposal.msgs.iter().any(|proposed| match proposed {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg: body,
+            funds,
+        }) => {
+            contract_addr == env.contract.address.as_str()
+                && funds.is_empty()
+                && matches!(from_binary::<ExecuteMsg>(body), Ok(body) if body == msg)
+        }
+        _ => false,
+    });
+    if !carries_msg {
+        return Err(ContractError::ProposalMismatch { proposal_id });
+    }
+
+    let key = (&escrow.arbiter, proposal_id);
+    if USED_PROPOSALS.has(deps.storage, key) {
+        return Err(ContractError::ProposalUsed { proposal_id });
+    }
+    USED_PROPOSALS.save(deps.storage, key, &Empty {})?;
+
+    Ok(MessageInfo {
+        sender: escrow.arbiter,
+        funds: vec![],
+    })
+}
+
+pub fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee_bps: Option<u16>,
+    treasury: Option<String>,
+    unbonding_period: Option<u64>,
+) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    if let Some(fee_bps) = fee_bps {
+        config.fee_bps = fee_bps;
+    }
+    if let Some(treasury) = treasury {
+        config.treasury = Some(deps.api.addr_validate(&treasury)?);
+    }
+    if let Some(unbonding_period) = unbonding_period {
+        config.unbonding_period = unbonding_period;
     }
+    validate_config(&config)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    let treasury = config.treasury.map(String::from).unwrap_or_default();
+    Ok(response("update_config").add_attributes(vec![
+        ("fee_bps", config.fee_bps.to_string()),
+        ("treasury", treasury),
+        ("unbonding_period", config.unbonding_period.to_string()),
+    ]))
+}
+
+fn validate_config(config: &Config) -> Result<(), ContractError> {
+    if config.fee_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidFee { max: MAX_FEE_BPS });
+    }
+    if config.fee_bps > 0 && config.treasury.is_none() {
+        return Err(ContractError::NoTreasury {});
+    }
+    Ok(())
 }
 
 pub fn execute_receive(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     wrapper: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
     let msg: ReceiveMsg = from_binary(&wrapper.msg)?;
-    let api = deps.api;
-    let validated_sender = api.addr_validate(&wrapper.sender)?;
-    let balance = Balance::Cw20(Cw20CoinVerified {
-        address: validated_sender,
+    // the token contract calls in on behalf of whoever sent the tokens
+    let token = Cw20CoinVerified {
+        address: info.sender,
         amount: wrapper.amount,
-    });
+    };
+    let balance = Balance::Cw20(token.clone());
+    let info = MessageInfo {
+        sender: deps.api.addr_validate(&wrapper.sender)?,
+        funds: vec![],
+    };
     match msg {
-        ReceiveMsg::Create(msg) => execute_create(deps, msg, info, balance),
-        ReceiveMsg::CreateMilestone(msg) => execute_create_milestone(deps, msg, info, balance),
+        ReceiveMsg::Create(msg) => {
+            // Created in a submessage, so a failure can send the tokens back rather than only
+            // failing the transfer into the contract
+            RECEIVED_CREATE.save(deps.storage, &(info.sender, token))?;
+            let create = WasmMsg::Execute {
+                contract_addr: env.contract.address.into(),
+                msg: to_binary(&ExecuteMsg::CreateReceived(msg))?,
+                funds: vec![],
+            };
+            Ok(Response::new().add_submessage(SubMsg::reply_on_error(create, CREATE_REPLY_ID)))
+        }
+        ReceiveMsg::CreateMilestone(msg) => execute_create_milestone(deps, env, msg, info, balance),
+        ReceiveMsg::TopUp { id } => execute_top_up(deps, env, info, id, balance),
+        ReceiveMsg::TopUpMilestone {
+            escrow_id,
+            milestone_id,
+        } => execute_top_up_milestone(deps, env, info, escrow_id, milestone_id, token),
+    }
+}
+
+/// Creates the escrow of the Receive in progress with its tokens
+pub fn execute_create_received(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: CreateMsg,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
     }
+    let (sender, token) = RECEIVED_CREATE.load(deps.storage)?;
+    let info = MessageInfo {
+        sender,
+        funds: vec![],
+    };
+    let res = execute_create(deps.branch(), env, msg, info, Balance::Cw20(token))?;
+    RECEIVED_CREATE.remove(deps.storage);
+    Ok(res)
 }
 
 pub fn execute_create(
-    deps: DepsMut,
+    mut deps: DepsMut,
+    env: Env,
     msg: CreateMsg,
     info: MessageInfo,
     balance: Balance,
 ) -> Result<Response, ContractError> {
+    if !is_valid_name(&msg.id) {
+        return Err(ContractError::InvalidId { id: msg.id });
+    }
+    validate_text(&msg.title, &msg.description)?;
+
     // check to make sure at least one milestone exists
     if msg.milestones.is_empty() {
         return Err(ContractError::EmptyMilestones {});
     }
+    for milestone in &msg.milestones {
+        validate_text(&milestone.title, &milestone.description)?;
+        validate_stream(&milestone.stream)?;
+    }
 
     // check to make sure at least one milestone contains a balance
     if msg.is_total_balance_empty() {
         return Err(ContractError::EmptyBalance {});
     }
 
-    // check to make sure the total balance of all milestones is equal to the funds sent
-    // only checks the first token for each type
-    if !msg.is_deposit_equal_to_milestones_balance(balance.clone()) {
-        return Err(ContractError::FundsMismatch {});
-    }
-
     // setup escrow properties
     let arbiter: Addr = deps.as_ref().api.addr_validate(&msg.arbiter)?;
+    let co_arbiters = msg.addr_co_arbiters(deps.api)?;
+    let threshold = validate_arbiters(&arbiter, &co_arbiters, msg.threshold)?;
+    let arbiter_kind = msg.arbiter_kind.unwrap_or(ArbiterKind::Address);
+    if arbiter_kind == ArbiterKind::Cw3 && cw3_threshold(&deps.querier, &arbiter).is_err() {
+        return Err(ContractError::NotCw3Arbiter {});
+    }
     let recipient: Option<Addr> = msg
         .clone()
         .recipient
         .and_then(|addr| deps.api.addr_validate(&addr).ok());
-    let mut cw20_whitelist = msg.addr_whitelist(deps.api)?;
-    let balance = match balance {
-        Balance::Native(balance) => GenericBalance {
-            native: balance.0,
-            cw20: vec![],
-        },
-        Balance::Cw20(token) => {
-            // make sure the token sent is on the whitelist by default
-            if !cw20_whitelist.iter().any(|t| t == &token.address) {
-                cw20_whitelist.push(token.address.clone())
-            }
-            GenericBalance {
-                native: vec![],
-                cw20: vec![token],
-            }
-        }
-    };
-    let end_time = msg.get_end_time();
-    let end_height = msg.get_end_height();
+    let cw20_whitelist = msg.addr_whitelist(deps.api)?;
+    let strict_whitelist = msg.strict_whitelist.unwrap_or(false);
+    let crowdfunded = msg.crowdfunded.unwrap_or(false);
+    let stake = msg
+        .stake
+        .clone()
+        .map(|stake| validate_stake(deps.as_ref(), stake))
+        .transpose()?;
 
     // create the escrow
     let mut escrow = Escrow {
         arbiter,
+        co_arbiters,
+        threshold,
+        arbiter_kind,
+        pending_arbiter: None,
         recipient,
         source: info.sender.clone(),
+        accepted: false,
         title: msg.title,
         description: msg.description,
-        end_height,
-        end_time,
-        balance,
+        balance: GenericBalance::default(),
         cw20_whitelist,
+        strict_whitelist,
+        crowdfunded,
         milestones: vec![],
+        milestone_count: 0,
+        stake,
+        created_at: BlockStamp::now(&env),
+        updated_at: BlockStamp::now(&env),
     };
+    if let Balance::Cw20(token) = &balance {
+        // make sure the token sent is on the whitelist by default
+        escrow.accept_cw20(&token.address)?;
+    }
 
-    // add the milestones to the escrow
+    // add the milestones, then the funds sent, which may leave it short of their total until
+    // topped up, but never over
     for milestone in msg.milestones {
-        escrow.create_milestone(milestone);
+        escrow.create_milestone(milestone, &env);
     }
+    let funds = deposit(balance);
+    escrow.deposit(&funds)?;
+    record_contribution(deps.storage, &msg.id, &escrow, &info.sender, &funds)?;
+    let messages = delegate_deposit(deps.branch(), &env, &mut escrow, &funds)?;
 
     // try to store the escrow, fail if the id was already in use
-    ESCROWS.update(deps.storage, &msg.id, |existing| match existing {
-        None => Ok(escrow),
-        Some(_) => Err(ContractError::AlreadyInUse {}),
-    })?;
-
-    let res = Response::new().add_attributes(vec![("action", "create"), ("id", msg.id.as_str())]);
-    Ok(res)
+    if escrows().may_load(deps.storage, &msg.id)?.is_some() {
+        return Err(ContractError::AlreadyInUse {});
+    }
+    count_created(deps.storage)?;
+    save_escrow(deps.storage, &env, &msg.id, &mut escrow)?;
+    // a closed escrow's history went with its id
+    clear_history(deps.storage, &msg.id)?;
+    append_history(
+        deps.storage,
+        &env,
+        &msg.id,
+        &info.sender,
+        "create",
+        None,
+        Some(&funds),
+    )?;
+
+    Ok(response("create")
+        .add_attribute(attr::ID, msg.id)
+        .add_messages(messages))
 }
 
 pub fn execute_create_milestone(
-    deps: DepsMut,
+    mut deps: DepsMut,
+    env: Env,
     msg: CreateMilestoneMsg,
     info: MessageInfo,
     amount: Balance,
@@ -173,49 +480,52 @@ pub fn execute_create_milestone(
     if msg.amount.native.is_empty() && msg.amount.cw20.is_empty() {
         return Err(ContractError::EmptyBalance {});
     }
+    validate_text(&msg.title, &msg.description)?;
+    validate_stream(&msg.stream)?;
 
-    let mut cw20_whitelist = escrow.cw20_whitelist;
-    let _amount = match amount {
-        Balance::Native(token) => GenericBalance {
-            native: token.0,
-            cw20: vec![],
-        },
-        Balance::Cw20(token) => {
-            // make sure the token sent is on the whitelist, otherwise throw an error
-            if !cw20_whitelist.iter().any(|t| t == &token.address) {
-                cw20_whitelist.push(token.clone().address)
-            }
-            GenericBalance {
-                native: vec![],
-                cw20: vec![token],
-            }
-        }
-    };
-    escrow.cw20_whitelist = cw20_whitelist;
-
-    // Create new milestone and add to escrow
-    escrow.create_milestone(msg.clone());
-    let next_id: String = escrow.milestones.len().to_string();
+    if let Balance::Cw20(token) = &amount {
+        // make sure the token sent is on the whitelist
+        escrow.accept_cw20(&token.address)?;
+    }
 
-    // Update escrow balance and expiration
-    escrow.update_calculated_properties();
+    // Create new milestone and add to escrow, along with whatever was sent to fund it
+    escrow.create_milestone(msg.clone(), &env);
+    let funds = deposit(amount);
+    escrow.deposit(&funds)?;
+    record_contribution(deps.storage, &msg.escrow_id, &escrow, &info.sender, &funds)?;
+    let messages = delegate_deposit(deps.branch(), &env, &mut escrow, &funds)?;
+    let next_id: String = escrow.milestone_count.to_string();
 
     // Save changes to escrow
-    ESCROWS.save(deps.storage, &msg.escrow_id, &escrow)?;
-
-    Ok(Response::new().add_attributes(vec![
-        ("action", "create_milestone"),
-        ("escrow_id", msg.escrow_id.as_str()),
-        ("milestone_id", &next_id),
-    ]))
+    save_escrow(deps.storage, &env, &msg.escrow_id, &mut escrow)?;
+    append_history(
+        deps.storage,
+        &env,
+        &msg.escrow_id,
+        &info.sender,
+        "create_milestone",
+        Some(&next_id),
+        Some(&funds),
+    )?;
+
+    Ok(response("create_milestone")
+        .add_attributes(vec![
+            (attr::ESCROW_ID, msg.escrow_id.as_str()),
+            (attr::MILESTONE_ID, &next_id),
+        ])
+        .add_messages(messages))
 }
 
-pub fn execute_set_recipient(
+#[allow(clippy::too_many_arguments)]
+pub fn execute_update_milestone(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     id: String,
-    recipient: String,
+    milestone_id: String,
+    title: Option<String>,
+    description: Option<String>,
+    amount: Option<GenericBalance>,
 ) -> Result<Response, ContractError> {
     let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
 
@@ -223,294 +533,2046 @@ pub fn execute_set_recipient(
         return Err(ContractError::Unauthorized {});
     }
 
-    let validated_recipient = validate_recipient(&deps, &recipient)?;
-    escrow.recipient = Some(validated_recipient.clone());
+    let index = escrow
+        .milestones
+        .iter()
+        .position(|m| m.id == milestone_id)
+        .ok_or(ContractError::MilestoneNotFound {})?;
+    let milestone = &mut escrow.milestones[index];
+    if !milestone.paid_out.is_empty() {
+        return Err(ContractError::AlreadyPaidOut {});
+    }
+    // once submitted the recipient delivered against these terms
+    if milestone.status != MilestoneStatus::Pending {
+        return Err(ContractError::MilestoneNotPending {});
+    }
 
-    ESCROWS.save(deps.storage, &id, &escrow)?;
+    let title = title.unwrap_or_else(|| milestone.title.clone());
+    let description = description.unwrap_or_else(|| milestone.description.clone());
+    validate_text(&title, &description)?;
+    milestone.title = title;
+    milestone.description = description;
+    // consents were given to cancel the old terms
+    milestone.cancel_consents.clear();
+
+    let mut res = response("update_milestone").add_attributes(vec![
+        (attr::ID, id.as_str()),
+        (attr::MILESTONE_ID, milestone_id.as_str()),
+    ]);
+    let mut refund = GenericBalance::default();
+    if let Some(amount) = amount {
+        if amount.is_empty() {
+            return Err(ContractError::EmptyBalance {});
+        }
+        // a lower amount refunds the difference
+        escrow.check_liquid(&env)?;
+        if amount
+            .cw20
+            .iter()
+            .any(|token| !escrow.cw20_whitelist.contains(&token.address))
+        {
+            return Err(ContractError::NotInWhitelist {});
+        }
+        refund = escrow.set_milestone_amount(index, amount)?;
+        if !refund.is_empty() {
+            credit_funders(deps.storage, &id, &escrow, &refund)?;
+            res = res.add_attribute(attr::TO, escrow.source.as_str());
+        }
+    }
 
-    Ok(Response::new().add_attributes(vec![
-        ("action", "set_recipient"),
-        ("id", id.as_str()),
-        ("recipient", validated_recipient.as_str()),
-    ]))
-}
+    save_escrow(deps.storage, &env, &id, &mut escrow)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "update_milestone",
+        Some(&milestone_id),
+        Some(&refund),
+    )?;
 
-fn validate_recipient(deps: &DepsMut, recipient: &String) -> Result<Addr, ContractError> {
-    match deps.api.addr_validate(recipient.as_str()) {
-        Ok(addr) => Ok(addr),
-        Err(_) => return Err(ContractError::InvalidAddress {}),
-    }
+    Ok(res)
 }
 
-pub fn execute_approve_milestone(
+pub fn execute_remove_milestone(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     id: String,
     milestone_id: String,
 ) -> Result<Response, ContractError> {
-    // fails if escrow doesn't exist
     let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
 
     if info.sender != escrow.arbiter {
         return Err(ContractError::Unauthorized {});
     }
-    if escrow.is_expired(&env) {
-        return Err(ContractError::Expired {});
-    }
+    escrow.check_liquid(&env)?;
 
-    let milestone = escrow
+    let index = escrow
         .milestones
-        .iter_mut()
-        .find(|m| m.id == milestone_id)
+        .iter()
+        .position(|m| m.id == milestone_id)
         .ok_or(ContractError::MilestoneNotFound {})?;
-
-    if milestone.is_expired(&env) {
-        return Err(ContractError::MilestoneExpired {});
+    let milestone = &escrow.milestones[index];
+    if !milestone.paid_out.is_empty() {
+        return Err(ContractError::AlreadyPaidOut {});
+    }
+    if milestone.status != MilestoneStatus::Pending {
+        return Err(ContractError::MilestoneNotPending {});
     }
 
-    milestone.is_completed = true;
-
-    // send milestone amount to recipient in a submessage
-    let recipient = escrow
-        .recipient
-        .as_ref()
-        .ok_or(ContractError::RecipientNotSet {})?;
-    let messages: Vec<SubMsg> = send_tokens(&recipient, &milestone.amount)?;
-
-    // if last milestone, send escrow balance to recipient and delete escrow using the approve function
-    // otherwise, just save the escrow
+    let refund = escrow.remove_milestone(index)?;
+    credit_funders(deps.storage, &id, &escrow, &refund)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "remove_milestone",
+        Some(&milestone_id),
+        Some(&refund),
+    )?;
+    let res = response("remove_milestone").add_attributes(vec![
+        (attr::ID, id.as_str()),
+        (attr::MILESTONE_ID, milestone_id.as_str()),
+        (attr::TO, escrow.source.as_str()),
+    ]);
+
+    // removing the last open milestone closes the escrow
     if escrow.is_complete() {
-        let approve_messages = execute_approve(deps, env, info, id.clone())?;
-
-        println!("\n approve_res: {:?}\n", approve_messages);
-
-        Ok(Response::new()
-            .add_attribute("action", "approve_milestone")
-            .add_attribute("id", id.as_str())
-            .add_attribute("is_escrow_complete", "true")
-            .add_submessages(approve_messages))
+        remove_escrow(deps.storage, &id, &escrow)?;
+        Ok(res.add_attribute("is_escrow_complete", "true"))
     } else {
-        escrow.update_calculated_properties();
-
-        ESCROWS.save(deps.storage, &id, &escrow)?;
-
-        Ok(Response::new()
-            .add_attributes(vec![
-                ("action", "approve_milestone"),
-                ("id", id.as_str()),
-                ("milestone_id", milestone_id.as_str()),
-            ])
-            .add_submessages(messages))
+        save_escrow(deps.storage, &env, &id, &mut escrow)?;
+        Ok(res)
     }
 }
 
-pub fn execute_extend_milestone(
+pub fn execute_top_up(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     id: String,
-    milestone_id: String,
-    end_height: Option<u64>,
-    end_time: Option<u64>,
+    amount: Balance,
 ) -> Result<Response, ContractError> {
-    // fails if escrow doesn't exist
-    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
-
-    if info.sender != escrow.arbiter {
-        return Err(ContractError::Unauthorized {});
-    }
+    top_up(deps, env, info, id, amount, None)
+}
 
-    let milestone = escrow
-        .milestones
-        .iter_mut()
-        .find(|m| m.id == milestone_id)
-        .ok_or(ContractError::MilestoneNotFound {})?;
+/// Deposits `amount` into escrow `id`, for milestone `milestone_id` if given
+fn top_up(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    amount: Balance,
+    milestone_id: Option<&str>,
+) -> Result<Response, ContractError> {
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
 
-    if milestone.is_expired(&env) {
-        return Err(ContractError::MilestoneExpired {});
+    // a crowdfunded escrow takes funds from anyone until it expires
+    if info.sender != escrow.source {
+        if !escrow.crowdfunded {
+            return Err(ContractError::Unauthorized {});
+        }
+        if escrow.is_expired(&env) {
+            return Err(ContractError::Expired {});
+        }
     }
-
-    if let Some(end_height) = end_height {
-        milestone.end_height = Some(end_height);
+    if amount.is_empty() {
+        return Err(ContractError::EmptyBalance {});
     }
-    if let Some(end_time) = end_time {
-        milestone.end_time = Some(end_time);
+    if let Balance::Cw20(token) = &amount {
+        if !escrow.cw20_whitelist.iter().any(|t| t == &token.address) {
+            return Err(ContractError::NotInWhitelist {});
+        }
     }
 
-    // Update escrow balance and expiration
-    escrow.update_calculated_properties();
-
-    ESCROWS.save(deps.storage, &id, &escrow)?;
-
-    Ok(Response::new().add_attributes(vec![
-        ("action", "extend_milestone"),
-        ("id", id.as_str()),
-        ("milestone_id", milestone_id.as_str()),
-    ]))
+    let funds = deposit(amount);
+    escrow.deposit(&funds)?;
+    record_contribution(deps.storage, &id, &escrow, &info.sender, &funds)?;
+    let messages = delegate_deposit(deps.branch(), &env, &mut escrow, &funds)?;
+    save_escrow(deps.storage, &env, &id, &mut escrow)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "top_up",
+        milestone_id,
+        Some(&funds),
+    )?;
+
+    Ok(response("top_up")
+        .add_attribute(attr::ID, id)
+        .add_attribute(attr::FROM, info.sender)
+        .add_messages(messages))
 }
 
-pub fn execute_refund(
+/// Tops escrow `id` up with cw20 `token` meant for milestone `milestone_id`, which has to pay in
+/// it and still be owed at least as much
+pub fn execute_top_up_milestone(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     id: String,
+    milestone_id: String,
+    token: Cw20CoinVerified,
 ) -> Result<Response, ContractError> {
-    // this fails is no escrow there
     let escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
-
-    // the arbiter can send anytime OR anyone can send after expiration
-    if !escrow.is_expired(&env) && info.sender != escrow.arbiter {
-        Err(ContractError::Unauthorized {})
-    } else {
-        // we delete the escrow
-        ESCROWS.remove(deps.storage, &id);
-
-        // send all tokens out
-        let messages = send_tokens(&escrow.source, &escrow.get_remaining_balance())?;
-
-        Ok(Response::new()
-            .add_attribute("action", "refund")
-            .add_attribute("id", id)
-            .add_attribute("to", escrow.source)
-            .add_submessages(messages))
+    let milestone = escrow
+        .get_milestone_by_id(&milestone_id)
+        .ok_or(ContractError::MilestoneNotFound {})?;
+    if !milestone.is_open() {
+        return Err(ContractError::MilestoneNotPending {});
+    }
+    let owed = milestone
+        .unpaid()?
+        .cw20
+        .into_iter()
+        .find(|owed| owed.address == token.address)
+        .map_or(Uint128::zero(), |owed| owed.amount);
+    if token.amount > owed {
+        return Err(ContractError::FundsMismatch {
+            token: token.address.into(),
+        });
     }
+
+    let res = top_up(
+        deps,
+        env,
+        info,
+        id,
+        Balance::Cw20(token),
+        Some(&milestone_id),
+    )?;
+    Ok(res.add_attribute(attr::MILESTONE_ID, milestone_id))
 }
 
-fn execute_approve(
+pub fn execute_unstake(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     id: String,
-) -> Result<Vec<SubMsg>, ContractError> {
-    // fails if escrow doesn't exist
-    let escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
+) -> Result<Response, ContractError> {
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
 
-    if info.sender != escrow.arbiter {
+    let mut stake = match escrow.stake.clone() {
+        Some(stake) if stake.unbonding_until.is_none() => stake,
+        _ => return Err(ContractError::NotStaked {}),
+    };
+    // the parties can stop staking anytime OR anyone can after expiration
+    let is_party = info.sender == escrow.arbiter
+        || info.sender == escrow.source
+        || escrow.recipient.as_ref() == Some(&info.sender);
+    if !is_party && !escrow.is_expired(&env) {
         return Err(ContractError::Unauthorized {});
     }
-    if escrow.is_expired(&env) {
-        return Err(ContractError::Expired {});
-    }
-
-    let recipient = escrow
-        .clone()
-        .recipient
-        .ok_or(ContractError::RecipientNotSet {})?;
 
-    // we delete the escrow
-    ESCROWS.remove(deps.storage, &id);
+    let denom = deps.querier.query_bonded_denom()?;
+    let mut validator = accrue_rewards(deps.as_ref(), &env, &stake.validator, &denom)?;
+    stake.accrue(validator.reward_index);
+    validator.bonded = validator
+        .bonded
+        .checked_sub(stake.bonded)
+        .map_err(StdError::from)?;
+    VALIDATOR_STAKES.save(deps.storage, &stake.validator, &validator)?;
+
+    let mut res = response("unstake")
+        .add_attribute(attr::ID, id.as_str())
+        .add_attribute("earned", stake.earned);
+    if !stake.bonded.is_zero() {
+        res = res.add_message(StakingMsg::Undelegate {
+            validator: stake.validator.clone(),
+            amount: coin(stake.bonded.u128(), &denom),
+        });
+    }
 
-    // send all tokens out
-    let messages: Vec<SubMsg> = send_tokens(&recipient, &escrow.get_remaining_balance())?;
+    // the rewards were withdrawn to the contract along the way, split them for both to claim
+    let to_recipient = match &escrow.recipient {
+        Some(recipient) => {
+            let amount = stake.earned * stake.recipient_yield;
+            credit(deps.storage, recipient, &native(amount, &denom))?;
+            amount
+        }
+        None => Uint128::zero(),
+    };
+    let to_source = stake.earned - to_recipient;
+    credit_funders(deps.storage, &id, &escrow, &native(to_source, &denom))?;
+    let earned = native(stake.earned, &denom);
+    stake.earned = Uint128::zero();
+
+    let config = CONFIG.load(deps.storage)?;
+    stake.unbonding_until = Some(env.block.time.seconds() + config.unbonding_period);
+    escrow.stake = Some(stake);
+    save_escrow(deps.storage, &env, &id, &mut escrow)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "unstake",
+        None,
+        Some(&earned),
+    )?;
 
-    Ok(messages)
+    Ok(res)
 }
 
-fn send_tokens(to: &Addr, balance: &GenericBalance) -> StdResult<Vec<SubMsg>> {
-    let native_balance = &balance.native;
-    let mut msgs: Vec<SubMsg> = if native_balance.is_empty() {
-        vec![]
-    } else {
-        vec![SubMsg::new(BankMsg::Send {
-            to_address: to.into(),
-            amount: native_balance.to_vec(),
-        })]
+/// Delegates the staking denom among `funds` deposited into `escrow`, if it is staked and
+/// wasn't unstaked yet
+fn delegate_deposit(
+    deps: DepsMut,
+    env: &Env,
+    escrow: &mut Escrow,
+    funds: &GenericBalance,
+) -> StdResult<Vec<CosmosMsg>> {
+    let stake = match &mut escrow.stake {
+        Some(stake) if stake.unbonding_until.is_none() => stake,
+        _ => return Ok(vec![]),
     };
-
-    let cw20_balance = &balance.cw20;
-    let cw20_msgs: StdResult<Vec<_>> = cw20_balance
+    let denom = deps.querier.query_bonded_denom()?;
+    let amount: Uint128 = funds
+        .native
         .iter()
-        .map(|c| {
-            let msg = Cw20ExecuteMsg::Transfer {
-                recipient: to.into(),
-                amount: c.amount,
-            };
-            let exec = SubMsg::new(WasmMsg::Execute {
-                contract_addr: c.address.to_string(),
-                msg: to_binary(&msg)?,
-                funds: vec![],
-            });
-            Ok(exec)
+        .filter(|c| c.denom == denom)
+        .map(|c| c.amount)
+        .sum();
+    if amount.is_zero() {
+        return Ok(vec![]);
+    }
+
+    let mut validator = accrue_rewards(deps.as_ref(), env, &stake.validator, &denom)?;
+    stake.accrue(validator.reward_index);
+    stake.bonded += amount;
+    validator.bonded += amount;
+    VALIDATOR_STAKES.save(deps.storage, &stake.validator, &validator)?;
+
+    Ok(vec![StakingMsg::Delegate {
+        validator: stake.validator.clone(),
+        amount: coin(amount.u128(), denom),
+    }
+    .into()])
+}
+
+/// Adds the rewards the contract's delegation to `validator` accumulated since it last changed
+/// to the validator's reward index. Call right before changing the delegation, which
+/// withdraws them
+fn accrue_rewards(
+    deps: Deps,
+    env: &Env,
+    validator: &str,
+    denom: &str,
+) -> StdResult<ValidatorStake> {
+    let mut stake = VALIDATOR_STAKES
+        .may_load(deps.storage, validator)?
+        .unwrap_or_default();
+    if stake.bonded.is_zero() {
+        return Ok(stake);
+    }
+    let rewards: Uint128 = deps
+        .querier
+        .query_delegation(&env.contract.address, validator)?
+        .map(|delegation| {
+            delegation
+                .accumulated_rewards
+                .iter()
+                .filter(|c| c.denom == denom)
+                .map(|c| c.amount)
+                .sum::<Uint128>()
         })
-        .collect();
-    msgs.append(&mut cw20_msgs?);
-    Ok(msgs)
+        .unwrap_or_default();
+    stake.reward_index += Decimal::from_ratio(rewards, stake.bonded);
+    Ok(stake)
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::List {} => to_binary(&query_list(deps)?),
-        QueryMsg::EscrowDetails { id } => to_binary(&query_escrow_details(deps, id)?),
-        QueryMsg::MilestoneDetails { id, milestone_id } => {
-            to_binary(&query_milestone_details(deps, id, milestone_id)?)
-        }
-        QueryMsg::ListMilestones { id } => to_binary(&query_list_milestones(deps, id)?),
+/// `amount` of native `denom`, as a balance
+fn native(amount: Uint128, denom: &str) -> GenericBalance {
+    let mut balance = GenericBalance::default();
+    if !amount.is_zero() {
+        balance.native.push(coin(amount.u128(), denom));
     }
+    balance
 }
 
-pub fn query_escrow_details(deps: Deps, id: String) -> StdResult<EscrowDetailsResponse> {
-    let escrow = ESCROWS.load(deps.storage, &id)?;
+/// Records `funds` deposited into crowdfunded escrow `id` as contributed by `funder`
+fn record_contribution(
+    storage: &mut dyn Storage,
+    id: &str,
+    escrow: &Escrow,
+    funder: &Addr,
+    funds: &GenericBalance,
+) -> StdResult<()> {
+    if !escrow.crowdfunded || funds.is_empty() {
+        return Ok(());
+    }
+    CONTRIBUTIONS.update(storage, (id, funder), |contributed| -> StdResult<_> {
+        let mut contributed = contributed.unwrap_or_default();
+        contributed.add_balance(funds);
+        Ok(contributed)
+    })?;
+    Ok(())
+}
 
-    let cw20_whitelist = escrow.human_whitelist();
+/// The tokens sent with a message, as a balance
+fn deposit(amount: Balance) -> GenericBalance {
+    let mut balance = GenericBalance::default();
+    balance.add_tokens(amount);
+    balance
+}
 
-    // transform tokens
-    let native_balance = escrow.balance.native;
+pub fn execute_set_recipient(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
 
-    let cw20_balance: StdResult<Vec<_>> = escrow
-        .balance
-        .cw20
-        .into_iter()
-        .map(|token| {
-            Ok(Cw20Coin {
-                address: token.address.into(),
-                amount: token.amount,
-            })
-        })
-        .collect();
+    if info.sender != escrow.arbiter {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let validated_recipient = validate_recipient(&deps, &recipient)?;
+    if escrow.recipient.as_ref() != Some(&validated_recipient) {
+        escrow.recipient = Some(validated_recipient.clone());
+        escrow.accepted = false;
+        // arbiters signed off on paying the previous recipient, for what it submitted
+        for milestone in escrow.milestones.iter_mut().filter(|m| m.is_open()) {
+            milestone.approvals.clear();
+            milestone.cancel_consents.clear();
+            if milestone.status == MilestoneStatus::Submitted {
+                milestone.status = MilestoneStatus::Pending;
+            }
+        }
+    }
+
+    save_escrow(deps.storage, &env, &id, &mut escrow)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "set_recipient",
+        None,
+        None,
+    )?;
+
+    Ok(response("set_recipient").add_attributes(vec![
+        (attr::ID, id.as_str()),
+        (attr::RECIPIENT, validated_recipient.as_str()),
+    ]))
+}
+
+pub fn execute_update_cw20_whitelist(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
+
+    if info.sender != escrow.arbiter {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let validate = |tokens: &[String]| -> StdResult<Vec<Addr>> {
+        tokens
+            .iter()
+            .map(|addr| deps.api.addr_validate(addr))
+            .collect()
+    };
+    let add = validate(&add)?;
+    let remove = validate(&remove)?;
+
+    // what the escrow holds, and the tokens its milestones still pay in, stay accepted
+    let owed = escrow.required_balance()?;
+    let mut in_use = escrow.balance.cw20.iter().chain(&owed.cw20);
+    if let Some(token) = in_use.find(|token| remove.contains(&token.address)) {
+        return Err(ContractError::TokenInUse {
+            token: token.address.to_string(),
+        });
+    }
+
+    for token in &add {
+        if !escrow.cw20_whitelist.contains(token) {
+            escrow.cw20_whitelist.push(token.clone());
+        }
+    }
+    escrow
+        .cw20_whitelist
+        .retain(|token| !remove.contains(token));
+    save_escrow(deps.storage, &env, &id, &mut escrow)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "update_cw20_whitelist",
+        None,
+        None,
+    )?;
+
+    let joined = |tokens: &[Addr]| {
+        tokens
+            .iter()
+            .map(Addr::as_str)
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    Ok(response("update_cw20_whitelist").add_attributes(vec![
+        (attr::ID, id.as_str()),
+        ("added", &joined(&add)),
+        ("removed", &joined(&remove)),
+    ]))
+}
+
+pub fn execute_accept_escrow(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
+
+    if escrow.recipient.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if escrow.accepted {
+        return Err(ContractError::AlreadyAccepted {});
+    }
+
+    escrow.accepted = true;
+    save_escrow(deps.storage, &env, &id, &mut escrow)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "accept_escrow",
+        None,
+        None,
+    )?;
+
+    Ok(response("accept_escrow").add_attributes(vec![
+        (attr::ID, id.as_str()),
+        (attr::RECIPIENT, info.sender.as_str()),
+    ]))
+}
+
+/// Offers the arbiter role of escrow `id` to `new_arbiter`, which takes it over once it accepts
+pub fn execute_transfer_arbiter(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    new_arbiter: String,
+) -> Result<Response, ContractError> {
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
+
+    if info.sender != escrow.arbiter {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let new_arbiter = deps.api.addr_validate(&new_arbiter)?;
+    if escrow.is_arbiter(&new_arbiter) {
+        return Err(ContractError::DuplicateArbiter {});
+    }
+    // the escrow keeps honouring proposals, so a cw3 arbiter hands over to another cw3 contract
+    if escrow.arbiter_kind == ArbiterKind::Cw3
+        && cw3_threshold(&deps.querier, &new_arbiter).is_err()
+    {
+        return Err(ContractError::NotCw3Arbiter {});
+    }
+
+    escrow.pending_arbiter = Some(new_arbiter.clone());
+    save_escrow(deps.storage, &env, &id, &mut escrow)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "transfer_arbiter",
+        None,
+        None,
+    )?;
+
+    Ok(response("transfer_arbiter").add_attributes(vec![
+        (attr::ID, id.as_str()),
+        ("pending_arbiter", new_arbiter.as_str()),
+    ]))
+}
+
+pub fn execute_accept_arbiter(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
+
+    let pending_arbiter = escrow
+        .pending_arbiter
+        .take()
+        .ok_or(ContractError::NoPendingArbiter {})?;
+    if info.sender != pending_arbiter {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // the new arbiter signs pending milestones for itself
+    let previous = std::mem::replace(&mut escrow.arbiter, pending_arbiter);
+    for milestone in escrow.milestones.iter_mut().filter(|m| m.is_open()) {
+        milestone.approvals.retain(|addr| addr != &previous);
+    }
+    save_escrow(deps.storage, &env, &id, &mut escrow)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "accept_arbiter",
+        None,
+        None,
+    )?;
+
+    Ok(response("accept_arbiter").add_attributes(vec![
+        (attr::ID, id.as_str()),
+        ("arbiter", escrow.arbiter.as_str()),
+        ("previous_arbiter", previous.as_str()),
+    ]))
+}
+
+/// Checks the arbiters are distinct, returning the approval threshold, all of them by default
+fn validate_arbiters(
+    arbiter: &Addr,
+    co_arbiters: &[Addr],
+    threshold: Option<u32>,
+) -> Result<u32, ContractError> {
+    for (i, co_arbiter) in co_arbiters.iter().enumerate() {
+        if co_arbiter == arbiter || co_arbiters[..i].contains(co_arbiter) {
+            return Err(ContractError::DuplicateArbiter {});
+        }
+    }
+    let arbiters = co_arbiters.len() as u32 + 1;
+    let threshold = threshold.unwrap_or(arbiters);
+    if threshold == 0 || threshold > arbiters {
+        return Err(ContractError::InvalidThreshold { arbiters });
+    }
+    Ok(threshold)
+}
+
+fn validate_text(title: &str, description: &str) -> Result<(), ContractError> {
+    if title.is_empty() || title.len() > MAX_TITLE_LENGTH {
+        return Err(ContractError::InvalidTitle {
+            max: MAX_TITLE_LENGTH,
+        });
+    }
+    if description.len() > MAX_DESCRIPTION_LENGTH {
+        return Err(ContractError::DescriptionTooLong {
+            max: MAX_DESCRIPTION_LENGTH,
+        });
+    }
+    Ok(())
+}
+
+fn validate_stream(stream: &Option<Stream>) -> Result<(), ContractError> {
+    match stream {
+        Some(stream) if stream.end <= stream.start => Err(ContractError::InvalidStream {}),
+        _ => Ok(()),
+    }
+}
+
+fn validate_stake(deps: Deps, stake: StakeMsg) -> Result<Stake, ContractError> {
+    if stake.recipient_yield > Decimal::one() {
+        return Err(ContractError::InvalidShare {});
+    }
+    if deps.querier.query_validator(&stake.validator)?.is_none() {
+        return Err(ContractError::UnknownValidator {
+            validator: stake.validator,
+        });
+    }
+    Ok(Stake {
+        validator: stake.validator,
+        recipient_yield: stake.recipient_yield,
+        bonded: Uint128::zero(),
+        reward_index: Decimal::zero(),
+        earned: Uint128::zero(),
+        unbonding_until: None,
+    })
+}
+
+fn validate_recipient(deps: &DepsMut, recipient: &str) -> Result<Addr, ContractError> {
+    match deps.api.addr_validate(recipient) {
+        Ok(addr) => Ok(addr),
+        Err(_) => Err(ContractError::InvalidAddress {}),
+    }
+}
+
+pub fn execute_submit_milestone(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    milestone_id: String,
+) -> Result<Response, ContractError> {
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
+
+    if escrow.recipient.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !escrow.accepted {
+        return Err(ContractError::NotAccepted {});
+    }
+    if escrow.is_expired(&env) {
+        return Err(ContractError::Expired {});
+    }
+
+    let milestone = escrow
+        .milestones
+        .iter_mut()
+        .find(|m| m.id == milestone_id)
+        .ok_or(ContractError::MilestoneNotFound {})?;
+    if milestone.is_expired(&env) {
+        return Err(ContractError::MilestoneExpired {});
+    }
+    if milestone.status != MilestoneStatus::Pending {
+        return Err(ContractError::MilestoneNotPending {});
+    }
+    milestone.status = MilestoneStatus::Submitted;
+
+    save_escrow(deps.storage, &env, &id, &mut escrow)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "submit_milestone",
+        Some(&milestone_id),
+        None,
+    )?;
+
+    Ok(response("submit_milestone").add_attributes(vec![
+        (attr::ID, id.as_str()),
+        (attr::MILESTONE_ID, milestone_id.as_str()),
+    ]))
+}
+
+pub fn execute_approve_milestone(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    milestone_id: String,
+) -> Result<Response, ContractError> {
+    // fails if escrow doesn't exist
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
+    let recipient = check_approver(&escrow, &env, &info.sender)?;
+
+    let (approvals, payout) = approve(&mut escrow, &env, &info.sender, &milestone_id)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "approve_milestone",
+        Some(&milestone_id),
+        payout.as_ref(),
+    )?;
+    let res = response("approve_milestone").add_attributes(vec![
+        (attr::ID, id.as_str()),
+        (attr::MILESTONE_ID, milestone_id.as_str()),
+        ("approvals", &approvals.to_string()),
+    ]);
+
+    // keep the signature until enough arbiters have approved
+    let payout = match payout {
+        Some(payout) => payout,
+        None => {
+            save_escrow(deps.storage, &env, &id, &mut escrow)?;
+            return Ok(res);
+        }
+    };
+
+    // credit what the milestone is still owed to the recipient, or start vesting it, out of the
+    // escrow balance. It is claimed separately, so a recipient that rejects transfers can't
+    // block the approval
+    let stream = stream_of(&escrow, &milestone_id);
+    release_payout(deps.storage, &recipient, stream, &payout)?;
+
+    // the last milestone closes the escrow, otherwise just save it
+    if escrow.is_complete() {
+        remove_escrow(deps.storage, &id, &escrow)?;
+        Ok(res.add_attribute("is_escrow_complete", "true"))
+    } else {
+        save_escrow(deps.storage, &env, &id, &mut escrow)?;
+        Ok(res)
+    }
+}
+
+pub fn execute_approve_milestones(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    milestone_ids: Vec<String>,
+) -> Result<Response, ContractError> {
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
+    let recipient = check_approver(&escrow, &env, &info.sender)?;
+    if milestone_ids.is_empty() {
+        return Err(ContractError::EmptyMilestones {});
+    }
+
+    // all or nothing, any milestone failing to approve fails the batch
+    let mut released = vec![];
+    let mut payouts = GenericBalance::default();
+    for milestone_id in &milestone_ids {
+        let (_, payout) = approve(&mut escrow, &env, &info.sender, milestone_id)?;
+        append_history(
+            deps.storage,
+            &env,
+            &id,
+            &info.sender,
+            "approve_milestones",
+            Some(milestone_id),
+            payout.as_ref(),
+        )?;
+        if let Some(payout) = payout {
+            // streamed payouts vest each on their own, the rest is credited together
+            match stream_of(&escrow, milestone_id) {
+                Some(stream) => release_payout(deps.storage, &recipient, Some(stream), &payout)?,
+                None => payouts.add_balance(&payout),
+            }
+            released.push(milestone_id.as_str());
+        }
+    }
+    credit_payout(deps.storage, &recipient, &payouts)?;
+
+    let res = response("approve_milestones").add_attributes(vec![
+        (attr::ID, id.as_str()),
+        ("milestone_ids", &milestone_ids.join(",")),
+        ("released", &released.join(",")),
+    ]);
+
+    if escrow.is_complete() {
+        remove_escrow(deps.storage, &id, &escrow)?;
+        Ok(res.add_attribute("is_escrow_complete", "true"))
+    } else {
+        save_escrow(deps.storage, &env, &id, &mut escrow)?;
+        Ok(res)
+    }
+}
+
+/// Checks `sender` may approve milestones of the escrow now, returning the recipient to credit
+fn check_approver(escrow: &Escrow, env: &Env, sender: &Addr) -> Result<Addr, ContractError> {
+    if !escrow.is_arbiter(sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if escrow.is_expired(env) {
+        return Err(ContractError::Expired {});
+    }
+    escrow.check_liquid(env)?;
+    let recipient = escrow
+        .recipient
+        .clone()
+        .ok_or(ContractError::RecipientNotSet {})?;
+    if !escrow.accepted {
+        return Err(ContractError::NotAccepted {});
+    }
+    Ok(recipient)
+}
+
+/// Signs milestone `milestone_id` for `arbiter`, paying it out of the escrow balance once the
+/// threshold is met. Returns how many arbiters have approved and the payout, if it was released
+fn approve(
+    escrow: &mut Escrow,
+    env: &Env,
+    arbiter: &Addr,
+    milestone_id: &str,
+) -> Result<(usize, Option<GenericBalance>), ContractError> {
+    let index = escrow
+        .milestones
+        .iter()
+        .position(|m| m.id == milestone_id)
+        .ok_or(ContractError::MilestoneNotFound {})?;
+    let threshold = escrow.threshold as usize;
+    let milestone = &mut escrow.milestones[index];
+
+    if milestone.is_expired(env) {
+        return Err(ContractError::MilestoneExpired {});
+    }
+    match milestone.status {
+        MilestoneStatus::Submitted => {}
+        MilestoneStatus::Pending => return Err(ContractError::NotSubmitted {}),
+        _ => return Err(ContractError::MilestoneNotPending {}),
+    }
+    if milestone.approvals.contains(arbiter) {
+        return Err(ContractError::AlreadyApproved {});
+    }
+    milestone.approvals.push(arbiter.clone());
+    let approvals = milestone.approvals.len();
+    if approvals < threshold {
+        return Ok((approvals, None));
+    }
+
+    // the escrow may still be waiting on top ups
+    if !escrow.balance.covers(&milestone.unpaid()?) {
+        return Err(ContractError::Underfunded {});
+    }
+    milestone.status = MilestoneStatus::Completed;
+    let payout = escrow.pay_out_milestone(index)?;
+    Ok((approvals, Some(payout)))
+}
+
+/// The stream milestone `milestone_id` vests its payout over, if it has one
+fn stream_of(escrow: &Escrow, milestone_id: &str) -> Option<Stream> {
+    escrow
+        .get_milestone_by_id(milestone_id)
+        .and_then(|milestone| milestone.stream.clone())
+}
+
+/// Credits a milestone payout to `recipient`, less the protocol fee credited to the treasury
+fn credit_payout(
+    storage: &mut dyn Storage,
+    recipient: &Addr,
+    payout: &GenericBalance,
+) -> StdResult<()> {
+    let net = take_fee(storage, payout)?;
+    credit(storage, recipient, &net)
+}
+
+/// Like `credit_payout`, but a payout with a `stream` vests to `recipient` over it instead
+fn release_payout(
+    storage: &mut dyn Storage,
+    recipient: &Addr,
+    stream: Option<Stream>,
+    payout: &GenericBalance,
+) -> StdResult<()> {
+    let stream = match stream {
+        Some(stream) => stream,
+        None => return credit_payout(storage, recipient, payout),
+    };
+    let total = take_fee(storage, payout)?;
+    if total.is_empty() {
+        return Ok(());
+    }
+    let id = VESTING_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    VESTING_COUNT.save(storage, &id)?;
+    let vesting = Vesting {
+        stream,
+        total,
+        claimed: GenericBalance::default(),
+    };
+    VESTINGS.save(storage, (recipient, id), &vesting)
+}
+
+/// Credits the protocol fee out of `payout` to the treasury, returning the rest
+fn take_fee(storage: &mut dyn Storage, payout: &GenericBalance) -> StdResult<GenericBalance> {
+    // no fee until one is configured
+    let config = CONFIG.may_load(storage)?.unwrap_or_default();
+    let fee = config.fee_of(payout);
+    let mut net = payout.clone();
+    if let Some(treasury) = &config.treasury {
+        net.sub_balance(&fee)?;
+        credit(storage, treasury, &fee)?;
+    }
+    Ok(net)
+}
+
+/// Credits `amount` back to whoever funded escrow `id`: its source, or the funders of a
+/// crowdfunded escrow in proportion to what each contributed, the source keeping what rounding
+/// leaves over
+fn credit_funders(
+    storage: &mut dyn Storage,
+    id: &str,
+    escrow: &Escrow,
+    amount: &GenericBalance,
+) -> StdResult<()> {
+    if !escrow.crowdfunded {
+        return credit(storage, &escrow.source, amount);
+    }
+    let contributions = CONTRIBUTIONS
+        .prefix(id)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let (parts, rest) = pro_rata(amount, &contributions)?;
+    for (funder, part) in parts {
+        credit(storage, &funder, &part)?;
+    }
+    credit(storage, &escrow.source, &rest)
+}
+
+/// Sends `amount` of escrow `id`, as it was before refunding it, back to its source. A
+/// crowdfunded escrow may have too many funders to send to, so they are credited their parts
+/// to claim instead
+fn refund_funders(
+    storage: &mut dyn Storage,
+    env: &Env,
+    id: &str,
+    escrow: &Escrow,
+    amount: &GenericBalance,
+) -> StdResult<Vec<SubMsg>> {
+    if escrow.crowdfunded {
+        credit_funders(storage, id, escrow, amount)?;
+        return Ok(vec![]);
+    }
+    let restore = Restore::Escrow {
+        id: id.to_string(),
+        escrow: Box::new(escrow.clone()),
+    };
+    send_payout(storage, env, &escrow.source, amount, restore)
+}
+
+/// Sends `amount` to `to`, one message for the native coins and one per cw20 token. Each
+/// replies if it fails, to put its tokens back as `restore` says
+fn send_payout(
+    storage: &mut dyn Storage,
+    env: &Env,
+    to: &Addr,
+    amount: &GenericBalance,
+    restore: Restore,
+) -> StdResult<Vec<SubMsg>> {
+    // replies come in the block the payouts were sent, the ones from before all went through
+    let sent = PAYOUTS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (reply_id, payout) in sent {
+        if payout.height < env.block.height {
+            PAYOUTS.remove(storage, reply_id);
+        }
+    }
+
+    let native = GenericBalance {
+        native: amount.native.clone(),
+        cw20: vec![],
+    };
+    let cw20 = amount.cw20.iter().map(|token| GenericBalance {
+        native: vec![],
+        cw20: vec![token.clone()],
+    });
+    let mut messages = vec![];
+    for part in std::iter::once(native).chain(cw20) {
+        if part.is_empty() {
+            continue;
+        }
+        let count = PAYOUT_COUNT.may_load(storage)?.unwrap_or_default();
+        PAYOUT_COUNT.save(storage, &(count + 1))?;
+        let reply_id = FIRST_PAYOUT_REPLY_ID + count;
+        for sub in send_tokens(to, &part)? {
+            messages.push(SubMsg::reply_on_error(sub.msg, reply_id));
+        }
+        let payout = Payout {
+            height: env.block.height,
+            amount: part,
+            restore: restore.clone(),
+        };
+        PAYOUTS.save(storage, reply_id, &payout)?;
+    }
+    Ok(messages)
+}
+
+/// Adds `amount` to what `addr` can claim
+fn credit(storage: &mut dyn Storage, addr: &Addr, amount: &GenericBalance) -> StdResult<()> {
+    if amount.is_empty() {
+        return Ok(());
+    }
+    CLAIMS.update(storage, addr, |claims| -> StdResult<_> {
+        let mut claims = claims.unwrap_or_default();
+        claims.add_balance(amount);
+        Ok(claims)
+    })?;
+    Ok(())
+}
+
+pub fn execute_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut claims = CLAIMS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    CLAIMS.remove(deps.storage, &info.sender);
+
+    // along with what has vested of its streamed payouts, dropping the ones fully claimed
+    let vestings = VESTINGS
+        .prefix(&info.sender)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (id, mut vesting) in vestings {
+        let vested = vesting.claimable(env.block.time)?;
+        claims.add_balance(&vested);
+        vesting.claimed.add_balance(&vested);
+        if vesting.claimed.covers(&vesting.total) {
+            VESTINGS.remove(deps.storage, (&info.sender, id));
+        } else {
+            VESTINGS.save(deps.storage, (&info.sender, id), &vesting)?;
+        }
+    }
+    if claims.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    let restore = Restore::Claims(info.sender.clone());
+    let messages = send_payout(deps.storage, &env, &info.sender, &claims, restore)?;
+    Ok(response("claim")
+        .add_attribute(attr::TO, info.sender)
+        .add_submessages(messages))
+}
+
+pub fn execute_raise_dispute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    milestone_id: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
+
+    if info.sender != escrow.source && escrow.recipient.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if reason.is_empty() || reason.len() > MAX_DESCRIPTION_LENGTH {
+        return Err(ContractError::InvalidReason {
+            max: MAX_DESCRIPTION_LENGTH,
+        });
+    }
+    if escrow.is_expired(&env) {
+        return Err(ContractError::Expired {});
+    }
+
+    let milestone = escrow
+        .milestones
+        .iter_mut()
+        .find(|m| m.id == milestone_id)
+        .ok_or(ContractError::MilestoneNotFound {})?;
+    if milestone.is_expired(&env) {
+        return Err(ContractError::MilestoneExpired {});
+    }
+    if !milestone.is_awaiting_approval() {
+        return Err(ContractError::MilestoneNotPending {});
+    }
+    milestone.status = MilestoneStatus::Disputed;
+    save_escrow(deps.storage, &env, &id, &mut escrow)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "raise_dispute",
+        Some(&milestone_id),
+        None,
+    )?;
+
+    let dispute_id = DISPUTE_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    DISPUTE_COUNT.save(deps.storage, &dispute_id)?;
+    let dispute = Dispute {
+        id: dispute_id,
+        escrow_id: id.clone(),
+        milestone_id: milestone_id.clone(),
+        raised_by: info.sender.clone(),
+        reason,
+        height: env.block.height,
+        resolution: None,
+    };
+    DISPUTES.save(deps.storage, (&id, dispute_id), &dispute)?;
+
+    Ok(response("raise_dispute").add_attributes(vec![
+        (attr::ID, id.as_str()),
+        (attr::MILESTONE_ID, milestone_id.as_str()),
+        ("dispute_id", &dispute_id.to_string()),
+        (attr::FROM, info.sender.as_str()),
+    ]))
+}
+
+pub fn execute_resolve_dispute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    milestone_id: String,
+    recipient_share: Decimal,
+) -> Result<Response, ContractError> {
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
+
+    if info.sender != escrow.arbiter {
+        return Err(ContractError::Unauthorized {});
+    }
+    escrow.check_liquid(&env)?;
+    if recipient_share > Decimal::one() {
+        return Err(ContractError::InvalidShare {});
+    }
+
+    let index = escrow
+        .milestones
+        .iter()
+        .position(|m| m.id == milestone_id)
+        .ok_or(ContractError::MilestoneNotFound {})?;
+    if escrow.milestones[index].status != MilestoneStatus::Disputed {
+        return Err(ContractError::NotDisputed {});
+    }
+    // a milestone is only ever disputed once, so its unresolved dispute is the open one
+    let mut dispute = DISPUTES
+        .prefix(&id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, dispute)| dispute))
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .find(|dispute| dispute.milestone_id == milestone_id && dispute.resolution.is_none())
+        .ok_or(ContractError::NotDisputed {})?;
+
+    // a dispute can be raised before the escrow is fully funded
+    if !escrow.balance.covers(&escrow.milestones[index].unpaid()?) {
+        return Err(ContractError::Underfunded {});
+    }
+
+    // split what the milestone is still owed, crediting both sides to claim
+    let (paid_out, refunded) = escrow.settle_milestone(index, recipient_share)?;
+    if !paid_out.is_empty() {
+        let recipient = escrow
+            .recipient
+            .clone()
+            .ok_or(ContractError::RecipientNotSet {})?;
+        credit_payout(deps.storage, &recipient, &paid_out)?;
+    }
+    credit_funders(deps.storage, &id, &escrow, &refunded)?;
+    let mut settled = paid_out.clone();
+    settled.add_balance(&refunded);
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "resolve_dispute",
+        Some(&milestone_id),
+        Some(&settled),
+    )?;
+
+    dispute.resolution = Some(DisputeResolution {
+        recipient_share,
+        paid_out,
+        refunded,
+    });
+    DISPUTES.save(deps.storage, (&id, dispute.id), &dispute)?;
+
+    let res = response("resolve_dispute").add_attributes(vec![
+        (attr::ID, id.as_str()),
+        (attr::MILESTONE_ID, milestone_id.as_str()),
+        ("dispute_id", &dispute.id.to_string()),
+        ("recipient_share", &recipient_share.to_string()),
+    ]);
+
+    // resolving the last milestone closes the escrow, like approving it
+    if escrow.is_complete() {
+        remove_escrow(deps.storage, &id, &escrow)?;
+        Ok(res.add_attribute("is_escrow_complete", "true"))
+    } else {
+        save_escrow(deps.storage, &env, &id, &mut escrow)?;
+        Ok(res)
+    }
+}
+
+pub fn execute_extend_milestone(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    milestone_id: String,
+    expires: Expiration,
+) -> Result<Response, ContractError> {
+    // fails if escrow doesn't exist
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
+
+    if info.sender != escrow.arbiter {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let milestone = escrow
+        .milestones
+        .iter_mut()
+        .find(|m| m.id == milestone_id)
+        .ok_or(ContractError::MilestoneNotFound {})?;
+
+    if milestone.is_expired(&env) {
+        return Err(ContractError::MilestoneExpired {});
+    }
+
+    milestone.extend_expiration(&env, expires)?;
+
+    save_escrow(deps.storage, &env, &id, &mut escrow)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "extend_milestone",
+        Some(&milestone_id),
+        None,
+    )?;
+
+    Ok(response("extend_milestone").add_attributes(vec![
+        (attr::ID, id.as_str()),
+        (attr::MILESTONE_ID, milestone_id.as_str()),
+    ]))
+}
+
+pub fn execute_cancel_milestone(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    milestone_id: String,
+) -> Result<Response, ContractError> {
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
+
+    let index = escrow
+        .milestones
+        .iter()
+        .position(|m| m.id == milestone_id)
+        .ok_or(ContractError::MilestoneNotFound {})?;
+    if !escrow.milestones[index].is_awaiting_approval() {
+        return Err(ContractError::MilestoneNotPending {});
+    }
+
+    let res = response("cancel_milestone").add_attributes(vec![
+        (attr::ID, id.as_str()),
+        (attr::MILESTONE_ID, milestone_id.as_str()),
+    ]);
+
+    // without the arbiter, source and recipient have to agree
+    if info.sender != escrow.arbiter {
+        let source = escrow.source.clone();
+        let recipient = escrow.recipient.clone();
+        if info.sender != source && recipient.as_ref() != Some(&info.sender) {
+            return Err(ContractError::Unauthorized {});
+        }
+        let consents = &mut escrow.milestones[index].cancel_consents;
+        if consents.contains(&info.sender) {
+            return Err(ContractError::AlreadyConsented {});
+        }
+        consents.push(info.sender.clone());
+        // `is_some_and` is newer than the optimizer image's compiler
+        #[allow(clippy::unnecessary_map_or)]
+        let agreed =
+            consents.contains(&source) && recipient.map_or(false, |r| consents.contains(&r));
+        if !agreed {
+            let count = consents.len();
+            save_escrow(deps.storage, &env, &id, &mut escrow)?;
+            append_history(
+                deps.storage,
+                &env,
+                &id,
+                &info.sender,
+                "cancel_milestone",
+                Some(&milestone_id),
+                None,
+            )?;
+            return Ok(res.add_attribute("consents", count.to_string()));
+        }
+    }
+
+    escrow.check_liquid(&env)?;
+    let refund = escrow.cancel_milestone(index)?;
+    credit_funders(deps.storage, &id, &escrow, &refund)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "cancel_milestone",
+        Some(&milestone_id),
+        Some(&refund),
+    )?;
+    let res = res.add_attribute(attr::TO, escrow.source.as_str());
+
+    // cancelling the last open milestone closes the escrow
+    if escrow.is_complete() {
+        remove_escrow(deps.storage, &id, &escrow)?;
+        Ok(res.add_attribute("is_escrow_complete", "true"))
+    } else {
+        save_escrow(deps.storage, &env, &id, &mut escrow)?;
+        Ok(res)
+    }
+}
+
+pub fn execute_refund(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    // this fails is no escrow there
+    let escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
+
+    // the arbiter can send anytime OR anyone can send after expiration
+    if !escrow.is_expired(&env) && info.sender != escrow.arbiter {
+        Err(ContractError::Unauthorized {})
+    } else if escrow.is_disputed() {
+        // the arbiter has to settle disputes first, they may owe the recipient something
+        Err(ContractError::EscrowDisputed {})
+    } else if let Err(err) = escrow.check_liquid(&env) {
+        Err(err)
+    } else {
+        // send all tokens out, then delete the escrow
+        let messages = refund_funders(deps.storage, &env, &id, &escrow, &escrow.balance)?;
+        remove_escrow(deps.storage, &id, &escrow)?;
+        append_history(
+            deps.storage,
+            &env,
+            &id,
+            &info.sender,
+            "refund",
+            None,
+            Some(&escrow.balance),
+        )?;
+
+        Ok(response("refund")
+            .add_attribute(attr::ID, id)
+            .add_attribute(attr::TO, escrow.source)
+            .add_submessages(messages))
+    }
+}
+
+pub fn execute_refund_milestone(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    milestone_id: String,
+) -> Result<Response, ContractError> {
+    let mut escrow = get_escrow_by_id(&deps.as_ref(), &id)?;
+
+    let index = escrow
+        .milestones
+        .iter()
+        .position(|m| m.id == milestone_id)
+        .ok_or(ContractError::MilestoneNotFound {})?;
+    let milestone = &escrow.milestones[index];
+    if !milestone.is_awaiting_approval() {
+        return Err(ContractError::MilestoneNotPending {});
+    }
+    // anyone may refund it, but only once its deadline passed
+    if !milestone.is_expired(&env) {
+        return Err(ContractError::MilestoneNotExpired {});
+    }
+    escrow.check_liquid(&env)?;
+
+    let before = escrow.clone();
+    let refund = escrow.cancel_milestone(index)?;
+    let messages = refund_funders(deps.storage, &env, &id, &before, &refund)?;
+    append_history(
+        deps.storage,
+        &env,
+        &id,
+        &info.sender,
+        "refund_milestone",
+        Some(&milestone_id),
+        Some(&refund),
+    )?;
+    let res = response("refund_milestone")
+        .add_attribute(attr::ID, id.as_str())
+        .add_attribute(attr::MILESTONE_ID, milestone_id)
+        .add_attribute(attr::TO, escrow.source.as_str())
+        .add_submessages(messages);
+
+    // refunding the last open milestone closes the escrow
+    if escrow.is_complete() {
+        remove_escrow(deps.storage, &id, &escrow)?;
+        Ok(res.add_attribute("is_escrow_complete", "true"))
+    } else {
+        save_escrow(deps.storage, &env, &id, &mut escrow)?;
+        Ok(res)
+    }
+}
+
+/// Refunds up to `limit` expired escrows without disputes or staked funds, in id order from
+/// where the previous call stopped. Reads no more than `MAX_EXPIRY_SCAN` escrows, so the gas
+/// doesn't grow with how many there are
+pub fn process_expired(
+    deps: DepsMut,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let cursor = EXPIRY_CURSOR.may_load(deps.storage)?;
+    let mut read = 0;
+    let mut last_read = None;
+    let mut expired = vec![];
+    for item in escrows()
+        .range(
+            deps.storage,
+            cursor.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(MAX_EXPIRY_SCAN)
+    {
+        let (id, escrow) = item?;
+        read += 1;
+        last_read = Some(id.clone());
+        if escrow.is_expired(&env) && !escrow.is_disputed() && escrow.check_liquid(&env).is_ok() {
+            expired.push((id, escrow));
+            if expired.len() == limit {
+                break;
+            }
+        }
+    }
+    // stopping early leaves escrows after the last one read, otherwise start over next time
+    match last_read {
+        Some(id) if expired.len() == limit || read == MAX_EXPIRY_SCAN => {
+            EXPIRY_CURSOR.save(deps.storage, &id)?
+        }
+        _ => EXPIRY_CURSOR.remove(deps.storage),
+    }
+
+    let mut res = response("process_expired").add_attribute("count", expired.len().to_string());
+    for (id, escrow) in expired {
+        let messages = refund_funders(deps.storage, &env, &id, &escrow, &escrow.balance)?;
+        remove_escrow(deps.storage, &id, &escrow)?;
+        append_history(
+            deps.storage,
+            &env,
+            &id,
+            &env.contract.address,
+            "process_expired",
+            None,
+            Some(&escrow.balance),
+        )?;
+        res = res.add_attribute(attr::ID, id).add_submessages(messages);
+    }
+    Ok(res)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::ProcessExpired { limit } => process_expired(deps, env, limit),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        CREATE_REPLY_ID => reply_create(deps, msg.result),
+        id if id >= FIRST_PAYOUT_REPLY_ID => reply_payout(deps, env, id, msg.result),
+        id => Err(StdError::generic_err(format!("Unknown reply id {}", id)).into()),
+    }
+}
+
+/// Sends the tokens of a Receive whose create failed back to their sender
+fn reply_create(deps: DepsMut, result: SubMsgResult) -> Result<Response, ContractError> {
+    let (sender, token) = RECEIVED_CREATE.load(deps.storage)?;
+    RECEIVED_CREATE.remove(deps.storage);
+    let error = match result {
+        SubMsgResult::Err(error) => error,
+        SubMsgResult::Ok(_) => return Ok(Response::new()),
+    };
+
+    let returned = GenericBalance {
+        native: vec![],
+        cw20: vec![token],
+    };
+    Ok(response("return_cw20")
+        .add_attribute(attr::RECIPIENT, &sender)
+        .add_attribute(attr::ERROR, error)
+        .add_submessages(send_tokens(&sender, &returned)?))
+}
+
+/// Puts the tokens of a payout that failed back where they came from
+fn reply_payout(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+    result: SubMsgResult,
+) -> Result<Response, ContractError> {
+    let payout = PAYOUTS.load(deps.storage, id)?;
+    PAYOUTS.remove(deps.storage, id);
+    let error = match result {
+        SubMsgResult::Err(error) => error,
+        SubMsgResult::Ok(_) => return Ok(Response::new()),
+    };
+
+    let res = response("restore_payout").add_attribute(attr::ERROR, error);
+    match payout.restore {
+        Restore::Claims(addr) => {
+            credit(deps.storage, &addr, &payout.amount)?;
+            Ok(res.add_attribute(attr::TO, addr))
+        }
+        Restore::Escrow { id, escrow: before } => {
+            let mut escrow = match escrows().may_load(deps.storage, &id)? {
+                Some(escrow) => escrow,
+                None => Escrow {
+                    balance: GenericBalance::default(),
+                    ..(*before).clone()
+                },
+            };
+            for milestone in escrow.milestones.iter_mut() {
+                match before.milestones.iter().find(|m| m.id == milestone.id) {
+                    Some(open) if open.is_open() && !milestone.is_open() => {
+                        *milestone = open.clone()
+                    }
+                    _ => {}
+                }
+            }
+            escrow.balance.add_balance(&payout.amount);
+            save_escrow(deps.storage, &env, &id, &mut escrow)?;
+            append_history(
+                deps.storage,
+                &env,
+                &id,
+                &env.contract.address,
+                "restore_payout",
+                None,
+                Some(&payout.amount),
+            )?;
+            Ok(res.add_attribute(attr::ID, id))
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // If state structure changes in a later version, register its transform here with
+    // `.step(version, transform)`
+    let migrated = Migration::new(CONTRACT_NAME, CONTRACT_VERSION)
+        .step("0.15.0", upgrade_escrows)
+        .run(deps.storage)?;
+
+    // Contracts instantiated before ownership existed are handed to the admin migrating them
+    if !OWNERSHIP.is_initialized(deps.storage)? {
+        let admin = deps
+            .querier
+            .query_wasm_contract_info(&env.contract.address)?
+            .admin
+            .ok_or_else(|| StdError::generic_err("Contract has no admin"))?;
+        OWNERSHIP.initialize(deps.storage, &deps.api.addr_validate(&admin)?)?;
+    }
+    // Contracts instantiated before fees existed charge none until the owner sets one
+    if CONFIG.may_load(deps.storage)?.is_none() {
+        CONFIG.save(deps.storage, &Config::default())?;
+    }
+    // Contracts from before the stats were kept count the escrows they hold now
+    if STATS.may_load(deps.storage)?.is_none() {
+        init_stats(deps.storage)?;
+    }
+
+    Ok(migrate_response(migrated.from, migrated.to))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::List { status } => to_binary(&query_list(deps, env, status)?),
+        QueryMsg::ListByArbiter {
+            arbiter,
+            start_after,
+            limit,
+        } => to_binary(&query_list_by_arbiter(
+            deps,
+            env,
+            arbiter,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::ListByRecipient {
+            recipient,
+            start_after,
+            limit,
+        } => to_binary(&query_list_by(
+            deps,
+            env,
+            &escrows().idx.recipient,
+            recipient,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::ListBySource {
+            source,
+            start_after,
+            limit,
+        } => to_binary(&query_list_by(
+            deps,
+            env,
+            &escrows().idx.source,
+            source,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::EscrowDetails { id } => to_binary(&query_escrow_details(deps, env, id)?),
+        QueryMsg::FundingStatus { id } => to_binary(&query_funding_status(deps, id)?),
+        QueryMsg::Claims { address } => to_binary(&query_claims(deps, env, address)?),
+        QueryMsg::Disputes {
+            id,
+            start_after,
+            limit,
+        } => to_binary(&query_disputes(deps, id, start_after, limit)?),
+        QueryMsg::EscrowHistory {
+            id,
+            start_after,
+            limit,
+        } => to_binary(&query_escrow_history(deps, id, start_after, limit)?),
+        QueryMsg::Contributions {
+            id,
+            start_after,
+            limit,
+        } => to_binary(&query_contributions(deps, id, start_after, limit)?),
+        QueryMsg::MilestoneApprovals { id, milestone_id } => {
+            to_binary(&query_milestone_approvals(deps, id, milestone_id)?)
+        }
+        QueryMsg::MilestoneDetails { id, milestone_id } => {
+            to_binary(&query_milestone_details(deps, env, id, milestone_id)?)
+        }
+        QueryMsg::ListMilestones {
+            id,
+            start_after,
+            limit,
+        } => to_binary(&query_list_milestones(deps, env, id, start_after, limit)?),
+        QueryMsg::Ownership {} => to_binary(&OWNERSHIP.query(deps.storage)?),
+        QueryMsg::Paused {} => to_binary(&PAUSE.query(deps.storage)?),
+        QueryMsg::Config {} => to_binary(&ConfigResponse {
+            config: CONFIG.load(deps.storage)?,
+        }),
+        QueryMsg::Stats {} => to_binary(&StatsResponse {
+            stats: STATS.may_load(deps.storage)?.unwrap_or_default(),
+        }),
+    }
+}
+
+pub fn query_escrow_details(deps: Deps, env: Env, id: String) -> StdResult<EscrowDetailsResponse> {
+    let escrow = escrows().load(deps.storage, &id)?;
+
+    let cw20_whitelist = escrow.human_whitelist();
+    let funded = escrow.is_funded()?;
+
+    // transform tokens
+    let native_balance = escrow.balance.native;
+    let cw20_balance = cw20_coins(escrow.balance.cw20);
 
     let recipient = escrow.recipient.map(|addr| addr.into_string());
 
     let details = EscrowDetailsResponse {
         id,
         arbiter: escrow.arbiter.into(),
+        co_arbiters: escrow.co_arbiters.into_iter().map(String::from).collect(),
+        threshold: escrow.threshold,
+        arbiter_kind: escrow.arbiter_kind,
+        pending_arbiter: escrow.pending_arbiter.map(String::from),
         recipient,
         source: escrow.source.into(),
+        accepted: escrow.accepted,
         title: escrow.title,
         description: escrow.description,
-        end_height: escrow.end_height,
-        end_time: escrow.end_time,
         native_balance,
-        cw20_balance: cw20_balance?,
+        cw20_balance,
+        funded,
         cw20_whitelist,
-        milestones: escrow.milestones,
+        strict_whitelist: escrow.strict_whitelist,
+        crowdfunded: escrow.crowdfunded,
+        milestones: escrow
+            .milestones
+            .into_iter()
+            .map(|m| with_current_status(&env, m))
+            .collect(),
+        stake: escrow.stake,
+        created_at: escrow.created_at,
+        updated_at: escrow.updated_at,
     };
     Ok(details)
 }
 
+pub fn query_funding_status(deps: Deps, id: String) -> StdResult<FundingStatusResponse> {
+    let escrow = escrows().load(deps.storage, &id)?;
+    let paid_out = escrow.paid_out();
+    let mut required = escrow.required_balance()?;
+    required.add_balance(&paid_out);
+    let mut deposited = escrow.balance.clone();
+    deposited.add_balance(&paid_out);
+    Ok(FundingStatusResponse {
+        required,
+        deposited,
+        paid_out,
+        funded: escrow.is_funded()?,
+        remaining: escrow.balance,
+    })
+}
+
 pub fn query_milestone_details(
     deps: Deps,
+    env: Env,
     id: String,
     milestone_id: String,
 ) -> StdResult<Milestone> {
-    let escrow = ESCROWS.load(deps.storage, &id)?;
+    let escrow = escrows().load(deps.storage, &id)?;
     let milestone = escrow
         .get_milestone_by_id(&milestone_id)
         .ok_or_else(|| StdError::generic_err("Milestone not found"))?;
-    Ok(milestone.to_owned())
+    Ok(with_current_status(&env, milestone.to_owned()))
 }
 
-pub fn query_list(deps: Deps) -> StdResult<ListEscrowsResponse> {
-    Ok(ListEscrowsResponse {
-        escrows: all_escrow_ids(deps.storage)?,
+pub fn query_milestone_approvals(
+    deps: Deps,
+    id: String,
+    milestone_id: String,
+) -> StdResult<MilestoneApprovalsResponse> {
+    let escrow = escrows().load(deps.storage, &id)?;
+    let milestone = escrow
+        .get_milestone_by_id(&milestone_id)
+        .ok_or_else(|| StdError::generic_err("Milestone not found"))?;
+    let waiting_on = escrow
+        .arbiters()
+        .into_iter()
+        .filter(|arbiter| !milestone.approvals.contains(arbiter))
+        .map(String::from)
+        .collect();
+    Ok(MilestoneApprovalsResponse {
+        approved_by: milestone.approvals.iter().map(Addr::to_string).collect(),
+        waiting_on,
+        threshold: escrow.threshold,
+    })
+}
+
+pub fn query_claims(deps: Deps, env: Env, address: String) -> StdResult<ClaimsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let mut claims = CLAIMS.may_load(deps.storage, &address)?.unwrap_or_default();
+    let vesting = VESTINGS
+        .prefix(&address)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, vesting)| vesting))
+        .collect::<StdResult<Vec<_>>>()?;
+    for stream in &vesting {
+        claims.add_balance(&stream.claimable(env.block.time)?);
+    }
+    Ok(ClaimsResponse {
+        native_balance: claims.native,
+        cw20_balance: cw20_coins(claims.cw20),
+        vesting,
     })
 }
 
-pub fn query_list_milestones(deps: Deps, id: String) -> StdResult<ListMilestonesResponse> {
+pub fn query_disputes(
+    deps: Deps,
+    id: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<DisputesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let disputes = DISPUTES
+        .prefix(&id)
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, dispute)| dispute))
+        .collect::<StdResult<_>>()?;
+    Ok(DisputesResponse { disputes })
+}
+
+pub fn query_escrow_history(
+    deps: Deps,
+    id: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<EscrowHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let entries = HISTORY
+        .prefix(&id)
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect::<StdResult<_>>()?;
+    Ok(EscrowHistoryResponse { entries })
+}
+
+pub fn query_contributions(
+    deps: Deps,
+    id: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ContributionsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|funder| deps.api.addr_validate(&funder))
+        .transpose()?;
+    let contributions = CONTRIBUTIONS
+        .prefix(&id)
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            item.map(|(funder, amount)| Contribution {
+                funder: funder.into(),
+                amount,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(ContributionsResponse { contributions })
+}
+
+fn with_current_status(env: &Env, mut milestone: Milestone) -> Milestone {
+    milestone.status = milestone.status_at(env);
+    milestone
+}
+
+pub fn query_list(
+    deps: Deps,
+    env: Env,
+    status: Option<EscrowStatus>,
+) -> StdResult<ListEscrowsResponse> {
+    let escrows = escrows()
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(id, escrow)| escrow_summary(&env, id, escrow)))
+        .filter(|item| match (item, status) {
+            (Ok(summary), Some(status)) => summary.status == status,
+            _ => true,
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(ListEscrowsResponse { escrows })
+}
+
+/// Lists the escrows `index` files under `addr`
+pub fn query_list_by(
+    deps: Deps,
+    env: Env,
+    index: &MultiIndex<Addr, Escrow, String>,
+    addr: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListEscrowsResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let escrows = index
+        .prefix(addr)
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(id, escrow)| escrow_summary(&env, id, escrow)))
+        .collect::<StdResult<_>>()?;
+    Ok(ListEscrowsResponse { escrows })
+}
+
+/// Like `query_list_by`, counting the escrows `arbiter` is a co-arbiter of too
+pub fn query_list_by_arbiter(
+    deps: Deps,
+    env: Env,
+    arbiter: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListEscrowsResponse> {
+    let arbiter = deps.api.addr_validate(&arbiter)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // the first `limit` ids of each, merged, hold the first `limit` of both
+    let mut ids = escrows()
+        .idx
+        .arbiter
+        .prefix(arbiter.clone())
+        .keys(
+            deps.storage,
+            start_after.clone().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let co_arbiter_ids = CO_ARBITER_ESCROWS
+        .prefix(&arbiter)
+        .keys(
+            deps.storage,
+            start_after.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    ids.extend(co_arbiter_ids);
+    ids.sort();
+    ids.dedup();
+    ids.truncate(limit);
+
+    let escrows = ids
+        .into_iter()
+        .map(|id| {
+            let escrow = escrows().load(deps.storage, &id)?;
+            Ok(escrow_summary(&env, id, escrow))
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(ListEscrowsResponse { escrows })
+}
+
+fn escrow_summary(env: &Env, id: String, escrow: Escrow) -> EscrowSummary {
+    let completed_milestones = escrow
+        .milestones
+        .iter()
+        .filter(|m| m.status == MilestoneStatus::Completed)
+        .count();
+    let status = escrow.status(env);
+    EscrowSummary {
+        id,
+        arbiter: escrow.arbiter.into(),
+        recipient: escrow.recipient.map(String::from),
+        title: escrow.title,
+        native_balance: escrow.balance.native,
+        cw20_balance: cw20_coins(escrow.balance.cw20),
+        milestones: escrow.milestones.len() as u64,
+        completed_milestones: completed_milestones as u64,
+        status,
+    }
+}
+
+fn cw20_coins(tokens: Vec<Cw20CoinVerified>) -> Vec<Cw20Coin> {
+    tokens
+        .into_iter()
+        .map(|token| Cw20Coin {
+            address: token.address.into(),
+            amount: token.amount,
+        })
+        .collect()
+}
+
+pub fn query_list_milestones(
+    deps: Deps,
+    env: Env,
+    id: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListMilestonesResponse> {
     let escrow = get_escrow_by_id(&deps, &id)
         .map_err(|err| StdError::generic_err(format!("Error: {:?}", err)))?;
-    Ok(ListMilestonesResponse {
-        milestones: escrow.milestones.iter().map(|m| m.id.clone()).collect(),
-    })
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = match start_after {
+        Some(start_after) => {
+            escrow
+                .milestones
+                .iter()
+                .position(|m| m.id == start_after)
+                .ok_or_else(|| StdError::generic_err("Milestone not found"))?
+                + 1
+        }
+        None => 0,
+    };
+    let milestones = escrow
+        .milestones
+        .into_iter()
+        .skip(start)
+        .take(limit)
+        .map(|milestone| with_current_status(&env, milestone))
+        .collect();
+    Ok(ListMilestonesResponse { milestones })
 }
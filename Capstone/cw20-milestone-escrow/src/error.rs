@@ -1,4 +1,6 @@
 use cosmwasm_std::StdError;
+use cosmwatch_access::AccessError;
+use cosmwatch_migrate::MigrateError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -6,12 +8,30 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Migrate(#[from] MigrateError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Escrows are paused")]
+    ContractPaused {},
+
+    #[error("No ownership transfer is pending")]
+    NoPendingOwner {},
+
+    #[error("Fee can't be more than {max} bps")]
+    InvalidFee { max: u16 },
+
+    #[error("A fee needs a treasury to collect it")]
+    NoTreasury {},
+
     #[error("Only accepts tokens in the cw20_whitelist")]
     NotInWhitelist {},
 
+    #[error("Escrow still holds or owes {token}, it has to stay whitelisted")]
+    TokenInUse { token: String },
+
     #[error("Escrow is expired")]
     Expired {},
 
@@ -24,21 +44,119 @@ pub enum ContractError {
     #[error("Send some coins to create an escrow")]
     EmptyBalance {},
 
-    #[error("Funds sent does not equal the total amount of all milestones")]
-    FundsMismatch {},
+    #[error("Funds sent exceed what the milestones still need of {token}")]
+    FundsMismatch { token: String },
+
+    #[error("Escrow does not hold enough to pay this milestone yet")]
+    Underfunded {},
 
     #[error("Escrow id already in use")]
     AlreadyInUse {},
 
+    #[error("Invalid escrow id {id}: use 3 to 20 bytes")]
+    InvalidId { id: String },
+
+    #[error("Title must be 1 to {max} bytes")]
+    InvalidTitle { max: usize },
+
+    #[error("Stream must end after it starts")]
+    InvalidStream {},
+
+    #[error("Description can't be longer than {max} bytes")]
+    DescriptionTooLong { max: usize },
+
+    #[error("Arbiters must be distinct")]
+    DuplicateArbiter {},
+
+    #[error("Threshold must be between 1 and the {arbiters} arbiters")]
+    InvalidThreshold { arbiters: u32 },
+
+    #[error("Arbiter is not a cw3 contract")]
+    NotCw3Arbiter {},
+
+    #[error("Proposal {proposal_id} has not passed")]
+    ProposalNotPassed { proposal_id: u64 },
+
+    #[error("Proposal {proposal_id} does not carry this message")]
+    ProposalMismatch { proposal_id: u64 },
+
+    #[error("Proposal {proposal_id} was already used")]
+    ProposalUsed { proposal_id: u64 },
+
+    #[error("Milestone already approved by this arbiter")]
+    AlreadyApproved {},
+
+    #[error("No arbiter transfer is pending")]
+    NoPendingArbiter {},
+
     #[error("Recipient is not set")]
     RecipientNotSet {},
 
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Recipient has not accepted the escrow")]
+    NotAccepted {},
+
+    #[error("Escrow was already accepted")]
+    AlreadyAccepted {},
+
     #[error("Milestone not found")]
     MilestoneNotFound,
 
     #[error("Milestone is expired")]
     MilestoneExpired {},
 
+    #[error("Milestone has not expired yet")]
+    MilestoneNotExpired {},
+
+    #[error("New deadline must be later than the current one and still ahead")]
+    CannotShortenDeadline {},
+
+    #[error("Milestone is not pending approval")]
+    MilestoneNotPending {},
+
+    #[error("Milestone has not been submitted by the recipient")]
+    NotSubmitted {},
+
+    #[error("Already asked to cancel this milestone")]
+    AlreadyConsented {},
+
+    #[error("Milestone has already paid out")]
+    AlreadyPaidOut {},
+
+    #[error("Reason must be 1 to {max} bytes")]
+    InvalidReason { max: usize },
+
+    #[error("Escrow has a milestone under dispute")]
+    EscrowDisputed {},
+
+    #[error("Milestone is not under dispute")]
+    NotDisputed {},
+
+    #[error("Recipient share must be between 0 and 1")]
+    InvalidShare {},
+
+    #[error("Unknown validator {validator}")]
+    UnknownValidator { validator: String },
+
+    #[error("Escrow funds are staked, unstake them and wait for unbonding")]
+    FundsStaked {},
+
+    #[error("Escrow is not staked")]
+    NotStaked {},
+
     #[error("Milestones can't be empty")]
     EmptyMilestones,
 }
+
+impl From<AccessError> for ContractError {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::Std(err) => ContractError::Std(err),
+            AccessError::Unauthorized {} => ContractError::Unauthorized {},
+            AccessError::ContractPaused {} => ContractError::ContractPaused {},
+            AccessError::NoPendingOwner {} => ContractError::NoPendingOwner {},
+        }
+    }
+}
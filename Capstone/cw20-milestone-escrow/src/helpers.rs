@@ -0,0 +1,519 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_binary, Addr, Coin, CosmosMsg, CustomQuery, Decimal, QuerierWrapper, QueryRequest,
+    StdResult, Uint128, WasmMsg, WasmQuery,
+};
+use cw20::Cw20ExecuteMsg;
+use cw3::Cw3QueryMsg;
+use cw_utils::{Expiration, ThresholdResponse};
+
+use crate::msg::{
+    ClaimsResponse, ContributionsResponse, CreateMilestoneMsg, CreateMsg, DisputesResponse,
+    EscrowDetailsResponse, EscrowHistoryResponse, EscrowStatus, ExecuteMsg, FundingStatusResponse,
+    ListEscrowsResponse, ListMilestonesResponse, MilestoneApprovalsResponse, QueryMsg, ReceiveMsg,
+};
+use crate::state::{GenericBalance, Milestone};
+
+/// EscrowContract is a wrapper around Addr that builds messages and queries
+/// for the escrow, so other contracts can act as arbiters or sources.
+#[cw_serde]
+pub struct EscrowContract(pub Addr);
+
+impl EscrowContract {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    pub fn call<T: Into<ExecuteMsg>>(&self, msg: T) -> StdResult<CosmosMsg> {
+        self.call_with_funds(msg, vec![])
+    }
+
+    pub fn call_with_funds<T: Into<ExecuteMsg>>(
+        &self,
+        msg: T,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg> {
+        let msg = to_binary(&msg.into())?;
+        Ok(WasmMsg::Execute {
+            contract_addr: self.addr().into(),
+            msg,
+            funds,
+        }
+        .into())
+    }
+
+    /// Create an escrow funded with the native `funds` sent alongside
+    pub fn create(&self, msg: CreateMsg, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        self.call_with_funds(ExecuteMsg::Create(msg), funds)
+    }
+
+    /// Create an escrow funded by sending `amount` of the cw20 `token` to the escrow
+    pub fn create_with_cw20(
+        &self,
+        token: &Addr,
+        amount: Uint128,
+        msg: CreateMsg,
+    ) -> StdResult<CosmosMsg> {
+        self.send_cw20(token, amount, ReceiveMsg::Create(msg))
+    }
+
+    /// Add a milestone funded with the native `funds` sent alongside
+    pub fn create_milestone(
+        &self,
+        msg: CreateMilestoneMsg,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg> {
+        self.call_with_funds(ExecuteMsg::CreateMilestone(msg), funds)
+    }
+
+    /// Add a milestone funded by sending `amount` of the cw20 `token` to the escrow
+    pub fn create_milestone_with_cw20(
+        &self,
+        token: &Addr,
+        amount: Uint128,
+        msg: CreateMilestoneMsg,
+    ) -> StdResult<CosmosMsg> {
+        self.send_cw20(token, amount, ReceiveMsg::CreateMilestone(msg))
+    }
+
+    /// Change a milestone the recipient hasn't submitted yet, as the arbiter. `None` keeps what
+    /// is there
+    pub fn update_milestone(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+        title: Option<String>,
+        description: Option<String>,
+        amount: Option<GenericBalance>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UpdateMilestone {
+            id: id.into(),
+            milestone_id: milestone_id.into(),
+            title,
+            description,
+            amount,
+        })
+    }
+
+    /// Delete a milestone the recipient hasn't submitted yet, as the arbiter
+    pub fn remove_milestone(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RemoveMilestone {
+            id: id.into(),
+            milestone_id: milestone_id.into(),
+        })
+    }
+
+    /// Add the native `funds` sent alongside to an escrow that isn't fully funded yet
+    pub fn top_up(&self, id: impl Into<String>, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        self.call_with_funds(ExecuteMsg::TopUp { id: id.into() }, funds)
+    }
+
+    /// Add `amount` of the cw20 `token` to an escrow that isn't fully funded yet
+    pub fn top_up_with_cw20(
+        &self,
+        token: &Addr,
+        amount: Uint128,
+        id: impl Into<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.send_cw20(token, amount, ReceiveMsg::TopUp { id: id.into() })
+    }
+
+    /// Add `amount` of the cw20 `token` to an escrow for one of its milestones
+    pub fn top_up_milestone_with_cw20(
+        &self,
+        token: &Addr,
+        amount: Uint128,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<CosmosMsg> {
+        let msg = ReceiveMsg::TopUpMilestone {
+            escrow_id: id.into(),
+            milestone_id: milestone_id.into(),
+        };
+        self.send_cw20(token, amount, msg)
+    }
+
+    pub fn set_recipient(
+        &self,
+        id: impl Into<String>,
+        recipient: impl Into<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetRecipient {
+            id: id.into(),
+            recipient: recipient.into(),
+        })
+    }
+
+    /// Whitelist the cw20 tokens in `add` and take those in `remove` off, as the arbiter
+    pub fn update_cw20_whitelist(
+        &self,
+        id: impl Into<String>,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UpdateCw20Whitelist {
+            id: id.into(),
+            add,
+            remove,
+        })
+    }
+
+    /// Accept the escrow's terms as its recipient, which lets its milestones be approved
+    pub fn accept_escrow(&self, id: impl Into<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::AcceptEscrow { id: id.into() })
+    }
+
+    /// Offer the arbiter role to `new_arbiter`, who takes it over with `accept_arbiter`
+    pub fn transfer_arbiter(
+        &self,
+        id: impl Into<String>,
+        new_arbiter: impl Into<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::TransferArbiter {
+            id: id.into(),
+            new_arbiter: new_arbiter.into(),
+        })
+    }
+
+    pub fn accept_arbiter(&self, id: impl Into<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::AcceptArbiter { id: id.into() })
+    }
+
+    /// Submit a milestone as delivered, for the arbiters to approve
+    pub fn submit_milestone(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SubmitMilestone {
+            id: id.into(),
+            milestone_id: milestone_id.into(),
+        })
+    }
+
+    pub fn approve_milestone(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ApproveMilestone {
+            id: id.into(),
+            milestone_id: milestone_id.into(),
+        })
+    }
+
+    /// Approve several submitted milestones in one go, failing if any of them can't be
+    pub fn approve_milestones(
+        &self,
+        id: impl Into<String>,
+        milestone_ids: Vec<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ApproveMilestones {
+            id: id.into(),
+            milestone_ids,
+        })
+    }
+
+    pub fn extend_milestone(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+        expires: Expiration,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ExtendMilestone {
+            id: id.into(),
+            milestone_id: milestone_id.into(),
+            expires,
+        })
+    }
+
+    /// Freeze a pending milestone until the arbiter resolves the dispute, as source or recipient
+    pub fn raise_dispute(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RaiseDispute {
+            id: id.into(),
+            milestone_id: milestone_id.into(),
+            reason: reason.into(),
+        })
+    }
+
+    /// Settle a disputed milestone as the arbiter, `recipient_share` to the recipient and the
+    /// rest to the source
+    pub fn resolve_dispute(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+        recipient_share: Decimal,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ResolveDispute {
+            id: id.into(),
+            milestone_id: milestone_id.into(),
+            recipient_share,
+        })
+    }
+
+    /// Cancel a milestone, as its arbiter or as one of source and recipient agreeing to
+    pub fn cancel_milestone(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::CancelMilestone {
+            id: id.into(),
+            milestone_id: milestone_id.into(),
+        })
+    }
+
+    /// Approve a milestone for a cw3 arbiter, with a passed proposal of it carrying the approval
+    pub fn approve_by_proposal(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+        proposal_id: u64,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ApproveByProposal {
+            id: id.into(),
+            milestone_id: milestone_id.into(),
+            proposal_id,
+        })
+    }
+
+    /// Refund an escrow for a cw3 arbiter, with a passed proposal of it carrying the refund
+    pub fn refund_by_proposal(
+        &self,
+        id: impl Into<String>,
+        proposal_id: u64,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RefundByProposal {
+            id: id.into(),
+            proposal_id,
+        })
+    }
+
+    pub fn refund(&self, id: impl Into<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Refund { id: id.into() })
+    }
+
+    /// Send a milestone's funds back to the source once its own deadline passed
+    pub fn refund_milestone(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RefundMilestone {
+            id: id.into(),
+            milestone_id: milestone_id.into(),
+        })
+    }
+
+    /// Refund up to `limit` expired escrows, as a recurring CronCat task would
+    pub fn process_expired(&self, limit: Option<u32>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ProcessExpired { limit })
+    }
+
+    /// Undelegate a staked escrow's funds and credit the yield they earned
+    pub fn unstake(&self, id: impl Into<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Unstake { id: id.into() })
+    }
+
+    /// Collect everything approved milestones have credited to the sender
+    pub fn claim(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Claim {})
+    }
+
+    fn send_cw20(&self, token: &Addr, amount: Uint128, msg: ReceiveMsg) -> StdResult<CosmosMsg> {
+        let send = Cw20ExecuteMsg::Send {
+            contract: self.addr().into(),
+            amount,
+            msg: to_binary(&msg)?,
+        };
+        Ok(WasmMsg::Execute {
+            contract_addr: token.into(),
+            msg: to_binary(&send)?,
+            funds: vec![],
+        }
+        .into())
+    }
+
+    /// Build a smart query against the escrow
+    pub fn query_request<C: CustomQuery>(&self, msg: &QueryMsg) -> StdResult<QueryRequest<C>> {
+        Ok(WasmQuery::Smart {
+            contract_addr: self.addr().into(),
+            msg: to_binary(msg)?,
+        }
+        .into())
+    }
+
+    pub fn list<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        status: Option<EscrowStatus>,
+    ) -> StdResult<ListEscrowsResponse> {
+        querier.query(&self.query_request(&QueryMsg::List { status })?)
+    }
+
+    pub fn list_by_arbiter<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        arbiter: impl Into<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ListEscrowsResponse> {
+        querier.query(&self.query_request(&QueryMsg::ListByArbiter {
+            arbiter: arbiter.into(),
+            start_after,
+            limit,
+        })?)
+    }
+
+    pub fn list_by_recipient<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        recipient: impl Into<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ListEscrowsResponse> {
+        querier.query(&self.query_request(&QueryMsg::ListByRecipient {
+            recipient: recipient.into(),
+            start_after,
+            limit,
+        })?)
+    }
+
+    pub fn list_by_source<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        source: impl Into<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ListEscrowsResponse> {
+        querier.query(&self.query_request(&QueryMsg::ListBySource {
+            source: source.into(),
+            start_after,
+            limit,
+        })?)
+    }
+
+    pub fn escrow_details<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        id: impl Into<String>,
+    ) -> StdResult<EscrowDetailsResponse> {
+        querier.query(&self.query_request(&QueryMsg::EscrowDetails { id: id.into() })?)
+    }
+
+    pub fn funding_status<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        id: impl Into<String>,
+    ) -> StdResult<FundingStatusResponse> {
+        querier.query(&self.query_request(&QueryMsg::FundingStatus { id: id.into() })?)
+    }
+
+    pub fn claims<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        address: impl Into<String>,
+    ) -> StdResult<ClaimsResponse> {
+        querier.query(&self.query_request(&QueryMsg::Claims {
+            address: address.into(),
+        })?)
+    }
+
+    pub fn disputes<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        id: impl Into<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<DisputesResponse> {
+        querier.query(&self.query_request(&QueryMsg::Disputes {
+            id: id.into(),
+            start_after,
+            limit,
+        })?)
+    }
+
+    pub fn escrow_history<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        id: impl Into<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<EscrowHistoryResponse> {
+        querier.query(&self.query_request(&QueryMsg::EscrowHistory {
+            id: id.into(),
+            start_after,
+            limit,
+        })?)
+    }
+
+    pub fn contributions<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        id: impl Into<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ContributionsResponse> {
+        querier.query(&self.query_request(&QueryMsg::Contributions {
+            id: id.into(),
+            start_after,
+            limit,
+        })?)
+    }
+
+    pub fn milestone_approvals<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<MilestoneApprovalsResponse> {
+        querier.query(&self.query_request(&QueryMsg::MilestoneApprovals {
+            id: id.into(),
+            milestone_id: milestone_id.into(),
+        })?)
+    }
+
+    pub fn milestone_details<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<Milestone> {
+        querier.query(&self.query_request(&QueryMsg::MilestoneDetails {
+            id: id.into(),
+            milestone_id: milestone_id.into(),
+        })?)
+    }
+
+    pub fn list_milestones<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        id: impl Into<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ListMilestonesResponse> {
+        querier.query(&self.query_request(&QueryMsg::ListMilestones {
+            id: id.into(),
+            start_after,
+            limit,
+        })?)
+    }
+}
+
+/// Query the voting threshold of a cw3 multisig. Fails if `arbiter` does not answer cw3
+/// queries, so a multisig arbiter can be checked before an escrow is created with it.
+pub fn cw3_threshold<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    arbiter: &Addr,
+) -> StdResult<ThresholdResponse> {
+    querier.query_wasm_smart(arbiter, &Cw3QueryMsg::Threshold {})
+}
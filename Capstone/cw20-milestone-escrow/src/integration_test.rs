@@ -1,35 +1,24 @@
 #![cfg(test)]
 
-use cosmwasm_std::{coins, to_binary, Addr, Coin, Empty, Uint128};
+use cosmwasm_std::{coins, to_binary, Addr, Coin, CosmosMsg, Decimal, Empty, Uint128};
 use cw20::{Cw20Coin, Cw20CoinVerified, Cw20Contract, Cw20ExecuteMsg};
+use cw3::Vote;
 use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_utils::{Duration, Expiration, Threshold, ThresholdResponse};
 
 use crate::{
+    helpers::{cw3_threshold, EscrowContract},
     msg::{
         CreateMilestoneMsg, CreateMsg, EscrowDetailsResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
         ReceiveMsg,
     },
-    state::GenericBalance,
+    state::{ArbiterKind, GenericBalance, MilestoneStatus},
+    suite::{
+        contract_cw20, contract_escrow_milestones, create_msg, cw20_milestone, native_milestone,
+        Suite, SuiteBuilder,
+    },
 };
 
-pub fn contract_escrow_milestones() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(
-        crate::contract::execute,
-        crate::contract::instantiate,
-        crate::contract::query,
-    );
-    Box::new(contract)
-}
-
-pub fn contract_cw20() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(
-        cw20_base::contract::execute,
-        cw20_base::contract::instantiate,
-        cw20_base::contract::query,
-    );
-    Box::new(contract)
-}
-
 #[test]
 // receive cw20 tokens and release upon approval
 fn test_escrow_lifecycle_cw20() {
@@ -73,7 +62,7 @@ fn test_escrow_lifecycle_cw20() {
         .instantiate_contract(
             escrow_id,
             owner.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Escrow",
             None,
@@ -108,16 +97,22 @@ fn test_escrow_lifecycle_cw20() {
         title: "milestone_1".to_string(),
         description: "milestone_description_1".to_string(),
         amount: amount.clone(),
-        end_height: None,
-        end_time: None,
+        expires: None,
+        stream: None,
     }];
     let create_msg = ReceiveMsg::Create(CreateMsg {
         id: id.to_string(),
         arbiter: arb.to_string(),
+        co_arbiters: None,
+        threshold: None,
+        arbiter_kind: None,
         recipient: Some(recipient.to_string()),
         title: "some_title".to_string(),
         description: "some_description".to_string(),
         cw20_whitelist: Some(vec![cash_addr.to_string()]),
+        strict_whitelist: None,
+        crowdfunded: None,
+        stake: None,
         milestones,
     });
     let send_msg = Cw20ExecuteMsg::Send {
@@ -128,14 +123,16 @@ fn test_escrow_lifecycle_cw20() {
     let res = router
         .execute_contract(arb.clone(), cash_addr.clone(), &send_msg, &[])
         .unwrap();
-    assert_eq!(4, res.events.len());
+    assert_eq!(5, res.events.len());
 
     assert_eq!(res.events[0].ty.as_str(), "execute");
     let cw20_attr = res.custom_attrs(1);
     assert_eq!(4, cw20_attr.len());
 
+    // the escrow takes the Receive, then creates in a call to itself
     assert_eq!(res.events[2].ty.as_str(), "execute");
-    let escrow_attr = res.custom_attrs(3);
+    assert_eq!(res.events[3].ty.as_str(), "execute");
+    let escrow_attr = res.custom_attrs(4);
     assert_eq!(2, escrow_attr.len());
 
     // ensure balances updated
@@ -160,7 +157,7 @@ fn test_escrow_lifecycle_cw20() {
     assert_eq!(Some(recipient.to_string()), details.recipient);
     assert_eq!(
         vec![Cw20Coin {
-            address: arb.to_string(),
+            address: cash_addr.to_string(),
             amount: Uint128::new(1000)
         }],
         details.cw20_balance
@@ -168,7 +165,26 @@ fn test_escrow_lifecycle_cw20() {
 
     println!("{:?}", details);
 
-    // release escrow
+    // the recipient agrees and submits the milestone, then the arbiter releases the escrow
+    router
+        .execute_contract(
+            recipient.clone(),
+            escrow_contract_addr.clone(),
+            &ExecuteMsg::AcceptEscrow { id: id.to_string() },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            recipient.clone(),
+            escrow_contract_addr.clone(),
+            &ExecuteMsg::SubmitMilestone {
+                id: id.to_string(),
+                milestone_id: String::from("1"),
+            },
+            &[],
+        )
+        .unwrap();
     let approve_msg = ExecuteMsg::ApproveMilestone {
         id: id.to_string(),
         milestone_id: String::from("1"),
@@ -176,6 +192,14 @@ fn test_escrow_lifecycle_cw20() {
     let _ = router
         .execute_contract(arb.clone(), escrow_contract_addr.clone(), &approve_msg, &[])
         .unwrap();
+    router
+        .execute_contract(
+            recipient.clone(),
+            escrow_contract_addr.clone(),
+            &ExecuteMsg::Claim {},
+            &[],
+        )
+        .unwrap();
 
     // ensure balances updated
     let arb_balance = cash.balance::<_, _, Empty>(&router, arb).unwrap();
@@ -214,7 +238,7 @@ fn test_escrow_lifecycle_native() {
         .instantiate_contract(
             escrow_id,
             owner.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg::default(),
             &[],
             "Escrow",
             None,
@@ -234,16 +258,22 @@ fn test_escrow_lifecycle_native() {
         title: "milestone_1".to_string(),
         description: "milestone_description_1".to_string(),
         amount: amount.clone(),
-        end_height: None,
-        end_time: None,
+        expires: None,
+        stream: None,
     }];
     let create_msg = ReceiveMsg::Create(CreateMsg {
         id: id.to_string(),
         arbiter: arb.to_string(),
+        co_arbiters: None,
+        threshold: None,
+        arbiter_kind: None,
         recipient: Some(recipient.to_string()),
         title: "some_title".to_string(),
         description: "some_description".to_string(),
         cw20_whitelist: None,
+        strict_whitelist: None,
+        crowdfunded: None,
+        stake: None,
         milestones,
     });
     let res = router
@@ -281,6 +311,25 @@ fn test_escrow_lifecycle_native() {
         .unwrap();
     assert_eq!(arb, details.arbiter);
     assert_eq!(Some(recipient.to_string()), details.recipient);
+    router
+        .execute_contract(
+            recipient.clone(),
+            escrow_contract_addr.clone(),
+            &ExecuteMsg::AcceptEscrow { id: id.to_string() },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            recipient,
+            escrow_contract_addr.clone(),
+            &ExecuteMsg::SubmitMilestone {
+                id: id.to_string(),
+                milestone_id: String::from("1"),
+            },
+            &[],
+        )
+        .unwrap();
 
     // Approve only milestone and release escrow
     let approve_msg = ExecuteMsg::ApproveMilestone {
@@ -293,3 +342,712 @@ fn test_escrow_lifecycle_native() {
 
     // ensure balances updated - release to recipient
 }
+
+#[test]
+// release each native milestone in turn, closing the escrow on the last one
+fn test_suite_native_milestones_release_in_turn() {
+    let mut suite = SuiteBuilder::new()
+        .with_native("funder", coins(3000, "juno"))
+        .build();
+
+    let milestones = vec![
+        native_milestone("demo", "first", coins(1000, "juno")),
+        native_milestone("demo", "second", coins(500, "juno")),
+    ];
+    suite
+        .create_escrow(
+            "funder",
+            create_msg("demo", "arbiter", Some("recipient"), milestones),
+            &coins(1500, "juno"),
+        )
+        .unwrap();
+    assert_eq!(suite.native_balance("funder", "juno"), 1500);
+    let milestones = suite.list_milestones("demo").unwrap().milestones;
+    assert_eq!(
+        milestones.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(),
+        vec!["1", "2"]
+    );
+    assert_eq!(milestones[0].status, MilestoneStatus::Pending);
+
+    // nothing is released before the recipient accepts the escrow
+    suite.approve("arbiter", "demo", "1").unwrap_err();
+    suite.accept("arbiter", "demo").unwrap_err();
+    suite.accept("recipient", "demo").unwrap();
+    assert!(suite.escrow_details("demo").unwrap().accepted);
+
+    // only the recipient submits, and only the arbiter approves what was submitted
+    suite.approve("arbiter", "demo", "1").unwrap_err();
+    suite.submit("arbiter", "demo", "1").unwrap_err();
+    suite.submit("recipient", "demo", "1").unwrap();
+    suite.submit("recipient", "demo", "2").unwrap();
+    suite.approve("recipient", "demo", "1").unwrap_err();
+
+    // approvals are credited to the recipient, who claims them
+    suite.approve("arbiter", "demo", "1").unwrap();
+    assert_eq!(suite.native_balance("recipient", "juno"), 0);
+    suite.claim("recipient").unwrap();
+    assert_eq!(suite.native_balance("recipient", "juno"), 1000);
+    assert_eq!(suite.native_balance(suite.escrow.as_str(), "juno"), 500);
+
+    suite.approve("arbiter", "demo", "2").unwrap();
+    suite.claim("recipient").unwrap();
+    assert_eq!(suite.native_balance("recipient", "juno"), 1500);
+    assert_eq!(suite.native_balance(suite.escrow.as_str(), "juno"), 0);
+    suite.escrow_details("demo").unwrap_err();
+}
+
+#[test]
+// partially release a cw20 escrow, leaving the rest in the contract
+fn test_suite_cw20_partial_release() {
+    let mut suite = SuiteBuilder::new()
+        .with_cw20("CASH", &[("funder", 5000)])
+        .with_cw20("GOLD", &[("funder", 100)])
+        .build();
+    let cash = suite.cw20(0);
+
+    let milestones = vec![
+        cw20_milestone("demo", "first", &cash, 600),
+        cw20_milestone("demo", "second", &cash, 400),
+    ];
+    suite
+        .create_escrow_cw20(
+            "funder",
+            &cash,
+            1000,
+            create_msg("demo", "arbiter", Some("recipient"), milestones),
+        )
+        .unwrap();
+    assert_eq!(suite.cw20_balance(&cash, "funder"), 4000);
+    assert_eq!(suite.cw20_balance(&cash, suite.escrow.as_str()), 1000);
+
+    suite.accept("recipient", "demo").unwrap();
+    suite.submit("recipient", "demo", "1").unwrap();
+    suite.approve("arbiter", "demo", "1").unwrap();
+    suite.claim("recipient").unwrap();
+    assert_eq!(suite.cw20_balance(&cash, "recipient"), 600);
+    assert_eq!(suite.cw20_balance(&cash, suite.escrow.as_str()), 400);
+
+    // the other token is untouched
+    assert_eq!(suite.cw20_balance(&suite.cw20(1), "funder"), 100);
+}
+
+#[test]
+// a disputed cw20 milestone is split by the arbiter, each side claiming its part
+fn test_suite_cw20_dispute_split() {
+    let mut suite = SuiteBuilder::new()
+        .with_cw20("CASH", &[("funder", 1000)])
+        .build();
+    let cash = suite.cw20(0);
+
+    let milestones = vec![
+        cw20_milestone("demo", "first", &cash, 600),
+        cw20_milestone("demo", "second", &cash, 400),
+    ];
+    suite
+        .create_escrow_cw20(
+            "funder",
+            &cash,
+            1000,
+            create_msg("demo", "arbiter", Some("recipient"), milestones),
+        )
+        .unwrap();
+    suite.accept("recipient", "demo").unwrap();
+    suite.submit("recipient", "demo", "1").unwrap();
+
+    suite
+        .raise_dispute("funder", "demo", "2", "only half delivered")
+        .unwrap();
+    suite.refund("arbiter", "demo").unwrap_err();
+    suite.approve("arbiter", "demo", "1").unwrap();
+    suite
+        .resolve_dispute("arbiter", "demo", "2", Decimal::percent(50))
+        .unwrap();
+
+    suite.claim("recipient").unwrap();
+    suite.claim("funder").unwrap();
+    assert_eq!(suite.cw20_balance(&cash, "recipient"), 800);
+    assert_eq!(suite.cw20_balance(&cash, "funder"), 200);
+    assert_eq!(suite.cw20_balance(&cash, suite.escrow.as_str()), 0);
+
+    let disputes = suite.disputes("demo").unwrap().disputes;
+    assert_eq!(disputes.len(), 1);
+    assert_eq!(disputes[0].raised_by, Addr::unchecked("funder"));
+    assert_eq!(
+        disputes[0].resolution.as_ref().unwrap().recipient_share,
+        Decimal::percent(50)
+    );
+}
+
+#[test]
+// an escrow without a recipient can't be approved until the arbiter sets one
+fn test_suite_set_recipient_then_approve() {
+    let mut suite = SuiteBuilder::new()
+        .with_native("funder", coins(1000, "juno"))
+        .build();
+
+    let milestones = vec![native_milestone("demo", "only", coins(1000, "juno"))];
+    suite
+        .create_escrow(
+            "funder",
+            create_msg("demo", "arbiter", None, milestones),
+            &coins(1000, "juno"),
+        )
+        .unwrap();
+
+    suite.approve("arbiter", "demo", "1").unwrap_err();
+    suite
+        .set_recipient("funder", "demo", "recipient")
+        .unwrap_err();
+    suite.set_recipient("arbiter", "demo", "recipient").unwrap();
+    assert_eq!(
+        suite.escrow_details("demo").unwrap().recipient,
+        Some("recipient".to_string())
+    );
+
+    suite.accept("recipient", "demo").unwrap();
+    suite.submit("recipient", "demo", "1").unwrap();
+    suite.approve("arbiter", "demo", "1").unwrap();
+    suite.claim("recipient").unwrap();
+    assert_eq!(suite.native_balance("recipient", "juno"), 1000);
+}
+
+#[test]
+// a cw20 milestone cancelled by both sides goes back to the funder, the rest stays live
+fn test_suite_cancel_cw20_milestone() {
+    let mut suite = SuiteBuilder::new()
+        .with_cw20("CASH", &[("funder", 1000)])
+        .build();
+    let cash = suite.cw20(0);
+
+    let milestones = vec![
+        cw20_milestone("demo", "first", &cash, 600),
+        cw20_milestone("demo", "second", &cash, 400),
+    ];
+    suite
+        .create_escrow_cw20(
+            "funder",
+            &cash,
+            1000,
+            create_msg("demo", "arbiter", Some("recipient"), milestones),
+        )
+        .unwrap();
+    suite.accept("recipient", "demo").unwrap();
+
+    suite.cancel_milestone("recipient", "demo", "2").unwrap();
+    suite.cancel_milestone("anyone", "demo", "2").unwrap_err();
+    suite.cancel_milestone("funder", "demo", "2").unwrap();
+    suite.claim("funder").unwrap();
+    assert_eq!(suite.cw20_balance(&cash, "funder"), 400);
+
+    suite.submit("recipient", "demo", "1").unwrap();
+    suite.approve("arbiter", "demo", "1").unwrap();
+    suite.claim("recipient").unwrap();
+    assert_eq!(suite.cw20_balance(&cash, "recipient"), 600);
+    assert_eq!(suite.cw20_balance(&cash, suite.escrow.as_str()), 0);
+}
+
+#[test]
+// the arbiter can refund whatever is left back to the funder
+fn test_suite_refund_native() {
+    let mut suite = SuiteBuilder::new()
+        .with_native("funder", coins(1000, "juno"))
+        .build();
+
+    let milestones = vec![
+        native_milestone("demo", "first", coins(300, "juno")),
+        native_milestone("demo", "second", coins(700, "juno")),
+    ];
+    suite
+        .create_escrow(
+            "funder",
+            create_msg("demo", "arbiter", Some("recipient"), milestones),
+            &coins(1000, "juno"),
+        )
+        .unwrap();
+    suite.accept("recipient", "demo").unwrap();
+    suite.submit("recipient", "demo", "1").unwrap();
+    suite.approve("arbiter", "demo", "1").unwrap();
+
+    // nobody else can refund before the escrow expires
+    suite.refund("funder", "demo").unwrap_err();
+
+    suite.refund("arbiter", "demo").unwrap();
+    suite.claim("recipient").unwrap();
+    assert_eq!(suite.native_balance("recipient", "juno"), 300);
+    assert_eq!(suite.native_balance("funder", "juno"), 700);
+    suite.escrow_details("demo").unwrap_err();
+}
+
+#[test]
+// anyone can refund once the latest milestone deadline has passed
+fn test_suite_refund_after_extended_expiry() {
+    let mut suite = SuiteBuilder::new()
+        .with_native("funder", coins(1000, "juno"))
+        .with_native("arbiter", coins(500, "juno"))
+        .build();
+    let end_height = suite.block_height() + 10;
+
+    let mut first = native_milestone("demo", "first", coins(1000, "juno"));
+    first.expires = Some(Expiration::AtHeight(end_height));
+    suite
+        .create_escrow(
+            "funder",
+            create_msg("demo", "arbiter", Some("recipient"), vec![first]),
+            &coins(1000, "juno"),
+        )
+        .unwrap();
+    suite
+        .create_milestone(
+            "arbiter",
+            native_milestone("demo", "second", coins(500, "juno")),
+            &coins(500, "juno"),
+        )
+        .unwrap();
+
+    // push the deadline back, so the escrow is still live after the original end height
+    suite
+        .extend(
+            "arbiter",
+            "demo",
+            "1",
+            Expiration::AtHeight(end_height + 10),
+        )
+        .unwrap();
+    suite.next_blocks(15);
+    suite.refund("anyone", "demo").unwrap_err();
+
+    suite.next_blocks(10);
+    suite.refund("anyone", "demo").unwrap();
+    assert_eq!(suite.native_balance("funder", "juno"), 1500);
+    assert_eq!(suite.native_balance(suite.escrow.as_str(), "juno"), 0);
+}
+
+#[test]
+// anyone can refund a single milestone once its own deadline passed, the rest carries on
+fn test_suite_refund_expired_milestone() {
+    let mut suite = SuiteBuilder::new()
+        .with_native("funder", coins(1000, "juno"))
+        .build();
+    let height = suite.block_height();
+
+    let mut first = native_milestone("demo", "first", coins(300, "juno"));
+    first.expires = Some(Expiration::AtHeight(height + 10));
+    let mut second = native_milestone("demo", "second", coins(700, "juno"));
+    second.expires = Some(Expiration::AtHeight(height + 100));
+    suite
+        .create_escrow(
+            "funder",
+            create_msg("demo", "arbiter", Some("recipient"), vec![first, second]),
+            &coins(1000, "juno"),
+        )
+        .unwrap();
+    suite.accept("recipient", "demo").unwrap();
+    suite.refund_milestone("anyone", "demo", "1").unwrap_err();
+
+    suite.next_blocks(15);
+    suite.refund_milestone("anyone", "demo", "2").unwrap_err();
+    suite.refund_milestone("anyone", "demo", "1").unwrap();
+    assert_eq!(suite.native_balance("funder", "juno"), 300);
+    let details = suite.escrow_details("demo").unwrap();
+    assert_eq!(details.milestones[0].status, MilestoneStatus::Cancelled);
+
+    suite.submit("recipient", "demo", "2").unwrap();
+    suite.approve("arbiter", "demo", "2").unwrap();
+    suite.claim("recipient").unwrap();
+    assert_eq!(suite.native_balance("recipient", "juno"), 700);
+    suite.escrow_details("demo").unwrap_err();
+}
+
+#[test]
+// another account drives the escrow purely through the helper-built messages
+fn test_helpers_build_escrow_messages() {
+    let mut suite = SuiteBuilder::new()
+        .with_cw20("CASH", &[("funder", 1000)])
+        .build();
+    let cash = suite.cw20(0);
+    let escrow = EscrowContract(suite.escrow.clone());
+
+    let msg = escrow
+        .create_with_cw20(
+            &cash,
+            Uint128::new(1000),
+            create_msg(
+                "demo",
+                "arbiter",
+                None,
+                vec![
+                    cw20_milestone("demo", "first", &cash, 250),
+                    cw20_milestone("demo", "second", &cash, 750),
+                ],
+            ),
+        )
+        .unwrap();
+    suite.app.execute(Addr::unchecked("funder"), msg).unwrap();
+
+    let arbiter = Addr::unchecked("arbiter");
+    let msg = escrow.set_recipient("demo", "recipient").unwrap();
+    suite.app.execute(arbiter.clone(), msg).unwrap();
+    let msg = escrow.accept_escrow("demo").unwrap();
+    suite
+        .app
+        .execute(Addr::unchecked("recipient"), msg)
+        .unwrap();
+    let msg = escrow.submit_milestone("demo", "1").unwrap();
+    suite
+        .app
+        .execute(Addr::unchecked("recipient"), msg)
+        .unwrap();
+    let msg = escrow.approve_milestone("demo", "1").unwrap();
+    suite.app.execute(arbiter, msg).unwrap();
+
+    let querier = suite.app.wrap();
+    let claims = escrow.claims(&querier, "recipient").unwrap();
+    assert_eq!(claims.cw20_balance[0].amount, Uint128::new(250));
+    let msg = escrow.claim().unwrap();
+    suite
+        .app
+        .execute(Addr::unchecked("recipient"), msg)
+        .unwrap();
+    assert_eq!(suite.cw20_balance(&cash, "recipient"), 250);
+
+    let querier = suite.app.wrap();
+    let escrows = escrow.list(&querier, None).unwrap().escrows;
+    assert_eq!(
+        escrows.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(),
+        vec!["demo"]
+    );
+    let details = escrow.escrow_details(&querier, "demo").unwrap();
+    assert_eq!(Some("recipient".to_string()), details.recipient);
+    let milestone = escrow.milestone_details(&querier, "demo", "1").unwrap();
+    assert_eq!(milestone.status, MilestoneStatus::Completed);
+    assert_eq!(
+        escrow
+            .list_milestones(&querier, "demo", None, None)
+            .unwrap()
+            .milestones
+            .len(),
+        2
+    );
+}
+
+#[cfg(feature = "interface")]
+#[test]
+// drive the escrow through its cw-orch interface on a mock chain
+fn test_interface_mock_lifecycle() {
+    use cw_orch::prelude::*;
+
+    use crate::interface::EscrowMilestones;
+    use crate::msg::{ExecuteMsgFns, QueryMsgFns};
+
+    let arbiter = Addr::unchecked("arbiter");
+    let mock = Mock::new(&arbiter);
+    mock.set_balance(&arbiter, coins(1000, "juno")).unwrap();
+
+    let escrow = EscrowMilestones::new("escrow", mock.clone());
+    escrow.upload().unwrap();
+    escrow
+        .instantiate(&InstantiateMsg::default(), None, None)
+        .unwrap();
+
+    let milestones = vec![native_milestone("demo", "only", coins(1000, "juno"))];
+    escrow
+        .execute(
+            &ExecuteMsg::Create(create_msg("demo", "arbiter", None, milestones)),
+            Some(&coins(1000, "juno")),
+        )
+        .unwrap();
+    escrow
+        .set_recipient("demo".to_string(), "recipient".to_string())
+        .unwrap();
+    let details = escrow.escrow_details("demo".to_string()).unwrap();
+    assert_eq!(Some("recipient".to_string()), details.recipient);
+
+    escrow
+        .call_as(&Addr::unchecked("recipient"))
+        .accept_escrow("demo".to_string())
+        .unwrap();
+    escrow
+        .call_as(&Addr::unchecked("recipient"))
+        .submit_milestone("demo".to_string(), "1".to_string())
+        .unwrap();
+    escrow
+        .approve_milestone("demo".to_string(), "1".to_string())
+        .unwrap();
+    escrow
+        .call_as(&Addr::unchecked("recipient"))
+        .claim()
+        .unwrap();
+    assert_eq!(
+        mock.query_balance(&Addr::unchecked("recipient"), "juno")
+            .unwrap(),
+        Uint128::new(1000)
+    );
+    assert!(escrow.list(None).unwrap().escrows.is_empty());
+}
+
+fn contract_cw3_fixed_multisig() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw3_fixed_multisig::contract::execute,
+        cw3_fixed_multisig::contract::instantiate,
+        cw3_fixed_multisig::contract::query,
+    ))
+}
+
+/// A cw3 multisig where two of signer1, signer2 and signer3 must agree
+fn instantiate_multisig(suite: &mut Suite) -> Addr {
+    let multisig_id = suite.app.store_code(contract_cw3_fixed_multisig());
+    let voters = ["signer1", "signer2", "signer3"]
+        .iter()
+        .map(|addr| cw3_fixed_multisig::msg::Voter {
+            addr: addr.to_string(),
+            weight: 1,
+        })
+        .collect();
+    suite
+        .app
+        .instantiate_contract(
+            multisig_id,
+            Addr::unchecked("owner"),
+            &cw3_fixed_multisig::msg::InstantiateMsg {
+                voters,
+                threshold: Threshold::AbsoluteCount { weight: 2 },
+                max_voting_period: Duration::Height(100),
+            },
+            &[],
+            "multisig",
+            None,
+        )
+        .unwrap()
+}
+
+/// `proposer` proposes `msg` to the multisig, which `voter` then votes for
+fn pass_proposal(suite: &mut Suite, multisig: &Addr, msg: CosmosMsg, proposer: &str, voter: &str) {
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(proposer),
+            multisig.clone(),
+            &cw3_fixed_multisig::msg::ExecuteMsg::Propose {
+                title: "Escrow".to_string(),
+                description: "Act on the escrow".to_string(),
+                msgs: vec![msg],
+                latest: None,
+            },
+            &[],
+        )
+        .unwrap();
+    let proposal_id = suite
+        .app
+        .wrap()
+        .query_wasm_smart::<cw3::ProposalListResponse>(
+            multisig,
+            &cw3::Cw3QueryMsg::ReverseProposals {
+                start_before: None,
+                limit: Some(1),
+            },
+        )
+        .unwrap()
+        .proposals[0]
+        .id;
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(voter),
+            multisig.clone(),
+            &cw3_fixed_multisig::msg::ExecuteMsg::Vote {
+                proposal_id,
+                vote: Vote::Yes,
+            },
+            &[],
+        )
+        .unwrap();
+}
+
+#[test]
+// a cw3 multisig as arbiter: milestones are only approved through executed proposals
+fn test_cw3_multisig_arbiter() {
+    const NATIVE_TOKEN_DENOM: &str = "juno";
+    let mut suite = SuiteBuilder::new()
+        .with_native("owner", coins(1000, NATIVE_TOKEN_DENOM))
+        .build();
+    let multisig = instantiate_multisig(&mut suite);
+
+    // check the arbiter speaks cw3 before handing it the escrow
+    let threshold = cw3_threshold(&suite.app.wrap(), &multisig).unwrap();
+    assert_eq!(
+        threshold,
+        ThresholdResponse::AbsoluteCount {
+            weight: 2,
+            total_weight: 3
+        }
+    );
+    let escrow = suite.escrow.clone();
+    cw3_threshold(&suite.app.wrap(), &escrow).unwrap_err();
+
+    let milestones = vec![
+        native_milestone("demo", "design", coins(400, NATIVE_TOKEN_DENOM)),
+        native_milestone("demo", "launch", coins(600, NATIVE_TOKEN_DENOM)),
+    ];
+    suite
+        .create_escrow(
+            "owner",
+            create_msg("demo", multisig.as_str(), Some("recipient"), milestones),
+            &coins(1000, NATIVE_TOKEN_DENOM),
+        )
+        .unwrap();
+
+    suite.accept("recipient", "demo").unwrap();
+    suite.submit("recipient", "demo", "1").unwrap();
+
+    // no single signer can approve directly
+    let err = suite.approve("signer1", "demo", "1").unwrap_err();
+    assert_eq!(
+        crate::ContractError::Unauthorized {},
+        err.downcast().unwrap()
+    );
+
+    // a proposal carrying the approval executes once it passes
+    let approve = EscrowContract(escrow)
+        .approve_milestone("demo", "1")
+        .unwrap();
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked("signer1"),
+            multisig.clone(),
+            &cw3_fixed_multisig::msg::ExecuteMsg::Propose {
+                title: "Release design".to_string(),
+                description: "Design milestone delivered".to_string(),
+                msgs: vec![approve],
+                latest: None,
+            },
+            &[],
+        )
+        .unwrap();
+    let execute = cw3_fixed_multisig::msg::ExecuteMsg::Execute { proposal_id: 1 };
+    suite
+        .app
+        .execute_contract(Addr::unchecked("signer1"), multisig.clone(), &execute, &[])
+        .unwrap_err();
+    assert_eq!(suite.native_balance("recipient", NATIVE_TOKEN_DENOM), 0);
+
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked("signer2"),
+            multisig.clone(),
+            &cw3_fixed_multisig::msg::ExecuteMsg::Vote {
+                proposal_id: 1,
+                vote: Vote::Yes,
+            },
+            &[],
+        )
+        .unwrap();
+    suite
+        .app
+        .execute_contract(Addr::unchecked("anyone"), multisig, &execute, &[])
+        .unwrap();
+    suite.claim("recipient").unwrap();
+    assert_eq!(suite.native_balance("recipient", NATIVE_TOKEN_DENOM), 400);
+}
+
+#[test]
+// with a cw3 arbiter, anyone can hand the escrow a passed proposal instead of executing it
+fn test_cw3_arbiter_by_proposal() {
+    let mut suite = SuiteBuilder::new()
+        .with_native("owner", coins(2000, "juno"))
+        .build();
+    let multisig = instantiate_multisig(&mut suite);
+    let escrow = EscrowContract(suite.escrow.clone());
+
+    let cw3_create = |id: &str, arbiter: &str| {
+        let milestones = vec![
+            native_milestone(id, "design", coins(400, "juno")),
+            native_milestone(id, "launch", coins(600, "juno")),
+        ];
+        CreateMsg {
+            arbiter_kind: Some(ArbiterKind::Cw3),
+            ..create_msg(id, arbiter, Some("recipient"), milestones)
+        }
+    };
+    let err = suite
+        .create_escrow("owner", cw3_create("demo", "arbiter"), &coins(1000, "juno"))
+        .unwrap_err();
+    assert_eq!(
+        crate::ContractError::NotCw3Arbiter {},
+        err.downcast().unwrap()
+    );
+    suite
+        .create_escrow(
+            "owner",
+            cw3_create("demo", multisig.as_str()),
+            &coins(1000, "juno"),
+        )
+        .unwrap();
+    suite.accept("recipient", "demo").unwrap();
+    suite.submit("recipient", "demo", "1").unwrap();
+
+    let approve_by_proposal = |suite: &mut Suite, milestone_id: &str, proposal_id: u64| {
+        let msg = escrow
+            .approve_by_proposal("demo", milestone_id, proposal_id)
+            .unwrap();
+        suite
+            .app
+            .execute(Addr::unchecked("recipient"), msg)
+            .map_err(|err| err.downcast::<crate::ContractError>().unwrap())
+    };
+
+    // an open proposal doesn't count yet
+    let approve = escrow.approve_milestone("demo", "1").unwrap();
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked("signer1"),
+            multisig.clone(),
+            &cw3_fixed_multisig::msg::ExecuteMsg::Propose {
+                title: "Release design".to_string(),
+                description: "Design milestone delivered".to_string(),
+                msgs: vec![approve],
+                latest: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        approve_by_proposal(&mut suite, "1", 1).unwrap_err(),
+        crate::ContractError::ProposalNotPassed { proposal_id: 1 }
+    );
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked("signer2"),
+            multisig.clone(),
+            &cw3_fixed_multisig::msg::ExecuteMsg::Vote {
+                proposal_id: 1,
+                vote: Vote::Yes,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // once passed, it approves the milestone it carries and nothing else, once
+    assert_eq!(
+        approve_by_proposal(&mut suite, "2", 1).unwrap_err(),
+        crate::ContractError::ProposalMismatch { proposal_id: 1 }
+    );
+    approve_by_proposal(&mut suite, "1", 1).unwrap();
+    assert_eq!(
+        approve_by_proposal(&mut suite, "1", 1).unwrap_err(),
+        crate::ContractError::ProposalUsed { proposal_id: 1 }
+    );
+    suite.claim("recipient").unwrap();
+    assert_eq!(suite.native_balance("recipient", "juno"), 400);
+
+    // refunds work the same way
+    let refund = escrow.refund("demo").unwrap();
+    pass_proposal(&mut suite, &multisig, refund, "signer3", "signer1");
+    let msg = escrow.refund_by_proposal("demo", 2).unwrap();
+    suite.app.execute(Addr::unchecked("anyone"), msg).unwrap();
+    assert_eq!(suite.native_balance("owner", "juno"), 1600);
+    suite.escrow_details("demo").unwrap_err();
+}
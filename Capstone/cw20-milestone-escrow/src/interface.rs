@@ -0,0 +1,27 @@
+use cw_orch::interface;
+use cw_orch::prelude::*;
+
+use crate::contract::{execute, instantiate, migrate, query, reply};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+
+/// cw-orchestrator interface for the escrow, usable against `Mock` in tests or a `Daemon` on a
+/// live chain
+#[interface(InstantiateMsg, ExecuteMsg, QueryMsg, MigrateMsg)]
+pub struct EscrowMilestones;
+
+impl<Chain: CwEnv> Uploadable for EscrowMilestones<Chain> {
+    /// Optimized wasm from `artifacts/`, as produced by rust-optimizer
+    fn wasm(&self) -> WasmPath {
+        ArtifactsDir::new(concat!(env!("CARGO_MANIFEST_DIR"), "/artifacts"))
+            .find_wasm_path("cw20_escrow_milestones")
+            .unwrap()
+    }
+
+    fn wrapper(&self) -> Box<dyn MockContract<Empty>> {
+        Box::new(
+            ContractWrapper::new_with_empty(execute, instantiate, query)
+                .with_migrate(migrate)
+                .with_reply(reply),
+        )
+    }
+}
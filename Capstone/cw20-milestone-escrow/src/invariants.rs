@@ -0,0 +1,444 @@
+//! Consistency checks over stored escrows, for use from tests after every state change.
+
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{Order, Storage, Uint128};
+
+use crate::state::{escrows, Escrow, GenericBalance, MilestoneStatus};
+
+/// Amounts keyed by native denom or cw20 address, with zero entries dropped,
+/// so balances can be compared regardless of coin order
+fn totals<'a>(balances: impl IntoIterator<Item = &'a GenericBalance>) -> BTreeMap<String, Uint128> {
+    let mut totals: BTreeMap<String, Uint128> = BTreeMap::new();
+    for balance in balances {
+        let native = balance
+            .native
+            .iter()
+            .map(|c| (format!("native:{}", c.denom), c.amount));
+        let cw20 = balance
+            .cw20
+            .iter()
+            .map(|c| (format!("cw20:{}", c.address), c.amount));
+        for (key, amount) in native.chain(cw20) {
+            *totals.entry(key).or_default() += amount;
+        }
+    }
+    totals.retain(|_, amount| !amount.is_zero());
+    totals
+}
+
+/// Checks a single escrow, returning a description of the first violation found
+pub fn check_escrow(id: &str, escrow: &Escrow) -> Result<(), String> {
+    let incomplete = totals(
+        escrow
+            .milestones
+            .iter()
+            .filter(|m| m.is_open())
+            .map(|m| &m.amount),
+    );
+    // escrows can be created short and topped up, but never hold more than is left to pay,
+    // so a refund can't pay out anything already paid
+    let stored = totals([&escrow.balance]);
+    // `map_or` rather than `is_none_or`, which the optimizer's toolchain predates
+    #[allow(clippy::unnecessary_map_or)]
+    let excess = stored
+        .iter()
+        .any(|(key, amount)| incomplete.get(key).map_or(true, |needed| amount > needed));
+    if excess {
+        return Err(format!(
+            "escrow {}: stored balance {:?} exceeds incomplete milestones {:?}",
+            id, stored, incomplete
+        ));
+    }
+
+    // only completed milestones pay out, all of their amount once approved, a share of it when
+    // a dispute was settled
+    for m in &escrow.milestones {
+        let paid = if m.status == MilestoneStatus::Completed {
+            !m.paid_out.is_empty() && m.amount.covers(&m.paid_out)
+        } else {
+            m.paid_out.is_empty()
+        };
+        if !paid {
+            return Err(format!(
+                "escrow {}: {:?} milestone {} paid out {:?} of {:?}",
+                id, m.status, m.id, m.paid_out, m.amount
+            ));
+        }
+    }
+
+    // open milestones are still short of the threshold, and only arbiters sign, once each and
+    // only after the recipient submitted
+    for m in &escrow.milestones {
+        let signers_valid = m
+            .approvals
+            .iter()
+            .enumerate()
+            .all(|(i, signer)| escrow.is_arbiter(signer) && !m.approvals[..i].contains(signer));
+        let unsubmitted = m.status == MilestoneStatus::Pending && !m.approvals.is_empty();
+        if !signers_valid
+            || unsubmitted
+            || (m.is_open() && m.approvals.len() >= escrow.threshold as usize)
+        {
+            return Err(format!(
+                "escrow {}: milestone {} approved by {:?} with threshold {}",
+                id, m.id, m.approvals, escrow.threshold
+            ));
+        }
+    }
+
+    // ids are handed out in order and never reused, removed milestones leaving gaps
+    let ids: Vec<u64> = escrow
+        .milestones
+        .iter()
+        .map(|m| m.id.parse().unwrap_or(0))
+        .collect();
+    if ids.windows(2).any(|pair| pair[0] >= pair[1])
+        || ids.iter().any(|&n| n == 0 || n > escrow.milestone_count)
+    {
+        return Err(format!(
+            "escrow {}: milestone ids {:?} out of order or past {}",
+            id, ids, escrow.milestone_count
+        ));
+    }
+
+    if escrow.milestones.iter().any(|m| m.amount.is_empty()) {
+        return Err(format!("escrow {}: milestone without an amount", id));
+    }
+
+    Ok(())
+}
+
+/// Panics if any stored escrow violates an invariant
+pub fn assert_invariants(storage: &dyn Storage) {
+    for item in escrows().range(storage, None, None, Order::Ascending) {
+        let (id, escrow) = item.expect("escrow must deserialize");
+        if let Err(violation) = check_escrow(&id, &escrow) {
+            panic!("invariant violated: {}", violation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{
+        coins, from_binary, to_binary, CosmosMsg, Decimal, Env, Reply, SubMsgResult, Uint128,
+        WasmMsg,
+    };
+    use cw20::Cw20ReceiveMsg;
+    use cw_utils::Expiration;
+
+    use crate::contract::{execute, instantiate, reply};
+    use crate::msg::{CreateMilestoneMsg, CreateMsg, ExecuteMsg, InstantiateMsg, ReceiveMsg};
+
+    const ARBITER: &str = "arbiter";
+    const CO_ARBITER: &str = "arbiter2";
+    const TOKEN: &str = "token";
+
+    /// Small xorshift generator, so runs are reproducible from their seed
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    fn random_milestones(
+        rng: &mut Rng,
+        id: &str,
+        cw20: bool,
+        env: &Env,
+    ) -> Vec<CreateMilestoneMsg> {
+        (0..1 + rng.below(4))
+            .map(|i| {
+                let amount = 1 + rng.below(1000) as u128;
+                let amount = if cw20 {
+                    GenericBalance {
+                        native: vec![],
+                        cw20: vec![cw20::Cw20CoinVerified {
+                            address: cosmwasm_std::Addr::unchecked(TOKEN),
+                            amount: Uint128::new(amount),
+                        }],
+                    }
+                } else {
+                    GenericBalance {
+                        native: coins(amount, "juno"),
+                        cw20: vec![],
+                    }
+                };
+                CreateMilestoneMsg {
+                    escrow_id: id.to_string(),
+                    title: format!("milestone_{}", i),
+                    description: "generated".to_string(),
+                    amount,
+                    expires: (rng.below(2) == 0)
+                        .then(|| Expiration::AtHeight(env.block.height + rng.below(50))),
+                    stream: None,
+                }
+            })
+            .collect()
+    }
+
+    fn run_sequence(seed: u64, steps: usize) {
+        let mut rng = Rng(seed);
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            // with a fee, so payouts are split with the treasury
+            InstantiateMsg {
+                owner: None,
+                fee_bps: Some(250),
+                treasury: Some("treasury".to_string()),
+                unbonding_period: None,
+            },
+        )
+        .unwrap();
+
+        for _ in 0..steps {
+            let id = format!("escrow_{}", rng.below(3));
+            let milestone_id = (1 + rng.below(5)).to_string();
+            let (sender, funds, msg) = match rng.below(16) {
+                0 => {
+                    let milestones = random_milestones(&mut rng, &id, false, &env);
+                    let total: u128 = milestones
+                        .iter()
+                        .map(|m| m.amount.native[0].amount.u128())
+                        .sum();
+                    // sometimes short, leaving the rest to top ups
+                    let total = total - rng.below(2) as u128 * rng.below(total as u64) as u128;
+                    // sometimes two arbiters, needing one or both to approve
+                    let (co_arbiters, threshold) = if rng.below(2) == 0 {
+                        (Some(vec![CO_ARBITER.to_string()]), 1 + rng.below(2) as u32)
+                    } else {
+                        (None, 1)
+                    };
+                    let msg = ExecuteMsg::Create(CreateMsg {
+                        id,
+                        arbiter: ARBITER.to_string(),
+                        co_arbiters,
+                        threshold: Some(threshold),
+                        arbiter_kind: None,
+                        recipient: Some("recipient".to_string()),
+                        title: "generated".to_string(),
+                        description: "generated".to_string(),
+                        cw20_whitelist: None,
+                        strict_whitelist: None,
+                        crowdfunded: None,
+                        stake: None,
+                        milestones,
+                    });
+                    ("source", coins(total, "juno"), msg)
+                }
+                1 => {
+                    let milestones = random_milestones(&mut rng, &id, true, &env);
+                    let total: u128 = milestones
+                        .iter()
+                        .map(|m| m.amount.cw20[0].amount.u128())
+                        .sum();
+                    let create = ReceiveMsg::Create(CreateMsg {
+                        id,
+                        arbiter: ARBITER.to_string(),
+                        co_arbiters: None,
+                        threshold: None,
+                        arbiter_kind: None,
+                        recipient: Some("recipient".to_string()),
+                        title: "generated".to_string(),
+                        description: "generated".to_string(),
+                        cw20_whitelist: None,
+                        strict_whitelist: None,
+                        crowdfunded: None,
+                        stake: None,
+                        milestones,
+                    });
+                    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                        sender: "source".to_string(),
+                        amount: Uint128::new(total),
+                        msg: to_binary(&create).unwrap(),
+                    });
+                    (TOKEN, vec![], msg)
+                }
+                2 | 3 => {
+                    let sender = if rng.below(2) == 0 {
+                        ARBITER
+                    } else {
+                        CO_ARBITER
+                    };
+                    let msg = ExecuteMsg::ApproveMilestone { id, milestone_id };
+                    (sender, vec![], msg)
+                }
+                4 => (
+                    ARBITER,
+                    vec![],
+                    ExecuteMsg::ExtendMilestone {
+                        id,
+                        milestone_id,
+                        expires: Expiration::AtHeight(env.block.height + rng.below(50)),
+                    },
+                ),
+                5 => (
+                    "source",
+                    coins(1 + rng.below(500) as u128, "juno"),
+                    ExecuteMsg::TopUp { id },
+                ),
+                6 => ("recipient", vec![], ExecuteMsg::AcceptEscrow { id }),
+                7 => ("recipient", vec![], ExecuteMsg::Claim {}),
+                8 => {
+                    let sender = if rng.below(2) == 0 {
+                        "source"
+                    } else {
+                        "recipient"
+                    };
+                    let reason = "disputed".to_string();
+                    let msg = ExecuteMsg::RaiseDispute {
+                        id,
+                        milestone_id,
+                        reason,
+                    };
+                    (sender, vec![], msg)
+                }
+                9 => (
+                    ARBITER,
+                    vec![],
+                    ExecuteMsg::ResolveDispute {
+                        id,
+                        milestone_id,
+                        recipient_share: Decimal::percent(rng.below(101)),
+                    },
+                ),
+                10 => (
+                    "recipient",
+                    vec![],
+                    ExecuteMsg::SubmitMilestone { id, milestone_id },
+                ),
+                11 => {
+                    let sender = ["source", "recipient", ARBITER][rng.below(3) as usize];
+                    (
+                        sender,
+                        vec![],
+                        ExecuteMsg::CancelMilestone { id, milestone_id },
+                    )
+                }
+                12 => {
+                    let amount = GenericBalance {
+                        native: coins(1 + rng.below(500) as u128, "juno"),
+                        cw20: vec![],
+                    };
+                    let msg = ExecuteMsg::UpdateMilestone {
+                        id,
+                        milestone_id,
+                        title: None,
+                        description: None,
+                        amount: Some(amount),
+                    };
+                    (ARBITER, vec![], msg)
+                }
+                13 => (
+                    ARBITER,
+                    vec![],
+                    ExecuteMsg::RemoveMilestone { id, milestone_id },
+                ),
+                14 => (
+                    "anyone",
+                    vec![],
+                    ExecuteMsg::RefundMilestone { id, milestone_id },
+                ),
+                _ => {
+                    let sender = if rng.below(2) == 0 { ARBITER } else { "anyone" };
+                    (sender, vec![], ExecuteMsg::Refund { id })
+                }
+            };
+
+            // failed calls must leave state consistent too
+            let res = execute(deps.as_mut(), env.clone(), mock_info(sender, &funds), msg);
+            // a cw20 create goes on in a call the contract makes on itself
+            for sub in res.map(|res| res.messages).unwrap_or_default() {
+                if let CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr, msg, ..
+                }) = sub.msg
+                {
+                    if contract_addr != env.contract.address {
+                        continue;
+                    }
+                    let info = mock_info(&contract_addr, &[]);
+                    let msg = from_binary(&msg).unwrap();
+                    if let Err(err) = execute(deps.as_mut(), env.clone(), info, msg) {
+                        let result = SubMsgResult::Err(err.to_string());
+                        let msg = Reply { id: sub.id, result };
+                        reply(deps.as_mut(), env.clone(), msg).unwrap();
+                    }
+                }
+            }
+            assert_invariants(&deps.storage);
+
+            env.block.height += rng.below(10);
+        }
+    }
+
+    #[test]
+    fn random_sequences_preserve_invariants() {
+        for seed in 1..=200 {
+            run_sequence(seed, 40);
+        }
+    }
+
+    #[test]
+    fn detects_stale_balance() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let milestones = vec![
+            CreateMilestoneMsg {
+                escrow_id: "demo".to_string(),
+                title: "first".to_string(),
+                description: "first".to_string(),
+                amount: GenericBalance {
+                    native: coins(100, "juno"),
+                    cw20: vec![],
+                },
+                expires: None,
+                stream: None,
+            };
+            2
+        ];
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "demo".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: None,
+            title: "demo".to_string(),
+            description: "demo".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones,
+        });
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("source", &coins(200, "juno")),
+            msg,
+        )
+        .unwrap();
+
+        let mut escrow = escrows().load(&deps.storage, "demo").unwrap();
+        assert_eq!(check_escrow("demo", &escrow), Ok(()));
+
+        escrow.milestones[0].status = MilestoneStatus::Completed;
+        assert!(check_escrow("demo", &escrow).is_err());
+    }
+}
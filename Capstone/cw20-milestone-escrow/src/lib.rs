@@ -1,8 +1,14 @@
 pub mod contract;
 mod error;
+pub mod helpers;
 mod integration_test;
+#[cfg(feature = "interface")]
+pub mod interface;
+#[cfg(any(test, feature = "testing"))]
+pub mod invariants;
 pub mod msg;
 pub mod state;
+mod suite;
 pub mod tests;
 
 pub use crate::error::ContractError;
@@ -1,60 +1,199 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
-use cosmwasm_std::{Addr, Api, Coin, StdResult};
+use cosmwasm_std::{Addr, Api, Coin, Decimal, StdResult};
 
-use cw20::{Balance, Cw20Coin, Cw20ReceiveMsg};
+use cw20::{Cw20Coin, Cw20ReceiveMsg};
+
+pub use cosmwatch_access::{OwnershipResponse, PausedResponse};
+
+use cw_utils::Expiration;
 
 use crate::state::{
-    get_end_height, get_end_time, get_total_balance_from, GenericBalance, HasAmount, HasEnd,
-    Milestone,
+    get_total_balance_from, ArbiterKind, BlockStamp, Config, Dispute, GenericBalance, HasAmount,
+    HistoryEntry, Milestone, Stake, Stats, Stream, Vesting,
 };
 
 #[cw_serde]
-pub struct InstantiateMsg {}
+#[derive(Default)]
+pub struct InstantiateMsg {
+    /// Operates the contract, the instantiator by default
+    pub owner: Option<String>,
+    /// Part of every milestone payout sent to `treasury`, in basis points. None by default
+    pub fee_bps: Option<u16>,
+    /// Collects the fees, required for a fee
+    pub treasury: Option<String>,
+    /// How long the chain takes to unbond staked tokens, in seconds. 21 days by default
+    pub unbonding_period: Option<u64>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
+
+#[cw_serde]
+pub enum SudoMsg {
+    /// Refunds up to `limit` expired escrows to their sources, like ExecuteMsg::ProcessExpired
+    ProcessExpired { limit: Option<u32> },
+}
 
 #[cw_serde]
+#[cfg_attr(feature = "interface", derive(cw_orch::ExecuteFns))]
 pub enum ExecuteMsg {
     /// Creates a new escrow with the given details
     Create(CreateMsg),
     /// Creates a new milestone for a given escrow
     CreateMilestone(CreateMilestoneMsg),
-    /// Set the recipient of the given escrow
+    /// Changes a milestone the recipient hasn't submitted yet, leaving out what stays the same.
+    /// What the escrow holds beyond the new amounts is credited back to the source to collect
+    /// with Claim, a raised amount needs a top up. Only the arbiter can do this
+    UpdateMilestone {
+        id: String,
+        milestone_id: String,
+        title: Option<String>,
+        description: Option<String>,
+        amount: Option<GenericBalance>,
+    },
+    /// Deletes a milestone the recipient hasn't submitted yet, crediting what the escrow holds
+    /// for it back to the source to collect with Claim. The other milestones keep their ids and
+    /// removed ones are never reused. Only the arbiter can do this
+    RemoveMilestone { id: String, milestone_id: String },
+    /// Set the recipient of the given escrow. A new recipient has to accept it again
     SetRecipient { id: String, recipient: String },
-    /// Approve sends all tokens to the recipient for a given milestone.
-    /// Only the arbiter can do this
+    /// Whitelists the cw20 tokens in `add` and takes those in `remove` off the escrow's
+    /// whitelist. A token the escrow holds, or an open milestone still pays in, can't be
+    /// removed. Only the arbiter can do this
+    UpdateCw20Whitelist {
+        id: String,
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Agrees to the escrow's terms, after which its milestones can be approved.
+    /// Only the recipient can do this
+    AcceptEscrow { id: String },
+    /// Offers the arbiter role to `new_arbiter`, who takes it over with AcceptArbiter. Offering
+    /// it again replaces the pending offer. Only the arbiter can do this
+    TransferArbiter { id: String, new_arbiter: String },
+    /// Takes over the arbiter role offered with TransferArbiter. Only the pending arbiter can
+    /// do this
+    AcceptArbiter { id: String },
+    /// Submits a milestone as delivered, asking the arbiters to approve it. Only the recipient
+    /// can do this, once it accepted the escrow
+    SubmitMilestone { id: String, milestone_id: String },
+    /// Approve confirms a submitted milestone and credits all its tokens to the recipient, to
+    /// collect with Claim. Each arbiter signs once, and the milestone pays out when the escrow's
+    /// threshold of them has
     ApproveMilestone {
         /// id is a human-readable name for the escrow from create
         id: String,
         milestone_id: String,
     },
+    /// Approves several submitted milestones at once, like ApproveMilestone for each in turn.
+    /// Fails as a whole if any of them can't be approved
+    ApproveMilestones {
+        id: String,
+        milestone_ids: Vec<String>,
+    },
     // Extend the escrow by the given time
     ExtendMilestone {
         /// id is a human-readable name for the escrow from create
         id: String,
         // The milestone to extend
         milestone_id: String,
-        /// When end height set and block height exceeds this value, the escrow is expired.
-        /// Once an escrow is expired, it can be returned to the original funder (via "refund").
-        end_height: Option<u64>,
-        /// When end time (in seconds since epoch 00:00:00 UTC on 1 January 1970) is set and
-        /// block time exceeds this value, the escrow is expired.
-        /// Once an escrow is expired, it can be returned to the original funder (via "refund").
-        end_time: Option<u64>,
+        /// The milestone's new deadline, replacing the current one. Once every milestone of
+        /// the escrow is expired, it can be returned to the original funder (via "refund").
+        expires: Expiration,
     },
+    /// Freezes a pending milestone until the arbiter resolves the dispute, which also stops
+    /// the escrow from being refunded. Only the source or recipient can do this
+    RaiseDispute {
+        id: String,
+        milestone_id: String,
+        /// Why the milestone is disputed, at most 1024 bytes
+        reason: String,
+    },
+    /// Settles a disputed milestone, crediting `recipient_share` of what it is still owed to the
+    /// recipient and the rest back to the source, both to collect with Claim.
+    /// Only the arbiter can do this
+    ResolveDispute {
+        id: String,
+        milestone_id: String,
+        /// Between 0 and 1
+        recipient_share: Decimal,
+    },
+    /// Cancels a milestone that wasn't approved yet, crediting what the escrow holds for it back
+    /// to the source to collect with Claim. The arbiter cancels right away, the source and
+    /// recipient only once both have asked to
+    CancelMilestone { id: String, milestone_id: String },
+    /// Approves a milestone on behalf of a cw3 arbiter, by pointing at a passed proposal of the
+    /// arbiter's that carries this very approval. Anyone can do this
+    ApproveByProposal {
+        id: String,
+        milestone_id: String,
+        proposal_id: u64,
+    },
+    /// Refunds an escrow on behalf of a cw3 arbiter, by pointing at a passed proposal of the
+    /// arbiter's that carries this very refund. Anyone can do this
+    RefundByProposal { id: String, proposal_id: u64 },
     /// Refund returns all remaining tokens to the original sender,
     /// The arbiter can do this any time, or anyone can do this after a timeout
     Refund {
         /// id is a human-readable name for the escrow from create
         id: String,
     },
+    /// Sends what the escrow holds for a milestone back to the source once the milestone's own
+    /// deadline passed without approval, leaving the rest of the escrow running. Anyone can do
+    /// this
+    RefundMilestone { id: String, milestone_id: String },
+    /// Refunds up to `limit` expired escrows to their sources, skipping disputed ones, so a
+    /// scheduler like CronCat can clear them. Anyone can do this
+    ProcessExpired { limit: Option<u32> },
+    /// Adds the native funds sent to an escrow that doesn't hold all its milestones need yet.
+    /// Only the escrow's source can do this, or anyone while a crowdfunded escrow hasn't
+    /// expired
+    TopUp { id: String },
+    /// Undelegates a staked escrow's funds, which can't be paid out or refunded until the
+    /// unbonding period has passed, and credits the yield earned to the recipient and source
+    /// to collect with Claim. The arbiter, source or recipient can do this, anyone once the
+    /// escrow expired
+    Unstake { id: String },
+    /// Sends the sender everything approved milestones have credited to it
+    Claim {},
     /// This accepts a properly-encoded ReceiveMsg from a cw20 contract
     Receive(Cw20ReceiveMsg),
+    /// Creates an escrow with the cw20 tokens a Receive brought in. Only the contract calls
+    /// this on itself, so that when the create fails the tokens go back to their sender
+    CreateReceived(CreateMsg),
+    /// Changes the protocol fee, the treasury collecting it or the unbonding period, leaving
+    /// out what stays the same. Only the owner can do this
+    UpdateConfig {
+        fee_bps: Option<u16>,
+        treasury: Option<String>,
+        unbonding_period: Option<u64>,
+    },
+    /// Stops new escrows and milestones from being funded and milestones from paying out,
+    /// during an incident. Refunds stay available. Only the owner can do this
+    Pause {},
+    /// Accepts new escrows and releases milestones again. Only the owner can do this
+    Unpause {},
+    /// Offers ownership to `new_owner`, replacing any earlier offer. Only the owner can do this
+    TransferOwnership { new_owner: String },
+    /// Takes over ownership. Only the address ownership was offered to can do this
+    AcceptOwnership {},
 }
 
 #[cw_serde]
 pub enum ReceiveMsg {
     Create(CreateMsg),
     CreateMilestone(CreateMilestoneMsg),
+    /// Adds the tokens sent to the escrow, like ExecuteMsg::TopUp
+    TopUp {
+        id: String,
+    },
+    /// Adds the tokens sent to the escrow for milestone `milestone_id`, which has to pay in
+    /// them and still be owed at least as much. Otherwise like TopUp
+    TopUpMilestone {
+        escrow_id: String,
+        milestone_id: String,
+    },
 }
 
 #[cw_serde]
@@ -64,6 +203,15 @@ pub struct CreateMsg {
     pub id: String,
     // arbiter can decide to approve or refund the escrow
     pub arbiter: String,
+    /// Other arbiters who sign milestone approvals alongside `arbiter`. Refunds, extensions
+    /// and recipient changes stay with `arbiter`
+    pub co_arbiters: Option<Vec<String>>,
+    /// How many arbiters, `arbiter` included, must approve a milestone before it pays out.
+    /// All of them by default
+    pub threshold: Option<u32>,
+    /// Set to cw3 when `arbiter` is a cw3 contract whose passed proposals anyone may hand to
+    /// the escrow. A plain address by default
+    pub arbiter_kind: Option<ArbiterKind>,
     /// if approved, funds go to the recipient
     pub recipient: Option<String>,
     /// Title of the escrow
@@ -73,6 +221,15 @@ pub struct CreateMsg {
     /// When end height set and block height exceeds this value, the escrow is expired.
     /// Once an escrow is expired, it can be returned to the original funder (via "refund").
     pub cw20_whitelist: Option<Vec<String>>,
+    /// Reject cw20 tokens not in `cw20_whitelist` instead of adding them to it when sent.
+    /// Off by default
+    pub strict_whitelist: Option<bool>,
+    /// Let anyone fund the escrow with TopUp until it expires. Refunds are then split between
+    /// the funders in proportion to what each contributed, for them to claim. Off by default
+    pub crowdfunded: Option<bool>,
+    /// Delegate the escrow's funds in the staking denom until it is unstaked, to earn yield.
+    /// They can't be paid out or refunded before
+    pub stake: Option<StakeMsg>,
     /// List of milestones
     /// Each milestone has a title, description, amount, and whether it has been completed or not
     pub milestones: Vec<CreateMilestoneMsg>,
@@ -86,39 +243,29 @@ impl CreateMsg {
         }
     }
 
-    pub fn total_balance_from_milestones(&self) -> GenericBalance {
-        get_total_balance_from(self.milestones.clone()).unwrap()
-    }
-
-    pub fn is_total_balance_empty(&self) -> bool {
-        match self.total_balance_from_milestones() {
-            balance => balance.native.is_empty() && balance.cw20.is_empty(),
+    pub fn addr_co_arbiters(&self, api: &dyn Api) -> StdResult<Vec<Addr>> {
+        match self.co_arbiters.as_ref() {
+            Some(v) => v.iter().map(|h| api.addr_validate(h)).collect(),
+            None => Ok(vec![]),
         }
     }
 
-    // Check sent balance against total milestones balance
-    // Only checks first token for each type
-    pub fn is_deposit_equal_to_milestones_balance(&self, deposit: Balance) -> bool {
-        let total_balance_from_milestones = self.total_balance_from_milestones();
-        match deposit {
-            Balance::Native(balance) => {
-                let total_balance = total_balance_from_milestones.native[0].amount;
-                balance.0[0].amount == total_balance
-            }
-            Balance::Cw20(balance) => {
-                let total_balance = total_balance_from_milestones.cw20[0].amount;
-                balance.amount == total_balance
-            }
-        }
+    pub fn total_balance_from_milestones(&self) -> GenericBalance {
+        get_total_balance_from(self.milestones.clone()).unwrap()
     }
 
-    pub fn get_end_time(&self) -> Option<u64> {
-        get_end_time(self.clone().milestones)
+    pub fn is_total_balance_empty(&self) -> bool {
+        self.total_balance_from_milestones().is_empty()
     }
+}
 
-    pub fn get_end_height(&self) -> Option<u64> {
-        get_end_height(self.clone().milestones)
-    }
+#[cw_serde]
+pub struct StakeMsg {
+    /// Validator to delegate to
+    pub validator: String,
+    /// Part of the staking yield credited to the recipient, between 0 and 1. The rest goes to
+    /// the source
+    pub recipient_yield: Decimal,
 }
 
 #[cw_serde]
@@ -131,11 +278,12 @@ pub struct CreateMilestoneMsg {
     pub description: String,
     /// Amount of tokens to be released when the milestone is completed
     pub amount: GenericBalance,
-    /// When end height set and block height exceeds this value, the escrow is expired.
-    pub end_height: Option<u64>,
-    /// When end time (in seconds since epoch 00:00:00 UTC on 1 January 1970) is set and
-    /// block time exceeds this value, the escrow is expired.
-    pub end_time: Option<u64>,
+    /// Deadline to have it approved by, after which its amount can be refunded. Never by
+    /// default
+    pub expires: Option<Expiration>,
+    /// Once approved, vest the amount to the recipient linearly over this window, to claim as
+    /// it vests, instead of crediting it all at once
+    pub stream: Option<Stream>,
 }
 
 impl HasAmount for CreateMilestoneMsg {
@@ -144,14 +292,12 @@ impl HasAmount for CreateMilestoneMsg {
     }
 }
 
-impl HasEnd for CreateMilestoneMsg {
-    fn get_end_time(&self) -> Option<u64> {
-        self.end_time
-    }
-    fn get_end_height(&self) -> Option<u64> {
-        self.end_height
-    }
-}
+/// Highest protocol fee the owner can set, in basis points
+pub const MAX_FEE_BPS: u16 = 1_000;
+/// Longest escrow or milestone title accepted, in bytes
+pub const MAX_TITLE_LENGTH: usize = 128;
+/// Longest escrow or milestone description accepted, in bytes
+pub const MAX_DESCRIPTION_LENGTH: usize = 1024;
 
 pub fn is_valid_name(name: &str) -> bool {
     let bytes = name.as_bytes();
@@ -163,35 +309,227 @@ pub fn is_valid_name(name: &str) -> bool {
 
 #[cw_serde]
 #[derive(QueryResponses)]
+#[cfg_attr(feature = "interface", derive(cw_orch::QueryFns))]
 pub enum QueryMsg {
-    /// Show all open escrows. Return type is ListResponse.
+    /// Show a summary of all open escrows, or with `status` set only those in that status.
+    /// Return type is ListEscrowsResponse.
+    #[returns(ListEscrowsResponse)]
+    List { status: Option<EscrowStatus> },
+
+    /// Show a summary of the escrows `arbiter` decides on, as the arbiter or a co-arbiter,
+    /// ordered by id and starting after the escrow id `start_after`. Return type is
+    /// ListEscrowsResponse.
+    #[returns(ListEscrowsResponse)]
+    ListByArbiter {
+        arbiter: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Show a summary of the escrows paying out to `recipient`, paginated like ListByArbiter
     #[returns(ListEscrowsResponse)]
-    List {},
+    ListByRecipient {
+        recipient: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Show a summary of the escrows `source` funded, paginated like ListByArbiter
+    #[returns(ListEscrowsResponse)]
+    ListBySource {
+        source: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 
     /// Returns the details of the named escrow, error if not created
     /// Return type: DetailsResponse.
     #[returns(EscrowDetailsResponse)]
     EscrowDetails { id: String },
 
+    /// Returns what the milestones of escrow `id` require against what was deposited and paid
+    /// out, so funders can see what it still lacks
+    #[returns(FundingStatusResponse)]
+    FundingStatus { id: String },
+
+    /// Returns what approved milestones have credited to `address` and it hasn't claimed yet
+    #[returns(ClaimsResponse)]
+    Claims { address: String },
+
+    /// Returns the disputes raised over escrow `id`, resolved ones included, ordered by dispute
+    /// id and starting after `start_after`
+    #[returns(DisputesResponse)]
+    Disputes {
+        id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the most recent actions taken on escrow `id`, at most MAX_HISTORY of them and
+    /// kept after it closed, ordered by entry id and starting after `start_after`
+    #[returns(EscrowHistoryResponse)]
+    EscrowHistory {
+        id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Returns what each funder contributed to crowdfunded escrow `id`, ordered by funder
+    /// address and starting after `start_after`
+    #[returns(ContributionsResponse)]
+    Contributions {
+        id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns which arbiters have approved a pending milestone and which are still to sign
+    #[returns(MilestoneApprovalsResponse)]
+    MilestoneApprovals { id: String, milestone_id: String },
+
     // Returns the details for a milestone
     #[returns(Milestone)]
     MilestoneDetails { id: String, milestone_id: String },
 
-    /// Returns the details of all milestones for a given escrow
+    /// Returns the details of the milestones of escrow `id`, in the order they were created and
+    /// starting after milestone `start_after`
     #[returns(ListMilestonesResponse)]
-    ListMilestones { id: String },
+    ListMilestones {
+        id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the owner and the address ownership was offered to, if any
+    #[returns(OwnershipResponse)]
+    Ownership {},
+
+    /// Returns whether deposits and payouts are paused
+    #[returns(PausedResponse)]
+    Paused {},
+
+    /// Returns the protocol fee and the treasury collecting it
+    #[returns(ConfigResponse)]
+    Config {},
+
+    /// Returns running totals over the escrows: how many were created, are open and completed,
+    /// and what the open ones hold
+    #[returns(StatsResponse)]
+    Stats {},
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub config: Config,
+}
+
+#[cw_serde]
+pub struct StatsResponse {
+    pub stats: Stats,
 }
 
 #[cw_serde]
 pub struct ListEscrowsResponse {
-    /// list all registered ids
-    pub escrows: Vec<String>,
+    /// summaries of all registered escrows, ordered by id
+    pub escrows: Vec<EscrowSummary>,
+}
+
+#[cw_serde]
+pub struct ClaimsResponse {
+    /// Claimable native tokens, what streamed payouts have vested so far included
+    pub native_balance: Vec<Coin>,
+    /// Claimable cw20 tokens, what streamed payouts have vested so far included
+    pub cw20_balance: Vec<Cw20Coin>,
+    /// Payouts still streaming to the address
+    pub vesting: Vec<Vesting>,
+}
+
+#[cw_serde]
+pub struct FundingStatusResponse {
+    /// What the milestones pay in total: what they paid out, and what the open ones still owe.
+    /// Cancelled milestones and the refunded part of settled disputes don't count
+    pub required: GenericBalance,
+    /// What the funders deposited and wasn't refunded, paid out or still held
+    pub deposited: GenericBalance,
+    /// What the milestones have paid out so far
+    pub paid_out: GenericBalance,
+    /// What the escrow still holds for the open milestones to claim
+    pub remaining: GenericBalance,
+    /// The escrow holds all the open milestones still owe
+    pub funded: bool,
+}
+
+#[cw_serde]
+pub struct ContributionsResponse {
+    pub contributions: Vec<Contribution>,
+}
+
+#[cw_serde]
+pub struct Contribution {
+    pub funder: String,
+    /// Everything the funder put in, including what has since been paid out or refunded
+    pub amount: GenericBalance,
+}
+
+#[cw_serde]
+pub struct MilestoneApprovalsResponse {
+    /// Arbiters who approved the milestone, in signing order
+    pub approved_by: Vec<String>,
+    /// Arbiters who haven't yet
+    pub waiting_on: Vec<String>,
+    /// How many approvals pay the milestone out
+    pub threshold: u32,
+}
+
+#[cw_serde]
+pub struct DisputesResponse {
+    pub disputes: Vec<Dispute>,
+}
+
+#[cw_serde]
+pub struct EscrowHistoryResponse {
+    pub entries: Vec<HistoryEntry>,
+}
+
+/// The parts of an escrow a listing shows, so clients don't need a details query per escrow
+#[cw_serde]
+pub struct EscrowSummary {
+    /// id of this escrow
+    pub id: String,
+    /// arbiter can decide to approve or refund the escrow
+    pub arbiter: String,
+    /// if approved, funds go to the recipient
+    pub recipient: Option<String>,
+    /// Title of the escrow
+    pub title: String,
+    /// Native tokens still held for incomplete milestones
+    pub native_balance: Vec<Coin>,
+    /// cw20 tokens still held for incomplete milestones
+    pub cw20_balance: Vec<Cw20Coin>,
+    /// Number of milestones
+    pub milestones: u64,
+    /// Number of milestones already approved
+    pub completed_milestones: u64,
+    /// Where the escrow stands at the queried block
+    pub status: EscrowStatus,
+}
+
+/// Where an escrow stands, derived from its milestones and deadlines
+#[cw_serde]
+#[derive(Copy)]
+pub enum EscrowStatus {
+    /// Milestones are left to approve and no deadline has passed
+    Active,
+    /// A deadline passed with milestones left to approve, so anyone can refund it
+    Expired,
+    /// Every milestone is approved
+    Completed,
 }
 
 #[cw_serde]
 pub struct ListMilestonesResponse {
-    /// list all registered milestone ids
-    pub milestones: Vec<String>,
+    /// milestones with their current status, as MilestoneDetails returns them
+    pub milestones: Vec<Milestone>,
 }
 
 #[cw_serde]
@@ -200,27 +538,42 @@ pub struct EscrowDetailsResponse {
     pub id: String,
     /// arbiter can decide to approve or refund the escrow
     pub arbiter: String,
+    /// Other arbiters signing milestone approvals
+    pub co_arbiters: Vec<String>,
+    /// How many arbiters must approve a milestone before it pays out
+    pub threshold: u32,
+    /// Whether the arbiter is a plain address or a cw3 contract
+    pub arbiter_kind: ArbiterKind,
+    /// Offered the arbiter role, until it accepts
+    pub pending_arbiter: Option<String>,
     /// if approved, funds go to the recipient
     pub recipient: Option<String>,
     /// if refunded, funds go to the source
     pub source: String,
+    /// Whether the recipient has accepted the escrow, milestones can't be approved until then
+    pub accepted: bool,
     /// Title of the escrow
     pub title: String,
     /// Longer description of the escrow, e.g. what conditions should be met
     pub description: String,
-    /// When end height set and block height exceeds this value, the escrow is expired.
-    /// Once an escrow is expired, it can be returned to the original funder (via "refund").
-    pub end_height: Option<u64>,
-    /// When end time (in seconds since epoch 00:00:00 UTC on 1 January 1970) is set and
-    /// block time exceeds this value, the escrow is expired.
-    /// Once an escrow is expired, it can be returned to the original funder (via "refund").
-    pub end_time: Option<u64>,
     /// Balance in native tokens
     pub native_balance: Vec<Coin>,
     /// Balance in cw20 tokens
     pub cw20_balance: Vec<Cw20Coin>,
+    /// Whether the balance covers every milestone left to pay, or it still needs top ups
+    pub funded: bool,
     /// Whitelisted cw20 tokens
     pub cw20_whitelist: Vec<String>,
+    /// Whether cw20 tokens not in `cw20_whitelist` are rejected
+    pub strict_whitelist: bool,
+    /// Whether anyone can fund the escrow, refunds going back to every funder
+    pub crowdfunded: bool,
     /// List of milestones
     pub milestones: Vec<Milestone>,
+    /// The delegation of the escrow's funds, if staked
+    pub stake: Option<Stake>,
+    /// Block the escrow was created in
+    pub created_at: BlockStamp,
+    /// Block of its latest change, its milestones' included
+    pub updated_at: BlockStamp,
 }
@@ -1,25 +1,305 @@
+use std::cmp::Ordering;
+
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin, Deps, Env, Order, StdResult, Storage, Timestamp};
+use cosmwasm_std::{
+    Addr, Coin, Decimal, Deps, Empty, Env, Order, StdResult, Storage, Timestamp, Uint128,
+};
+use cosmwatch_access::{Ownable, Pausable};
 use cw20::{Balance, Cw20CoinVerified};
-use cw_storage_plus::Map;
-use cw_utils::NativeBalance;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::{Expiration, NativeBalance};
 
-use crate::{msg::CreateMilestoneMsg, ContractError};
+pub use cosmwatch_balances::GenericBalance;
 
-pub const ESCROWS: Map<&str, Escrow> = Map::new("escrow");
+use crate::msg::{CreateMilestoneMsg, EscrowStatus};
+use crate::ContractError;
 
-macro_rules! is_expired {
-    ($self:ident, $env:ident) => {{
-        (if let Some(end_height) = $self.end_height {
-            $env.block.height > end_height
-        } else {
-            false
-        }) || (if let Some(end_time) = $self.end_time {
-            $env.block.time > Timestamp::from_seconds(end_time)
+pub struct EscrowIndexes<'a> {
+    pub arbiter: MultiIndex<'a, Addr, Escrow, String>,
+    /// Escrows without a recipient yet are indexed under an empty address
+    pub recipient: MultiIndex<'a, Addr, Escrow, String>,
+    pub source: MultiIndex<'a, Addr, Escrow, String>,
+}
+
+impl<'a> IndexList<Escrow> for EscrowIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Escrow>> + '_> {
+        let v: Vec<&dyn Index<Escrow>> = vec![&self.arbiter, &self.recipient, &self.source];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Escrows by id, indexed by arbiter, recipient and source so participants can find theirs
+pub fn escrows<'a>() -> IndexedMap<'a, &'a str, Escrow, EscrowIndexes<'a>> {
+    let indexes = EscrowIndexes {
+        arbiter: MultiIndex::new(|_, e| e.arbiter.clone(), "escrow", "escrow__arbiter"),
+        recipient: MultiIndex::new(
+            |_, e| e.recipient.clone().unwrap_or_else(|| Addr::unchecked("")),
+            "escrow",
+            "escrow__recipient",
+        ),
+        source: MultiIndex::new(|_, e| e.source.clone(), "escrow", "escrow__source"),
+    };
+    IndexedMap::new("escrow", indexes)
+}
+
+/// Escrows by co-arbiter, which a `MultiIndex` can't do as an escrow has several. Kept in step
+/// with `escrows()` by `save_escrow` and `remove_escrow`
+pub const CO_ARBITER_ESCROWS: Map<(&Addr, &str), Empty> = Map::new("escrow__co_arbiter");
+
+/// Unbonding period assumed unless the owner sets the chain's, 21 days in seconds
+pub const DEFAULT_UNBONDING_PERIOD: u64 = 21 * 24 * 60 * 60;
+
+/// Protocol fee taken from milestone payouts and the chain's unbonding period, set by the owner
+#[cw_serde]
+pub struct Config {
+    /// Part of every payout to recipients sent to the treasury, in basis points
+    pub fee_bps: u16,
+    /// Credited the fees, to collect with Claim. Always set while `fee_bps` is
+    pub treasury: Option<Addr>,
+    /// How long undelegated tokens take to come back, in seconds
+    pub unbonding_period: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            fee_bps: 0,
+            treasury: None,
+            unbonding_period: DEFAULT_UNBONDING_PERIOD,
+        }
+    }
+}
+
+impl Config {
+    /// The fee owed to the treasury out of `payout`, rounded down
+    pub fn fee_of(&self, payout: &GenericBalance) -> GenericBalance {
+        if self.treasury.is_none() {
+            return GenericBalance::default();
+        }
+        share_of(payout, Decimal::from_ratio(self.fee_bps, 10_000u16))
+    }
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Running totals over the escrows, kept as they change so dashboards don't scan them all
+#[cw_serde]
+#[derive(Default)]
+pub struct Stats {
+    /// Escrows created
+    pub escrows: u64,
+    /// Escrows not closed yet
+    pub open: u64,
+    /// Escrows closed once none of their milestones was left open, rather than refunded
+    pub completed: u64,
+    /// What the open escrows hold, per native denom and cw20 token
+    pub locked: GenericBalance,
+}
+
+pub const STATS: Item<Stats> = Item::new("stats");
+
+/// What each funder put into a crowdfunded escrow, by escrow id and funder. Refunds are
+/// split in proportion to it
+pub const CONTRIBUTIONS: Map<(&str, &Addr), GenericBalance> = Map::new("contributions");
+
+/// Sender and cw20 tokens of the Receive creating an escrow right now, sent back to the
+/// sender if the create fails
+pub const RECEIVED_CREATE: Item<(Addr, Cw20CoinVerified)> = Item::new("received_create");
+
+/// Payouts sent by id of the reply to their failure, kept until a later block. A failed one
+/// puts its tokens back where they came from
+pub const PAYOUTS: Map<u64, Payout> = Map::new("payouts");
+
+/// Number of payouts sent so far
+pub const PAYOUT_COUNT: Item<u64> = Item::new("payout_count");
+
+/// Id of the last escrow ProcessExpired read, where the next call carries on. Unset once it
+/// reached the last escrow, so it starts over from the first
+pub const EXPIRY_CURSOR: Item<String> = Item::new("expiry_cursor");
+
+/// What approved milestones have credited to each recipient and it hasn't claimed yet
+pub const CLAIMS: Map<&Addr, GenericBalance> = Map::new("claims");
+
+/// Payouts of streamed milestones still vesting, by recipient and vesting id. The recipient
+/// claims what has vested along with its claims, and a vesting is gone once fully claimed
+pub const VESTINGS: Map<(&Addr, u64), Vesting> = Map::new("vestings");
+
+/// Number of vestings started so far, the last one's id
+pub const VESTING_COUNT: Item<u64> = Item::new("vesting_count");
+
+/// What the escrows have delegated to each validator, by validator address
+pub const VALIDATOR_STAKES: Map<&str, ValidatorStake> = Map::new("validator_stakes");
+
+/// Every dispute raised, by escrow id and dispute id, resolved ones included
+pub const DISPUTES: Map<(&str, u64), Dispute> = Map::new("disputes");
+
+/// Number of disputes raised so far, the last one's id
+pub const DISPUTE_COUNT: Item<u64> = Item::new("dispute_count");
+
+/// Most recent actions kept in each escrow's history
+pub const MAX_HISTORY: u64 = 100;
+
+/// What happened to each escrow, by escrow id and entry id, the last MAX_HISTORY entries of it.
+/// The history stays after the escrow closes, until a new escrow takes its id
+pub const HISTORY: Map<(&str, u64), HistoryEntry> = Map::new("history");
+
+/// Number of entries logged per escrow id so far, the last one's id
+pub const HISTORY_COUNT: Map<&str, u64> = Map::new("history_count");
+
+/// Proposals of cw3 arbiters already acted on, by arbiter and proposal id, so none is used twice
+pub const USED_PROPOSALS: Map<(&Addr, u64), Empty> = Map::new("used_proposals");
+
+/// Operator of the escrow contract. It can pause deposits and payouts, never move escrowed funds
+pub const OWNERSHIP: Ownable = Ownable::new("owner", "pending_owner");
+
+/// While set, no new escrows or milestones can be funded and no milestones pay out. Existing
+/// escrows can still be extended and refunded, and credited claims withdrawn
+pub const PAUSE: Pausable = Pausable::new("paused");
+
+/// What kind of account the arbiter is, which decides how it can act on the escrow
+#[cw_serde]
+#[derive(Copy)]
+pub enum ArbiterKind {
+    /// Acts by sending messages itself
+    Address,
+    /// A cw3 multisig or DAO. Besides executing proposals itself, any passed proposal of it
+    /// carrying an approval or refund can be handed to the escrow by anyone
+    Cw3,
+}
+
+#[cw_serde]
+#[derive(Copy)]
+pub enum MilestoneStatus {
+    /// Waiting for the recipient to submit it as delivered
+    Pending,
+    /// Submitted by the recipient, waiting for the arbiters' approval
+    Submitted,
+    /// Approved and paid out to the recipient, or resolved after a dispute with the recipient
+    /// getting at least part of it
+    Completed,
+    /// Its deadline passed before approval. Never stored, queries report pending and
+    /// submitted milestones past their deadline this way
+    Expired,
+    /// Under dispute, it can't be approved until the dispute is resolved
+    Disputed,
+    /// Called off, or resolved after a dispute with everything refunded. It will never pay out
+    Cancelled,
+}
+
+/// Window a payout vests over, in seconds since epoch 00:00:00 UTC on 1 January 1970
+#[cw_serde]
+pub struct Stream {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl Stream {
+    /// Part of the payout vested at `time`, growing linearly from none at `start` to all of it
+    /// at `end`
+    pub fn vested_share(&self, time: Timestamp) -> Decimal {
+        let now = time.seconds();
+        if now >= self.end {
+            Decimal::one()
+        } else if now <= self.start {
+            Decimal::zero()
         } else {
-            false
-        })
-    }};
+            Decimal::from_ratio(now - self.start, self.end - self.start)
+        }
+    }
+}
+
+/// An approved milestone's payout streaming to its recipient
+#[cw_serde]
+pub struct Vesting {
+    pub stream: Stream,
+    /// The whole payout, the protocol fee already taken
+    pub total: GenericBalance,
+    /// What the recipient has claimed of it so far
+    pub claimed: GenericBalance,
+}
+
+impl Vesting {
+    /// What has vested at `time` and isn't claimed yet
+    pub fn claimable(&self, time: Timestamp) -> StdResult<GenericBalance> {
+        let mut vested = share_of(&self.total, self.stream.vested_share(time));
+        vested.sub_balance(&self.claimed)?;
+        Ok(vested)
+    }
+}
+
+/// The delegation of a staked escrow's funds in the staking denom
+#[cw_serde]
+pub struct Stake {
+    pub validator: String,
+    /// Part of the staking yield credited to the recipient, the rest goes to the source
+    pub recipient_yield: Decimal,
+    /// Delegated for the escrow, unbonding once it is unstaked
+    pub bonded: Uint128,
+    /// The validator's reward index when `bonded` last changed
+    pub reward_index: Decimal,
+    /// Yield `bonded` earned up to `reward_index`, not credited yet
+    pub earned: Uint128,
+    /// When the unstaked funds are back, in seconds since epoch. Set by Unstake
+    pub unbonding_until: Option<u64>,
+}
+
+impl Stake {
+    /// Adds what `bonded` earned while the validator's reward index rose to `reward_index`
+    pub fn accrue(&mut self, reward_index: Decimal) {
+        self.earned += self.bonded * (reward_index - self.reward_index);
+        self.reward_index = reward_index;
+    }
+
+    /// Unstaked, and every token it had delegated is back
+    pub fn is_liquid(&self, env: &Env) -> bool {
+        matches!(self.unbonding_until, Some(until) if env.block.time.seconds() >= until)
+    }
+}
+
+/// Everything the escrows delegated to one validator, and the rewards it paid per token. The
+/// chain withdraws rewards to the contract whenever a delegation changes, so the index is
+/// brought up to date right before
+#[cw_serde]
+#[derive(Default)]
+pub struct ValidatorStake {
+    pub bonded: Uint128,
+    pub reward_index: Decimal,
+}
+
+/// Tokens on their way out in one bank send or cw20 transfer
+#[cw_serde]
+pub struct Payout {
+    /// Block the payout was sent in
+    pub height: u64,
+    pub amount: GenericBalance,
+    pub restore: Restore,
+}
+
+/// Where a payout's tokens go back to if sending them fails
+#[cw_serde]
+pub enum Restore {
+    /// Back into escrow `id`, holding `escrow` as it was before paying out. The milestones
+    /// the payout closed are opened again, and a closed escrow is brought back
+    Escrow { id: String, escrow: Box<Escrow> },
+    /// Back to the address's claims
+    Claims(Addr),
+}
+
+/// The block something happened in
+#[cw_serde]
+#[derive(Default)]
+pub struct BlockStamp {
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+impl BlockStamp {
+    pub fn now(env: &Env) -> Self {
+        BlockStamp {
+            height: env.block.height,
+            time: env.block.time,
+        }
+    }
 }
 
 #[cw_serde]
@@ -28,9 +308,22 @@ pub struct Milestone {
     pub title: String,
     pub description: String,
     pub amount: GenericBalance,
-    pub end_height: Option<u64>,
-    pub end_time: Option<u64>,
-    pub is_completed: bool,
+    /// Deadline to have it approved by, after which its amount can be refunded
+    pub expires: Expiration,
+    pub status: MilestoneStatus,
+    /// What the recipient has been paid for this milestone so far
+    pub paid_out: GenericBalance,
+    /// Arbiters who approved it, it pays out once the escrow's threshold of them have
+    pub approvals: Vec<Addr>,
+    /// Source and recipient asking to cancel it, it is cancelled once both have
+    pub cancel_consents: Vec<Addr>,
+    /// Vests the payout to the recipient over this window once approved, instead of crediting
+    /// it all at once
+    pub stream: Option<Stream>,
+    /// Block the milestone was added in
+    pub created_at: BlockStamp,
+    /// Block of its latest change
+    pub updated_at: BlockStamp,
 }
 
 impl HasAmount for Milestone {
@@ -39,117 +332,196 @@ impl HasAmount for Milestone {
     }
 }
 
-impl HasEnd for Milestone {
-    fn get_end_time(&self) -> Option<u64> {
-        self.end_time
-    }
-    fn get_end_height(&self) -> Option<u64> {
-        self.end_height
-    }
-}
-
 impl Milestone {
     pub fn is_empty(&self) -> bool {
-        match &self.amount {
-            balance => balance.native.is_empty() && balance.cw20.is_empty(),
-        }
+        self.amount.is_empty()
     }
 
     pub fn is_expired(&self, env: &Env) -> bool {
-        is_expired!(self, env)
+        self.expires.is_expired(&env.block)
     }
 
-    pub fn extend_expiration(&mut self, end_height: Option<u64>, end_time: Option<u64>) {
-        // Check if new time is in the past
-        if end_height < self.end_height || end_time < self.end_time {
-            return;
-        }
+    /// What is still owed for this milestone
+    pub fn unpaid(&self) -> StdResult<GenericBalance> {
+        let mut unpaid = self.amount.clone();
+        unpaid.sub_balance(&self.paid_out)?;
+        Ok(unpaid)
+    }
+
+    /// Still holds its amount, waiting to be paid out or refunded
+    pub fn is_open(&self) -> bool {
+        matches!(
+            self.status,
+            MilestoneStatus::Pending | MilestoneStatus::Submitted | MilestoneStatus::Disputed
+        )
+    }
+
+    /// Whether it waits on the recipient or the arbiters, so can still be approved in time
+    pub fn is_awaiting_approval(&self) -> bool {
+        matches!(
+            self.status,
+            MilestoneStatus::Pending | MilestoneStatus::Submitted
+        )
+    }
 
-        if let Some(height) = end_height {
-            self.end_height = Some(height);
+    /// The stored status, with a milestone awaiting approval past its deadline reported as
+    /// expired
+    pub fn status_at(&self, env: &Env) -> MilestoneStatus {
+        if self.is_awaiting_approval() && self.is_expired(env) {
+            MilestoneStatus::Expired
+        } else {
+            self.status
         }
-        if let Some(time) = end_time {
-            self.end_time = Some(time);
+    }
+
+    /// Moves the deadline to `expires`, which can't be earlier than the current one or already
+    /// passed. A height and a time don't compare, so the kind can't change other than to never
+    pub fn extend_expiration(
+        &mut self,
+        env: &Env,
+        expires: Expiration,
+    ) -> Result<(), ContractError> {
+        let later = matches!(
+            expires.partial_cmp(&self.expires),
+            Some(Ordering::Greater | Ordering::Equal)
+        );
+        if !later || expires.is_expired(&env.block) {
+            return Err(ContractError::CannotShortenDeadline {});
         }
+
+        self.expires = expires;
+        Ok(())
     }
 }
 
+/// A milestone the source or recipient objected to, frozen until the arbiter resolves it
 #[cw_serde]
-#[derive(Default)]
-pub struct GenericBalance {
-    pub native: Vec<Coin>,
-    pub cw20: Vec<Cw20CoinVerified>,
-}
-
-impl GenericBalance {
-    pub fn add_tokens(&mut self, add: Balance) {
-        match add {
-            Balance::Native(balance) => {
-                for token in balance.0 {
-                    let index = self.native.iter().enumerate().find_map(|(i, exist)| {
-                        if exist.denom == token.denom {
-                            Some(i)
-                        } else {
-                            None
-                        }
-                    });
-                    match index {
-                        Some(idx) => self.native[idx].amount += token.amount,
-                        None => self.native.push(token),
-                    }
-                }
-            }
-            Balance::Cw20(token) => {
-                let index = self.cw20.iter().enumerate().find_map(|(i, exist)| {
-                    if exist.address == token.address {
-                        Some(i)
-                    } else {
-                        None
-                    }
-                });
-                match index {
-                    Some(idx) => self.cw20[idx].amount += token.amount,
-                    None => self.cw20.push(token),
-                }
-            }
-        };
-    }
+pub struct Dispute {
+    pub id: u64,
+    pub escrow_id: String,
+    pub milestone_id: String,
+    /// The source or recipient who raised it
+    pub raised_by: Addr,
+    pub reason: String,
+    /// Block height it was raised at
+    pub height: u64,
+    /// Set once the arbiter has resolved it
+    pub resolution: Option<DisputeResolution>,
+}
+
+/// An action that changed an escrow
+#[cw_serde]
+pub struct HistoryEntry {
+    pub id: u64,
+    /// Who acted, the cw20 sender for tokens sent with Receive and the contract itself for
+    /// expired escrows it refunded in bulk and failed payouts it restored
+    pub actor: Addr,
+    /// The message handled, as in its response's action attribute
+    pub action: String,
+    /// The milestone acted on, if only one
+    pub milestone_id: Option<String>,
+    /// Tokens the action moved into, out of or within the escrow, if any
+    pub amount: Option<GenericBalance>,
+    /// Block height it happened at
+    pub height: u64,
+}
+
+/// How the arbiter split a disputed milestone between recipient and source
+#[cw_serde]
+pub struct DisputeResolution {
+    /// Part of what the milestone was still owed that went to the recipient
+    pub recipient_share: Decimal,
+    /// Credited to the recipient
+    pub paid_out: GenericBalance,
+    /// Credited back to the source
+    pub refunded: GenericBalance,
 }
 
 #[cw_serde]
 pub struct Escrow {
     /// arbiter can decide to approve or refund the escrow
     pub arbiter: Addr,
+    /// Other arbiters signing milestone approvals, never including `arbiter`
+    pub co_arbiters: Vec<Addr>,
+    /// How many arbiters must approve a milestone before it pays out, 1 for a lone arbiter
+    pub threshold: u32,
+    pub arbiter_kind: ArbiterKind,
+    /// Offered the arbiter role with TransferArbiter, takes it over once it accepts
+    pub pending_arbiter: Option<Addr>,
     /// if approved, funds go to the recipient, cannot approve if recipient is none
     pub recipient: Option<Addr>,
     /// if refunded, funds go to the source
     pub source: Addr,
+    /// the recipient agreed to the terms, milestones can't be approved before it has
+    pub accepted: bool,
     /// Title of the escrow, for example for a bug bounty "Fix issue in contract.rs"
     pub title: String,
     /// Description of the escrow, a more in depth description of how to meet the escrow condition
     pub description: String,
-    /// When end height set and block height exceeds this value, the escrow is expired.
-    /// Once an escrow is expired, it can be returned to the original funder (via "refund").
-    pub end_height: Option<u64>,
-    /// When end time (in seconds since epoch 00:00:00 UTC on 1 January 1970) is set and
-    /// block time exceeds this value, the escrow is expired.
-    /// Once an escrow is expired, it can be returned to the original funder (via "refund").
-    pub end_time: Option<u64>,
     /// Balance in Native and Cw20 tokens
     pub balance: GenericBalance,
     /// All possible contracts that we accept tokens from
     pub cw20_whitelist: Vec<Addr>,
+    /// Reject cw20 tokens not in the whitelist rather than adding them to it
+    pub strict_whitelist: bool,
+    /// Anyone can top the escrow up, and refunds go back to every funder
+    pub crowdfunded: bool,
     // Milestones to be met
     pub milestones: Vec<Milestone>,
+    /// Milestones ever added, the last one's id. Ids of removed milestones are never reused
+    pub milestone_count: u64,
+    /// Set when the escrow's funds in the staking denom are delegated to earn yield
+    pub stake: Option<Stake>,
+    /// Block the escrow was created in. Zero for escrows from before 0.15.0
+    pub created_at: BlockStamp,
+    /// Block of its latest change, its milestones' included
+    pub updated_at: BlockStamp,
 }
 
 impl Escrow {
+    /// Expired once every milestone deadline has passed, so it can be returned to the original
+    /// funder (via "refund"). Never expires if no milestone has a deadline
     pub fn is_expired(&self, env: &Env) -> bool {
-        is_expired!(self, env)
+        let mut deadlines = self
+            .milestones
+            .iter()
+            .map(|m| m.expires)
+            .filter(|expires| !matches!(expires, Expiration::Never {}))
+            .peekable();
+        deadlines.peek().is_some() && deadlines.all(|expires| expires.is_expired(&env.block))
+    }
+
+    /// Whether `addr` signs milestone approvals for this escrow
+    pub fn is_arbiter(&self, addr: &Addr) -> bool {
+        &self.arbiter == addr || self.co_arbiters.contains(addr)
+    }
+
+    /// Every arbiter, `arbiter` first
+    pub fn arbiters(&self) -> Vec<Addr> {
+        let mut arbiters = vec![self.arbiter.clone()];
+        arbiters.extend(self.co_arbiters.iter().cloned());
+        arbiters
+    }
+
+    /// Whether a milestone is under dispute, which holds off refunds until the arbiter settles it
+    pub fn is_disputed(&self) -> bool {
+        self.milestones
+            .iter()
+            .any(|m| m.status == MilestoneStatus::Disputed)
     }
 
     pub fn is_complete(&self) -> bool {
-        self.milestones.iter().all(|m| m.is_completed)
+        !self.milestones.iter().any(Milestone::is_open)
+    }
+
+    pub fn status(&self, env: &Env) -> EscrowStatus {
+        if self.is_complete() {
+            EscrowStatus::Completed
+        } else if self.is_expired(env) {
+            EscrowStatus::Expired
+        } else {
+            EscrowStatus::Active
+        }
     }
 
     pub fn human_whitelist(&self) -> Vec<String> {
@@ -161,26 +533,51 @@ impl Escrow {
             .iter()
             .map(|m| {
                 format!(
-                    "id: {}\ntitle: {}\ndescription: {}\ncomplete: {}",
-                    m.id, m.title, m.description, m.is_completed
+                    "id: {}\ntitle: {}\ndescription: {}\nstatus: {:?}",
+                    m.id, m.title, m.description, m.status
                 )
             })
             .collect()
     }
 
-    pub fn create_milestone(&mut self, milestone: CreateMilestoneMsg) {
-        let id = (self.milestones.len() + 1).to_string();
+    pub fn create_milestone(&mut self, milestone: CreateMilestoneMsg, env: &Env) {
+        self.milestone_count += 1;
         self.milestones.push(Milestone {
-            id,
+            id: self.milestone_count.to_string(),
             title: milestone.title,
             description: milestone.description,
             amount: milestone.amount,
-            is_completed: false,
-            end_height: milestone.end_height,
-            end_time: milestone.end_time,
+            status: MilestoneStatus::Pending,
+            expires: milestone.expires.unwrap_or_default(),
+            paid_out: GenericBalance::default(),
+            approvals: vec![],
+            cancel_consents: vec![],
+            stream: milestone.stream,
+            created_at: BlockStamp::now(env),
+            updated_at: BlockStamp::now(env),
         });
     }
 
+    /// Fails while staked funds are delegated or unbonding, as they can't be paid out
+    pub fn check_liquid(&self, env: &Env) -> Result<(), ContractError> {
+        match &self.stake {
+            Some(stake) if !stake.is_liquid(env) => Err(ContractError::FundsStaked {}),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whitelists cw20 `token` when it is sent, unless the whitelist is strict and it isn't
+    /// already on it
+    pub fn accept_cw20(&mut self, token: &Addr) -> Result<(), ContractError> {
+        if !self.cw20_whitelist.contains(token) {
+            if self.strict_whitelist {
+                return Err(ContractError::NotInWhitelist {});
+            }
+            self.cw20_whitelist.push(token.clone());
+        }
+        Ok(())
+    }
+
     pub fn get_milestone_by_id(&self, id: &str) -> Option<&Milestone> {
         self.milestones.iter().find(|m| m.id == id)
     }
@@ -189,32 +586,237 @@ impl Escrow {
         get_total_balance_from(self.clone().milestones).unwrap()
     }
 
-    pub fn get_remaining_balance(&self) -> GenericBalance {
-        get_remaining_balance(self.clone().milestones).unwrap()
+    /// What the open milestones still need paid out
+    pub fn required_balance(&self) -> StdResult<GenericBalance> {
+        let mut required = GenericBalance::default();
+        for milestone in self.milestones.iter().filter(|m| m.is_open()) {
+            required.add_balance(&milestone.unpaid()?);
+        }
+        Ok(required)
+    }
+
+    /// What the milestones have paid out so far
+    pub fn paid_out(&self) -> GenericBalance {
+        let mut paid_out = GenericBalance::default();
+        for milestone in &self.milestones {
+            paid_out.add_balance(&milestone.paid_out);
+        }
+        paid_out
+    }
+
+    /// The balance covers every milestone left to pay
+    pub fn is_funded(&self) -> StdResult<bool> {
+        Ok(self.balance.covers(&self.required_balance()?))
     }
 
-    pub fn get_end_height(&self) -> Option<u64> {
-        get_end_height(self.clone().milestones)
+    /// Adds `amount` to the balance, failing on the first native denom or cw20 address the
+    /// balance then holds more of than the open milestones need
+    pub fn deposit(&mut self, amount: &GenericBalance) -> Result<(), ContractError> {
+        self.balance.add_balance(amount);
+        let excess = surplus(&self.balance, &self.required_balance()?, &self.balance);
+        let token = match (excess.native.first(), excess.cw20.first()) {
+            (Some(coin), _) => coin.denom.clone(),
+            (None, Some(token)) => token.address.to_string(),
+            (None, None) => return Ok(()),
+        };
+        Err(ContractError::FundsMismatch { token })
+    }
+
+    /// Pays out what is still owed for milestone `index`, taking it from the escrow balance
+    pub fn pay_out_milestone(&mut self, index: usize) -> StdResult<GenericBalance> {
+        let milestone = &mut self.milestones[index];
+        let payout = milestone.unpaid()?;
+        self.balance.sub_balance(&payout)?;
+        milestone.paid_out.add_balance(&payout);
+        Ok(payout)
+    }
+
+    /// Settles disputed milestone `index`, paying `recipient_share` of what it is still owed
+    /// and refunding the rest, both out of the escrow balance. The milestone counts as completed
+    /// if the recipient got anything, cancelled otherwise
+    pub fn settle_milestone(
+        &mut self,
+        index: usize,
+        recipient_share: Decimal,
+    ) -> StdResult<(GenericBalance, GenericBalance)> {
+        let milestone = &mut self.milestones[index];
+        let mut refund = milestone.unpaid()?;
+        let payout = share_of(&refund, recipient_share);
+        refund.sub_balance(&payout)?;
+        self.balance.sub_balance(&payout)?;
+        self.balance.sub_balance(&refund)?;
+        milestone.paid_out.add_balance(&payout);
+        milestone.status = if payout.is_empty() {
+            MilestoneStatus::Cancelled
+        } else {
+            MilestoneStatus::Completed
+        };
+        Ok((payout, refund))
+    }
+
+    /// Cancels milestone `index`, taking what the escrow holds for it out of the balance to
+    /// refund. An escrow still waiting on top ups only gives back what it holds beyond the
+    /// milestones left to pay
+    pub fn cancel_milestone(&mut self, index: usize) -> StdResult<GenericBalance> {
+        let unpaid = self.milestones[index].unpaid()?;
+        self.milestones[index].status = MilestoneStatus::Cancelled;
+        let refund = surplus(&self.balance, &self.required_balance()?, &unpaid);
+        self.balance.sub_balance(&refund)?;
+        Ok(refund)
     }
 
-    pub fn get_end_time(&self) -> Option<u64> {
-        get_end_time(self.clone().milestones)
+    /// Removes milestone `index`, taking what the escrow holds for it out of the balance to
+    /// refund like `cancel_milestone` does
+    pub fn remove_milestone(&mut self, index: usize) -> StdResult<GenericBalance> {
+        let unpaid = self.milestones.remove(index).unpaid()?;
+        let refund = surplus(&self.balance, &self.required_balance()?, &unpaid);
+        self.balance.sub_balance(&refund)?;
+        Ok(refund)
     }
 
-    pub fn update_calculated_properties(&mut self) {
-        self.balance = self.get_total_balance();
-        self.end_height = self.get_end_height();
-        self.end_time = self.get_end_time();
+    /// Sets milestone `index` to pay `amount`, taking what the escrow then holds beyond what the
+    /// open milestones need out of the balance to refund
+    pub fn set_milestone_amount(
+        &mut self,
+        index: usize,
+        amount: GenericBalance,
+    ) -> StdResult<GenericBalance> {
+        self.milestones[index].amount = amount;
+        let held = self.balance.clone();
+        let refund = surplus(&held, &self.required_balance()?, &held);
+        self.balance.sub_balance(&refund)?;
+        Ok(refund)
     }
 }
 
-pub trait HasAmount {
-    fn get_amount(&self) -> GenericBalance;
+/// `share` of every token in `balance`, rounded down, leaving out what rounds to zero
+fn share_of(balance: &GenericBalance, share: Decimal) -> GenericBalance {
+    let mut part = GenericBalance {
+        native: balance
+            .native
+            .iter()
+            .map(|coin| Coin::new((coin.amount * share).u128(), &coin.denom))
+            .collect(),
+        cw20: balance
+            .cw20
+            .iter()
+            .map(|token| Cw20CoinVerified {
+                address: token.address.clone(),
+                amount: token.amount * share,
+            })
+            .collect(),
+    };
+    part.native.retain(|coin| !coin.amount.is_zero());
+    part.cw20.retain(|token| !token.amount.is_zero());
+    part
+}
+
+fn native_of(balance: &GenericBalance, denom: &str) -> Uint128 {
+    balance
+        .native
+        .iter()
+        .find(|coin| coin.denom == denom)
+        .map_or(Uint128::zero(), |coin| coin.amount)
 }
 
-pub trait HasEnd {
-    fn get_end_height(&self) -> Option<u64>;
-    fn get_end_time(&self) -> Option<u64>;
+fn cw20_of(balance: &GenericBalance, address: &Addr) -> Uint128 {
+    balance
+        .cw20
+        .iter()
+        .find(|token| &token.address == address)
+        .map_or(Uint128::zero(), |token| token.amount)
+}
+
+/// What `held` has beyond `needed` of every token in `cap`, but no more than `cap`
+fn surplus(held: &GenericBalance, needed: &GenericBalance, cap: &GenericBalance) -> GenericBalance {
+    let mut part = GenericBalance {
+        native: cap
+            .native
+            .iter()
+            .map(|coin| {
+                let spare =
+                    native_of(held, &coin.denom).saturating_sub(native_of(needed, &coin.denom));
+                Coin::new(spare.min(coin.amount).u128(), &coin.denom)
+            })
+            .collect(),
+        cw20: cap
+            .cw20
+            .iter()
+            .map(|token| {
+                let spare =
+                    cw20_of(held, &token.address).saturating_sub(cw20_of(needed, &token.address));
+                Cw20CoinVerified {
+                    address: token.address.clone(),
+                    amount: spare.min(token.amount),
+                }
+            })
+            .collect(),
+    };
+    part.native.retain(|coin| !coin.amount.is_zero());
+    part.cw20.retain(|token| !token.amount.is_zero());
+    part
+}
+
+/// Splits `amount` between the funders in `contributions` in proportion to what each put in
+/// of every token, rounding down. Returns the funders' parts and what rounding leaves over
+pub fn pro_rata(
+    amount: &GenericBalance,
+    contributions: &[(Addr, GenericBalance)],
+) -> StdResult<(Vec<(Addr, GenericBalance)>, GenericBalance)> {
+    let native_total = |denom: &str| -> Uint128 {
+        contributions
+            .iter()
+            .map(|(_, contributed)| native_of(contributed, denom))
+            .sum()
+    };
+    let cw20_total = |address: &Addr| -> Uint128 {
+        contributions
+            .iter()
+            .map(|(_, contributed)| cw20_of(contributed, address))
+            .sum()
+    };
+
+    let mut parts = vec![];
+    let mut rest = amount.clone();
+    for (funder, contributed) in contributions {
+        let mut part = GenericBalance {
+            native: amount
+                .native
+                .iter()
+                .filter(|coin| !native_total(&coin.denom).is_zero())
+                .map(|coin| {
+                    let share = coin.amount.multiply_ratio(
+                        native_of(contributed, &coin.denom),
+                        native_total(&coin.denom),
+                    );
+                    Coin::new(share.u128(), &coin.denom)
+                })
+                .collect(),
+            cw20: amount
+                .cw20
+                .iter()
+                .filter(|token| !cw20_total(&token.address).is_zero())
+                .map(|token| Cw20CoinVerified {
+                    address: token.address.clone(),
+                    amount: token.amount.multiply_ratio(
+                        cw20_of(contributed, &token.address),
+                        cw20_total(&token.address),
+                    ),
+                })
+                .collect(),
+        };
+        part.native.retain(|coin| !coin.amount.is_zero());
+        part.cw20.retain(|token| !token.amount.is_zero());
+        if !part.is_empty() {
+            rest.sub_balance(&part)?;
+            parts.push((funder.clone(), part));
+        }
+    }
+    Ok((parts, rest))
+}
+
+pub trait HasAmount {
+    fn get_amount(&self) -> GenericBalance;
 }
 
 // Helper functions
@@ -230,30 +832,129 @@ pub fn get_total_balance_from<T: HasAmount>(milestones: Vec<T>) -> StdResult<Gen
     Ok(total_balance)
 }
 
-pub fn get_remaining_balance(milestones: Vec<Milestone>) -> StdResult<GenericBalance> {
-    let mut remaining_balance = GenericBalance::default();
-    for milestone in milestones.iter() {
-        if !milestone.is_completed {
-            let amount = milestone.get_amount();
-            remaining_balance.add_tokens(Balance::Native(NativeBalance(amount.native)));
-            for token in &amount.cw20 {
-                remaining_balance.add_tokens(Balance::Cw20(token.clone()));
-            }
+/// Saves escrow `id` after changing it, stamping it and the milestones that changed with the
+/// current block
+pub fn save_escrow(
+    storage: &mut dyn Storage,
+    env: &Env,
+    id: &str,
+    escrow: &mut Escrow,
+) -> StdResult<()> {
+    let now = BlockStamp::now(env);
+    let stored = escrows().may_load(storage, id)?;
+    let stored_milestones = stored.iter().flat_map(|stored| &stored.milestones);
+    for milestone in escrow.milestones.iter_mut() {
+        if !stored_milestones.clone().any(|stored| stored == milestone) {
+            milestone.updated_at = now.clone();
         }
     }
-    Ok(remaining_balance)
+    if stored.as_ref() != Some(escrow) {
+        escrow.updated_at = now;
+    }
+
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    match &stored {
+        Some(stored) => stats.locked.sub_balance(&stored.balance)?,
+        None => stats.open += 1,
+    }
+    stats.locked.add_balance(&escrow.balance);
+    STATS.save(storage, &stats)?;
+    for co_arbiter in stored.iter().flat_map(|stored| &stored.co_arbiters) {
+        CO_ARBITER_ESCROWS.remove(storage, (co_arbiter, id));
+    }
+    for co_arbiter in &escrow.co_arbiters {
+        CO_ARBITER_ESCROWS.save(storage, (co_arbiter, id), &Empty {})?;
+    }
+    escrows().save(storage, id, escrow)
 }
 
-pub fn get_end_height<T: HasEnd>(milestones: Vec<T>) -> Option<u64> {
-    milestones.iter().filter_map(|m| m.get_end_height()).max()
+/// Counts a new escrow in the stats, before it is first saved
+pub fn count_created(storage: &mut dyn Storage) -> StdResult<()> {
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    stats.escrows += 1;
+    STATS.save(storage, &stats)
 }
 
-pub fn get_end_time<T: HasEnd>(milestones: Vec<T>) -> Option<u64> {
-    milestones.iter().filter_map(|m| m.get_end_time()).max()
+/// Counts the escrows already stored into new stats, for contracts from before they were kept.
+/// Completed escrows were removed without a trace, so they start from zero
+pub fn init_stats(storage: &mut dyn Storage) -> StdResult<()> {
+    let mut stats = Stats::default();
+    for item in escrows().range(storage, None, None, Order::Ascending) {
+        let (_, escrow) = item?;
+        stats.escrows += 1;
+        stats.open += 1;
+        stats.locked.add_balance(&escrow.balance);
+    }
+    STATS.save(storage, &stats)
+}
+
+/// Adds `action` of `actor` to the history of escrow `id`, dropping the oldest entry past
+/// MAX_HISTORY
+pub fn append_history(
+    storage: &mut dyn Storage,
+    env: &Env,
+    id: &str,
+    actor: &Addr,
+    action: &str,
+    milestone_id: Option<&str>,
+    amount: Option<&GenericBalance>,
+) -> StdResult<()> {
+    let entry_id = HISTORY_COUNT.may_load(storage, id)?.unwrap_or_default() + 1;
+    HISTORY_COUNT.save(storage, id, &entry_id)?;
+    let entry = HistoryEntry {
+        id: entry_id,
+        actor: actor.clone(),
+        action: action.to_string(),
+        milestone_id: milestone_id.map(str::to_string),
+        amount: amount.filter(|amount| !amount.is_empty()).cloned(),
+        height: env.block.height,
+    };
+    HISTORY.save(storage, (id, entry_id), &entry)?;
+    if entry_id > MAX_HISTORY {
+        HISTORY.remove(storage, (id, entry_id - MAX_HISTORY));
+    }
+    Ok(())
+}
+
+/// Forgets the history of escrow `id`, for a new escrow taking the id
+pub fn clear_history(storage: &mut dyn Storage, id: &str) -> StdResult<()> {
+    let entries = HISTORY
+        .prefix(id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for entry in entries {
+        HISTORY.remove(storage, (id, entry));
+    }
+    HISTORY_COUNT.remove(storage, id);
+    Ok(())
 }
 
-pub fn get_escrow_by_id(deps: &Deps, id: &String) -> Result<Escrow, ContractError> {
-    match ESCROWS.may_load(deps.storage, &id)? {
+/// Removes escrow `id` once it closed as `escrow`, along with what its funders contributed
+pub fn remove_escrow(storage: &mut dyn Storage, id: &str, escrow: &Escrow) -> StdResult<()> {
+    let stored = escrows().load(storage, id)?;
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    stats.locked.sub_balance(&stored.balance)?;
+    stats.open -= 1;
+    if escrow.is_complete() {
+        stats.completed += 1;
+    }
+    STATS.save(storage, &stats)?;
+    for co_arbiter in &stored.co_arbiters {
+        CO_ARBITER_ESCROWS.remove(storage, (co_arbiter, id));
+    }
+    escrows().remove(storage, id)?;
+    let funders = CONTRIBUTIONS
+        .prefix(id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for funder in funders {
+        CONTRIBUTIONS.remove(storage, (id, &funder));
+    }
+    Ok(())
+}
+
+pub fn get_escrow_by_id(deps: &Deps, id: &str) -> Result<Escrow, ContractError> {
+    match escrows().may_load(deps.storage, id)? {
         Some(escrow) => Ok(escrow),
         None => Err(ContractError::NotFound {}),
     }
@@ -261,13 +962,126 @@ pub fn get_escrow_by_id(deps: &Deps, id: &String) -> Result<Escrow, ContractErro
 
 /// This returns the list of ids for all registered escrows
 pub fn all_escrow_ids(storage: &dyn Storage) -> StdResult<Vec<String>> {
-    ESCROWS
+    escrows()
         .keys(storage, None, None, Order::Ascending)
         .collect()
 }
+
+// Milestone as stored up to 0.14, with a flag instead of a status. Only read when migrating
+#[cw_serde]
+pub struct LegacyMilestone {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub amount: GenericBalance,
+    pub end_height: Option<u64>,
+    pub end_time: Option<u64>,
+    pub is_completed: bool,
+}
+
+// Escrow as stored up to 0.14. Only read when migrating
+#[cw_serde]
+pub struct LegacyEscrow {
+    pub arbiter: Addr,
+    pub recipient: Option<Addr>,
+    pub source: Addr,
+    pub title: String,
+    pub description: String,
+    pub end_height: Option<u64>,
+    pub end_time: Option<u64>,
+    pub balance: GenericBalance,
+    pub cw20_whitelist: Vec<Addr>,
+    pub milestones: Vec<LegacyMilestone>,
+}
+
+impl LegacyMilestone {
+    /// The deadline in the current format. It was past once the block went beyond it, and a
+    /// milestone with both a height and a time keeps the height
+    fn expires(&self) -> Expiration {
+        match (self.end_height, self.end_time) {
+            (Some(height), _) => Expiration::AtHeight(height + 1),
+            (None, Some(time)) => Expiration::AtTime(Timestamp::from_seconds(time + 1)),
+            (None, None) => Expiration::Never {},
+        }
+    }
+}
+
+pub const LEGACY_ESCROWS: Map<&str, LegacyEscrow> = Map::new("escrow");
+
+/// Rewrites every escrow stored by 0.14 in the current layout, which also fills the indexes
+pub fn upgrade_escrows(storage: &mut dyn Storage) -> StdResult<()> {
+    let all = LEGACY_ESCROWS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (id, legacy) in all {
+        // completed milestones were approved by the lone arbiter
+        let arbiter = legacy.arbiter.clone();
+        let milestones = legacy
+            .milestones
+            .into_iter()
+            .map(|m| {
+                let (status, paid_out, approvals) = if m.is_completed {
+                    let approvals = vec![arbiter.clone()];
+                    (MilestoneStatus::Completed, m.amount.clone(), approvals)
+                } else {
+                    (MilestoneStatus::Pending, GenericBalance::default(), vec![])
+                };
+                let expires = m.expires();
+                Milestone {
+                    id: m.id,
+                    title: m.title,
+                    description: m.description,
+                    amount: m.amount,
+                    expires,
+                    status,
+                    paid_out,
+                    approvals,
+                    cancel_consents: vec![],
+                    stream: None,
+                    created_at: BlockStamp::default(),
+                    updated_at: BlockStamp::default(),
+                }
+            })
+            .collect::<Vec<_>>();
+        // 0.14 kept the total of all milestones here, paid out or not, so only what the open
+        // ones still hold is left
+        let mut balance = GenericBalance::default();
+        for milestone in milestones.iter().filter(|m| m.is_open()) {
+            balance.add_balance(&milestone.amount);
+        }
+        // these recipients were paid without being asked, keep it that way
+        let accepted = legacy.recipient.is_some();
+        let escrow = Escrow {
+            arbiter: legacy.arbiter,
+            co_arbiters: vec![],
+            threshold: 1,
+            arbiter_kind: ArbiterKind::Address,
+            pending_arbiter: None,
+            recipient: legacy.recipient,
+            source: legacy.source,
+            accepted,
+            title: legacy.title,
+            description: legacy.description,
+            balance,
+            cw20_whitelist: legacy.cw20_whitelist,
+            strict_whitelist: false,
+            crowdfunded: false,
+            milestone_count: milestones.len() as u64,
+            milestones,
+            stake: None,
+            created_at: BlockStamp::default(),
+            updated_at: BlockStamp::default(),
+        };
+        // `save` would load the old value under the same key to update the indexes, which
+        // doesn't parse in the current layout. It had no index entries, so there is none to drop
+        escrows().replace(storage, &id, Some(&escrow), None)?;
+    }
+    Ok(())
+}
+
 // This returns the list of ids for all milestones for a given escrow
 pub fn all_escrow_milestone_ids(storage: &dyn Storage, escrow_id: &str) -> StdResult<Vec<String>> {
-    let escrow = ESCROWS.load(storage, escrow_id)?;
+    let escrow = escrows().load(storage, escrow_id)?;
     Ok(escrow.milestones.iter().map(|m| m.id.clone()).collect())
 }
 
@@ -287,26 +1101,39 @@ mod tests {
     fn dummy_escrow() -> Escrow {
         Escrow {
             arbiter: Addr::unchecked("arb"),
+            co_arbiters: vec![],
+            threshold: 1,
+            arbiter_kind: ArbiterKind::Address,
+            pending_arbiter: None,
             recipient: Some(Addr::unchecked("recip")),
             source: Addr::unchecked("source"),
+            accepted: true,
             title: "some_escrow".to_string(),
             description: "some escrow desc".to_string(),
-            end_height: None,
-            end_time: None,
             balance: Default::default(),
             cw20_whitelist: vec![],
+            strict_whitelist: false,
+            crowdfunded: false,
             milestones: vec![],
+            milestone_count: 0,
+            stake: None,
+            created_at: BlockStamp::default(),
+            updated_at: BlockStamp::default(),
         }
     }
 
     #[test]
     fn test_all_escrow_ids_in_order() {
         let mut storage = MockStorage::new();
-        ESCROWS.save(&mut storage, "lazy", &dummy_escrow()).unwrap();
-        ESCROWS
+        escrows()
+            .save(&mut storage, "lazy", &dummy_escrow())
+            .unwrap();
+        escrows()
             .save(&mut storage, "assign", &dummy_escrow())
             .unwrap();
-        ESCROWS.save(&mut storage, "zen", &dummy_escrow()).unwrap();
+        escrows()
+            .save(&mut storage, "zen", &dummy_escrow())
+            .unwrap();
 
         let ids = all_escrow_ids(&storage).unwrap();
         assert_eq!(3, ids.len());
@@ -315,4 +1142,107 @@ mod tests {
             ids
         )
     }
+
+    #[test]
+    fn test_pro_rata_leaves_rounding_over() {
+        let balance = |native: Vec<Coin>| GenericBalance {
+            native,
+            cw20: vec![],
+        };
+        let contributions = vec![
+            (Addr::unchecked("a"), balance(vec![Coin::new(1, "tokens")])),
+            (
+                Addr::unchecked("b"),
+                balance(vec![Coin::new(2, "tokens"), Coin::new(5, "other")]),
+            ),
+        ];
+        let amount = balance(vec![Coin::new(10, "tokens"), Coin::new(3, "other")]);
+
+        let (parts, rest) = pro_rata(&amount, &contributions).unwrap();
+        assert_eq!(
+            parts,
+            vec![
+                (Addr::unchecked("a"), balance(vec![Coin::new(3, "tokens")])),
+                (
+                    Addr::unchecked("b"),
+                    balance(vec![Coin::new(6, "tokens"), Coin::new(3, "other")]),
+                ),
+            ]
+        );
+        assert_eq!(rest, balance(vec![Coin::new(1, "tokens")]));
+    }
+
+    #[test]
+    fn test_upgrade_escrows_stored_by_0_14() {
+        let mut storage = MockStorage::new();
+        let tokens = |amount: u128| GenericBalance {
+            native: vec![Coin::new(amount, "tokens")],
+            cw20: vec![],
+        };
+        let escrow = dummy_escrow();
+        let legacy = LegacyEscrow {
+            arbiter: escrow.arbiter,
+            recipient: escrow.recipient,
+            source: escrow.source,
+            title: escrow.title,
+            description: escrow.description,
+            end_height: None,
+            end_time: None,
+            balance: tokens(1_000),
+            cw20_whitelist: vec![],
+            milestones: vec![
+                LegacyMilestone {
+                    id: "1".to_string(),
+                    title: "done".to_string(),
+                    description: "done".to_string(),
+                    amount: tokens(400),
+                    end_height: None,
+                    end_time: None,
+                    is_completed: true,
+                },
+                LegacyMilestone {
+                    id: "2".to_string(),
+                    title: "todo".to_string(),
+                    description: "todo".to_string(),
+                    amount: tokens(600),
+                    end_height: None,
+                    end_time: Some(1_000),
+                    is_completed: false,
+                },
+            ],
+        };
+        LEGACY_ESCROWS.save(&mut storage, "old", &legacy).unwrap();
+
+        let by_arbiter = |storage: &MockStorage| -> Vec<String> {
+            escrows()
+                .idx
+                .arbiter
+                .prefix(Addr::unchecked("arb"))
+                .keys(storage, None, None, Order::Ascending)
+                .collect::<StdResult<_>>()
+                .unwrap()
+        };
+        assert!(by_arbiter(&storage).is_empty());
+
+        upgrade_escrows(&mut storage).unwrap();
+        assert_eq!(vec!["old".to_string()], by_arbiter(&storage));
+        let escrow = escrows().load(&storage, "old").unwrap();
+        // the completed milestone was paid out, so only the open one is still held
+        assert_eq!(tokens(600), escrow.balance);
+        let milestones = escrow.milestones;
+        let statuses: Vec<_> = milestones.iter().map(|m| m.status).collect();
+        assert_eq!(
+            vec![MilestoneStatus::Completed, MilestoneStatus::Pending],
+            statuses
+        );
+        // deadlines were past once the block went beyond them, they now are as it reaches them
+        let expires: Vec<_> = milestones.iter().map(|m| m.expires).collect();
+        assert_eq!(
+            vec![
+                Expiration::Never {},
+                Expiration::AtTime(Timestamp::from_seconds(1_001))
+            ],
+            expires
+        );
+    }
 }
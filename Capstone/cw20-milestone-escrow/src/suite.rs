@@ -0,0 +1,477 @@
+#![cfg(test)]
+
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{to_binary, Addr, Coin, Decimal, Empty, StdResult, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+use cw_utils::Expiration;
+
+use crate::{
+    msg::{
+        CreateMilestoneMsg, CreateMsg, DisputesResponse, EscrowDetailsResponse, ExecuteMsg,
+        InstantiateMsg, ListMilestonesResponse, QueryMsg, ReceiveMsg,
+    },
+    state::GenericBalance,
+};
+
+pub fn contract_escrow_milestones() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    )
+    .with_reply(crate::contract::reply);
+    Box::new(contract)
+}
+
+pub fn contract_cw20() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    );
+    Box::new(contract)
+}
+
+struct Cw20Init {
+    symbol: String,
+    initial_balances: Vec<Cw20Coin>,
+}
+
+/// Builds a multi-test `App` with the escrow contract and any number of cw20 tokens
+#[derive(Default)]
+pub struct SuiteBuilder {
+    native: Vec<(String, Vec<Coin>)>,
+    cw20s: Vec<Cw20Init>,
+}
+
+impl SuiteBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Funds `addr` with native `coins` at genesis
+    pub fn with_native(mut self, addr: &str, coins: Vec<Coin>) -> Self {
+        self.native.push((addr.to_string(), coins));
+        self
+    }
+
+    /// Adds a cw20 token with the given symbol and initial `(address, amount)` balances
+    pub fn with_cw20(mut self, symbol: &str, balances: &[(&str, u128)]) -> Self {
+        self.cw20s.push(Cw20Init {
+            symbol: symbol.to_string(),
+            initial_balances: balances
+                .iter()
+                .map(|(address, amount)| Cw20Coin {
+                    address: address.to_string(),
+                    amount: Uint128::new(*amount),
+                })
+                .collect(),
+        });
+        self
+    }
+
+    pub fn build(self) -> Suite {
+        let owner = Addr::unchecked("owner");
+        let native = self.native;
+
+        let mut app = App::new(|router, _, storage| {
+            for (addr, coins) in native {
+                router
+                    .bank
+                    .init_balance(storage, &Addr::unchecked(addr), coins)
+                    .unwrap();
+            }
+        });
+
+        let cw20_id = app.store_code(contract_cw20());
+        let cw20s = self
+            .cw20s
+            .into_iter()
+            .map(|token| {
+                let msg = cw20_base::msg::InstantiateMsg {
+                    name: format!("{} Token", token.symbol),
+                    symbol: token.symbol.clone(),
+                    decimals: 6,
+                    initial_balances: token.initial_balances,
+                    mint: None,
+                    marketing: None,
+                };
+                app.instantiate_contract(cw20_id, owner.clone(), &msg, &[], token.symbol, None)
+                    .unwrap()
+            })
+            .collect();
+
+        let escrow_id = app.store_code(contract_escrow_milestones());
+        let escrow = app
+            .instantiate_contract(
+                escrow_id,
+                owner.clone(),
+                &InstantiateMsg::default(),
+                &[],
+                "Escrow",
+                None,
+            )
+            .unwrap();
+
+        Suite { app, escrow, cw20s }
+    }
+}
+
+/// A running escrow deployment with typed helpers for the common calls
+pub struct Suite {
+    pub app: App,
+    pub escrow: Addr,
+    /// cw20 token addresses, in the order they were added to the builder
+    pub cw20s: Vec<Addr>,
+}
+
+impl Suite {
+    /// Address of the `index`-th cw20 token added to the builder
+    pub fn cw20(&self, index: usize) -> Addr {
+        self.cw20s[index].clone()
+    }
+
+    /// Advances the chain by `blocks` blocks, five seconds apart
+    pub fn next_blocks(&mut self, blocks: u64) {
+        self.app.update_block(|block| {
+            block.height += blocks;
+            block.time = block.time.plus_seconds(5 * blocks);
+        });
+    }
+
+    pub fn block_height(&self) -> u64 {
+        self.app.block_info().height
+    }
+
+    /// Creates an escrow funded with native tokens sent alongside the message
+    pub fn create_escrow(
+        &mut self,
+        sender: &str,
+        msg: CreateMsg,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.escrow.clone(),
+            &ExecuteMsg::Create(msg),
+            funds,
+        )
+    }
+
+    /// Creates an escrow funded by sending `amount` of `token` through the cw20 receive hook
+    pub fn create_escrow_cw20(
+        &mut self,
+        sender: &str,
+        token: &Addr,
+        amount: u128,
+        msg: CreateMsg,
+    ) -> AnyResult<AppResponse> {
+        let send_msg = Cw20ExecuteMsg::Send {
+            contract: self.escrow.to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&ReceiveMsg::Create(msg))?,
+        };
+        self.app
+            .execute_contract(Addr::unchecked(sender), token.clone(), &send_msg, &[])
+    }
+
+    /// Adds a milestone funded with native tokens sent alongside the message
+    pub fn create_milestone(
+        &mut self,
+        sender: &str,
+        msg: CreateMilestoneMsg,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.escrow.clone(),
+            &ExecuteMsg::CreateMilestone(msg),
+            funds,
+        )
+    }
+
+    pub fn submit(&mut self, sender: &str, id: &str, milestone_id: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.escrow.clone(),
+            &ExecuteMsg::SubmitMilestone {
+                id: id.to_string(),
+                milestone_id: milestone_id.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn approve(
+        &mut self,
+        sender: &str,
+        id: &str,
+        milestone_id: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.escrow.clone(),
+            &ExecuteMsg::ApproveMilestone {
+                id: id.to_string(),
+                milestone_id: milestone_id.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn extend(
+        &mut self,
+        sender: &str,
+        id: &str,
+        milestone_id: &str,
+        expires: Expiration,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.escrow.clone(),
+            &ExecuteMsg::ExtendMilestone {
+                id: id.to_string(),
+                milestone_id: milestone_id.to_string(),
+                expires,
+            },
+            &[],
+        )
+    }
+
+    pub fn set_recipient(
+        &mut self,
+        sender: &str,
+        id: &str,
+        recipient: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.escrow.clone(),
+            &ExecuteMsg::SetRecipient {
+                id: id.to_string(),
+                recipient: recipient.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn accept(&mut self, sender: &str, id: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.escrow.clone(),
+            &ExecuteMsg::AcceptEscrow { id: id.to_string() },
+            &[],
+        )
+    }
+
+    /// Collects what approved milestones have credited to `sender`
+    pub fn claim(&mut self, sender: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.escrow.clone(),
+            &ExecuteMsg::Claim {},
+            &[],
+        )
+    }
+
+    pub fn raise_dispute(
+        &mut self,
+        sender: &str,
+        id: &str,
+        milestone_id: &str,
+        reason: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.escrow.clone(),
+            &ExecuteMsg::RaiseDispute {
+                id: id.to_string(),
+                milestone_id: milestone_id.to_string(),
+                reason: reason.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn resolve_dispute(
+        &mut self,
+        sender: &str,
+        id: &str,
+        milestone_id: &str,
+        recipient_share: Decimal,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.escrow.clone(),
+            &ExecuteMsg::ResolveDispute {
+                id: id.to_string(),
+                milestone_id: milestone_id.to_string(),
+                recipient_share,
+            },
+            &[],
+        )
+    }
+
+    pub fn cancel_milestone(
+        &mut self,
+        sender: &str,
+        id: &str,
+        milestone_id: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.escrow.clone(),
+            &ExecuteMsg::CancelMilestone {
+                id: id.to_string(),
+                milestone_id: milestone_id.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn refund(&mut self, sender: &str, id: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.escrow.clone(),
+            &ExecuteMsg::Refund { id: id.to_string() },
+            &[],
+        )
+    }
+
+    pub fn refund_milestone(
+        &mut self,
+        sender: &str,
+        id: &str,
+        milestone_id: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.escrow.clone(),
+            &ExecuteMsg::RefundMilestone {
+                id: id.to_string(),
+                milestone_id: milestone_id.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn escrow_details(&self, id: &str) -> StdResult<EscrowDetailsResponse> {
+        self.app.wrap().query_wasm_smart(
+            &self.escrow,
+            &QueryMsg::EscrowDetails { id: id.to_string() },
+        )
+    }
+
+    pub fn disputes(&self, id: &str) -> StdResult<DisputesResponse> {
+        self.app.wrap().query_wasm_smart(
+            &self.escrow,
+            &QueryMsg::Disputes {
+                id: id.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+    }
+
+    pub fn list_milestones(&self, id: &str) -> StdResult<ListMilestonesResponse> {
+        self.app.wrap().query_wasm_smart(
+            &self.escrow,
+            &QueryMsg::ListMilestones {
+                id: id.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+    }
+
+    pub fn native_balance(&self, addr: &str, denom: &str) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(addr, denom)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    pub fn cw20_balance(&self, token: &Addr, addr: &str) -> u128 {
+        let res: BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                token,
+                &Cw20QueryMsg::Balance {
+                    address: addr.to_string(),
+                },
+            )
+            .unwrap();
+        res.balance.u128()
+    }
+}
+
+/// A milestone paying out native `coins`
+pub fn native_milestone(escrow_id: &str, title: &str, coins: Vec<Coin>) -> CreateMilestoneMsg {
+    milestone(
+        escrow_id,
+        title,
+        GenericBalance {
+            native: coins,
+            cw20: vec![],
+        },
+    )
+}
+
+/// A milestone paying out `amount` of the cw20 `token`
+pub fn cw20_milestone(
+    escrow_id: &str,
+    title: &str,
+    token: &Addr,
+    amount: u128,
+) -> CreateMilestoneMsg {
+    milestone(
+        escrow_id,
+        title,
+        GenericBalance {
+            native: vec![],
+            cw20: vec![Cw20CoinVerified {
+                address: token.clone(),
+                amount: Uint128::new(amount),
+            }],
+        },
+    )
+}
+
+fn milestone(escrow_id: &str, title: &str, amount: GenericBalance) -> CreateMilestoneMsg {
+    CreateMilestoneMsg {
+        escrow_id: escrow_id.to_string(),
+        title: title.to_string(),
+        description: format!("{} description", title),
+        amount,
+        expires: None,
+        stream: None,
+    }
+}
+
+/// An escrow with the given arbiter, optional recipient and milestones
+pub fn create_msg(
+    id: &str,
+    arbiter: &str,
+    recipient: Option<&str>,
+    milestones: Vec<CreateMilestoneMsg>,
+) -> CreateMsg {
+    CreateMsg {
+        id: id.to_string(),
+        arbiter: arbiter.to_string(),
+        co_arbiters: None,
+        threshold: None,
+        arbiter_kind: None,
+        recipient: recipient.map(String::from),
+        title: format!("{} title", id),
+        description: format!("{} description", id),
+        cw20_whitelist: None,
+        strict_whitelist: None,
+        crowdfunded: None,
+        stake: None,
+        milestones,
+    }
+}
@@ -1,20 +1,85 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coin, coins, from_binary, BankMsg, Coin, CosmosMsg, SubMsg};
-    use cw20::Cw20Coin;
+    use cosmwasm_std::{
+        coin, coins, from_binary, to_binary, Addr, BankMsg, Coin, ContractInfoResponse,
+        ContractResult, CosmosMsg, Decimal, Deps, DepsMut, Env, FullDelegation, MessageInfo, Reply,
+        Response, StakingMsg, Storage, SubMsg, SubMsgResult, SystemResult, Timestamp, Validator,
+        WasmMsg, WasmQuery,
+    };
+    use cosmwatch_migrate::MigrateError;
+    use cw2::{get_contract_version, set_contract_version};
+    use cw20::{Cw20Coin, Cw20ExecuteMsg};
+    use cw_utils::Expiration;
 
-    use crate::contract::{execute, instantiate, query, query_escrow_details};
+    use crate::contract::{
+        execute, instantiate, migrate, query, query_escrow_details, reply, sudo, CREATE_REPLY_ID,
+        FIRST_PAYOUT_REPLY_ID, MAX_EXPIRY_SCAN,
+    };
     use crate::msg::{
-        CreateMilestoneMsg, CreateMsg, EscrowDetailsResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
+        ClaimsResponse, ConfigResponse, ContributionsResponse, CreateMilestoneMsg, CreateMsg,
+        DisputesResponse, EscrowDetailsResponse, EscrowHistoryResponse, EscrowStatus,
+        EscrowSummary, ExecuteMsg, FundingStatusResponse, InstantiateMsg, ListEscrowsResponse,
+        ListMilestonesResponse, MigrateMsg, MilestoneApprovalsResponse, OwnershipResponse,
+        PausedResponse, QueryMsg, ReceiveMsg, StakeMsg, StatsResponse, SudoMsg,
+        MAX_DESCRIPTION_LENGTH, MAX_FEE_BPS, MAX_TITLE_LENGTH,
+    };
+    use crate::state::{
+        ArbiterKind, BlockStamp, Config, GenericBalance, HistoryEntry, LegacyEscrow,
+        LegacyMilestone, Milestone, MilestoneStatus, Stats, Stream, Vesting, EXPIRY_CURSOR,
+        LEGACY_ESCROWS, MAX_HISTORY, STATS,
     };
-    use crate::state::{GenericBalance, Milestone};
     use crate::ContractError;
 
     const ARBITER: &str = "arbiter";
     const RECIPIENT: &str = "recipient";
     const RECIPIENT2: &str = "recipient2";
 
+    /// The recipient accepts escrow `id`, so its milestones can be approved
+    fn accept(deps: DepsMut, id: &str) {
+        let msg = ExecuteMsg::AcceptEscrow { id: id.to_string() };
+        execute(deps, mock_env(), mock_info(RECIPIENT, &[]), msg).unwrap();
+    }
+
+    /// The recipient submits milestone `milestone_id` of escrow `id` for approval
+    fn submit(deps: DepsMut, id: &str, milestone_id: &str) {
+        let msg = ExecuteMsg::SubmitMilestone {
+            id: id.to_string(),
+            milestone_id: milestone_id.to_string(),
+        };
+        execute(deps, mock_env(), mock_info(RECIPIENT, &[]), msg).unwrap();
+    }
+
+    /// Runs `msg` along with the calls the contract makes on itself, as the chain would. A
+    /// failing call is replied to and its error returned
+    fn execute_with_self_calls(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        let res = execute(deps.branch(), env.clone(), info, msg)?;
+        for sub in &res.messages {
+            if let CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr, msg, ..
+            }) = &sub.msg
+            {
+                if *contract_addr != env.contract.address {
+                    continue;
+                }
+                let info = mock_info(contract_addr, &[]);
+                let msg = from_binary(msg).unwrap();
+                if let Err(err) = execute(deps.branch(), env.clone(), info, msg) {
+                    let result = SubMsgResult::Err(err.to_string());
+                    reply(deps.branch(), env.clone(), Reply { id: sub.id, result }).unwrap();
+                    return Err(err);
+                }
+            }
+        }
+        Ok(res)
+    }
+
     fn empty_strings() -> Vec<String> {
         vec![]
     }
@@ -29,10 +94,46 @@ mod tests {
         let env = mock_env();
         let info = mock_info("creator", &coins(1000, "native"));
 
-        let res = instantiate(deps.as_mut(), env, info, InstantiateMsg {}).unwrap();
+        let res = instantiate(deps.as_mut(), env, info, InstantiateMsg::default()).unwrap();
         assert_eq!(0, res.messages.len());
     }
 
+    #[test]
+    fn test_migrate() {
+        let mut deps = mock_dependencies();
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg::default()).unwrap();
+
+        set_contract_version(
+            deps.as_mut().storage,
+            "crates.io:cw20-escrow-milestones",
+            "0.1.0",
+        )
+        .unwrap();
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(env!("CARGO_PKG_VERSION"), version.version);
+
+        // other contracts and newer versions are rejected
+        set_contract_version(
+            deps.as_mut().storage,
+            "crates.io:cw20-escrow-milestones",
+            "99.0.0",
+        )
+        .unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Migrate(MigrateError::CannotDowngrade { .. })
+        ));
+        set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.1.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Migrate(MigrateError::InvalidContract { .. })
+        ));
+    }
+
     /**
      * Test create escrow with one milestone
      * - Native tokens
@@ -43,8 +144,8 @@ mod tests {
         let mut deps = mock_dependencies();
 
         // instantiate an empty contract
-        let instantiate_msg = InstantiateMsg {};
-        let info = mock_info(&ARBITER, &[]);
+        let instantiate_msg = InstantiateMsg::default();
+        let info = mock_info(ARBITER, &[]);
         let res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
         assert_eq!(0, res.messages.len());
 
@@ -57,17 +158,23 @@ mod tests {
                 native: vec![coin(100, "tokens")],
                 cw20: vec![],
             },
-            end_height: None,
-            end_time: None,
+            expires: None,
+            stream: None,
         }];
 
         // create an escrow
         let create_msg = CreateMsg {
             id: "escrow_1".to_string(),
             arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
             recipient: Some(RECIPIENT.to_string()),
             title: "escrow_1_title".to_string(),
             cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
             description: "escrow_1_description".to_string(),
             milestones,
         };
@@ -80,21 +187,28 @@ mod tests {
         assert_eq!(("action", "create"), res.attributes[0]);
 
         // ensure the details is what we expect
-        let details = query_escrow_details(deps.as_ref(), "escrow_1".to_string()).unwrap();
+        let details =
+            query_escrow_details(deps.as_ref(), mock_env(), "escrow_1".to_string()).unwrap();
         assert_eq!(
             details,
             EscrowDetailsResponse {
                 id: "escrow_1".to_string(),
                 arbiter: ARBITER.to_string(),
+                co_arbiters: vec![],
+                threshold: 1,
+                arbiter_kind: ArbiterKind::Address,
+                pending_arbiter: None,
                 recipient: Some(RECIPIENT.to_string()),
                 source: ARBITER.to_string(),
+                accepted: false,
                 title: "escrow_1_title".to_string(),
                 description: "escrow_1_description".to_string(),
-                end_height: None,
-                end_time: None,
                 native_balance: balance.clone(),
                 cw20_balance: vec![],
+                funded: true,
                 cw20_whitelist: vec![],
+                strict_whitelist: false,
+                crowdfunded: false,
                 milestones: vec![Milestone {
                     id: String::from("1"),
                     title: "milestone_1_title".to_string(),
@@ -103,13 +217,68 @@ mod tests {
                         native: vec![coin(100, "tokens")],
                         cw20: vec![],
                     },
-                    end_height: None,
-                    end_time: None,
-                    is_completed: false,
+                    expires: Expiration::Never {},
+                    status: MilestoneStatus::Pending,
+                    paid_out: GenericBalance::default(),
+                    approvals: vec![],
+                    cancel_consents: vec![],
+                    stream: None,
+                    created_at: BlockStamp::now(&mock_env()),
+                    updated_at: BlockStamp::now(&mock_env()),
                 }],
+                stake: None,
+                created_at: BlockStamp::now(&mock_env()),
+                updated_at: BlockStamp::now(&mock_env()),
             }
         );
 
+        // nothing can be approved before the recipient accepts, which only it can do
+        let approve = ExecuteMsg::ApproveMilestone {
+            id: create_msg.id.clone(),
+            milestone_id: String::from("1"),
+        };
+        let info = mock_info(&create_msg.arbiter, &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, approve).unwrap_err();
+        assert!(matches!(err, ContractError::NotAccepted {}));
+
+        let accept = ExecuteMsg::AcceptEscrow {
+            id: create_msg.id.clone(),
+        };
+        let info = mock_info(ARBITER, &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, accept.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let info = mock_info(RECIPIENT, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), accept.clone()).unwrap();
+        assert_eq!(("action", "accept_escrow"), res.attributes[0]);
+        let err = execute(deps.as_mut(), mock_env(), info, accept).unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyAccepted {}));
+        let details =
+            query_escrow_details(deps.as_ref(), mock_env(), "escrow_1".to_string()).unwrap();
+        assert!(details.accepted);
+
+        // the arbiter only approves what the recipient submitted, once
+        let submit = ExecuteMsg::SubmitMilestone {
+            id: create_msg.id.clone(),
+            milestone_id: String::from("1"),
+        };
+        let approve = ExecuteMsg::ApproveMilestone {
+            id: create_msg.id.clone(),
+            milestone_id: String::from("1"),
+        };
+        let info = mock_info(&create_msg.arbiter, &[]);
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), approve).unwrap_err();
+        assert!(matches!(err, ContractError::NotSubmitted {}));
+        let err = execute(deps.as_mut(), mock_env(), info, submit.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let info = mock_info(RECIPIENT, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), submit.clone()).unwrap();
+        assert_eq!(("action", "submit_milestone"), res.attributes[0]);
+        let err = execute(deps.as_mut(), mock_env(), info, submit).unwrap_err();
+        assert!(matches!(err, ContractError::MilestoneNotPending {}));
+        let details =
+            query_escrow_details(deps.as_ref(), mock_env(), "escrow_1".to_string()).unwrap();
+        assert_eq!(details.milestones[0].status, MilestoneStatus::Submitted);
+
         // approve it
         let id = create_msg.id.clone();
         let milestone_id = String::from("1");
@@ -121,14 +290,21 @@ mod tests {
             ExecuteMsg::ApproveMilestone { id, milestone_id },
         )
         .unwrap();
-        assert_eq!(1, res.messages.len());
+        assert_eq!(0, res.messages.len());
         assert_eq!(("action", "approve_milestone"), res.attributes[0]);
+
+        // the recipient collects the payout
+        let info = mock_info(RECIPIENT, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Claim {}).unwrap();
         assert_eq!(
             res.messages[0],
-            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
-                to_address: create_msg.recipient.unwrap(),
-                amount: balance,
-            }))
+            SubMsg::reply_on_error(
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: create_msg.recipient.unwrap(),
+                    amount: balance,
+                }),
+                FIRST_PAYOUT_REPLY_ID
+            )
         );
 
         // second attempt fails (not found)
@@ -157,15 +333,122 @@ mod tests {
         let msg = ExecuteMsg::Create(CreateMsg {
             id: "escrow1".to_string(),
             arbiter: "arbiter".to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
             recipient: Some("recipient".to_string()),
             title: "Title".to_string(),
             description: "Description".to_string(),
             cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
             milestones: vec![],
         });
 
         let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
-        assert!(matches!(res, Err(ContractError::EmptyMilestones {})));
+        assert!(matches!(res, Err(ContractError::EmptyMilestones)));
+    }
+
+    /**
+     * Test id and text validation on create
+     */
+    #[test]
+    fn test_create_invalid_text() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &coins(100, "tokens"));
+
+        let create = |id: &str, title: &str, milestone_description: &str| {
+            ExecuteMsg::Create(CreateMsg {
+                id: id.to_string(),
+                arbiter: ARBITER.to_string(),
+                co_arbiters: None,
+                threshold: None,
+                arbiter_kind: None,
+                recipient: Some(RECIPIENT.to_string()),
+                title: title.to_string(),
+                description: "Description".to_string(),
+                cw20_whitelist: None,
+                strict_whitelist: None,
+                crowdfunded: None,
+                stake: None,
+                milestones: vec![CreateMilestoneMsg {
+                    escrow_id: id.to_string(),
+                    title: "milestone".to_string(),
+                    description: milestone_description.to_string(),
+                    amount: GenericBalance {
+                        native: vec![coin(100, "tokens")],
+                        cw20: vec![],
+                    },
+                    expires: None,
+                    stream: None,
+                }],
+            })
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            create("e1", "Title", ""),
+        );
+        assert!(matches!(res, Err(ContractError::InvalidId { id }) if id == "e1"));
+
+        let long_id = "e".repeat(21);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            create(&long_id, "Title", ""),
+        );
+        assert!(matches!(res, Err(ContractError::InvalidId { .. })));
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            create("escrow1", "", ""),
+        );
+        assert!(matches!(
+            res,
+            Err(ContractError::InvalidTitle {
+                max: MAX_TITLE_LENGTH
+            })
+        ));
+
+        let long_title = "t".repeat(MAX_TITLE_LENGTH + 1);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            create("escrow1", &long_title, ""),
+        );
+        assert!(matches!(res, Err(ContractError::InvalidTitle { .. })));
+
+        let long_description = "d".repeat(MAX_DESCRIPTION_LENGTH + 1);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            create("escrow1", "Title", &long_description),
+        );
+        assert!(matches!(
+            res,
+            Err(ContractError::DescriptionTooLong {
+                max: MAX_DESCRIPTION_LENGTH
+            })
+        ));
+
+        let title = "t".repeat(MAX_TITLE_LENGTH);
+        let description = "d".repeat(MAX_DESCRIPTION_LENGTH);
+        execute(
+            deps.as_mut(),
+            env,
+            info,
+            create("escrow1", &title, &description),
+        )
+        .unwrap();
     }
 
     /**
@@ -182,10 +465,16 @@ mod tests {
         let msg = ExecuteMsg::Create(CreateMsg {
             id: "escrow_1".to_string(),
             arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
             recipient: Some(RECIPIENT.to_string()),
             title: "escrow_1_title".to_string(),
             description: "escrow_1_description".to_string(),
             cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
             milestones: vec![
                 CreateMilestoneMsg {
                     escrow_id: "escrow_1".to_string(),
@@ -195,8 +484,8 @@ mod tests {
                         native: vec![coin(100, "tokens")],
                         cw20: vec![],
                     },
-                    end_height: None,
-                    end_time: None,
+                    expires: None,
+                    stream: None,
                 },
                 CreateMilestoneMsg {
                     escrow_id: "escrow_1".to_string(),
@@ -206,8 +495,8 @@ mod tests {
                         native: vec![coin(100, "tokens")],
                         cw20: vec![],
                     },
-                    end_height: None,
-                    end_time: None,
+                    expires: None,
+                    stream: None,
                 },
             ],
         });
@@ -216,6 +505,427 @@ mod tests {
         assert_eq!(0, res.messages.len());
     }
 
+    #[test]
+    fn test_create_reconciles_every_token() {
+        let mut deps = mock_dependencies();
+        let create = |id: &str| CreateMsg {
+            id: id.to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "title".to_string(),
+            description: "description".to_string(),
+            cw20_whitelist: Some(vec!["token_a".to_string(), "token_b".to_string()]),
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![
+                CreateMilestoneMsg {
+                    escrow_id: id.to_string(),
+                    title: "milestone_1_title".to_string(),
+                    description: "milestone_1_description".to_string(),
+                    amount: GenericBalance {
+                        native: vec![coin(100, "tokens"), coin(50, "stake")],
+                        cw20: vec![],
+                    },
+                    expires: None,
+                    stream: None,
+                },
+                CreateMilestoneMsg {
+                    escrow_id: id.to_string(),
+                    title: "milestone_2_title".to_string(),
+                    description: "milestone_2_description".to_string(),
+                    amount: GenericBalance {
+                        native: vec![coin(100, "tokens")],
+                        cw20: vec![cw20::Cw20CoinVerified {
+                            address: Addr::unchecked("token_a"),
+                            amount: 30u128.into(),
+                        }],
+                    },
+                    expires: None,
+                    stream: None,
+                },
+            ],
+        };
+        let mismatch = |token: &str| ContractError::FundsMismatch {
+            token: token.to_string(),
+        };
+
+        // every denom sent is checked, not just the first one
+        let info = mock_info("source", &[coin(200, "tokens"), coin(51, "stake")]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Create(create("escrow_1")),
+        )
+        .unwrap_err();
+        assert_eq!(err, mismatch("stake"));
+        let info = mock_info("source", &[coin(200, "tokens"), coin(1, "uatom")]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Create(create("escrow_1")),
+        )
+        .unwrap_err();
+        assert_eq!(err, mismatch("uatom"));
+
+        // and so is every cw20 token
+        let mut receive = |token: &str, amount: u128| {
+            let msg = ExecuteMsg::Receive(cw20::Cw20ReceiveMsg {
+                sender: "source".to_string(),
+                amount: amount.into(),
+                msg: to_binary(&ReceiveMsg::Create(create("escrow_1"))).unwrap(),
+            });
+            execute_with_self_calls(deps.as_mut(), mock_env(), mock_info(token, &[]), msg)
+        };
+        assert_eq!(receive("token_b", 1).unwrap_err(), mismatch("token_b"));
+        assert_eq!(receive("token_a", 31).unwrap_err(), mismatch("token_a"));
+        receive("token_a", 30).unwrap();
+
+        // all of it, or less to top up later, is fine
+        let info = mock_info("source", &[coin(200, "tokens"), coin(50, "stake")]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Create(create("escrow_2")),
+        )
+        .unwrap();
+        let info = mock_info("source", &[coin(150, "tokens")]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Create(create("escrow_3")),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_strict_whitelist() {
+        let mut deps = mock_dependencies();
+        let create = |id: &str, strict_whitelist: Option<bool>| CreateMsg {
+            id: id.to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "title".to_string(),
+            description: "description".to_string(),
+            cw20_whitelist: Some(vec!["listed".to_string()]),
+            strict_whitelist,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![CreateMilestoneMsg {
+                escrow_id: id.to_string(),
+                title: "milestone_1_title".to_string(),
+                description: "milestone_1_description".to_string(),
+                amount: GenericBalance {
+                    native: vec![],
+                    cw20: vec![
+                        cw20::Cw20CoinVerified {
+                            address: Addr::unchecked("listed"),
+                            amount: 100u128.into(),
+                        },
+                        cw20::Cw20CoinVerified {
+                            address: Addr::unchecked("unlisted"),
+                            amount: 100u128.into(),
+                        },
+                    ],
+                },
+                expires: None,
+                stream: None,
+            }],
+        };
+        let receive = |token: &str, sender: &str, msg: ReceiveMsg| {
+            let msg = ExecuteMsg::Receive(cw20::Cw20ReceiveMsg {
+                sender: sender.to_string(),
+                amount: 100u128.into(),
+                msg: to_binary(&msg).unwrap(),
+            });
+            (mock_info(token, &[]), msg)
+        };
+
+        // by default a token sent is added to the whitelist
+        let (info, msg) = receive(
+            "unlisted",
+            "source",
+            ReceiveMsg::Create(create("lenient", None)),
+        );
+        execute_with_self_calls(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let details =
+            query_escrow_details(deps.as_ref(), mock_env(), "lenient".to_string()).unwrap();
+        assert!(!details.strict_whitelist);
+        assert_eq!(details.cw20_whitelist, vec!["listed", "unlisted"]);
+
+        // a strict escrow only takes the tokens listed up front
+        let (info, msg) = receive(
+            "unlisted",
+            "source",
+            ReceiveMsg::Create(create("strict", Some(true))),
+        );
+        let err = execute_with_self_calls(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::NotInWhitelist {});
+        let (info, msg) = receive(
+            "listed",
+            "source",
+            ReceiveMsg::Create(create("strict", Some(true))),
+        );
+        execute_with_self_calls(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let details =
+            query_escrow_details(deps.as_ref(), mock_env(), "strict".to_string()).unwrap();
+        assert!(details.strict_whitelist);
+        assert_eq!(details.cw20_whitelist, vec!["listed"]);
+
+        let (info, msg) = receive(
+            "unlisted",
+            "source",
+            ReceiveMsg::TopUp {
+                id: "strict".to_string(),
+            },
+        );
+        let err = execute_with_self_calls(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::NotInWhitelist {});
+        let milestone = create("strict", None).milestones.remove(0);
+        let (info, msg) = receive("unlisted", ARBITER, ReceiveMsg::CreateMilestone(milestone));
+        let err = execute_with_self_calls(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::NotInWhitelist {});
+    }
+
+    #[test]
+    fn test_receive_create_returns_tokens_on_failure() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let create = |id: &str| CreateMsg {
+            id: id.to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "title".to_string(),
+            description: "description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![CreateMilestoneMsg {
+                escrow_id: id.to_string(),
+                title: "milestone_1_title".to_string(),
+                description: "milestone_1_description".to_string(),
+                amount: GenericBalance {
+                    native: vec![],
+                    cw20: vec![cw20::Cw20CoinVerified {
+                        address: Addr::unchecked("token"),
+                        amount: 100u128.into(),
+                    }],
+                },
+                expires: None,
+                stream: None,
+            }],
+        };
+        let receive = |msg: CreateMsg| {
+            ExecuteMsg::Receive(cw20::Cw20ReceiveMsg {
+                sender: "source".to_string(),
+                amount: 100u128.into(),
+                msg: to_binary(&ReceiveMsg::Create(msg)).unwrap(),
+            })
+        };
+
+        // the create is handed to the contract itself, and only it can make that call
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("token", &[]),
+            receive(create("no")),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: env.contract.address.to_string(),
+                    msg: to_binary(&ExecuteMsg::CreateReceived(create("no"))).unwrap(),
+                    funds: vec![],
+                },
+                CREATE_REPLY_ID,
+            )]
+        );
+        let msg = ExecuteMsg::CreateReceived(create("escrow_1"));
+        let err = execute(deps.as_mut(), env.clone(), mock_info("source", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // when it fails the tokens go back to their sender
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(env.contract.address.as_str(), &[]),
+            ExecuteMsg::CreateReceived(create("no")),
+        )
+        .unwrap_err();
+        let result = SubMsgResult::Err(err.to_string());
+        let res = reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: CREATE_REPLY_ID,
+                result,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "source".to_string(),
+                    amount: 100u128.into(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+
+        // and when it succeeds they fund the escrow
+        execute_with_self_calls(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("token", &[]),
+            receive(create("escrow_1")),
+        )
+        .unwrap();
+        let details = query_escrow_details(deps.as_ref(), env, "escrow_1".to_string()).unwrap();
+        assert_eq!(details.source, "source");
+        assert!(details.funded);
+    }
+
+    #[test]
+    fn test_update_cw20_whitelist() {
+        let mut deps = mock_dependencies();
+        let cw20 = |address: &str, amount: u128| cw20::Cw20CoinVerified {
+            address: Addr::unchecked(address),
+            amount: amount.into(),
+        };
+        let create = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "title".to_string(),
+            description: "description".to_string(),
+            cw20_whitelist: Some(vec!["token_a".to_string()]),
+            strict_whitelist: Some(true),
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![CreateMilestoneMsg {
+                escrow_id: "escrow_1".to_string(),
+                title: "milestone_1_title".to_string(),
+                description: "milestone_1_description".to_string(),
+                amount: GenericBalance {
+                    native: vec![coin(100, "tokens")],
+                    cw20: vec![cw20("token_a", 10), cw20("token_b", 10)],
+                },
+                expires: None,
+                stream: None,
+            }],
+        });
+        let info = mock_info("source", &coins(100, "tokens"));
+        execute(deps.as_mut(), mock_env(), info, create).unwrap();
+        let update = |add: &[&str], remove: &[&str]| ExecuteMsg::UpdateCw20Whitelist {
+            id: "escrow_1".to_string(),
+            add: add.iter().map(|token| token.to_string()).collect(),
+            remove: remove.iter().map(|token| token.to_string()).collect(),
+        };
+        let top_up = |token: &str| {
+            let msg = ExecuteMsg::Receive(cw20::Cw20ReceiveMsg {
+                sender: "source".to_string(),
+                amount: 10u128.into(),
+                msg: to_binary(&ReceiveMsg::TopUp {
+                    id: "escrow_1".to_string(),
+                })
+                .unwrap(),
+            });
+            (mock_info(token, &[]), msg)
+        };
+        let whitelist = |deps: Deps| {
+            query_escrow_details(deps, mock_env(), "escrow_1".to_string())
+                .unwrap()
+                .cw20_whitelist
+        };
+
+        // only the arbiter changes the whitelist
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("source", &[]),
+            update(&["token_b"], &[]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // the strict escrow takes token_b once it is whitelisted
+        let (info, msg) = top_up("token_b");
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::NotInWhitelist {});
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ARBITER, &[]),
+            update(&["token_b", "token_c"], &[]),
+        )
+        .unwrap();
+        assert_eq!(("added", "token_b,token_c"), res.attributes[2]);
+        assert_eq!(
+            whitelist(deps.as_ref()),
+            vec!["token_a", "token_b", "token_c"]
+        );
+        let (info, msg) = top_up("token_b");
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // tokens held or still owed stay, unused ones can go
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ARBITER, &[]),
+            update(&[], &["token_c", "token_b"]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::TokenInUse {
+                token: "token_b".to_string()
+            }
+        );
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ARBITER, &[]),
+            update(&[], &["token_a"]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::TokenInUse {
+                token: "token_a".to_string()
+            }
+        );
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ARBITER, &[]),
+            update(&[], &["token_c"]),
+        )
+        .unwrap();
+        assert_eq!(whitelist(deps.as_ref()), vec!["token_a", "token_b"]);
+    }
+
     #[test]
     fn test_set_receipient() {
         let mut deps = mock_dependencies();
@@ -226,10 +936,16 @@ mod tests {
         let create_msg = CreateMsg {
             id: "escrow_1".to_string(),
             arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
             recipient: Some(RECIPIENT.to_string()),
             title: "escrow_1_title".to_string(),
             description: "escrow_1_description".to_string(),
             cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
             milestones: vec![CreateMilestoneMsg {
                 escrow_id: "escrow_1".to_string(),
                 title: "milestone_1_title".to_string(),
@@ -238,15 +954,16 @@ mod tests {
                     native: vec![coin(100, "tokens")],
                     cw20: vec![],
                 },
-                end_height: None,
-                end_time: None,
+                expires: None,
+                stream: None,
             }],
         };
         let msg = ExecuteMsg::Create(create_msg.clone());
         let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
         assert_eq!(0, res.messages.len());
+        accept(deps.as_mut(), "escrow_1");
 
-        // Set recipient
+        // Set recipient, who has to accept the escrow again
         let id = create_msg.id.clone();
         let info = mock_info(&create_msg.arbiter, &[]);
         let msg = ExecuteMsg::SetRecipient {
@@ -255,6 +972,9 @@ mod tests {
         };
         let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
         assert_eq!(0, res.messages.len());
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert!(!details.accepted);
 
         // Attempt setting empty recipient and assert failure
         let id = create_msg.id.clone();
@@ -277,10 +997,16 @@ mod tests {
         let msg = ExecuteMsg::Create(CreateMsg {
             id: "escrow_1".to_string(),
             arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
             recipient: Some(RECIPIENT.to_string()),
             title: "escrow_1_title".to_string(),
             description: "escrow_1_description".to_string(),
             cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
             milestones: vec![
                 CreateMilestoneMsg {
                     escrow_id: "escrow_1".to_string(),
@@ -290,8 +1016,8 @@ mod tests {
                         native: vec![coin(100, "tokens")],
                         cw20: vec![],
                     },
-                    end_height: None,
-                    end_time: None,
+                    expires: None,
+                    stream: None,
                 },
                 CreateMilestoneMsg {
                     escrow_id: "escrow_1".to_string(),
@@ -301,8 +1027,8 @@ mod tests {
                         native: vec![coin(100, "tokens")],
                         cw20: vec![],
                     },
-                    end_height: None,
-                    end_time: None,
+                    expires: None,
+                    stream: None,
                 },
             ],
         });
@@ -322,146 +1048,3116 @@ mod tests {
         assert_eq!(ARBITER, escrow.arbiter);
         assert_eq!(ARBITER, escrow.source);
         assert_eq!(RECIPIENT, escrow.recipient.unwrap());
-        assert_eq!(None, escrow.end_height);
-        assert_eq!(None, escrow.end_time);
+        assert_eq!(Expiration::Never {}, escrow.milestones[0].expires);
         assert_eq!(empty_strings(), escrow.cw20_whitelist);
         assert_eq!(vec![Coin::new(200, "tokens")], escrow.native_balance);
         assert_eq!(empty_cw20_coins(), escrow.cw20_balance);
+        let created = BlockStamp::now(&env);
+        assert_eq!(created, escrow.created_at);
+        assert_eq!(created, escrow.updated_at);
+
+        // a change stamps the escrow and the milestones it touched
+        let mut later = env.clone();
+        later.block.height += 5;
+        later.block.time = later.block.time.plus_seconds(30);
+        let msg = ExecuteMsg::AcceptEscrow {
+            id: "escrow_1".to_string(),
+        };
+        execute(deps.as_mut(), later.clone(), mock_info(RECIPIENT, &[]), msg).unwrap();
+        let msg = ExecuteMsg::SubmitMilestone {
+            id: "escrow_1".to_string(),
+            milestone_id: "2".to_string(),
+        };
+        execute(deps.as_mut(), later.clone(), mock_info(RECIPIENT, &[]), msg).unwrap();
+        let escrow =
+            query_escrow_details(deps.as_ref(), later.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(created, escrow.created_at);
+        assert_eq!(BlockStamp::now(&later), escrow.updated_at);
+        assert_eq!(created, escrow.milestones[0].updated_at);
+        let msg = QueryMsg::MilestoneDetails {
+            id: "escrow_1".to_string(),
+            milestone_id: "2".to_string(),
+        };
+        let milestone: Milestone = from_binary(&query(deps.as_ref(), later, msg).unwrap()).unwrap();
+        assert_eq!(created, milestone.created_at);
+        assert_eq!(created.height + 5, milestone.updated_at.height);
     }
 
     #[test]
-    fn test_extend_escrow_milestone_time() {
+    fn test_list_summaries() {
         let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ARBITER, &coins(300, "tokens"));
 
-        // instantiate an empty contract
-        let instantiate_msg = InstantiateMsg {};
-        let info = mock_info(&ARBITER, &[]);
-        let res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        let milestone = |amount: u128| CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: "milestone_title".to_string(),
+            description: "milestone_description".to_string(),
+            amount: GenericBalance {
+                native: vec![coin(amount, "tokens")],
+                cw20: vec![],
+            },
+            expires: None,
+            stream: None,
+        };
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![milestone(100), milestone(200)],
+        });
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ARBITER, &[]),
+            ExecuteMsg::ApproveMilestone {
+                id: "escrow_1".to_string(),
+                milestone_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), env, QueryMsg::List { status: None }).unwrap();
+        let list: ListEscrowsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            list.escrows,
+            vec![EscrowSummary {
+                id: "escrow_1".to_string(),
+                arbiter: ARBITER.to_string(),
+                recipient: Some(RECIPIENT.to_string()),
+                title: "escrow_1_title".to_string(),
+                native_balance: vec![coin(200, "tokens")],
+                cw20_balance: vec![],
+                milestones: 2,
+                completed_milestones: 1,
+                status: EscrowStatus::Active,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_list_by_status() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        for (id, expires) in [
+            ("escrow_1", None),
+            (
+                "escrow_2",
+                Some(Expiration::AtHeight(env.block.height + 10)),
+            ),
+        ] {
+            let msg = ExecuteMsg::Create(CreateMsg {
+                id: id.to_string(),
+                arbiter: ARBITER.to_string(),
+                co_arbiters: None,
+                threshold: None,
+                arbiter_kind: None,
+                recipient: Some(RECIPIENT.to_string()),
+                title: format!("{}_title", id),
+                description: format!("{}_description", id),
+                cw20_whitelist: None,
+                strict_whitelist: None,
+                crowdfunded: None,
+                stake: None,
+                milestones: vec![CreateMilestoneMsg {
+                    escrow_id: id.to_string(),
+                    title: "milestone_title".to_string(),
+                    description: "milestone_description".to_string(),
+                    amount: GenericBalance {
+                        native: vec![coin(100, "tokens")],
+                        cw20: vec![],
+                    },
+                    expires,
+                    stream: None,
+                }],
+            });
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(ARBITER, &coins(100, "tokens")),
+                msg,
+            )
+            .unwrap();
+        }
+
+        let mut later = mock_env();
+        later.block.height += 11;
+        let ids = |status: Option<EscrowStatus>| -> Vec<String> {
+            let res = query(deps.as_ref(), later.clone(), QueryMsg::List { status }).unwrap();
+            let list: ListEscrowsResponse = from_binary(&res).unwrap();
+            list.escrows.into_iter().map(|e| e.id).collect()
+        };
+        assert_eq!(ids(None), vec!["escrow_1", "escrow_2"]);
+        assert_eq!(ids(Some(EscrowStatus::Active)), vec!["escrow_1"]);
+        assert_eq!(ids(Some(EscrowStatus::Expired)), vec!["escrow_2"]);
+        // completed escrows pay out and are removed
+        assert!(ids(Some(EscrowStatus::Completed)).is_empty());
+    }
+
+    #[test]
+    fn test_milestone_status() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let milestone = |expires: Option<Expiration>| CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: "milestone_title".to_string(),
+            description: "milestone_description".to_string(),
+            amount: GenericBalance {
+                native: vec![coin(100, "tokens")],
+                cw20: vec![],
+            },
+            expires,
+            stream: None,
+        };
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![
+                milestone(None),
+                milestone(Some(Expiration::AtHeight(env.block.height + 10))),
+            ],
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ARBITER, &coins(200, "tokens")),
+            msg,
+        )
+        .unwrap();
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+        submit(deps.as_mut(), "escrow_1", "2");
+
+        let approve = ExecuteMsg::ApproveMilestone {
+            id: "escrow_1".to_string(),
+            milestone_id: "1".to_string(),
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ARBITER, &[]),
+            approve.clone(),
+        )
+        .unwrap();
+        // a completed milestone can't pay out twice
+        let err = execute(deps.as_mut(), env, mock_info(ARBITER, &[]), approve).unwrap_err();
+        assert_eq!(err, ContractError::MilestoneNotPending {});
+
+        // the submitted milestone reads as expired once its deadline passes unapproved
+        let mut later = mock_env();
+        later.block.height += 11;
+        let details =
+            query_escrow_details(deps.as_ref(), later.clone(), "escrow_1".to_string()).unwrap();
+        let statuses: Vec<_> = details.milestones.iter().map(|m| m.status).collect();
+        assert_eq!(
+            statuses,
+            vec![MilestoneStatus::Completed, MilestoneStatus::Expired]
+        );
+
+        // and lists the same way, a page at a time
+        let list = |start_after: Option<&str>| {
+            let msg = QueryMsg::ListMilestones {
+                id: "escrow_1".to_string(),
+                start_after: start_after.map(String::from),
+                limit: Some(1),
+            };
+            from_binary::<ListMilestonesResponse>(
+                &query(deps.as_ref(), later.clone(), msg).unwrap(),
+            )
+            .unwrap()
+            .milestones
+        };
+        let first = list(None);
+        assert_eq!(first, vec![details.milestones[0].clone()]);
+        let second = list(Some("1"));
+        assert_eq!(second, vec![details.milestones[1].clone()]);
+        assert!(list(Some("2")).is_empty());
+    }
+
+    #[test]
+    fn test_refund_after_partial_approval() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let milestone = |amount: u128| CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: "milestone_title".to_string(),
+            description: "milestone_description".to_string(),
+            amount: GenericBalance {
+                native: vec![coin(amount, "tokens")],
+                cw20: vec![],
+            },
+            expires: None,
+            stream: None,
+        };
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![milestone(100), milestone(200)],
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("source", &coins(300, "tokens")),
+            msg,
+        )
+        .unwrap();
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ARBITER, &[]),
+            ExecuteMsg::ApproveMilestone {
+                id: "escrow_1".to_string(),
+                milestone_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(details.native_balance, coins(200, "tokens"));
+        assert_eq!(details.milestones[0].paid_out.native, coins(100, "tokens"));
+        assert!(details.milestones[1].paid_out.is_empty());
+
+        // the refund returns only what was not paid out
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(ARBITER, &[]),
+            ExecuteMsg::Refund {
+                id: "escrow_1".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_on_error(
+                BankMsg::Send {
+                    to_address: "source".to_string(),
+                    amount: coins(200, "tokens"),
+                },
+                FIRST_PAYOUT_REPLY_ID
+            )]
+        );
+    }
+
+    #[test]
+    fn test_escrow_history() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+
+        let create = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![CreateMilestoneMsg {
+                escrow_id: "escrow_1".to_string(),
+                title: "milestone_title".to_string(),
+                description: "milestone_description".to_string(),
+                amount: GenericBalance {
+                    native: coins(100, "tokens"),
+                    cw20: vec![],
+                },
+                expires: None,
+                stream: None,
+            }],
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("source", &coins(100, "tokens")),
+            create.clone(),
+        )
+        .unwrap();
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+        env.block.height += 1;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ARBITER, &[]),
+            ExecuteMsg::ApproveMilestone {
+                id: "escrow_1".to_string(),
+                milestone_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+
+        // the history outlives the escrow the approval closed
+        let history = |deps: Deps, start_after: Option<u64>, limit: Option<u32>| {
+            let msg = QueryMsg::EscrowHistory {
+                id: "escrow_1".to_string(),
+                start_after,
+                limit,
+            };
+            from_binary::<EscrowHistoryResponse>(&query(deps, mock_env(), msg).unwrap())
+                .unwrap()
+                .entries
+        };
+        let paid = GenericBalance {
+            native: coins(100, "tokens"),
+            cw20: vec![],
+        };
+        let entry =
+            |id: u64, actor: &str, action: &str, milestone_id, amount, height| HistoryEntry {
+                id,
+                actor: Addr::unchecked(actor),
+                action: action.to_string(),
+                milestone_id,
+                amount,
+                height,
+            };
+        let height = mock_env().block.height;
+        let one = Some("1".to_string());
+        assert_eq!(
+            history(deps.as_ref(), None, None),
+            vec![
+                entry(1, "source", "create", None, Some(paid.clone()), height),
+                entry(2, RECIPIENT, "accept_escrow", None, None, height),
+                entry(3, RECIPIENT, "submit_milestone", one.clone(), None, height),
+                entry(4, ARBITER, "approve_milestone", one, Some(paid), height + 1),
+            ]
+        );
+        let page = history(deps.as_ref(), Some(2), Some(1));
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].id, 3);
+
+        // a new escrow under the id starts its own history, which keeps only the latest entries
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("source", &coins(100, "tokens")),
+            create,
+        )
+        .unwrap();
+        assert_eq!(history(deps.as_ref(), None, None).len(), 1);
+        for _ in 0..MAX_HISTORY {
+            let msg = ExecuteMsg::TransferArbiter {
+                id: "escrow_1".to_string(),
+                new_arbiter: "new_arbiter".to_string(),
+            };
+            execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), msg).unwrap();
+        }
+        let oldest = &history(deps.as_ref(), None, Some(1))[0];
+        assert_eq!(oldest.id, 2);
+        assert_eq!(oldest.action, "transfer_arbiter");
+        let latest = history(deps.as_ref(), Some(MAX_HISTORY), None);
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].id, MAX_HISTORY + 1);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), env.clone(), info, InstantiateMsg::default()).unwrap();
+
+        let create = |id: &str, amounts: &[u128]| {
+            ExecuteMsg::Create(CreateMsg {
+                id: id.to_string(),
+                arbiter: ARBITER.to_string(),
+                co_arbiters: None,
+                threshold: None,
+                arbiter_kind: None,
+                recipient: Some(RECIPIENT.to_string()),
+                title: "escrow_title".to_string(),
+                description: "escrow_description".to_string(),
+                cw20_whitelist: None,
+                strict_whitelist: None,
+                crowdfunded: None,
+                stake: None,
+                milestones: amounts
+                    .iter()
+                    .map(|&amount| CreateMilestoneMsg {
+                        escrow_id: id.to_string(),
+                        title: "milestone_title".to_string(),
+                        description: "milestone_description".to_string(),
+                        amount: GenericBalance {
+                            native: coins(amount, "tokens"),
+                            cw20: vec![],
+                        },
+                        expires: None,
+                        stream: None,
+                    })
+                    .collect(),
+            })
+        };
+        let stats = |deps: Deps| {
+            from_binary::<StatsResponse>(&query(deps, mock_env(), QueryMsg::Stats {}).unwrap())
+                .unwrap()
+                .stats
+        };
+        let locked = |amount: u128| GenericBalance {
+            native: coins(amount, "tokens"),
+            cw20: vec![],
+        };
+
+        let info = mock_info("source", &coins(300, "tokens"));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            create("escrow_1", &[100, 200]),
+        )
+        .unwrap();
+        let info = mock_info("source", &coins(50, "tokens"));
+        execute(deps.as_mut(), env.clone(), info, create("escrow_2", &[50])).unwrap();
+        assert_eq!(
+            stats(deps.as_ref()),
+            Stats {
+                escrows: 2,
+                open: 2,
+                completed: 0,
+                locked: locked(350),
+            }
+        );
+
+        // payouts leave the locked value, the last one completing the escrow
+        accept(deps.as_mut(), "escrow_1");
+        for milestone_id in ["1", "2"] {
+            submit(deps.as_mut(), "escrow_1", milestone_id);
+            let msg = ExecuteMsg::ApproveMilestone {
+                id: "escrow_1".to_string(),
+                milestone_id: milestone_id.to_string(),
+            };
+            execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), msg).unwrap();
+        }
+        assert_eq!(
+            stats(deps.as_ref()),
+            Stats {
+                escrows: 2,
+                open: 1,
+                completed: 1,
+                locked: locked(50),
+            }
+        );
+
+        // a refund closes an escrow without completing it
+        let msg = ExecuteMsg::Refund {
+            id: "escrow_2".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), msg).unwrap();
+        assert_eq!(
+            stats(deps.as_ref()),
+            Stats {
+                escrows: 2,
+                open: 0,
+                completed: 1,
+                locked: GenericBalance::default(),
+            }
+        );
+
+        // contracts from before the stats count the escrows they hold when migrated
+        let info = mock_info("source", &coins(50, "tokens"));
+        execute(deps.as_mut(), env.clone(), info, create("escrow_3", &[50])).unwrap();
+        STATS.remove(deps.as_mut().storage);
+        migrate(deps.as_mut(), env, MigrateMsg {}).unwrap();
+        assert_eq!(
+            stats(deps.as_ref()),
+            Stats {
+                escrows: 1,
+                open: 1,
+                completed: 0,
+                locked: locked(50),
+            }
+        );
+    }
+
+    #[test]
+    fn test_claims() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        for (id, amount) in [("escrow_1", 100), ("escrow_2", 50)] {
+            let msg = ExecuteMsg::Create(CreateMsg {
+                id: id.to_string(),
+                arbiter: ARBITER.to_string(),
+                co_arbiters: None,
+                threshold: None,
+                arbiter_kind: None,
+                recipient: Some(RECIPIENT.to_string()),
+                title: format!("{}_title", id),
+                description: format!("{}_description", id),
+                cw20_whitelist: None,
+                strict_whitelist: None,
+                crowdfunded: None,
+                stake: None,
+                milestones: vec![CreateMilestoneMsg {
+                    escrow_id: id.to_string(),
+                    title: "milestone_title".to_string(),
+                    description: "milestone_description".to_string(),
+                    amount: GenericBalance {
+                        native: vec![coin(amount, "tokens")],
+                        cw20: vec![],
+                    },
+                    expires: None,
+                    stream: None,
+                }],
+            });
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("source", &coins(amount, "tokens")),
+                msg,
+            )
+            .unwrap();
+            accept(deps.as_mut(), id);
+            submit(deps.as_mut(), id, "1");
+            let approve = ExecuteMsg::ApproveMilestone {
+                id: id.to_string(),
+                milestone_id: "1".to_string(),
+            };
+            execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), approve).unwrap();
+        }
+
+        // payouts from every escrow add up until claimed
+        let claims = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| -> ClaimsResponse {
+            let msg = QueryMsg::Claims {
+                address: RECIPIENT.to_string(),
+            };
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+        };
+        assert_eq!(claims(&deps).native_balance, coins(150, "tokens"));
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NothingToClaim {});
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(RECIPIENT, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_on_error(
+                BankMsg::Send {
+                    to_address: RECIPIENT.to_string(),
+                    amount: coins(150, "tokens"),
+                },
+                FIRST_PAYOUT_REPLY_ID
+            )]
+        );
+        assert!(claims(&deps).native_balance.is_empty());
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info(RECIPIENT, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NothingToClaim {});
+    }
+
+    #[test]
+    fn test_failed_payout_is_restored() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+
+        let milestone = |amount: u128, expires: Option<Expiration>| CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: "milestone_title".to_string(),
+            description: "milestone_description".to_string(),
+            amount: GenericBalance {
+                native: coins(amount, "tokens"),
+                cw20: vec![],
+            },
+            expires,
+            stream: None,
+        };
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![
+                milestone(100, None),
+                milestone(200, Some(Expiration::AtHeight(env.block.height + 10))),
+            ],
+        });
+        let info = mock_info("source", &coins(300, "tokens"));
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+        let msg = ExecuteMsg::ApproveMilestone {
+            id: "escrow_1".to_string(),
+            milestone_id: "1".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), msg).unwrap();
+        let fail = |deps: DepsMut, env: Env, id: u64| {
+            let result = SubMsgResult::Err("send failed".to_string());
+            reply(deps, env, Reply { id, result }).unwrap()
+        };
+
+        // a claim that doesn't go through is credited back
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(RECIPIENT, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap();
+        assert_eq!(res.messages[0].id, FIRST_PAYOUT_REPLY_ID);
+        fail(deps.as_mut(), env.clone(), FIRST_PAYOUT_REPLY_ID);
+        let msg = QueryMsg::Claims {
+            address: RECIPIENT.to_string(),
+        };
+        let claims: ClaimsResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(claims.native_balance, coins(100, "tokens"));
+
+        // and an escrow closed by a refund that doesn't go through is back as it was
+        env.block.height += 11;
+        let msg = ExecuteMsg::RefundMilestone {
+            id: "escrow_1".to_string(),
+            milestone_id: "2".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "is_escrow_complete"));
+        assert!(query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).is_err());
+        fail(deps.as_mut(), env.clone(), res.messages[0].id);
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(details.native_balance, coins(200, "tokens"));
+        assert_eq!(details.milestones[0].status, MilestoneStatus::Completed);
+        assert_eq!(details.milestones[1].status, MilestoneStatus::Expired);
+
+        // a reply only ever comes for a failure, one that went through is forgotten
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(RECIPIENT, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap();
+        env.block.height += 1;
+        let msg = ExecuteMsg::Refund {
+            id: "escrow_1".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), msg).unwrap();
+        let msg = Reply {
+            id: res.messages[0].id,
+            result: SubMsgResult::Err("too late".to_string()),
+        };
+        reply(deps.as_mut(), env, msg).unwrap_err();
+    }
+
+    #[test]
+    fn test_streamed_milestone() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let now = env.block.time.seconds();
+        let at = |seconds: u64| {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(seconds);
+            env
+        };
+
+        let milestone = |title: &str, amount: u128, stream: Option<Stream>| CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: title.to_string(),
+            description: format!("{} description", title),
+            amount: GenericBalance {
+                native: coins(amount, "tokens"),
+                cw20: vec![],
+            },
+            expires: None,
+            stream,
+        };
+        let create = |stream: Stream| {
+            ExecuteMsg::Create(CreateMsg {
+                id: "escrow_1".to_string(),
+                arbiter: ARBITER.to_string(),
+                co_arbiters: None,
+                threshold: None,
+                arbiter_kind: None,
+                recipient: Some(RECIPIENT.to_string()),
+                title: "escrow_1_title".to_string(),
+                description: "escrow_1_description".to_string(),
+                cw20_whitelist: None,
+                strict_whitelist: None,
+                crowdfunded: None,
+                stake: None,
+                milestones: vec![
+                    milestone("streamed", 100, Some(stream)),
+                    milestone("lump", 50, None),
+                ],
+            })
+        };
+        let info = mock_info("source", &coins(150, "tokens"));
+        let empty_stream = Stream {
+            start: now + 100,
+            end: now + 100,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            create(empty_stream),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidStream {});
+        let stream = Stream {
+            start: now,
+            end: now + 100,
+        };
+        execute(deps.as_mut(), env.clone(), info, create(stream.clone())).unwrap();
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+        submit(deps.as_mut(), "escrow_1", "2");
+        let approve = ExecuteMsg::ApproveMilestones {
+            id: "escrow_1".to_string(),
+            milestone_ids: vec!["1".to_string(), "2".to_string()],
+        };
+        execute(deps.as_mut(), env, mock_info(ARBITER, &[]), approve).unwrap();
+
+        // the lump sum is claimable right away, the streamed one as it vests
+        let claims = |deps: Deps, env: Env| -> ClaimsResponse {
+            let msg = QueryMsg::Claims {
+                address: RECIPIENT.to_string(),
+            };
+            from_binary(&query(deps, env, msg).unwrap()).unwrap()
+        };
+        let res = claims(deps.as_ref(), at(0));
+        assert_eq!(res.native_balance, coins(50, "tokens"));
+        assert_eq!(
+            res.vesting,
+            vec![Vesting {
+                stream,
+                total: GenericBalance {
+                    native: coins(100, "tokens"),
+                    cw20: vec![],
+                },
+                claimed: GenericBalance::default(),
+            }]
+        );
+        assert_eq!(
+            claims(deps.as_ref(), at(25)).native_balance,
+            coins(75, "tokens")
+        );
+
+        let claim = |deps: DepsMut, seconds: u64| {
+            execute(
+                deps,
+                at(seconds),
+                mock_info(RECIPIENT, &[]),
+                ExecuteMsg::Claim {},
+            )
+        };
+        let res = claim(deps.as_mut(), 25).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_on_error(
+                BankMsg::Send {
+                    to_address: RECIPIENT.to_string(),
+                    amount: coins(75, "tokens"),
+                },
+                FIRST_PAYOUT_REPLY_ID
+            )]
+        );
+        let err = claim(deps.as_mut(), 25).unwrap_err();
+        assert_eq!(err, ContractError::NothingToClaim {});
+        assert_eq!(
+            claims(deps.as_ref(), at(60)).native_balance,
+            coins(35, "tokens")
+        );
+
+        // the rest is claimable once the stream ends, and the vesting is gone once claimed
+        let res = claim(deps.as_mut(), 200).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_on_error(
+                BankMsg::Send {
+                    to_address: RECIPIENT.to_string(),
+                    amount: coins(75, "tokens"),
+                },
+                FIRST_PAYOUT_REPLY_ID + 1
+            )]
+        );
+        assert!(claims(deps.as_ref(), at(200)).vesting.is_empty());
+        let err = claim(deps.as_mut(), 300).unwrap_err();
+        assert_eq!(err, ContractError::NothingToClaim {});
+    }
+
+    #[test]
+    fn test_disputes() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let milestone = |amount: u128| CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: "milestone_title".to_string(),
+            description: "milestone_description".to_string(),
+            amount: GenericBalance {
+                native: vec![coin(amount, "tokens")],
+                cw20: vec![],
+            },
+            expires: None,
+            stream: None,
+        };
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![milestone(100), milestone(200)],
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("source", &coins(300, "tokens")),
+            msg,
+        )
+        .unwrap();
+        accept(deps.as_mut(), "escrow_1");
+        // submitted milestones can be disputed as well as pending ones
+        submit(deps.as_mut(), "escrow_1", "1");
+
+        let raise = |milestone_id: &str, reason: &str| ExecuteMsg::RaiseDispute {
+            id: "escrow_1".to_string(),
+            milestone_id: milestone_id.to_string(),
+            reason: reason.to_string(),
+        };
+        let resolve = |milestone_id: &str, recipient_share: Decimal| ExecuteMsg::ResolveDispute {
+            id: "escrow_1".to_string(),
+            milestone_id: milestone_id.to_string(),
+            recipient_share,
+        };
+
+        // only the source and recipient can dispute, and need to say why
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            raise("1", "not paid"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(RECIPIENT, &[]),
+            raise("1", ""),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidReason {
+                max: MAX_DESCRIPTION_LENGTH
+            }
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(RECIPIENT, &[]),
+            raise("1", "work was delivered"),
+        )
+        .unwrap();
+
+        // the disputed milestone can't be approved, disputed again or refunded
+        let approve = ExecuteMsg::ApproveMilestone {
+            id: "escrow_1".to_string(),
+            milestone_id: "1".to_string(),
+        };
+        let err =
+            execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), approve).unwrap_err();
+        assert_eq!(err, ContractError::MilestoneNotPending {});
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("source", &[]),
+            raise("1", "work was not delivered"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::MilestoneNotPending {});
+        let refund = ExecuteMsg::Refund {
+            id: "escrow_1".to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), refund).unwrap_err();
+        assert_eq!(err, ContractError::EscrowDisputed {});
+
+        // only the arbiter resolves, and only disputed milestones
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(RECIPIENT, &[]),
+            resolve("1", Decimal::one()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ARBITER, &[]),
+            resolve("2", Decimal::one()),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotDisputed {});
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ARBITER, &[]),
+            resolve("1", Decimal::percent(150)),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidShare {});
+
+        // the split is credited to both sides to claim
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ARBITER, &[]),
+            resolve("1", Decimal::percent(30)),
+        )
+        .unwrap();
+        assert_eq!(0, res.messages.len());
+        let claims = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, address: &str| -> Vec<Coin> {
+            let msg = QueryMsg::Claims {
+                address: address.to_string(),
+            };
+            let claims: ClaimsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            claims.native_balance
+        };
+        assert_eq!(claims(&deps, RECIPIENT), coins(30, "tokens"));
+        assert_eq!(claims(&deps, "source"), coins(70, "tokens"));
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(details.native_balance, coins(200, "tokens"));
+        assert_eq!(details.milestones[0].status, MilestoneStatus::Completed);
+        assert_eq!(details.milestones[0].paid_out.native, coins(30, "tokens"));
+
+        // settling the last milestone with nothing for the recipient cancels it and closes the
+        // escrow, the disputes stay on record
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("source", &[]),
+            raise("2", "deadline missed"),
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ARBITER, &[]),
+            resolve("2", Decimal::zero()),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "is_escrow_complete"));
+        assert_eq!(claims(&deps, RECIPIENT), coins(30, "tokens"));
+        assert_eq!(claims(&deps, "source"), coins(270, "tokens"));
+        assert!(query_escrow_details(deps.as_ref(), env, "escrow_1".to_string()).is_err());
+
+        let msg = QueryMsg::Disputes {
+            id: "escrow_1".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let disputes: DisputesResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let summary: Vec<_> = disputes
+            .disputes
+            .iter()
+            .map(|d| {
+                let resolution = d.resolution.as_ref().unwrap();
+                (
+                    d.id,
+                    d.milestone_id.as_str(),
+                    d.raised_by.as_str(),
+                    resolution.paid_out.native.clone(),
+                    resolution.refunded.native.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, "1", RECIPIENT, coins(30, "tokens"), coins(70, "tokens")),
+                (2, "2", "source", vec![], coins(200, "tokens")),
+            ]
+        );
+        let msg = QueryMsg::Disputes {
+            id: "escrow_1".to_string(),
+            start_after: Some(1),
+            limit: None,
+        };
+        let disputes: DisputesResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(disputes.disputes.len(), 1);
+        assert_eq!(disputes.disputes[0].id, 2);
+    }
+
+    #[test]
+    fn test_multi_arbiter_approval() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let create = |co_arbiters: &[&str], threshold: Option<u32>| {
+            ExecuteMsg::Create(CreateMsg {
+                id: "escrow_1".to_string(),
+                arbiter: ARBITER.to_string(),
+                co_arbiters: Some(co_arbiters.iter().map(|a| a.to_string()).collect()),
+                threshold,
+                arbiter_kind: None,
+                recipient: Some(RECIPIENT.to_string()),
+                title: "escrow_1_title".to_string(),
+                description: "escrow_1_description".to_string(),
+                cw20_whitelist: None,
+                strict_whitelist: None,
+                crowdfunded: None,
+                stake: None,
+                milestones: vec![CreateMilestoneMsg {
+                    escrow_id: "escrow_1".to_string(),
+                    title: "milestone_title".to_string(),
+                    description: "milestone_description".to_string(),
+                    amount: GenericBalance {
+                        native: vec![coin(100, "tokens")],
+                        cw20: vec![],
+                    },
+                    expires: None,
+                    stream: None,
+                }],
+            })
+        };
+        let info = mock_info("source", &coins(100, "tokens"));
+
+        // arbiters are distinct and the threshold within their number
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            create(&["arbiter2", ARBITER], None),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::DuplicateArbiter {});
+        for threshold in [0, 4] {
+            let msg = create(&["arbiter2", "arbiter3"], Some(threshold));
+            let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+            assert_eq!(err, ContractError::InvalidThreshold { arbiters: 3 });
+        }
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            create(&["arbiter2", "arbiter3"], Some(2)),
+        )
+        .unwrap();
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+
+        // co-arbiters find the escrow among theirs too
+        let by_arbiter = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, arbiter: &str| -> Vec<String> {
+            let msg = QueryMsg::ListByArbiter {
+                arbiter: arbiter.to_string(),
+                start_after: None,
+                limit: None,
+            };
+            let list: ListEscrowsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            list.escrows.into_iter().map(|e| e.id).collect()
+        };
+        for arbiter in [ARBITER, "arbiter2", "arbiter3"] {
+            assert_eq!(by_arbiter(&deps, arbiter), vec!["escrow_1"]);
+        }
+
+        let approve = |deps: DepsMut, sender: &str| {
+            let msg = ExecuteMsg::ApproveMilestone {
+                id: "escrow_1".to_string(),
+                milestone_id: "1".to_string(),
+            };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        };
+        let approvals = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| -> MilestoneApprovalsResponse {
+            let msg = QueryMsg::MilestoneApprovals {
+                id: "escrow_1".to_string(),
+                milestone_id: "1".to_string(),
+            };
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+        };
+
+        // a single signature is kept, but pays nothing yet
+        let err = approve(deps.as_mut(), "anyone").unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        approve(deps.as_mut(), "arbiter2").unwrap();
+        let err = approve(deps.as_mut(), "arbiter2").unwrap_err();
+        assert_eq!(err, ContractError::AlreadyApproved {});
+        assert_eq!(
+            approvals(&deps),
+            MilestoneApprovalsResponse {
+                approved_by: vec!["arbiter2".to_string()],
+                waiting_on: vec![ARBITER.to_string(), "arbiter3".to_string()],
+                threshold: 2,
+            }
+        );
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(details.milestones[0].status, MilestoneStatus::Submitted);
+        assert_eq!(details.co_arbiters, vec!["arbiter2", "arbiter3"]);
+        assert_eq!(details.threshold, 2);
+
+        // a new recipient voids the submission and the signatures given so far
+        let msg = ExecuteMsg::SetRecipient {
+            id: "escrow_1".to_string(),
+            recipient: RECIPIENT2.to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), msg).unwrap();
+        assert!(approvals(&deps).approved_by.is_empty());
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(details.milestones[0].status, MilestoneStatus::Pending);
+        let msg = ExecuteMsg::SetRecipient {
+            id: "escrow_1".to_string(),
+            recipient: RECIPIENT.to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), msg).unwrap();
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+
+        // the second signature releases the milestone
+        approve(deps.as_mut(), "arbiter3").unwrap();
+        let res = approve(deps.as_mut(), ARBITER).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "is_escrow_complete"));
+        assert!(by_arbiter(&deps, "arbiter2").is_empty());
+        let msg = QueryMsg::Claims {
+            address: RECIPIENT.to_string(),
+        };
+        let claims: ClaimsResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+        assert_eq!(claims.native_balance, coins(100, "tokens"));
+    }
+
+    #[test]
+    fn test_transfer_arbiter() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: Some(vec!["arbiter2".to_string()]),
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![CreateMilestoneMsg {
+                escrow_id: "escrow_1".to_string(),
+                title: "milestone_title".to_string(),
+                description: "milestone_description".to_string(),
+                amount: GenericBalance {
+                    native: vec![coin(100, "tokens")],
+                    cw20: vec![],
+                },
+                expires: None,
+                stream: None,
+            }],
+        });
+        let info = mock_info("source", &coins(100, "tokens"));
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+
+        let approve = |deps: DepsMut, sender: &str| {
+            let msg = ExecuteMsg::ApproveMilestone {
+                id: "escrow_1".to_string(),
+                milestone_id: "1".to_string(),
+            };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        };
+        let transfer = |deps: DepsMut, sender: &str, new_arbiter: &str| {
+            let msg = ExecuteMsg::TransferArbiter {
+                id: "escrow_1".to_string(),
+                new_arbiter: new_arbiter.to_string(),
+            };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        };
+        let accept_arbiter = |deps: DepsMut, sender: &str| {
+            let msg = ExecuteMsg::AcceptArbiter {
+                id: "escrow_1".to_string(),
+            };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        };
+        approve(deps.as_mut(), ARBITER).unwrap();
+
+        // only the arbiter offers the role, and not to someone who already has it
+        let err = accept_arbiter(deps.as_mut(), "new_arbiter").unwrap_err();
+        assert_eq!(err, ContractError::NoPendingArbiter {});
+        let err = transfer(deps.as_mut(), "arbiter2", "new_arbiter").unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let err = transfer(deps.as_mut(), ARBITER, "arbiter2").unwrap_err();
+        assert_eq!(err, ContractError::DuplicateArbiter {});
+        transfer(deps.as_mut(), ARBITER, "new_arbiter").unwrap();
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(details.arbiter, ARBITER);
+        assert_eq!(details.pending_arbiter.as_deref(), Some("new_arbiter"));
+
+        // only the pending arbiter takes it over, dropping the old arbiter's signature
+        let err = accept_arbiter(deps.as_mut(), "anyone").unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        accept_arbiter(deps.as_mut(), "new_arbiter").unwrap();
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(details.arbiter, "new_arbiter");
+        assert_eq!(details.pending_arbiter, None);
+        assert!(details.milestones[0].approvals.is_empty());
+        let err = accept_arbiter(deps.as_mut(), "new_arbiter").unwrap_err();
+        assert_eq!(err, ContractError::NoPendingArbiter {});
+
+        let msg = QueryMsg::ListByArbiter {
+            arbiter: "new_arbiter".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let list: ListEscrowsResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(list.escrows.len(), 1);
+
+        let err = approve(deps.as_mut(), ARBITER).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        approve(deps.as_mut(), "arbiter2").unwrap();
+        approve(deps.as_mut(), "new_arbiter").unwrap();
+        let msg = QueryMsg::Claims {
+            address: RECIPIENT.to_string(),
+        };
+        let claims: ClaimsResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+        assert_eq!(claims.native_balance, coins(100, "tokens"));
+    }
+
+    #[test]
+    fn test_approve_milestones() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let milestone = |amount: u128| CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: "milestone_title".to_string(),
+            description: "milestone_description".to_string(),
+            amount: GenericBalance {
+                native: coins(amount, "tokens"),
+                cw20: vec![],
+            },
+            expires: None,
+            stream: None,
+        };
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![milestone(100), milestone(200), milestone(300)],
+        });
+        let info = mock_info("source", &coins(600, "tokens"));
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+        submit(deps.as_mut(), "escrow_1", "2");
+
+        let approve = |deps: DepsMut, sender: &str, milestone_ids: &[&str]| {
+            let msg = ExecuteMsg::ApproveMilestones {
+                id: "escrow_1".to_string(),
+                milestone_ids: milestone_ids.iter().map(|id| id.to_string()).collect(),
+            };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        };
+
+        let err = approve(deps.as_mut(), RECIPIENT, &["1", "2"]).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let err = approve(deps.as_mut(), ARBITER, &[]).unwrap_err();
+        assert_eq!(err, ContractError::EmptyMilestones {});
+        // one milestone that can't be approved fails the whole batch
+        let err = approve(deps.as_mut(), ARBITER, &["1", "3"]).unwrap_err();
+        assert_eq!(err, ContractError::NotSubmitted {});
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(details.milestones[0].status, MilestoneStatus::Submitted);
+
+        let res = approve(deps.as_mut(), ARBITER, &["1", "2"]).unwrap();
+        let released = res.attributes.iter().find(|attr| attr.key == "released");
+        assert_eq!(released.unwrap().value, "1,2");
+        let msg = QueryMsg::Claims {
+            address: RECIPIENT.to_string(),
+        };
+        let claims: ClaimsResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(claims.native_balance, coins(300, "tokens"));
+        let details = query_escrow_details(deps.as_ref(), env, "escrow_1".to_string()).unwrap();
+        assert_eq!(details.native_balance, coins(300, "tokens"));
+        assert_eq!(details.milestones[1].status, MilestoneStatus::Completed);
+
+        let err = approve(deps.as_mut(), ARBITER, &["2"]).unwrap_err();
+        assert_eq!(err, ContractError::MilestoneNotPending {});
+    }
+
+    #[test]
+    fn test_cancel_milestone() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let milestone = |amount: u128| CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: "milestone_title".to_string(),
+            description: "milestone_description".to_string(),
+            amount: GenericBalance {
+                native: vec![coin(amount, "tokens")],
+                cw20: vec![],
+            },
+            expires: None,
+            stream: None,
+        };
+        // funded for all but 150 of the third milestone
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![milestone(100), milestone(200), milestone(300)],
+        });
+        let info = mock_info("source", &coins(450, "tokens"));
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        accept(deps.as_mut(), "escrow_1");
+
+        let cancel = |deps: DepsMut, sender: &str, milestone_id: &str| {
+            let msg = ExecuteMsg::CancelMilestone {
+                id: "escrow_1".to_string(),
+                milestone_id: milestone_id.to_string(),
+            };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        };
+        let refunds = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| -> Vec<Coin> {
+            let msg = QueryMsg::Claims {
+                address: "source".to_string(),
+            };
+            let claims: ClaimsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            claims.native_balance
+        };
+
+        // the arbiter cancels on its own. Short of funds, the escrow only gives back what it
+        // holds beyond what the other milestones need
+        let err = cancel(deps.as_mut(), "anyone", "3").unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        cancel(deps.as_mut(), ARBITER, "3").unwrap();
+        assert_eq!(refunds(&deps), coins(150, "tokens"));
+        cancel(deps.as_mut(), ARBITER, "1").unwrap();
+        assert_eq!(refunds(&deps), coins(250, "tokens"));
+        let err = cancel(deps.as_mut(), ARBITER, "1").unwrap_err();
+        assert_eq!(err, ContractError::MilestoneNotPending {});
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(details.native_balance, coins(200, "tokens"));
+        assert_eq!(details.milestones[0].status, MilestoneStatus::Cancelled);
+        assert!(details.funded);
+
+        // source and recipient both have to ask, which closes the escrow with its last milestone
+        let res = cancel(deps.as_mut(), "source", "2").unwrap();
+        assert!(res.attributes.iter().any(|attr| attr.key == "consents"));
+        let err = cancel(deps.as_mut(), "source", "2").unwrap_err();
+        assert_eq!(err, ContractError::AlreadyConsented {});
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(details.milestones[1].status, MilestoneStatus::Pending);
+        let res = cancel(deps.as_mut(), RECIPIENT, "2").unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "is_escrow_complete"));
+        assert_eq!(refunds(&deps), coins(450, "tokens"));
+        assert!(query_escrow_details(deps.as_ref(), env, "escrow_1".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_update_milestone() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let amount = |amount: u128| GenericBalance {
+            native: coins(amount, "tokens"),
+            cw20: vec![],
+        };
+        let milestone = |value: u128| CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: "milestone_title".to_string(),
+            description: "milestone_description".to_string(),
+            amount: amount(value),
+            expires: None,
+            stream: None,
+        };
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![milestone(100), milestone(200)],
+        });
+        let info = mock_info("source", &coins(300, "tokens"));
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "2");
+
+        let update = |deps: DepsMut,
+                      sender: &str,
+                      milestone_id: &str,
+                      title: Option<&str>,
+                      value: Option<u128>| {
+            let msg = ExecuteMsg::UpdateMilestone {
+                id: "escrow_1".to_string(),
+                milestone_id: milestone_id.to_string(),
+                title: title.map(str::to_string),
+                description: None,
+                amount: value.map(amount),
+            };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        };
+        let refunds = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| -> Vec<Coin> {
+            let msg = QueryMsg::Claims {
+                address: "source".to_string(),
+            };
+            let claims: ClaimsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            claims.native_balance
+        };
+
+        let err = update(deps.as_mut(), "source", "1", None, Some(60)).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        // the recipient already delivered against the terms of a submitted milestone
+        let err = update(deps.as_mut(), ARBITER, "2", None, Some(60)).unwrap_err();
+        assert_eq!(err, ContractError::MilestoneNotPending {});
+        let err = update(deps.as_mut(), ARBITER, "1", Some(""), None).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidTitle {
+                max: MAX_TITLE_LENGTH
+            }
+        );
+        let msg = ExecuteMsg::UpdateMilestone {
+            id: "escrow_1".to_string(),
+            milestone_id: "1".to_string(),
+            title: None,
+            description: None,
+            amount: Some(GenericBalance::default()),
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::EmptyBalance {});
+
+        // lowering the amount credits what is no longer needed back to the source
+        let res = update(deps.as_mut(), ARBITER, "1", Some("scoped down"), Some(60)).unwrap();
+        assert!(res.attributes.iter().any(|attr| attr.key == "to"));
+        assert_eq!(refunds(&deps), coins(40, "tokens"));
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(details.native_balance, coins(260, "tokens"));
+        assert_eq!(details.milestones[0].title, "scoped down");
+        assert_eq!(details.milestones[0].description, "milestone_description");
+        assert!(details.funded);
+
+        // raising it leaves the escrow waiting on a top up
+        update(deps.as_mut(), ARBITER, "1", None, Some(150)).unwrap();
+        assert_eq!(refunds(&deps), coins(40, "tokens"));
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(details.milestones[0].amount, amount(150));
+        assert!(!details.funded);
+
+        let msg = ExecuteMsg::ApproveMilestone {
+            id: "escrow_1".to_string(),
+            milestone_id: "2".to_string(),
+        };
+        execute(deps.as_mut(), env, mock_info(ARBITER, &[]), msg).unwrap();
+        let err = update(deps.as_mut(), ARBITER, "2", None, Some(100)).unwrap_err();
+        assert_eq!(err, ContractError::AlreadyPaidOut {});
+    }
+
+    #[test]
+    fn test_remove_milestone() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let milestone = |amount: u128, blocks: u64| CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: "milestone_title".to_string(),
+            description: "milestone_description".to_string(),
+            amount: GenericBalance {
+                native: coins(amount, "tokens"),
+                cw20: vec![],
+            },
+            expires: Some(Expiration::AtHeight(env.block.height + blocks)),
+            stream: None,
+        };
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![milestone(100, 10), milestone(200, 20), milestone(300, 100)],
+        });
+        let info = mock_info("source", &coins(600, "tokens"));
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+
+        let remove = |deps: DepsMut, sender: &str, milestone_id: &str| {
+            let msg = ExecuteMsg::RemoveMilestone {
+                id: "escrow_1".to_string(),
+                milestone_id: milestone_id.to_string(),
+            };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        };
+
+        let err = remove(deps.as_mut(), "source", "3").unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let err = remove(deps.as_mut(), ARBITER, "1").unwrap_err();
+        assert_eq!(err, ContractError::MilestoneNotPending {});
+
+        // the removed milestone's tokens go back to the source and its deadline no longer counts
+        remove(deps.as_mut(), ARBITER, "3").unwrap();
+        let msg = QueryMsg::Claims {
+            address: "source".to_string(),
+        };
+        let claims: ClaimsResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(claims.native_balance, coins(300, "tokens"));
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(details.native_balance, coins(300, "tokens"));
+        let mut later = env.clone();
+        later.block.height += 20;
+        let msg = QueryMsg::List {
+            status: Some(EscrowStatus::Expired),
+        };
+        let expired: ListEscrowsResponse =
+            from_binary(&query(deps.as_ref(), later, msg).unwrap()).unwrap();
+        assert_eq!(expired.escrows.len(), 1);
+        let err = remove(deps.as_mut(), ARBITER, "3").unwrap_err();
+        assert_eq!(err, ContractError::MilestoneNotFound {});
+
+        // a milestone added later doesn't take the removed one's id
+        let msg = ExecuteMsg::CreateMilestone(milestone(50, 30));
+        let info = mock_info(ARBITER, &coins(50, "tokens"));
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let details = query_escrow_details(deps.as_ref(), env, "escrow_1".to_string()).unwrap();
+        let ids: Vec<_> = details.milestones.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", "4"]);
+    }
+
+    #[test]
+    fn test_process_expired() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+
+        let create = |deps: DepsMut, id: &str, amount: u128, expires: Option<Expiration>| {
+            let msg = ExecuteMsg::Create(CreateMsg {
+                id: id.to_string(),
+                arbiter: ARBITER.to_string(),
+                co_arbiters: None,
+                threshold: None,
+                arbiter_kind: None,
+                recipient: Some(RECIPIENT.to_string()),
+                title: "title".to_string(),
+                description: "description".to_string(),
+                cw20_whitelist: None,
+                strict_whitelist: None,
+                crowdfunded: None,
+                stake: None,
+                milestones: vec![CreateMilestoneMsg {
+                    escrow_id: id.to_string(),
+                    title: "milestone_title".to_string(),
+                    description: "milestone_description".to_string(),
+                    amount: GenericBalance {
+                        native: coins(amount, "tokens"),
+                        cw20: vec![],
+                    },
+                    expires,
+                    stream: None,
+                }],
+            });
+            let info = mock_info("source", &coins(amount, "tokens"));
+            execute(deps, mock_env(), info, msg).unwrap();
+        };
+        let expires = Some(Expiration::AtHeight(env.block.height + 5));
+        create(deps.as_mut(), "escrow_1", 100, expires);
+        create(deps.as_mut(), "escrow_2", 200, expires);
+        create(deps.as_mut(), "escrow_3", 300, expires);
+        create(deps.as_mut(), "escrow_4", 400, None);
+        let msg = ExecuteMsg::RaiseDispute {
+            id: "escrow_2".to_string(),
+            milestone_id: "1".to_string(),
+            reason: "late".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("source", &[]), msg).unwrap();
+
+        env.block.height += 10;
+        let refund = |amount: u128, payout: u64| {
+            SubMsg::reply_on_error(
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "source".to_string(),
+                    amount: coins(amount, "tokens"),
+                }),
+                FIRST_PAYOUT_REPLY_ID + payout,
+            )
+        };
+
+        // the chain can clear expired escrows a few at a time
+        let msg = SudoMsg::ProcessExpired { limit: Some(1) };
+        let res = sudo(deps.as_mut(), env.clone(), msg).unwrap();
+        assert_eq!(res.messages, vec![refund(100, 0)]);
+
+        // disputed escrows wait for the arbiter, escrows without a deadline never expire
+        let msg = ExecuteMsg::ProcessExpired { limit: None };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(res.messages, vec![refund(300, 1)]);
+        for id in ["escrow_1", "escrow_3"] {
+            assert!(query_escrow_details(deps.as_ref(), env.clone(), id.to_string()).is_err());
+        }
+        for id in ["escrow_2", "escrow_4"] {
+            assert!(query_escrow_details(deps.as_ref(), env.clone(), id.to_string()).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_process_expired_reads_a_bounded_number() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+
+        let create = |deps: DepsMut, id: &str, expires: Option<Expiration>| {
+            let msg = ExecuteMsg::Create(CreateMsg {
+                id: id.to_string(),
+                arbiter: ARBITER.to_string(),
+                co_arbiters: None,
+                threshold: None,
+                arbiter_kind: None,
+                recipient: Some(RECIPIENT.to_string()),
+                title: "title".to_string(),
+                description: "description".to_string(),
+                cw20_whitelist: None,
+                strict_whitelist: None,
+                crowdfunded: None,
+                stake: None,
+                milestones: vec![CreateMilestoneMsg {
+                    escrow_id: id.to_string(),
+                    title: "milestone_title".to_string(),
+                    description: "milestone_description".to_string(),
+                    amount: GenericBalance {
+                        native: coins(100, "tokens"),
+                        cw20: vec![],
+                    },
+                    expires,
+                    stream: None,
+                }],
+            });
+            let info = mock_info("source", &coins(100, "tokens"));
+            execute(deps, mock_env(), info, msg).unwrap();
+        };
+        // a full batch of escrows that never expire, ahead of one that does
+        for i in 0..MAX_EXPIRY_SCAN {
+            create(deps.as_mut(), &format!("escrow_{:03}", i), None);
+        }
+        let last = format!("escrow_{:03}", MAX_EXPIRY_SCAN);
+        let expires = Some(Expiration::AtHeight(env.block.height + 5));
+        create(deps.as_mut(), &last, expires);
+        env.block.height += 10;
+
+        let process = |deps: DepsMut| {
+            let msg = SudoMsg::ProcessExpired { limit: None };
+            sudo(deps, env.clone(), msg).unwrap()
+        };
+        let count = |res: &Response| {
+            res.attributes
+                .iter()
+                .find(|a| a.key == "count")
+                .unwrap()
+                .value
+                .clone()
+        };
+
+        // the first call stops reading before the expired one, the next carries on to it and,
+        // having reached the end, leaves the one after to start over
+        let res = process(deps.as_mut());
+        assert_eq!(count(&res), "0");
+        let cursor = EXPIRY_CURSOR.may_load(deps.as_ref().storage).unwrap();
+        assert_eq!(cursor, Some(format!("escrow_{:03}", MAX_EXPIRY_SCAN - 1)));
+        let res = process(deps.as_mut());
+        assert_eq!(count(&res), "1");
+        assert!(query_escrow_details(deps.as_ref(), env.clone(), last).is_err());
+        let cursor = EXPIRY_CURSOR.may_load(deps.as_ref().storage).unwrap();
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn test_top_up() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let milestone = |amount: u128| CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: "milestone_title".to_string(),
+            description: "milestone_description".to_string(),
+            amount: GenericBalance {
+                native: vec![coin(amount, "tokens")],
+                cw20: vec![],
+            },
+            expires: None,
+            stream: None,
+        };
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![milestone(100), milestone(200)],
+        });
+        // created with only the first milestone funded
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("source", &coins(100, "tokens")),
+            msg,
+        )
+        .unwrap();
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert!(!details.funded);
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+        submit(deps.as_mut(), "escrow_1", "2");
+
+        let approve = |milestone_id: &str| ExecuteMsg::ApproveMilestone {
+            id: "escrow_1".to_string(),
+            milestone_id: milestone_id.to_string(),
+        };
+        let top_up = ExecuteMsg::TopUp {
+            id: "escrow_1".to_string(),
+        };
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ARBITER, &[]),
+            approve("2"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Underfunded {});
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ARBITER, &[]),
+            approve("1"),
+        )
+        .unwrap();
+        let tokens = |amount: u128| GenericBalance {
+            native: coins(amount, "tokens"),
+            cw20: vec![],
+        };
+        let funding_status = |deps: Deps| {
+            let msg = QueryMsg::FundingStatus {
+                id: "escrow_1".to_string(),
+            };
+            from_binary::<FundingStatusResponse>(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        };
+        assert_eq!(
+            funding_status(deps.as_ref()),
+            FundingStatusResponse {
+                required: tokens(300),
+                deposited: tokens(100),
+                paid_out: tokens(100),
+                remaining: GenericBalance::default(),
+                funded: false,
+            }
+        );
+
+        // only the source tops up, and never past what is left to pay
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &coins(200, "tokens")),
+            top_up.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("source", &coins(201, "tokens")),
+            top_up.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::FundsMismatch {
+                token: "tokens".to_string()
+            }
+        );
+        let receive = ExecuteMsg::Receive(cw20::Cw20ReceiveMsg {
+            sender: "source".to_string(),
+            amount: 200u128.into(),
+            msg: to_binary(&ReceiveMsg::TopUp {
+                id: "escrow_1".to_string(),
+            })
+            .unwrap(),
+        });
+        let err =
+            execute(deps.as_mut(), env.clone(), mock_info("token", &[]), receive).unwrap_err();
+        assert_eq!(err, ContractError::NotInWhitelist {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("source", &coins(200, "tokens")),
+            top_up,
+        )
+        .unwrap();
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert!(details.funded);
+        assert_eq!(details.native_balance, coins(200, "tokens"));
+        let status = funding_status(deps.as_ref());
+        assert_eq!(status.deposited, tokens(300));
+        assert_eq!(status.remaining, tokens(200));
+        assert!(status.funded);
+        execute(deps.as_mut(), env, mock_info(ARBITER, &[]), approve("2")).unwrap();
+    }
+
+    #[test]
+    fn test_top_up_milestone() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let milestone = |amount: GenericBalance| CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: "milestone_title".to_string(),
+            description: "milestone_description".to_string(),
+            amount,
+            expires: None,
+            stream: None,
+        };
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: Some(vec!["token".to_string()]),
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![
+                milestone(GenericBalance {
+                    native: coins(100, "tokens"),
+                    cw20: vec![],
+                }),
+                milestone(GenericBalance {
+                    native: vec![],
+                    cw20: vec![cw20::Cw20CoinVerified {
+                        address: Addr::unchecked("token"),
+                        amount: 200u128.into(),
+                    }],
+                }),
+            ],
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("source", &coins(100, "tokens")),
+            msg,
+        )
+        .unwrap();
+
+        let top_up = |milestone_id: &str, amount: u128| {
+            ExecuteMsg::Receive(cw20::Cw20ReceiveMsg {
+                sender: "source".to_string(),
+                amount: amount.into(),
+                msg: to_binary(&ReceiveMsg::TopUpMilestone {
+                    escrow_id: "escrow_1".to_string(),
+                    milestone_id: milestone_id.to_string(),
+                })
+                .unwrap(),
+            })
+        };
+
+        // the milestone has to exist, pay in the token and still be owed as much
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("token", &[]),
+            top_up("1", 100),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::FundsMismatch {
+                token: "token".to_string()
+            }
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("token", &[]),
+            top_up("3", 200),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::MilestoneNotFound {});
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("token", &[]),
+            top_up("2", 201),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::FundsMismatch {
+                token: "token".to_string()
+            }
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("token", &[]),
+            top_up("2", 200),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "milestone_id" && attr.value == "2"));
+        let details = query_escrow_details(deps.as_ref(), env, "escrow_1".to_string()).unwrap();
+        assert!(details.funded);
+    }
+
+    #[test]
+    fn test_list_by_participant() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let create = |id: &str, recipient: Option<&str>| {
+            ExecuteMsg::Create(CreateMsg {
+                id: id.to_string(),
+                arbiter: ARBITER.to_string(),
+                co_arbiters: None,
+                threshold: None,
+                arbiter_kind: None,
+                recipient: recipient.map(String::from),
+                title: format!("{}_title", id),
+                description: format!("{}_description", id),
+                cw20_whitelist: None,
+                strict_whitelist: None,
+                crowdfunded: None,
+                stake: None,
+                milestones: vec![CreateMilestoneMsg {
+                    escrow_id: id.to_string(),
+                    title: "milestone_title".to_string(),
+                    description: "milestone_description".to_string(),
+                    amount: GenericBalance {
+                        native: vec![coin(100, "tokens")],
+                        cw20: vec![],
+                    },
+                    expires: None,
+                    stream: None,
+                }],
+            })
+        };
+        for (id, source, recipient) in [
+            ("escrow_1", "funder", Some(RECIPIENT)),
+            ("escrow_2", "funder", Some(RECIPIENT2)),
+            ("escrow_3", "other_funder", None),
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(source, &coins(100, "tokens")),
+                create(id, recipient),
+            )
+            .unwrap();
+        }
+
+        let ids = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, msg: QueryMsg| -> Vec<String> {
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            let list: ListEscrowsResponse = from_binary(&res).unwrap();
+            list.escrows.into_iter().map(|e| e.id).collect()
+        };
+        let by_recipient = |recipient: &str| QueryMsg::ListByRecipient {
+            recipient: recipient.to_string(),
+            start_after: None,
+            limit: None,
+        };
+
+        assert_eq!(
+            ids(
+                &deps,
+                QueryMsg::ListByArbiter {
+                    arbiter: ARBITER.to_string(),
+                    start_after: Some("escrow_1".to_string()),
+                    limit: Some(1),
+                }
+            ),
+            vec!["escrow_2"]
+        );
+        assert_eq!(
+            ids(
+                &deps,
+                QueryMsg::ListBySource {
+                    source: "funder".to_string(),
+                    start_after: None,
+                    limit: None,
+                }
+            ),
+            vec!["escrow_1", "escrow_2"]
+        );
+        assert_eq!(ids(&deps, by_recipient(RECIPIENT)), vec!["escrow_1"]);
+
+        // the recipient index follows a new recipient
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ARBITER, &[]),
+            ExecuteMsg::SetRecipient {
+                id: "escrow_3".to_string(),
+                recipient: RECIPIENT.to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            ids(&deps, by_recipient(RECIPIENT)),
+            vec!["escrow_1", "escrow_3"]
+        );
+
+        // and drops escrows once they are gone
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(ARBITER, &[]),
+            ExecuteMsg::Refund {
+                id: "escrow_1".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(ids(&deps, by_recipient(RECIPIENT)), vec!["escrow_3"]);
+        assert!(ids(&deps, by_recipient("nobody")).is_empty());
+    }
+
+    #[test]
+    fn test_extend_escrow_milestone_time() {
+        let mut deps = mock_dependencies();
+
+        // instantiate an empty contract
+        let instantiate_msg = InstantiateMsg::default();
+        let info = mock_info(ARBITER, &[]);
+        let res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // create one milestone with a deadline
+        let timestamp = Timestamp::from_seconds(1_681_516_799);
+        let milestones = vec![CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: "milestone_1_title".to_string(),
+            description: "milestone_1_description".to_string(),
+            amount: GenericBalance {
+                native: vec![coin(100, "tokens")],
+                cw20: vec![],
+            },
+            expires: Some(Expiration::AtTime(timestamp)),
+            stream: None,
+        }];
+
+        // create an escrow
+        let create_msg = CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            description: "escrow_1_description".to_string(),
+            milestones,
+        };
+        let sender = ARBITER.to_string();
+        let balance = coins(100, "tokens");
+        let info = mock_info(&sender, &balance);
+        let msg = ExecuteMsg::Create(create_msg.clone());
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!(("action", "create"), res.attributes[0]);
+
+        // extend the escrow
+        let extended_timestamp = Timestamp::from_seconds(1_681_603_199);
+        let id = create_msg.id.clone();
+        let info = mock_info(&create_msg.arbiter, &[]);
+        let msg = ExecuteMsg::ExtendMilestone {
+            id,
+            milestone_id: String::from("1"),
+            expires: Expiration::AtTime(extended_timestamp),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!(("action", "extend_milestone"), res.attributes[0]);
+
+        // query the extended escrow
+        let query_msg = QueryMsg::EscrowDetails {
+            id: "escrow_1".to_string(),
+        };
+        let query_res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let escrow: EscrowDetailsResponse = from_binary(&query_res).unwrap();
+
+        // check the milestone deadline
+        assert!(extended_timestamp > timestamp);
+        assert_eq!(
+            Expiration::AtTime(extended_timestamp),
+            escrow.milestones[0].expires
+        );
+    }
+
+    #[test]
+    fn test_extend_escrow_milestone_block() {
+        let mut deps = mock_dependencies();
+
+        // instantiate an empty contract
+        let instantiate_msg = InstantiateMsg::default();
+        let info = mock_info(ARBITER, &[]);
+        let res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // create one milestone with a deadline
+        let height = 7_807_000u64;
+        let milestones = vec![CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: "milestone_1_title".to_string(),
+            description: "milestone_1_description".to_string(),
+            amount: GenericBalance {
+                native: vec![coin(100, "tokens")],
+                cw20: vec![],
+            },
+            expires: Some(Expiration::AtHeight(height)),
+            stream: None,
+        }];
+
+        // create an escrow
+        let create_msg = CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            description: "escrow_1_description".to_string(),
+            milestones,
+        };
+        let sender = ARBITER.to_string();
+        let balance = coins(100, "tokens");
+        let info = mock_info(&sender, &balance);
+        let msg = ExecuteMsg::Create(create_msg.clone());
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!(("action", "create"), res.attributes[0]);
+
+        // extend the escrow
+        let extended_height = 7_810_000u64;
+        let id = create_msg.id.clone();
+        let info = mock_info(&create_msg.arbiter, &[]);
+        let msg = ExecuteMsg::ExtendMilestone {
+            id,
+            milestone_id: String::from("1"),
+            expires: Expiration::AtHeight(extended_height),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!(("action", "extend_milestone"), res.attributes[0]);
+
+        // query the extended escrow
+        let query_msg = QueryMsg::EscrowDetails {
+            id: "escrow_1".to_string(),
+        };
+        let query_res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let escrow: EscrowDetailsResponse = from_binary(&query_res).unwrap();
+
+        // check the milestone deadline
+        assert!(extended_height > height);
+        assert_eq!(
+            Expiration::AtHeight(extended_height),
+            escrow.milestones[0].expires
+        );
+    }
+
+    #[test]
+    fn test_extend_milestone_rejects_shorter_deadline() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let milestone = |expires: Option<Expiration>| CreateMilestoneMsg {
+            escrow_id: "escrow_1".to_string(),
+            title: "milestone_title".to_string(),
+            description: "milestone_description".to_string(),
+            amount: GenericBalance {
+                native: vec![coin(100, "tokens")],
+                cw20: vec![],
+            },
+            expires,
+            stream: None,
+        };
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![
+                milestone(Some(Expiration::AtHeight(env.block.height + 10))),
+                milestone(None),
+            ],
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("source", &coins(200, "tokens")),
+            msg,
+        )
+        .unwrap();
+
+        let extend = |deps: DepsMut, milestone_id: &str, expires: Expiration| {
+            let msg = ExecuteMsg::ExtendMilestone {
+                id: "escrow_1".to_string(),
+                milestone_id: milestone_id.to_string(),
+                expires,
+            };
+            execute(deps, mock_env(), mock_info(ARBITER, &[]), msg)
+        };
+
+        // earlier, already passed or dropping a deadline the milestone never had
+        for (milestone_id, expires) in [
+            ("1", Expiration::AtHeight(env.block.height + 5)),
+            ("1", Expiration::AtHeight(env.block.height)),
+            ("1", Expiration::AtTime(env.block.time)),
+            ("2", Expiration::AtHeight(env.block.height + 100)),
+        ] {
+            let err = extend(deps.as_mut(), milestone_id, expires).unwrap_err();
+            assert_eq!(err, ContractError::CannotShortenDeadline {});
+        }
+
+        // a later height, or no deadline at all
+        extend(
+            deps.as_mut(),
+            "1",
+            Expiration::AtHeight(env.block.height + 20),
+        )
+        .unwrap();
+        extend(deps.as_mut(), "1", Expiration::Never {}).unwrap();
+        let details = query_escrow_details(deps.as_ref(), env, "escrow_1".to_string()).unwrap();
+        assert_eq!(details.milestones[0].expires, Expiration::Never {});
+    }
+
+    #[test]
+    fn test_extend_milestone_rejects_other_deadline_kind() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let far = Expiration::AtTime(env.block.time.plus_seconds(365 * 24 * 60 * 60));
+        let msg = ExecuteMsg::Create(CreateMsg {
+            id: "escrow_1".to_string(),
+            arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some(RECIPIENT.to_string()),
+            title: "escrow_1_title".to_string(),
+            description: "escrow_1_description".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![CreateMilestoneMsg {
+                escrow_id: "escrow_1".to_string(),
+                title: "milestone_title".to_string(),
+                description: "milestone_description".to_string(),
+                amount: GenericBalance {
+                    native: vec![coin(100, "tokens")],
+                    cw20: vec![],
+                },
+                expires: Some(far),
+                stream: None,
+            }],
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("source", &coins(100, "tokens")),
+            msg,
+        )
+        .unwrap();
 
-        // create one milestone with an expired end_time
-        let timestamp = 1_681_516_799u64;
-        let milestones = vec![CreateMilestoneMsg {
-            escrow_id: "escrow_1".to_string(),
-            title: "milestone_1_title".to_string(),
-            description: "milestone_1_description".to_string(),
+        // a height right ahead of the block would cut a year long deadline short
+        let msg = ExecuteMsg::ExtendMilestone {
+            id: "escrow_1".to_string(),
+            milestone_id: "1".to_string(),
+            expires: Expiration::AtHeight(env.block.height + 1),
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::CannotShortenDeadline {});
+        let details = query_escrow_details(deps.as_ref(), env, "escrow_1".to_string()).unwrap();
+        assert_eq!(details.milestones[0].expires, far);
+    }
+
+    #[test]
+    fn test_migrate_assigns_admin_as_owner() {
+        let mut deps = mock_dependencies();
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg::default()).unwrap();
+        // deployments from before ownership and fees have neither stored
+        deps.storage.remove(b"owner");
+        deps.storage.remove(b"config");
+
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::ContractInfo { .. } => {
+                let mut info = ContractInfoResponse::default();
+                info.admin = Some("admin".to_string());
+                SystemResult::Ok(ContractResult::Ok(to_binary(&info).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Ownership {}).unwrap();
+        let ownership: OwnershipResponse = from_binary(&res).unwrap();
+        assert_eq!(ownership.owner, Addr::unchecked("admin"));
+        assert_eq!(ownership.pending_owner, None);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let config: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(config.config, Config::default());
+    }
+
+    #[test]
+    fn test_migrate_from_0_14() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        // a 0.14 deployment: its cw2 version and escrows, no owner, config or stats yet
+        set_contract_version(
+            deps.as_mut().storage,
+            "crates.io:cw20-escrow-milestones",
+            "0.14.2",
+        )
+        .unwrap();
+        let legacy_milestone = |id: &str, amount: u128, is_completed: bool| LegacyMilestone {
+            id: id.to_string(),
+            title: format!("milestone_{}", id),
+            description: "description".to_string(),
             amount: GenericBalance {
-                native: vec![coin(100, "tokens")],
+                native: coins(amount, "tokens"),
                 cw20: vec![],
             },
-            end_height: None,
-            end_time: Some(timestamp),
-        }];
+            end_height: Some(env.block.height + 10),
+            end_time: None,
+            is_completed,
+        };
+        let legacy = LegacyEscrow {
+            arbiter: Addr::unchecked(ARBITER),
+            recipient: Some(Addr::unchecked(RECIPIENT)),
+            source: Addr::unchecked("source"),
+            title: "title".to_string(),
+            description: "description".to_string(),
+            end_height: Some(env.block.height + 10),
+            end_time: None,
+            balance: GenericBalance {
+                native: coins(1000, "tokens"),
+                cw20: vec![],
+            },
+            cw20_whitelist: vec![],
+            milestones: vec![
+                legacy_milestone("1", 400, true),
+                legacy_milestone("2", 600, false),
+            ],
+        };
+        LEGACY_ESCROWS
+            .save(deps.as_mut().storage, "escrow_1", &legacy)
+            .unwrap();
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::ContractInfo { .. } => {
+                let mut info = ContractInfoResponse::default();
+                info.admin = Some("admin".to_string());
+                SystemResult::Ok(ContractResult::Ok(to_binary(&info).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
 
-        // create an escrow
-        let create_msg = CreateMsg {
+        migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(env!("CARGO_PKG_VERSION"), version.version);
+
+        // the escrow reads in the current layout, is indexed and counted
+        let details =
+            query_escrow_details(deps.as_ref(), env.clone(), "escrow_1".to_string()).unwrap();
+        assert!(details.accepted);
+        // 0.14 still counted the paid out milestone in the balance
+        assert_eq!(details.native_balance, coins(600, "tokens"));
+        let statuses: Vec<_> = details.milestones.iter().map(|m| m.status).collect();
+        assert_eq!(
+            statuses,
+            vec![MilestoneStatus::Completed, MilestoneStatus::Pending]
+        );
+        assert_eq!(
+            details.milestones[1].expires,
+            Expiration::AtHeight(env.block.height + 11)
+        );
+        let msg = QueryMsg::ListByArbiter {
+            arbiter: ARBITER.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let list: ListEscrowsResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(list.escrows.len(), 1);
+        let stats: StatsResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Stats {}).unwrap()).unwrap();
+        assert_eq!(stats.stats.open, 1);
+        assert_eq!(stats.stats.locked.native, coins(600, "tokens"));
+
+        // and carries on paying out
+        submit(deps.as_mut(), "escrow_1", "2");
+        let msg = ExecuteMsg::ApproveMilestone {
+            id: "escrow_1".to_string(),
+            milestone_id: "2".to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), msg).unwrap();
+        assert_eq!(
+            ("is_escrow_complete", "true"),
+            res.attributes.last().unwrap()
+        );
+        let msg = QueryMsg::Claims {
+            address: RECIPIENT.to_string(),
+        };
+        let claims: ClaimsResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+        assert_eq!(claims.native_balance, coins(600, "tokens"));
+    }
+
+    #[test]
+    fn test_protocol_fee() {
+        let mut deps = mock_dependencies();
+        let info = mock_info("creator", &[]);
+
+        let msg = InstantiateMsg {
+            owner: Some("owner".to_string()),
+            fee_bps: Some(MAX_FEE_BPS + 1),
+            treasury: Some("treasury".to_string()),
+            unbonding_period: None,
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidFee { max: MAX_FEE_BPS });
+        let msg = InstantiateMsg {
+            owner: Some("owner".to_string()),
+            fee_bps: Some(250),
+            treasury: None,
+            unbonding_period: None,
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::NoTreasury {});
+        let msg = InstantiateMsg {
+            owner: Some("owner".to_string()),
+            fee_bps: Some(250),
+            treasury: Some("treasury".to_string()),
+            unbonding_period: None,
+        };
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Ownership {}).unwrap();
+        let ownership: OwnershipResponse = from_binary(&res).unwrap();
+        assert_eq!(ownership.owner, Addr::unchecked("owner"));
+
+        let msg = ExecuteMsg::Create(CreateMsg {
             id: "escrow_1".to_string(),
             arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
             recipient: Some(RECIPIENT.to_string()),
-            title: "escrow_1_title".to_string(),
+            title: "title".to_string(),
+            description: "description".to_string(),
             cw20_whitelist: None,
-            description: "escrow_1_description".to_string(),
-            milestones,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![CreateMilestoneMsg {
+                escrow_id: "escrow_1".to_string(),
+                title: "milestone_title".to_string(),
+                description: "milestone_description".to_string(),
+                amount: GenericBalance {
+                    native: coins(1000, "tokens"),
+                    cw20: vec![],
+                },
+                expires: None,
+                stream: None,
+            }],
+        });
+        let info = mock_info("source", &coins(1000, "tokens"));
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+        let msg = ExecuteMsg::ApproveMilestone {
+            id: "escrow_1".to_string(),
+            milestone_id: "1".to_string(),
         };
-        let sender = ARBITER.to_string();
-        let balance = coins(100, "tokens");
-        let info = mock_info(&sender, &balance);
-        let msg = ExecuteMsg::Create(create_msg.clone());
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
-        assert_eq!(("action", "create"), res.attributes[0]);
+        execute(deps.as_mut(), mock_env(), mock_info(ARBITER, &[]), msg).unwrap();
 
-        // extend the escrow
-        let extended_timestamp = 1_681_603_199u64;
-        let id = create_msg.id.clone();
-        let info = mock_info(&create_msg.arbiter, &[]);
-        let msg = ExecuteMsg::ExtendMilestone {
-            id,
-            milestone_id: String::from("1"),
-            end_height: None,
-            end_time: Some(extended_timestamp),
+        // 2.5% of the payout goes to the treasury
+        let claims = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, address: &str| -> Vec<Coin> {
+            let msg = QueryMsg::Claims {
+                address: address.to_string(),
+            };
+            let claims: ClaimsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            claims.native_balance
         };
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
-        assert_eq!(("action", "extend_milestone"), res.attributes[0]);
+        assert_eq!(claims(&deps, RECIPIENT), coins(975, "tokens"));
+        assert_eq!(claims(&deps, "treasury"), coins(25, "tokens"));
 
-        // query the extended escrow
-        let query_msg = QueryMsg::EscrowDetails {
+        let update = ExecuteMsg::UpdateConfig {
+            fee_bps: Some(0),
+            treasury: None,
+            unbonding_period: Some(14 * 24 * 60 * 60),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            update.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), update).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let config: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(config.config.fee_bps, 0);
+        assert_eq!(config.config.treasury, Some(Addr::unchecked("treasury")));
+        assert_eq!(config.config.unbonding_period, 14 * 24 * 60 * 60);
+    }
+
+    #[test]
+    fn test_pause() {
+        let mut deps = mock_dependencies();
+        let info = mock_info("owner", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg::default()).unwrap();
+
+        let create_msg = |id: &str| {
+            ExecuteMsg::Create(CreateMsg {
+                id: id.to_string(),
+                arbiter: ARBITER.to_string(),
+                co_arbiters: None,
+                threshold: None,
+                arbiter_kind: None,
+                recipient: Some(RECIPIENT.to_string()),
+                title: "title".to_string(),
+                description: "description".to_string(),
+                cw20_whitelist: None,
+                strict_whitelist: None,
+                crowdfunded: None,
+                stake: None,
+                milestones: vec![CreateMilestoneMsg {
+                    escrow_id: id.to_string(),
+                    title: "milestone_title".to_string(),
+                    description: "milestone_description".to_string(),
+                    amount: GenericBalance {
+                        native: vec![coin(100, "tokens")],
+                        cw20: vec![],
+                    },
+                    expires: None,
+                    stream: None,
+                }],
+            })
+        };
+        let funder = mock_info("funder", &coins(100, "tokens"));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            funder.clone(),
+            create_msg("escrow_1"),
+        )
+        .unwrap();
+
+        // only the owner pauses
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+        assert_eq!(("action", "pause"), res.attributes[0]);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Paused {}).unwrap();
+        let paused: PausedResponse = from_binary(&res).unwrap();
+        assert!(paused.paused);
+
+        // no new escrows and no payouts, but recipients can still get ready for them
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            funder.clone(),
+            create_msg("escrow_2"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ContractPaused {});
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+        let approve = ExecuteMsg::ApproveMilestone {
             id: "escrow_1".to_string(),
+            milestone_id: "1".to_string(),
         };
-        let query_res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
-        let escrow: EscrowDetailsResponse = from_binary(&query_res).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ARBITER, &[]),
+            approve.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ContractPaused {});
 
-        // check the milestone end_time
-        assert!(extended_timestamp > timestamp);
-        assert_eq!(extended_timestamp, escrow.milestones[0].end_time.unwrap());
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info(ARBITER, &[]), approve).unwrap();
+        execute(deps.as_mut(), mock_env(), funder, create_msg("escrow_2")).unwrap();
+
+        // refunds stay open while paused
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+        let refund = ExecuteMsg::Refund {
+            id: "escrow_2".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(ARBITER, &[]), refund).unwrap();
     }
 
     #[test]
-    fn test_extend_escrow_milestone_block() {
+    fn test_staked_escrow() {
         let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = InstantiateMsg {
+            unbonding_period: Some(100),
+            ..InstantiateMsg::default()
+        };
+        instantiate(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+        let validator = Validator {
+            address: "validator".to_string(),
+            commission: Decimal::percent(5),
+            max_commission: Decimal::percent(10),
+            max_change_rate: Decimal::percent(1),
+        };
+        // the delegation as the chain reports it, with the rewards not withdrawn yet
+        let delegation = |amount: u128, rewards: u128| FullDelegation {
+            delegator: env.contract.address.clone(),
+            validator: "validator".to_string(),
+            amount: coin(amount, "ustake"),
+            can_redelegate: coin(amount, "ustake"),
+            accumulated_rewards: coins(rewards, "ustake"),
+        };
+        deps.querier
+            .update_staking("ustake", std::slice::from_ref(&validator), &[]);
 
-        // instantiate an empty contract
-        let instantiate_msg = InstantiateMsg {};
-        let info = mock_info(&ARBITER, &[]);
-        let res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        let create = |validator: &str, recipient_yield: Decimal| {
+            ExecuteMsg::Create(CreateMsg {
+                id: "escrow_1".to_string(),
+                arbiter: ARBITER.to_string(),
+                co_arbiters: None,
+                threshold: None,
+                arbiter_kind: None,
+                recipient: Some(RECIPIENT.to_string()),
+                title: "title".to_string(),
+                description: "description".to_string(),
+                cw20_whitelist: None,
+                strict_whitelist: None,
+                crowdfunded: None,
+                stake: Some(StakeMsg {
+                    validator: validator.to_string(),
+                    recipient_yield,
+                }),
+                milestones: vec![CreateMilestoneMsg {
+                    escrow_id: "escrow_1".to_string(),
+                    title: "milestone_title".to_string(),
+                    description: "milestone_description".to_string(),
+                    amount: GenericBalance {
+                        native: coins(1000, "ustake"),
+                        cw20: vec![],
+                    },
+                    expires: None,
+                    stream: None,
+                }],
+            })
+        };
+        let info = mock_info("source", &coins(600, "ustake"));
+        let msg = create("unknown", Decimal::percent(80));
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UnknownValidator {
+                validator: "unknown".to_string()
+            }
+        );
+        let msg = create("validator", Decimal::percent(150));
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidShare {});
 
-        // create one milestone with an expired end_time
-        let height = 7_807_000u64;
-        let milestones = vec![CreateMilestoneMsg {
+        // the deposit is delegated, and so is the top up once the first funds earned 60
+        let msg = create("validator", Decimal::percent(80));
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(StakingMsg::Delegate {
+                validator: "validator".to_string(),
+                amount: coin(600, "ustake"),
+            })]
+        );
+        deps.querier.update_staking(
+            "ustake",
+            std::slice::from_ref(&validator),
+            &[delegation(600, 60)],
+        );
+        let top_up = ExecuteMsg::TopUp {
+            id: "escrow_1".to_string(),
+        };
+        let info = mock_info("source", &coins(400, "ustake"));
+        let res = execute(deps.as_mut(), env.clone(), info, top_up).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(StakingMsg::Delegate {
+                validator: "validator".to_string(),
+                amount: coin(400, "ustake"),
+            })]
+        );
+        deps.querier
+            .update_staking("ustake", &[validator], &[delegation(1000, 100)]);
+
+        // nothing pays out of delegated funds
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+        let approve = ExecuteMsg::ApproveMilestone {
+            id: "escrow_1".to_string(),
+            milestone_id: "1".to_string(),
+        };
+        let arbiter = mock_info(ARBITER, &[]);
+        let err =
+            execute(deps.as_mut(), env.clone(), arbiter.clone(), approve.clone()).unwrap_err();
+        assert_eq!(err, ContractError::FundsStaked {});
+        let refund = ExecuteMsg::Refund {
+            id: "escrow_1".to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), arbiter.clone(), refund).unwrap_err();
+        assert_eq!(err, ContractError::FundsStaked {});
+
+        let unstake = ExecuteMsg::Unstake {
+            id: "escrow_1".to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            unstake.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let info = mock_info("source", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), unstake.clone()).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(StakingMsg::Undelegate {
+                validator: "validator".to_string(),
+                amount: coin(1000, "ustake"),
+            })]
+        );
+        assert_eq!(("earned", "160"), res.attributes[2]);
+        let err = execute(deps.as_mut(), env.clone(), info, unstake).unwrap_err();
+        assert_eq!(err, ContractError::NotStaked {});
+
+        // the yield is split for both to claim
+        let claims = |deps: Deps, address: &str| -> Vec<Coin> {
+            let msg = QueryMsg::Claims {
+                address: address.to_string(),
+            };
+            let claims: ClaimsResponse =
+                from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            claims.native_balance
+        };
+        assert_eq!(claims(deps.as_ref(), RECIPIENT), coins(128, "ustake"));
+        assert_eq!(claims(deps.as_ref(), "source"), coins(32, "ustake"));
+
+        // the milestone pays out once the funds are back
+        let err =
+            execute(deps.as_mut(), env.clone(), arbiter.clone(), approve.clone()).unwrap_err();
+        assert_eq!(err, ContractError::FundsStaked {});
+        let mut unbonded = env.clone();
+        unbonded.block.time = env.block.time.plus_seconds(100);
+        let details =
+            query_escrow_details(deps.as_ref(), unbonded.clone(), "escrow_1".to_string()).unwrap();
+        assert_eq!(
+            details.stake.unwrap().unbonding_until,
+            Some(unbonded.block.time.seconds())
+        );
+        execute(deps.as_mut(), unbonded, arbiter, approve).unwrap();
+        assert_eq!(claims(deps.as_ref(), RECIPIENT), coins(1128, "ustake"));
+    }
+
+    #[test]
+    fn test_crowdfunded_escrow() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            InstantiateMsg::default(),
+        )
+        .unwrap();
+
+        let milestone = |amount: u128| CreateMilestoneMsg {
             escrow_id: "escrow_1".to_string(),
-            title: "milestone_1_title".to_string(),
-            description: "milestone_1_description".to_string(),
+            title: "milestone_title".to_string(),
+            description: "milestone_description".to_string(),
             amount: GenericBalance {
-                native: vec![coin(100, "tokens")],
+                native: coins(amount, "tokens"),
                 cw20: vec![],
             },
-            end_height: Some(height),
-            end_time: None,
-        }];
-
-        // create an escrow
-        let create_msg = CreateMsg {
+            expires: Some(Expiration::AtTime(env.block.time.plus_seconds(100))),
+            stream: None,
+        };
+        let msg = ExecuteMsg::Create(CreateMsg {
             id: "escrow_1".to_string(),
             arbiter: ARBITER.to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
             recipient: Some(RECIPIENT.to_string()),
             title: "escrow_1_title".to_string(),
-            cw20_whitelist: None,
             description: "escrow_1_description".to_string(),
-            milestones,
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: Some(true),
+            stake: None,
+            milestones: vec![milestone(300), milestone(300)],
+        });
+        let info = mock_info("source", &coins(100, "tokens"));
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // anyone chips in until the escrow expires
+        let top_up = |deps: DepsMut, env: Env, funder: &str, amount: u128| {
+            let msg = ExecuteMsg::TopUp {
+                id: "escrow_1".to_string(),
+            };
+            execute(deps, env, mock_info(funder, &coins(amount, "tokens")), msg)
         };
-        let sender = ARBITER.to_string();
-        let balance = coins(100, "tokens");
-        let info = mock_info(&sender, &balance);
-        let msg = ExecuteMsg::Create(create_msg.clone());
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
-        assert_eq!(("action", "create"), res.attributes[0]);
+        top_up(deps.as_mut(), env.clone(), "backer1", 150).unwrap();
+        top_up(deps.as_mut(), env.clone(), "backer2", 300).unwrap();
+        top_up(deps.as_mut(), env.clone(), "backer1", 50).unwrap();
+        let mut expired = env.clone();
+        expired.block.time = env.block.time.plus_seconds(101);
+        let err = top_up(deps.as_mut(), expired, "backer3", 10).unwrap_err();
+        assert_eq!(err, ContractError::Expired {});
 
-        // extend the escrow
-        let extended_height = 7_810_000u64;
-        let id = create_msg.id.clone();
-        let info = mock_info(&create_msg.arbiter, &[]);
-        let msg = ExecuteMsg::ExtendMilestone {
-            id,
-            milestone_id: String::from("1"),
-            end_height: Some(extended_height),
-            end_time: None,
+        let msg = QueryMsg::Contributions {
+            id: "escrow_1".to_string(),
+            start_after: None,
+            limit: None,
         };
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
-        assert_eq!(("action", "extend_milestone"), res.attributes[0]);
+        let res: ContributionsResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        let contributed: Vec<_> = res
+            .contributions
+            .into_iter()
+            .map(|contribution| (contribution.funder, contribution.amount.native))
+            .collect();
+        assert_eq!(
+            contributed,
+            vec![
+                ("backer1".to_string(), coins(200, "tokens")),
+                ("backer2".to_string(), coins(300, "tokens")),
+                ("source".to_string(), coins(100, "tokens")),
+            ]
+        );
 
-        // query the extended escrow
-        let query_msg = QueryMsg::EscrowDetails {
+        // what the cancelled milestone held goes back to every funder, pro rata
+        accept(deps.as_mut(), "escrow_1");
+        submit(deps.as_mut(), "escrow_1", "1");
+        let approve = ExecuteMsg::ApproveMilestone {
             id: "escrow_1".to_string(),
+            milestone_id: "1".to_string(),
         };
-        let query_res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
-        let escrow: EscrowDetailsResponse = from_binary(&query_res).unwrap();
+        execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), approve).unwrap();
+        let cancel = ExecuteMsg::CancelMilestone {
+            id: "escrow_1".to_string(),
+            milestone_id: "2".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(ARBITER, &[]), cancel).unwrap();
 
-        // check the milestone end_time
-        assert!(extended_height > height);
-        assert_eq!(extended_height, escrow.milestones[0].end_height.unwrap());
+        let claims = |deps: Deps, address: &str| -> Vec<Coin> {
+            let msg = QueryMsg::Claims {
+                address: address.to_string(),
+            };
+            let claims: ClaimsResponse =
+                from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            claims.native_balance
+        };
+        assert_eq!(claims(deps.as_ref(), RECIPIENT), coins(300, "tokens"));
+        assert_eq!(claims(deps.as_ref(), "backer1"), coins(100, "tokens"));
+        assert_eq!(claims(deps.as_ref(), "backer2"), coins(150, "tokens"));
+        assert_eq!(claims(deps.as_ref(), "source"), coins(50, "tokens"));
+
+        // the escrow closed, and its contributions with it
+        let msg = QueryMsg::Contributions {
+            id: "escrow_1".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res: ContributionsResponse =
+            from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+        assert!(res.contributions.is_empty());
     }
 }
@@ -1,11 +1,11 @@
 use cosmwasm_schema::write_api;
 
-use cw20_escrow_milestones::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use cw20_stake::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
-        query: QueryMsg,
         execute: ExecuteMsg,
+        query: QueryMsg,
     }
 }
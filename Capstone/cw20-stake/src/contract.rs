@@ -0,0 +1,223 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Uint128, WasmMsg,
+};
+use cosmwatch_events::{attr, response};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+
+use crate::error::ContractError;
+use crate::msg::{
+    ClaimsResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg,
+    StakedBalanceAtHeightResponse, TotalStakedAtHeightResponse,
+};
+use crate::state::{Claim, Config, CLAIMS, CONFIG, STAKED, TOTAL_STAKED};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:cw20-stake";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        token: deps.api.addr_validate(&msg.token)?,
+        unbonding_period: msg.unbonding_period,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    TOTAL_STAKED.save(deps.storage, &Uint128::zero(), env.block.height)?;
+
+    Ok(response("instantiate").add_attribute("token", config.token))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::Unstake { amount } => execute_unstake(deps, env, info, amount),
+        ExecuteMsg::Claim {} => execute_claim(deps, env, info),
+    }
+}
+
+fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.token {
+        return Err(ContractError::InvalidToken {
+            token: config.token.into(),
+            received: info.sender.into(),
+        });
+    }
+    let staker = deps.api.addr_validate(&wrapper.sender)?;
+    match from_binary(&wrapper.msg)? {
+        ReceiveMsg::Stake {} => execute_stake(deps, env, staker, wrapper.amount),
+    }
+}
+
+fn execute_stake(
+    deps: DepsMut,
+    env: Env,
+    staker: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let height = env.block.height;
+    STAKED.update(deps.storage, &staker, height, |staked| -> StdResult<_> {
+        Ok(staked.unwrap_or_default().checked_add(amount)?)
+    })?;
+    TOTAL_STAKED.update(deps.storage, height, |total| -> StdResult<_> {
+        Ok(total.unwrap_or_default().checked_add(amount)?)
+    })?;
+
+    Ok(response("stake")
+        .add_attribute("staker", staker)
+        .add_attribute(attr::AMOUNT, amount))
+}
+
+fn execute_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let staked = STAKED
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    if amount > staked {
+        return Err(ContractError::InsufficientStake { staked });
+    }
+
+    let height = env.block.height;
+    STAKED.save(deps.storage, &info.sender, &(staked - amount), height)?;
+    TOTAL_STAKED.update(deps.storage, height, |total| -> StdResult<_> {
+        Ok(total.unwrap_or_default().checked_sub(amount)?)
+    })?;
+
+    let claim = Claim {
+        amount,
+        release_at: config.unbonding_period.after(&env.block),
+    };
+    CLAIMS.update(deps.storage, &info.sender, |claims| -> StdResult<_> {
+        let mut claims = claims.unwrap_or_default();
+        claims.push(claim.clone());
+        Ok(claims)
+    })?;
+
+    Ok(response("unstake")
+        .add_attribute("staker", info.sender)
+        .add_attribute(attr::AMOUNT, amount)
+        .add_attribute("release_at", claim.release_at.to_string()))
+}
+
+fn execute_claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let (released, pending): (Vec<Claim>, Vec<Claim>) = CLAIMS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|claim| claim.release_at.is_expired(&env.block));
+    let amount: Uint128 = released.iter().map(|claim| claim.amount).sum();
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    if pending.is_empty() {
+        CLAIMS.remove(deps.storage, &info.sender);
+    } else {
+        CLAIMS.save(deps.storage, &info.sender, &pending)?;
+    }
+
+    let transfer = WasmMsg::Execute {
+        contract_addr: config.token.into(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: info.sender.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    };
+
+    Ok(response("claim")
+        .add_attribute("staker", info.sender)
+        .add_attribute(attr::AMOUNT, amount)
+        .add_message(transfer))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&ConfigResponse {
+            config: CONFIG.load(deps.storage)?,
+        }),
+        QueryMsg::StakedBalanceAtHeight { address, height } => {
+            to_binary(&query_staked_balance_at_height(deps, env, address, height)?)
+        }
+        QueryMsg::TotalStakedAtHeight { height } => {
+            to_binary(&query_total_staked_at_height(deps, env, height)?)
+        }
+        QueryMsg::Claims { address } => to_binary(&ClaimsResponse {
+            claims: CLAIMS
+                .may_load(deps.storage, &deps.api.addr_validate(&address)?)?
+                .unwrap_or_default(),
+        }),
+    }
+}
+
+fn query_staked_balance_at_height(
+    deps: Deps,
+    env: Env,
+    address: String,
+    height: Option<u64>,
+) -> StdResult<StakedBalanceAtHeightResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let (balance, height) = match height {
+        Some(height) => (
+            STAKED.may_load_at_height(deps.storage, &address, height)?,
+            height,
+        ),
+        None => (STAKED.may_load(deps.storage, &address)?, env.block.height),
+    };
+
+    Ok(StakedBalanceAtHeightResponse {
+        balance: balance.unwrap_or_default(),
+        height,
+    })
+}
+
+fn query_total_staked_at_height(
+    deps: Deps,
+    env: Env,
+    height: Option<u64>,
+) -> StdResult<TotalStakedAtHeightResponse> {
+    let (total, height) = match height {
+        Some(height) => (
+            TOTAL_STAKED.may_load_at_height(deps.storage, height)?,
+            height,
+        ),
+        None => (TOTAL_STAKED.may_load(deps.storage)?, env.block.height),
+    };
+
+    Ok(TotalStakedAtHeightResponse {
+        total: total.unwrap_or_default(),
+        height,
+    })
+}
@@ -0,0 +1,20 @@
+use cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Only {token} can be staked, received {received}")]
+    InvalidToken { token: String, received: String },
+
+    #[error("Amount must be greater than zero")]
+    ZeroAmount {},
+
+    #[error("Cannot unstake more than the {staked} staked")]
+    InsufficientStake { staked: Uint128 },
+
+    #[error("No unbonded claims are ready yet")]
+    NothingToClaim {},
+}
@@ -0,0 +1,358 @@
+#![cfg(test)]
+
+use cosmwasm_std::{to_binary, Addr, Empty, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_utils::Duration;
+
+use crate::msg::{
+    ClaimsResponse, ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg,
+    StakedBalanceAtHeightResponse, TotalStakedAtHeightResponse,
+};
+use crate::ContractError;
+
+/// Blocks unstaked tokens stay locked
+const UNBONDING_BLOCKS: u64 = 10;
+
+fn contract_stake() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    ))
+}
+
+fn contract_cw20() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    ))
+}
+
+fn contract_poll() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cosm_wasm_zero2_hero::contract::execute,
+        cosm_wasm_zero2_hero::contract::instantiate,
+        cosm_wasm_zero2_hero::contract::query,
+    ))
+}
+
+struct Setup {
+    app: App,
+    stake: Addr,
+    token: Addr,
+}
+
+/// Staking contract over a cw20 token that `holders` start out with
+fn setup(holders: &[(&str, u128)]) -> Setup {
+    let mut app = App::default();
+    let owner = Addr::unchecked("owner");
+
+    let cw20_id = app.store_code(contract_cw20());
+    let token = app
+        .instantiate_contract(
+            cw20_id,
+            owner.clone(),
+            &cw20_base::msg::InstantiateMsg {
+                name: "Governance".to_string(),
+                symbol: "GOV".to_string(),
+                decimals: 6,
+                initial_balances: holders
+                    .iter()
+                    .map(|(address, amount)| Cw20Coin {
+                        address: address.to_string(),
+                        amount: Uint128::new(*amount),
+                    })
+                    .collect(),
+                mint: None,
+                marketing: None,
+            },
+            &[],
+            "token",
+            None,
+        )
+        .unwrap();
+
+    let stake_id = app.store_code(contract_stake());
+    let stake = app
+        .instantiate_contract(
+            stake_id,
+            owner,
+            &InstantiateMsg {
+                token: token.to_string(),
+                unbonding_period: Duration::Height(UNBONDING_BLOCKS),
+            },
+            &[],
+            "stake",
+            None,
+        )
+        .unwrap();
+
+    Setup { app, stake, token }
+}
+
+impl Setup {
+    fn stake(&mut self, staker: &str, amount: u128) {
+        self.app
+            .execute_contract(
+                Addr::unchecked(staker),
+                self.token.clone(),
+                &Cw20ExecuteMsg::Send {
+                    contract: self.stake.to_string(),
+                    amount: Uint128::new(amount),
+                    msg: to_binary(&ReceiveMsg::Stake {}).unwrap(),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    fn execute(&mut self, sender: &str, msg: &ExecuteMsg) -> Result<(), ContractError> {
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.stake.clone(), msg, &[])
+            .map(|_| ())
+            .map_err(|err| err.downcast().unwrap())
+    }
+
+    fn staked_at(&self, address: &str, height: Option<u64>) -> u128 {
+        let res: StakedBalanceAtHeightResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.stake,
+                &QueryMsg::StakedBalanceAtHeight {
+                    address: address.to_string(),
+                    height,
+                },
+            )
+            .unwrap();
+        res.balance.u128()
+    }
+
+    fn token_balance(&self, address: &str) -> u128 {
+        let res: BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.token,
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        res.balance.u128()
+    }
+
+    fn next_block(&mut self) {
+        self.app.update_block(|block| block.height += 1);
+    }
+}
+
+#[test]
+fn unstaked_tokens_unbond_before_claim() {
+    let mut setup = setup(&[("alice", 100)]);
+    let staked_at = setup.app.block_info().height;
+    setup.stake("alice", 100);
+    setup.next_block();
+    assert_eq!(setup.staked_at("alice", None), 100);
+    // a stake counts from the block after it was made
+    assert_eq!(setup.staked_at("alice", Some(staked_at)), 0);
+    assert_eq!(setup.staked_at("alice", Some(staked_at + 1)), 100);
+
+    assert_eq!(
+        setup
+            .execute(
+                "alice",
+                &ExecuteMsg::Unstake {
+                    amount: Uint128::new(150)
+                }
+            )
+            .unwrap_err(),
+        ContractError::InsufficientStake {
+            staked: Uint128::new(100)
+        }
+    );
+    let unstaked_at = setup.app.block_info().height;
+    setup
+        .execute(
+            "alice",
+            &ExecuteMsg::Unstake {
+                amount: Uint128::new(40),
+            },
+        )
+        .unwrap();
+    setup.next_block();
+    assert_eq!(setup.staked_at("alice", None), 60);
+    assert_eq!(setup.staked_at("alice", Some(unstaked_at)), 100);
+    let total: TotalStakedAtHeightResponse = setup
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &setup.stake,
+            &QueryMsg::TotalStakedAtHeight { height: None },
+        )
+        .unwrap();
+    assert_eq!(total.total.u128(), 60);
+
+    // locked until the unbonding period ends
+    assert_eq!(
+        setup.execute("alice", &ExecuteMsg::Claim {}).unwrap_err(),
+        ContractError::NothingToClaim {}
+    );
+    setup
+        .app
+        .update_block(|block| block.height += UNBONDING_BLOCKS);
+    setup.execute("alice", &ExecuteMsg::Claim {}).unwrap();
+    assert_eq!(setup.token_balance("alice"), 40);
+    assert_eq!(setup.token_balance(setup.stake.as_str()), 60);
+    let claims: ClaimsResponse = setup
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &setup.stake,
+            &QueryMsg::Claims {
+                address: "alice".to_string(),
+            },
+        )
+        .unwrap();
+    assert!(claims.claims.is_empty());
+}
+
+#[test]
+fn stake_weights_poll_votes() {
+    let mut setup = setup(&[("alice", 300), ("bob", 100), ("carol", 500)]);
+    setup.stake("alice", 300);
+    setup.stake("bob", 100);
+    setup.next_block();
+
+    let poll_id = setup.app.store_code(contract_poll());
+    let poll = setup
+        .app
+        .instantiate_contract(
+            poll_id,
+            Addr::unchecked("owner"),
+            &cosm_wasm_zero2_hero::msg::InstantiateMsg { admin: None },
+            &[],
+            "poll",
+            None,
+        )
+        .unwrap();
+    setup
+        .app
+        .execute_contract(
+            Addr::unchecked("owner"),
+            poll.clone(),
+            &cosm_wasm_zero2_hero::msg::ExecuteMsg::CreateWeightedPoll {
+                poll_id: "treasury".to_string(),
+                question: "Fund the grant?".to_string(),
+                options: vec!["yes".to_string(), "no".to_string()],
+                staking: setup.stake.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    // staking after the poll opens does not add voting power
+    setup.stake("carol", 500);
+    setup.next_block();
+
+    let vote = |app: &mut App, voter: &str, option: &str| {
+        app.execute_contract(
+            Addr::unchecked(voter),
+            poll.clone(),
+            &cosm_wasm_zero2_hero::msg::ExecuteMsg::Vote {
+                poll_id: "treasury".to_string(),
+                vote: option.to_string(),
+            },
+            &[],
+        )
+    };
+    let tally = |app: &App| {
+        let res: cosm_wasm_zero2_hero::msg::PollResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &poll,
+                &cosm_wasm_zero2_hero::msg::QueryMsg::Poll {
+                    poll_id: "treasury".to_string(),
+                },
+            )
+            .unwrap();
+        res.poll.unwrap().options
+    };
+
+    vote(&mut setup.app, "alice", "no").unwrap();
+    vote(&mut setup.app, "bob", "yes").unwrap();
+    let err = vote(&mut setup.app, "carol", "yes").unwrap_err();
+    assert_eq!(
+        err.downcast::<cosm_wasm_zero2_hero::ContractError>()
+            .unwrap()
+            .to_string(),
+        "No stake at the poll's snapshot height"
+    );
+    assert_eq!(
+        tally(&setup.app),
+        vec![
+            ("yes".to_string(), Uint128::new(100)),
+            ("no".to_string(), Uint128::new(300))
+        ]
+    );
+
+    // changing a vote moves the voter's whole weight
+    vote(&mut setup.app, "alice", "yes").unwrap();
+    assert_eq!(
+        tally(&setup.app),
+        vec![
+            ("yes".to_string(), Uint128::new(400)),
+            ("no".to_string(), Uint128::new(0))
+        ]
+    );
+}
+
+#[test]
+fn only_the_configured_token_stakes() {
+    let mut setup = setup(&[("alice", 100)]);
+    let cw20_id = setup.app.store_code(contract_cw20());
+    let other = setup
+        .app
+        .instantiate_contract(
+            cw20_id,
+            Addr::unchecked("owner"),
+            &cw20_base::msg::InstantiateMsg {
+                name: "Other".to_string(),
+                symbol: "OTHER".to_string(),
+                decimals: 6,
+                initial_balances: vec![Cw20Coin {
+                    address: "alice".to_string(),
+                    amount: Uint128::new(100),
+                }],
+                mint: None,
+                marketing: None,
+            },
+            &[],
+            "other",
+            None,
+        )
+        .unwrap();
+
+    let err = setup
+        .app
+        .execute_contract(
+            Addr::unchecked("alice"),
+            other.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: setup.stake.to_string(),
+                amount: Uint128::new(100),
+                msg: to_binary(&ReceiveMsg::Stake {}).unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidToken {
+            token: setup.token.to_string(),
+            received: other.to_string()
+        }
+    );
+    assert_eq!(setup.staked_at("alice", None), 0);
+}
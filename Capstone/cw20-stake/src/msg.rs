@@ -0,0 +1,70 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+use cw_utils::Duration;
+
+use crate::state::{Claim, Config};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The cw20 token to stake
+    pub token: String,
+    pub unbonding_period: Duration,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Stakes cw20 tokens sent with `Send` and a `ReceiveMsg`
+    Receive(Cw20ReceiveMsg),
+    /// Stops `amount` counting as stake and locks it until the unbonding period ends
+    Unstake { amount: Uint128 },
+    /// Sends the sender every claim whose unbonding period has ended
+    Claim {},
+}
+
+#[cw_serde]
+pub enum ReceiveMsg {
+    Stake {},
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+
+    /// Stake at the start of block `height`, or the current stake if `height` isn't given
+    #[returns(StakedBalanceAtHeightResponse)]
+    StakedBalanceAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+
+    #[returns(TotalStakedAtHeightResponse)]
+    TotalStakedAtHeight { height: Option<u64> },
+
+    #[returns(ClaimsResponse)]
+    Claims { address: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub config: Config,
+}
+
+#[cw_serde]
+pub struct StakedBalanceAtHeightResponse {
+    pub balance: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct TotalStakedAtHeightResponse {
+    pub total: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct ClaimsResponse {
+    pub claims: Vec<Claim>,
+}
@@ -0,0 +1,37 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
+use cw_utils::{Duration, Expiration};
+
+#[cw_serde]
+pub struct Config {
+    pub token: Addr,
+    /// How long unstaked tokens stay locked before they can be claimed
+    pub unbonding_period: Duration,
+}
+
+/// Unstaked tokens waiting out the unbonding period
+#[cw_serde]
+pub struct Claim {
+    pub amount: Uint128,
+    pub release_at: Expiration,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Checkpointed every block, so balances can be read back at any past height
+pub const STAKED: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "staked",
+    "staked__checkpoints",
+    "staked__changelog",
+    Strategy::EveryBlock,
+);
+
+pub const TOTAL_STAKED: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_staked",
+    "total_staked__checkpoints",
+    "total_staked__changelog",
+    Strategy::EveryBlock,
+);
+
+pub const CLAIMS: Map<&Addr, Vec<Claim>> = Map::new("claims");
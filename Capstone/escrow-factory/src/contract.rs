@@ -0,0 +1,184 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Order, Reply, Response, StdResult,
+    SubMsg, WasmMsg,
+};
+use cosmwatch_events::{attr, response};
+use cw2::set_contract_version;
+use cw20_escrow_milestones::msg::ExecuteMsg as EscrowExecuteMsg;
+use cw_storage_plus::Bound;
+use cw_utils::parse_reply_instantiate_data;
+
+use crate::error::ContractError;
+use crate::msg::{ConfigResponse, ExecuteMsg, InstancesResponse, InstantiateMsg, QueryMsg};
+use crate::state::{Config, Instance, CLIENT_INSTANCES, CONFIG, INSTANCES, PENDING_CLIENT};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:escrow-factory";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const INSTANTIATE_ESCROW_REPLY_ID: u64 = 1;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        owner: info.sender,
+        escrow_code_id: msg.escrow_code_id,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(response("instantiate")
+        .add_attribute(attr::OWNER, config.owner)
+        .add_attribute("escrow_code_id", config.escrow_code_id.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreateEscrow { label } => execute_create_escrow(deps, info, label),
+        ExecuteMsg::UpdateCodeId { escrow_code_id } => {
+            execute_update_code_id(deps, info, escrow_code_id)
+        }
+    }
+}
+
+fn execute_create_escrow(
+    deps: DepsMut,
+    info: MessageInfo,
+    label: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    PENDING_CLIENT.save(deps.storage, &info.sender)?;
+
+    let instantiate = WasmMsg::Instantiate {
+        admin: Some(info.sender.to_string()),
+        code_id: config.escrow_code_id,
+        msg: to_binary(&cw20_escrow_milestones::msg::InstantiateMsg::default())?,
+        funds: vec![],
+        label,
+    };
+
+    Ok(response("create_escrow")
+        .add_attribute("client", info.sender)
+        .add_submessage(SubMsg::reply_on_success(
+            instantiate,
+            INSTANTIATE_ESCROW_REPLY_ID,
+        )))
+}
+
+fn execute_update_code_id(
+    deps: DepsMut,
+    info: MessageInfo,
+    escrow_code_id: u64,
+) -> Result<Response, ContractError> {
+    CONFIG.update(deps.storage, |mut config| {
+        if info.sender != config.owner {
+            return Err(ContractError::Unauthorized {});
+        }
+        config.escrow_code_id = escrow_code_id;
+        Ok(config)
+    })?;
+
+    Ok(response("update_code_id").add_attribute("escrow_code_id", escrow_code_id.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    if msg.id != INSTANTIATE_ESCROW_REPLY_ID {
+        return Err(ContractError::UnknownReply { id: msg.id });
+    }
+
+    let res = parse_reply_instantiate_data(msg)?;
+    let address = deps.api.addr_validate(&res.contract_address)?;
+    let client = PENDING_CLIENT.load(deps.storage)?;
+    PENDING_CLIENT.remove(deps.storage);
+
+    let instance = Instance {
+        address,
+        client,
+        code_id: CONFIG.load(deps.storage)?.escrow_code_id,
+    };
+    INSTANCES.save(deps.storage, &instance.address, &instance)?;
+    CLIENT_INSTANCES.save(
+        deps.storage,
+        (&instance.client, &instance.address),
+        &Empty {},
+    )?;
+
+    // the factory instantiated the escrow and so owns it, offer ownership on to the client
+    let transfer = WasmMsg::Execute {
+        contract_addr: instance.address.to_string(),
+        msg: to_binary(&EscrowExecuteMsg::TransferOwnership {
+            new_owner: instance.client.to_string(),
+        })?,
+        funds: vec![],
+    };
+
+    Ok(response("escrow_created")
+        .add_message(transfer)
+        .add_attribute(attr::ESCROW, instance.address)
+        .add_attribute("client", instance.client))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&ConfigResponse {
+            config: CONFIG.load(deps.storage)?,
+        }),
+        QueryMsg::Instances { start_after, limit } => {
+            to_binary(&query_instances(deps, start_after, limit)?)
+        }
+        QueryMsg::InstancesByClient { client } => {
+            to_binary(&query_instances_by_client(deps, client)?)
+        }
+    }
+}
+
+fn query_instances(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<InstancesResponse> {
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let instances = INSTANCES
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, instance)| instance))
+        .collect::<StdResult<_>>()?;
+    Ok(InstancesResponse { instances })
+}
+
+fn query_instances_by_client(deps: Deps, client: String) -> StdResult<InstancesResponse> {
+    let client = deps.api.addr_validate(&client)?;
+    let instances = CLIENT_INSTANCES
+        .prefix(&client)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|address| INSTANCES.load(deps.storage, &address?))
+        .collect::<StdResult<_>>()?;
+    Ok(InstancesResponse { instances })
+}
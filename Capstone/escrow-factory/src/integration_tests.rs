@@ -0,0 +1,232 @@
+#![cfg(test)]
+
+use cosmwasm_std::{coins, Addr, Empty};
+use cw20_escrow_milestones::msg::{
+    CreateMilestoneMsg, CreateMsg, ExecuteMsg as EscrowExecuteMsg, ListEscrowsResponse,
+    OwnershipResponse, QueryMsg as EscrowQueryMsg,
+};
+use cw20_escrow_milestones::state::GenericBalance;
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+
+use crate::msg::{ExecuteMsg, InstancesResponse, InstantiateMsg, QueryMsg};
+use crate::state::Instance;
+use crate::ContractError;
+
+const OWNER: &str = "owner";
+const DENOM: &str = "juno";
+
+fn contract_factory() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            crate::contract::execute,
+            crate::contract::instantiate,
+            crate::contract::query,
+        )
+        .with_reply(crate::contract::reply),
+    )
+}
+
+fn contract_escrow() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            cw20_escrow_milestones::contract::execute,
+            cw20_escrow_milestones::contract::instantiate,
+            cw20_escrow_milestones::contract::query,
+        )
+        .with_reply(cw20_escrow_milestones::contract::reply),
+    )
+}
+
+struct Setup {
+    app: App,
+    factory: Addr,
+    escrow_code_id: u64,
+}
+
+fn setup() -> Setup {
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &Addr::unchecked("client1"), coins(100, DENOM))
+            .unwrap();
+    });
+    let escrow_code_id = app.store_code(contract_escrow());
+    let factory_id = app.store_code(contract_factory());
+    let factory = app
+        .instantiate_contract(
+            factory_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg { escrow_code_id },
+            &[],
+            "factory",
+            None,
+        )
+        .unwrap();
+
+    Setup {
+        app,
+        factory,
+        escrow_code_id,
+    }
+}
+
+impl Setup {
+    fn execute(&mut self, sender: &str, msg: ExecuteMsg) -> Result<(), ContractError> {
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.factory.clone(), &msg, &[])
+            .map(|_| ())
+            .map_err(|err| err.downcast().unwrap())
+    }
+
+    fn create_escrow(&mut self, client: &str) {
+        self.execute(
+            client,
+            ExecuteMsg::CreateEscrow {
+                label: format!("escrow for {}", client),
+            },
+        )
+        .unwrap();
+    }
+
+    fn instances(&self, msg: QueryMsg) -> Vec<Instance> {
+        let res: InstancesResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.factory, &msg)
+            .unwrap();
+        res.instances
+    }
+
+    fn by_client(&self, client: &str) -> Vec<Instance> {
+        self.instances(QueryMsg::InstancesByClient {
+            client: client.to_string(),
+        })
+    }
+}
+
+#[test]
+fn creates_an_escrow_per_client() {
+    let mut setup = setup();
+    setup.create_escrow("client1");
+    setup.create_escrow("client2");
+    setup.create_escrow("client1");
+
+    let all = setup.instances(QueryMsg::Instances {
+        start_after: None,
+        limit: None,
+    });
+    assert_eq!(all.len(), 3);
+    assert_eq!(setup.by_client("client1").len(), 2);
+    assert_eq!(setup.by_client("client2").len(), 1);
+
+    // each instance is a live escrow administered by its client
+    let instance = &setup.by_client("client1")[0];
+    assert_eq!(instance.code_id, setup.escrow_code_id);
+    let info = setup
+        .app
+        .wrap()
+        .query_wasm_contract_info(&instance.address)
+        .unwrap();
+    assert_eq!(info.admin, Some("client1".to_string()));
+    assert_eq!(info.code_id, setup.escrow_code_id);
+
+    // escrow ownership is offered to the client, who takes it over
+    setup
+        .app
+        .execute_contract(
+            Addr::unchecked("client1"),
+            instance.address.clone(),
+            &EscrowExecuteMsg::AcceptOwnership {},
+            &[],
+        )
+        .unwrap();
+    let ownership: OwnershipResponse = setup
+        .app
+        .wrap()
+        .query_wasm_smart(&instance.address, &EscrowQueryMsg::Ownership {})
+        .unwrap();
+    assert_eq!(ownership.owner, Addr::unchecked("client1"));
+
+    let create = EscrowExecuteMsg::Create(CreateMsg {
+        id: "job".to_string(),
+        arbiter: "client1".to_string(),
+        co_arbiters: None,
+        threshold: None,
+        arbiter_kind: None,
+        recipient: Some("contractor".to_string()),
+        title: "job".to_string(),
+        description: "job".to_string(),
+        cw20_whitelist: None,
+        strict_whitelist: None,
+        crowdfunded: None,
+        stake: None,
+        milestones: vec![CreateMilestoneMsg {
+            escrow_id: "job".to_string(),
+            title: "done".to_string(),
+            description: "done".to_string(),
+            amount: GenericBalance {
+                native: coins(100, DENOM),
+                cw20: vec![],
+            },
+            expires: None,
+            stream: None,
+        }],
+    });
+    setup
+        .app
+        .execute_contract(
+            Addr::unchecked("client1"),
+            instance.address.clone(),
+            &create,
+            &coins(100, DENOM),
+        )
+        .unwrap();
+    let res: ListEscrowsResponse = setup
+        .app
+        .wrap()
+        .query_wasm_smart(&instance.address, &EscrowQueryMsg::List { status: None })
+        .unwrap();
+    assert_eq!(res.escrows.len(), 1);
+    assert_eq!(res.escrows[0].id, "job");
+
+    // pagination walks every instance once
+    let first = setup.instances(QueryMsg::Instances {
+        start_after: None,
+        limit: Some(2),
+    });
+    let rest = setup.instances(QueryMsg::Instances {
+        start_after: Some(first[1].address.to_string()),
+        limit: Some(2),
+    });
+    assert_eq!(first.len() + rest.len(), 3);
+    assert!(!rest.contains(&first[0]) && !rest.contains(&first[1]));
+}
+
+#[test]
+fn only_owner_updates_code_id() {
+    let mut setup = setup();
+    let update = ExecuteMsg::UpdateCodeId { escrow_code_id: 42 };
+    assert_eq!(
+        setup.execute("client1", update.clone()).unwrap_err(),
+        ContractError::Unauthorized {}
+    );
+
+    // instances already created keep their code
+    setup.create_escrow("client1");
+    let new_code_id = setup.app.store_code(contract_escrow());
+    setup
+        .execute(
+            OWNER,
+            ExecuteMsg::UpdateCodeId {
+                escrow_code_id: new_code_id,
+            },
+        )
+        .unwrap();
+    setup.create_escrow("client1");
+    let code_ids: Vec<u64> = setup
+        .by_client("client1")
+        .iter()
+        .map(|instance| instance.code_id)
+        .collect();
+    assert!(code_ids.contains(&setup.escrow_code_id) && code_ids.contains(&new_code_id));
+}
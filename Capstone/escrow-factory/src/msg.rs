@@ -0,0 +1,43 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+
+use crate::state::{Config, Instance};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub escrow_code_id: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Instantiates a new escrow contract for the sender, who becomes its wasm admin
+    CreateEscrow { label: String },
+    /// Only the owner can do this. Existing instances are unaffected
+    UpdateCodeId { escrow_code_id: u64 },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+
+    /// Every instance created, ordered by address
+    #[returns(InstancesResponse)]
+    Instances {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(InstancesResponse)]
+    InstancesByClient { client: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub config: Config,
+}
+
+#[cw_serde]
+pub struct InstancesResponse {
+    pub instances: Vec<Instance>,
+}
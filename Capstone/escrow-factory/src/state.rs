@@ -0,0 +1,26 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Empty};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    /// Can change the escrow code id
+    pub owner: Addr,
+    /// Code id new escrow instances are created from
+    pub escrow_code_id: u64,
+}
+
+#[cw_serde]
+pub struct Instance {
+    pub address: Addr,
+    /// Address the instance was created for, also its wasm admin
+    pub client: Addr,
+    pub code_id: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+/// Client of the instantiation in flight, read back in the reply
+pub const PENDING_CLIENT: Item<Addr> = Item::new("pending_client");
+pub const INSTANCES: Map<&Addr, Instance> = Map::new("instances");
+/// Instances per client, for listing
+pub const CLIENT_INSTANCES: Map<(&Addr, &Addr), Empty> = Map::new("client_instances");
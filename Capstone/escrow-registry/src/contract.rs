@@ -0,0 +1,167 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response, StdResult,
+};
+use cosmwatch_clients::EscrowClient;
+use cosmwatch_events::{attr, response};
+use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, NamesResponse, QueryMsg, ResolveResponse};
+use crate::state::{Entry, NAMES, OWNER_NAMES};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:escrow-registry";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(response("instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Register {
+            name,
+            escrow,
+            escrow_id,
+        } => execute_register(deps, info, name, escrow, escrow_id),
+        ExecuteMsg::Transfer { name, new_owner } => execute_transfer(deps, info, name, new_owner),
+        ExecuteMsg::Release { name } => execute_release(deps, info, name),
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), ContractError> {
+    let valid_char = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || "-_/".contains(c);
+    if !(3..=64).contains(&name.len()) || !name.chars().all(valid_char) {
+        return Err(ContractError::InvalidName {
+            name: name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn execute_register(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+    escrow: String,
+    escrow_id: String,
+) -> Result<Response, ContractError> {
+    validate_name(&name)?;
+    if NAMES.has(deps.storage, &name) {
+        return Err(ContractError::NameTaken { name });
+    }
+
+    // only names for escrows that exist
+    let escrow = deps.api.addr_validate(&escrow)?;
+    EscrowClient::new(&escrow)
+        .escrow_details(&escrow_id)?
+        .fetch(&deps.querier)?;
+
+    let entry = Entry {
+        owner: info.sender,
+        escrow,
+        escrow_id,
+    };
+    NAMES.save(deps.storage, &name, &entry)?;
+    OWNER_NAMES.save(deps.storage, (&entry.owner, &name), &Empty {})?;
+
+    Ok(response("register")
+        .add_attribute("name", name)
+        .add_attribute(attr::OWNER, entry.owner)
+        .add_attribute(attr::ESCROW, entry.escrow)
+        .add_attribute(attr::ESCROW_ID, entry.escrow_id))
+}
+
+fn load_owned(deps: &DepsMut, info: &MessageInfo, name: &str) -> Result<Entry, ContractError> {
+    let entry = NAMES
+        .may_load(deps.storage, name)?
+        .ok_or_else(|| ContractError::NameNotFound {
+            name: name.to_string(),
+        })?;
+    if entry.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(entry)
+}
+
+fn execute_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    let mut entry = load_owned(&deps, &info, &name)?;
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+
+    OWNER_NAMES.remove(deps.storage, (&entry.owner, &name));
+    entry.owner = new_owner;
+    NAMES.save(deps.storage, &name, &entry)?;
+    OWNER_NAMES.save(deps.storage, (&entry.owner, &name), &Empty {})?;
+
+    Ok(response("transfer")
+        .add_attribute("name", name)
+        .add_attribute(attr::OWNER, entry.owner))
+}
+
+fn execute_release(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    let entry = load_owned(&deps, &info, &name)?;
+
+    NAMES.remove(deps.storage, &name);
+    OWNER_NAMES.remove(deps.storage, (&entry.owner, &name));
+
+    Ok(response("release").add_attribute("name", name))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Resolve { name } => to_binary(&ResolveResponse {
+            entry: NAMES.may_load(deps.storage, &name)?,
+        }),
+        QueryMsg::Names {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_names(deps, owner, start_after, limit)?),
+    }
+}
+
+fn query_names(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<NamesResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let names = OWNER_NAMES
+        .prefix(&owner)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    Ok(NamesResponse { names })
+}
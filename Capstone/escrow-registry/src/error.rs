@@ -0,0 +1,20 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid name {name}: use 3 to 64 lowercase letters, digits, '-', '_' or '/'")]
+    InvalidName { name: String },
+
+    #[error("Name {name} is already registered")]
+    NameTaken { name: String },
+
+    #[error("Name {name} is not registered")]
+    NameNotFound { name: String },
+}
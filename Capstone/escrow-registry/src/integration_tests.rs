@@ -0,0 +1,240 @@
+#![cfg(test)]
+
+use cosmwasm_std::{coins, Addr, Empty};
+use cw20_escrow_milestones::msg::{CreateMilestoneMsg, CreateMsg, ExecuteMsg as EscrowExecuteMsg};
+use cw20_escrow_milestones::state::GenericBalance;
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+
+use crate::msg::{ExecuteMsg, InstantiateMsg, NamesResponse, QueryMsg, ResolveResponse};
+use crate::state::Entry;
+use crate::ContractError;
+
+const OWNER: &str = "wba";
+const DENOM: &str = "juno";
+
+fn contract_registry() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    ))
+}
+
+fn contract_escrow() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            cw20_escrow_milestones::contract::execute,
+            cw20_escrow_milestones::contract::instantiate,
+            cw20_escrow_milestones::contract::query,
+        )
+        .with_reply(cw20_escrow_milestones::contract::reply),
+    )
+}
+
+struct Setup {
+    app: App,
+    registry: Addr,
+    escrow: Addr,
+}
+
+/// Registry and an escrow holding one escrow with id "website"
+fn setup() -> Setup {
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &Addr::unchecked(OWNER), coins(100, DENOM))
+            .unwrap();
+    });
+
+    let escrow_id = app.store_code(contract_escrow());
+    let escrow = app
+        .instantiate_contract(
+            escrow_id,
+            Addr::unchecked(OWNER),
+            &cw20_escrow_milestones::msg::InstantiateMsg::default(),
+            &[],
+            "escrow",
+            None,
+        )
+        .unwrap();
+    let create = EscrowExecuteMsg::Create(CreateMsg {
+        id: "website".to_string(),
+        arbiter: "arbiter".to_string(),
+        co_arbiters: None,
+        threshold: None,
+        arbiter_kind: None,
+        recipient: Some("designer".to_string()),
+        title: "Website redesign".to_string(),
+        description: "New landing page".to_string(),
+        cw20_whitelist: None,
+        strict_whitelist: None,
+        crowdfunded: None,
+        stake: None,
+        milestones: vec![CreateMilestoneMsg {
+            escrow_id: "website".to_string(),
+            title: "mockups".to_string(),
+            description: "mockups".to_string(),
+            amount: GenericBalance {
+                native: coins(100, DENOM),
+                cw20: vec![],
+            },
+            expires: None,
+            stream: None,
+        }],
+    });
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        escrow.clone(),
+        &create,
+        &coins(100, DENOM),
+    )
+    .unwrap();
+
+    let registry_id = app.store_code(contract_registry());
+    let registry = app
+        .instantiate_contract(
+            registry_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg {},
+            &[],
+            "registry",
+            None,
+        )
+        .unwrap();
+
+    Setup {
+        app,
+        registry,
+        escrow,
+    }
+}
+
+impl Setup {
+    fn execute(&mut self, sender: &str, msg: ExecuteMsg) -> Result<(), ContractError> {
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.registry.clone(), &msg, &[])
+            .map(|_| ())
+            .map_err(|err| err.downcast().unwrap())
+    }
+
+    fn register(&mut self, sender: &str, name: &str, escrow_id: &str) -> Result<(), ContractError> {
+        let escrow = self.escrow.to_string();
+        self.execute(
+            sender,
+            ExecuteMsg::Register {
+                name: name.to_string(),
+                escrow,
+                escrow_id: escrow_id.to_string(),
+            },
+        )
+    }
+
+    fn resolve(&self, name: &str) -> Option<Entry> {
+        let res: ResolveResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.registry,
+                &QueryMsg::Resolve {
+                    name: name.to_string(),
+                },
+            )
+            .unwrap();
+        res.entry
+    }
+
+    fn names(&self, owner: &str) -> Vec<String> {
+        let res: NamesResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.registry,
+                &QueryMsg::Names {
+                    owner: owner.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        res.names
+    }
+}
+
+#[test]
+fn register_and_resolve() {
+    let mut setup = setup();
+    setup
+        .register(OWNER, "wba/website-redesign", "website")
+        .unwrap();
+    assert_eq!(
+        setup.resolve("wba/website-redesign"),
+        Some(Entry {
+            owner: Addr::unchecked(OWNER),
+            escrow: setup.escrow.clone(),
+            escrow_id: "website".to_string()
+        })
+    );
+    assert_eq!(setup.names(OWNER), vec!["wba/website-redesign"]);
+
+    // names are first come, first served
+    assert_eq!(
+        setup
+            .register("someone", "wba/website-redesign", "website")
+            .unwrap_err(),
+        ContractError::NameTaken {
+            name: "wba/website-redesign".to_string()
+        }
+    );
+    // and only point at escrows that exist
+    assert!(matches!(
+        setup.register(OWNER, "wba/missing", "missing").unwrap_err(),
+        ContractError::Std(_)
+    ));
+    for name in ["ab", "WBA/site", "wba site"] {
+        assert_eq!(
+            setup.register(OWNER, name, "website").unwrap_err(),
+            ContractError::InvalidName {
+                name: name.to_string()
+            }
+        );
+    }
+}
+
+#[test]
+fn transfer_and_release() {
+    let mut setup = setup();
+    setup.register(OWNER, "wba/website", "website").unwrap();
+
+    let transfer = ExecuteMsg::Transfer {
+        name: "wba/website".to_string(),
+        new_owner: "dao".to_string(),
+    };
+    assert_eq!(
+        setup.execute("dao", transfer.clone()).unwrap_err(),
+        ContractError::Unauthorized {}
+    );
+    setup.execute(OWNER, transfer).unwrap();
+    assert_eq!(setup.resolve("wba/website").unwrap().owner, "dao");
+    assert!(setup.names(OWNER).is_empty());
+    assert_eq!(setup.names("dao"), vec!["wba/website"]);
+
+    let release = ExecuteMsg::Release {
+        name: "wba/website".to_string(),
+    };
+    assert_eq!(
+        setup.execute(OWNER, release.clone()).unwrap_err(),
+        ContractError::Unauthorized {}
+    );
+    setup.execute("dao", release.clone()).unwrap();
+    assert_eq!(setup.resolve("wba/website"), None);
+    assert!(setup.names("dao").is_empty());
+    assert_eq!(
+        setup.execute("dao", release).unwrap_err(),
+        ContractError::NameNotFound {
+            name: "wba/website".to_string()
+        }
+    );
+
+    // released names can be registered again
+    setup.register("someone", "wba/website", "website").unwrap();
+}
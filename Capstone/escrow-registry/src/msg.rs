@@ -0,0 +1,46 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+
+use crate::state::Entry;
+
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Registers `name` for an existing escrow, owned by the sender. Names such as
+    /// `wba/website-redesign` are first come, first served
+    Register {
+        name: String,
+        escrow: String,
+        escrow_id: String,
+    },
+    /// Hands the name to another owner. Only the current owner can do this
+    Transfer { name: String, new_owner: String },
+    /// Frees the name for anyone to register. Only the current owner can do this
+    Release { name: String },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ResolveResponse)]
+    Resolve { name: String },
+
+    /// Names held by `owner`, in lexicographic order
+    #[returns(NamesResponse)]
+    Names {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct ResolveResponse {
+    pub entry: Option<Entry>,
+}
+
+#[cw_serde]
+pub struct NamesResponse {
+    pub names: Vec<String>,
+}
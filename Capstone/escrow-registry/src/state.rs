@@ -0,0 +1,15 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Empty};
+use cw_storage_plus::Map;
+
+#[cw_serde]
+pub struct Entry {
+    /// Can transfer or release the name
+    pub owner: Addr,
+    pub escrow: Addr,
+    pub escrow_id: String,
+}
+
+pub const NAMES: Map<&str, Entry> = Map::new("names");
+/// Names held by each owner, for listing
+pub const OWNER_NAMES: Map<(&Addr, &str), Empty> = Map::new("owner_names");
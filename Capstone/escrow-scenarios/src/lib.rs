@@ -0,0 +1,13 @@
+//! Regression scenarios for the escrow, cw20 and poll contracts. Each file in `scenarios/` lists
+//! genesis balances and a sequence of instantiate, execute and query steps with their expected
+//! results, so a new regression case is a new JSON file rather than a new Rust test.
+//!
+//! Inside a scenario, `"$label"` stands for the address of the contract instantiated with that
+//! label, and `{"$binary": msg}` for `msg` encoded as `Binary`, as cw20 `Send` expects.
+
+pub mod runner;
+pub mod scenario;
+mod tests;
+
+pub use crate::runner::{run, run_file, Runner};
+pub use crate::scenario::{Code, Scenario, Step};
@@ -0,0 +1,289 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result as AnyResult};
+use cosmwasm_std::{Addr, Binary, Empty};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use serde_json::{Map, Value};
+
+use crate::scenario::{Code, Scenario, Step};
+
+fn contract_escrow() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            cw20_escrow_milestones::contract::execute,
+            cw20_escrow_milestones::contract::instantiate,
+            cw20_escrow_milestones::contract::query,
+        )
+        .with_reply(cw20_escrow_milestones::contract::reply),
+    )
+}
+
+fn contract_cw20() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    ))
+}
+
+fn contract_poll() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cosm_wasm_zero2_hero::contract::execute,
+        cosm_wasm_zero2_hero::contract::instantiate,
+        cosm_wasm_zero2_hero::contract::query,
+    ))
+}
+
+fn contract_adapter() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        poll_escrow_adapter::contract::execute,
+        poll_escrow_adapter::contract::instantiate,
+        poll_escrow_adapter::contract::query,
+    ))
+}
+
+/// Runs `scenario` on a fresh chain, failing on the first step that doesn't go as expected
+pub fn run(scenario: &Scenario) -> AnyResult<()> {
+    let mut runner = Runner::new(scenario);
+    for (index, step) in scenario.steps.iter().enumerate() {
+        runner
+            .step(step)
+            .with_context(|| format!("step {}: {:?}", index + 1, step))?;
+    }
+    Ok(())
+}
+
+/// Reads the scenario at `path` and runs it
+pub fn run_file(path: &Path) -> AnyResult<()> {
+    let scenario: Scenario = serde_json::from_str(&fs::read_to_string(path)?)
+        .with_context(|| format!("parsing {}", path.display()))?;
+    run(&scenario).with_context(|| format!("{}: {}", path.display(), scenario.description))
+}
+
+/// A multi-test chain with every contract code stored, and the contracts the scenario has
+/// instantiated so far
+pub struct Runner {
+    pub app: App,
+    codes: BTreeMap<Code, u64>,
+    contracts: BTreeMap<String, Addr>,
+}
+
+impl Runner {
+    pub fn new(scenario: &Scenario) -> Self {
+        let balances = scenario.balances.clone();
+        let mut app = App::new(|router, _, storage| {
+            for (addr, coins) in balances {
+                router
+                    .bank
+                    .init_balance(storage, &Addr::unchecked(addr), coins)
+                    .unwrap();
+            }
+        });
+
+        let codes = [
+            (Code::Escrow, contract_escrow()),
+            (Code::Cw20, contract_cw20()),
+            (Code::Poll, contract_poll()),
+            (Code::Adapter, contract_adapter()),
+        ]
+        .into_iter()
+        .map(|(code, contract)| (code, app.store_code(contract)))
+        .collect();
+
+        Runner {
+            app,
+            codes,
+            contracts: BTreeMap::new(),
+        }
+    }
+
+    pub fn step(&mut self, step: &Step) -> AnyResult<()> {
+        match step {
+            Step::Instantiate {
+                code,
+                label,
+                sender,
+                msg,
+                funds,
+            } => {
+                let sender = self.addr(sender)?;
+                let msg = self.resolve(msg)?;
+                let addr = self.app.instantiate_contract(
+                    self.codes[code],
+                    sender,
+                    &msg,
+                    funds,
+                    label.clone(),
+                    None,
+                )?;
+                self.contracts.insert(label.clone(), addr);
+            }
+            Step::Execute {
+                contract,
+                sender,
+                msg,
+                funds,
+                error,
+            } => {
+                let sender = self.addr(sender)?;
+                let contract = self.addr(contract)?;
+                let msg = self.resolve(msg)?;
+                let res = self.app.execute_contract(sender, contract, &msg, funds);
+                check_outcome(res, error)?;
+            }
+            Step::Query {
+                contract,
+                msg,
+                expect,
+                error,
+            } => {
+                let contract = self.addr(contract)?;
+                let msg = self.resolve(msg)?;
+                let res = self.app.wrap().query_wasm_smart(contract, &msg);
+                if let Some(actual) = check_outcome::<Value, _>(res, error)? {
+                    if let Some(expect) = expect {
+                        let expect = self.resolve(expect)?;
+                        if !is_subset(&expect, &actual) {
+                            bail!("expected {} to match {}", actual, expect);
+                        }
+                    }
+                }
+            }
+            Step::Balance {
+                address,
+                denom,
+                amount,
+            } => {
+                let address = self.addr(address)?;
+                let balance = self.app.wrap().query_balance(address, denom)?.amount;
+                if balance != *amount {
+                    bail!(
+                        "expected a balance of {}{}, found {}",
+                        amount,
+                        denom,
+                        balance
+                    );
+                }
+            }
+            Step::NextBlocks(blocks) => self.app.update_block(|block| {
+                block.height += blocks;
+                block.time = block.time.plus_seconds(5 * blocks);
+            }),
+        }
+        Ok(())
+    }
+
+    /// `$label` is the contract instantiated with that label, anything else an address as is
+    pub fn addr(&self, name: &str) -> AnyResult<Addr> {
+        match name.strip_prefix('$') {
+            Some(label) => self
+                .contracts
+                .get(label)
+                .cloned()
+                .ok_or_else(|| anyhow!("no contract labelled {}", label)),
+            None => Ok(Addr::unchecked(name)),
+        }
+    }
+
+    /// Swaps `$label` strings for contract addresses and `{"$binary": msg}` for the base64
+    /// JSON encoding of `msg`
+    fn resolve(&self, value: &Value) -> AnyResult<Value> {
+        Ok(match value {
+            Value::String(name) if name.starts_with('$') => {
+                Value::String(self.addr(name)?.into_string())
+            }
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.resolve(item))
+                    .collect::<AnyResult<_>>()?,
+            ),
+            Value::Object(fields) => match fields.get("$binary") {
+                Some(msg) if fields.len() == 1 => {
+                    let msg = serde_json::to_vec(&self.resolve(msg)?)?;
+                    Value::String(Binary::from(msg).to_base64())
+                }
+                _ => Value::Object(
+                    fields
+                        .iter()
+                        .map(|(key, field)| Ok((key.clone(), self.resolve(field)?)))
+                        .collect::<AnyResult<Map<_, _>>>()?,
+                ),
+            },
+            other => other.clone(),
+        })
+    }
+}
+
+/// Matches a result against the error a step expects, returning the value on expected success
+fn check_outcome<T, E: Display>(res: Result<T, E>, error: &Option<String>) -> AnyResult<Option<T>> {
+    match (res, error) {
+        (Ok(value), None) => Ok(Some(value)),
+        (Err(err), None) => bail!("unexpected error: {:#}", err),
+        (Ok(_), Some(expected)) => bail!("expected an error containing {:?}", expected),
+        (Err(err), Some(expected)) => {
+            let err = format!("{:#}", err);
+            if !err.contains(expected.as_str()) {
+                bail!("expected an error containing {:?}, got {:?}", expected, err);
+            }
+            Ok(None)
+        }
+    }
+}
+
+/// Every field of an `expected` object must match in `actual`, which may have more. Arrays
+/// match element by element and everything else must be equal
+fn is_subset(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => expected
+            .iter()
+            .all(|(key, field)| matches!(actual.get(key), Some(value) if is_subset(field, value))),
+        (Value::Array(expected), Value::Array(actual)) => {
+            expected.len() == actual.len()
+                && expected
+                    .iter()
+                    .zip(actual)
+                    .all(|(expected, actual)| is_subset(expected, actual))
+        }
+        _ => expected == actual,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn subset_ignores_unlisted_fields() {
+        let actual = json!({"id": "grant", "milestones": [{"id": "1", "title": "design"}]});
+        assert!(is_subset(&json!({"milestones": [{"id": "1"}]}), &actual));
+        assert!(!is_subset(&json!({"milestones": []}), &actual));
+        assert!(!is_subset(&json!({"id": "other"}), &actual));
+        assert!(!is_subset(&json!({"missing": null}), &actual));
+    }
+
+    #[test]
+    fn resolves_labels_and_binary() {
+        let mut runner = Runner::new(&Scenario {
+            description: String::new(),
+            balances: BTreeMap::new(),
+            steps: vec![],
+        });
+        runner
+            .contracts
+            .insert("cash".to_string(), Addr::unchecked("contract0"));
+
+        let resolved = runner
+            .resolve(&json!({"send": {"contract": "$cash", "msg": {"$binary": {"id": "$cash"}}}}))
+            .unwrap();
+        assert_eq!(
+            resolved,
+            json!({"send": {"contract": "contract0", "msg": "eyJpZCI6ImNvbnRyYWN0MCJ9"}})
+        );
+        assert!(runner.addr("$missing").is_err());
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{Coin, Uint128};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// A scenario file: genesis balances and the steps run against them, in order
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// What the scenario checks, reported when it fails
+    #[serde(default)]
+    pub description: String,
+    /// Native coins each account holds at genesis
+    #[serde(default)]
+    pub balances: BTreeMap<String, Vec<Coin>>,
+    pub steps: Vec<Step>,
+}
+
+/// Contract codes stored before the first step
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Code {
+    Escrow,
+    Cw20,
+    Poll,
+    Adapter,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Step {
+    /// Instantiates `code`. Later steps refer to the new contract as `$<label>`
+    Instantiate {
+        code: Code,
+        label: String,
+        sender: String,
+        msg: Value,
+        #[serde(default)]
+        funds: Vec<Coin>,
+    },
+    /// Executes `msg` on `contract`. Expects success, or with `error` set, an error whose
+    /// message contains it
+    Execute {
+        contract: String,
+        sender: String,
+        msg: Value,
+        #[serde(default)]
+        funds: Vec<Coin>,
+        #[serde(default)]
+        error: Option<String>,
+    },
+    /// Queries `contract`. Every field in `expect` must match the response, fields it leaves
+    /// out are not checked. With `error` set, expects the query to fail instead
+    Query {
+        contract: String,
+        msg: Value,
+        #[serde(default)]
+        expect: Option<Value>,
+        #[serde(default)]
+        error: Option<String>,
+    },
+    /// Checks the native `denom` balance of `address`
+    Balance {
+        address: String,
+        denom: String,
+        amount: Uint128,
+    },
+    /// Advances the chain by this many blocks, five seconds apart
+    NextBlocks(u64),
+}
@@ -0,0 +1,26 @@
+#![cfg(test)]
+
+use std::fs;
+use std::path::Path;
+
+use crate::runner::run_file;
+
+#[test]
+fn scenarios_pass() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("scenarios");
+    let mut paths: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no scenarios in {}", dir.display());
+
+    // run every scenario so one failure doesn't hide the others
+    let failures: Vec<String> = paths
+        .iter()
+        .filter_map(|path| run_file(path).err())
+        .map(|err| format!("{:#}", err))
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
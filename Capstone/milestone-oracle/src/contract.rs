@@ -0,0 +1,151 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult,
+};
+use cosmwatch_clients::EscrowClient;
+use cosmwatch_events::{attr, response};
+use cw2::set_contract_version;
+
+use crate::error::ContractError;
+use crate::msg::{
+    AttestationResponse, AttestationsResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
+};
+use crate::state::{Attestation, Config, ATTESTATIONS, CONFIG};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:milestone-oracle";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        owner: info.sender,
+        escrow: deps.api.addr_validate(&msg.escrow)?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(response("instantiate")
+        .add_attribute(attr::OWNER, config.owner)
+        .add_attribute(attr::ESCROW, config.escrow))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Attest {
+            escrow_id,
+            milestone_id,
+            passed,
+        } => execute_attest(deps, env, info, escrow_id, milestone_id, passed),
+        ExecuteMsg::Settle {
+            escrow_id,
+            milestone_id,
+        } => execute_settle(deps, escrow_id, milestone_id),
+    }
+}
+
+fn execute_attest(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    escrow_id: String,
+    milestone_id: String,
+    passed: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let key = (escrow_id.as_str(), milestone_id.as_str());
+    if let Some(Attestation { settled: true, .. }) = ATTESTATIONS.may_load(deps.storage, key)? {
+        return Err(ContractError::AlreadySettled {
+            escrow_id,
+            milestone_id,
+        });
+    }
+    let attestation = Attestation {
+        passed,
+        height: env.block.height,
+        settled: false,
+    };
+    ATTESTATIONS.save(deps.storage, key, &attestation)?;
+
+    Ok(response("attest")
+        .add_attribute(attr::ESCROW_ID, escrow_id)
+        .add_attribute(attr::MILESTONE_ID, milestone_id)
+        .add_attribute("passed", passed.to_string()))
+}
+
+fn execute_settle(
+    deps: DepsMut,
+    escrow_id: String,
+    milestone_id: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let key = (escrow_id.as_str(), milestone_id.as_str());
+    let mut attestation =
+        ATTESTATIONS
+            .may_load(deps.storage, key)?
+            .ok_or_else(|| ContractError::NotAttested {
+                escrow_id: escrow_id.clone(),
+                milestone_id: milestone_id.clone(),
+            })?;
+    if attestation.settled {
+        return Err(ContractError::AlreadySettled {
+            escrow_id,
+            milestone_id,
+        });
+    }
+    if !attestation.passed {
+        return Err(ContractError::NotPassed {
+            escrow_id,
+            milestone_id,
+        });
+    }
+
+    attestation.settled = true;
+    ATTESTATIONS.save(deps.storage, key, &attestation)?;
+
+    // this contract is the escrow's arbiter, so the approval is sent in its name
+    let approve = EscrowClient::new(config.escrow).approve_milestone(&escrow_id, &milestone_id)?;
+
+    Ok(response("settle")
+        .add_attribute(attr::ESCROW_ID, escrow_id)
+        .add_attribute(attr::MILESTONE_ID, milestone_id)
+        .add_message(approve))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&ConfigResponse {
+            config: CONFIG.load(deps.storage)?,
+        }),
+        QueryMsg::Attestation {
+            escrow_id,
+            milestone_id,
+        } => to_binary(&AttestationResponse {
+            attestation: ATTESTATIONS.may_load(deps.storage, (&escrow_id, &milestone_id))?,
+        }),
+        QueryMsg::Attestations { escrow_id } => to_binary(&AttestationsResponse {
+            attestations: ATTESTATIONS
+                .prefix(&escrow_id)
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<_>>()?,
+        }),
+    }
+}
@@ -0,0 +1,29 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("No attestation for milestone {milestone_id} of escrow {escrow_id}")]
+    NotAttested {
+        escrow_id: String,
+        milestone_id: String,
+    },
+
+    #[error("Milestone {milestone_id} of escrow {escrow_id} did not pass")]
+    NotPassed {
+        escrow_id: String,
+        milestone_id: String,
+    },
+
+    #[error("Milestone {milestone_id} of escrow {escrow_id} was already settled")]
+    AlreadySettled {
+        escrow_id: String,
+        milestone_id: String,
+    },
+}
@@ -0,0 +1,274 @@
+#![cfg(test)]
+
+use cosmwasm_std::{coins, Addr, Empty};
+use cw20_escrow_milestones::msg::{
+    ClaimsResponse, CreateMilestoneMsg, CreateMsg, ExecuteMsg as EscrowExecuteMsg,
+    QueryMsg as EscrowQueryMsg,
+};
+use cw20_escrow_milestones::state::GenericBalance;
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+
+use crate::msg::{AttestationResponse, AttestationsResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::ContractError;
+
+const OWNER: &str = "owner";
+const FUNDER: &str = "funder";
+const RECIPIENT: &str = "recipient";
+const DENOM: &str = "juno";
+
+fn contract_oracle() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    ))
+}
+
+fn contract_escrow() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            cw20_escrow_milestones::contract::execute,
+            cw20_escrow_milestones::contract::instantiate,
+            cw20_escrow_milestones::contract::query,
+        )
+        .with_reply(cw20_escrow_milestones::contract::reply),
+    )
+}
+
+struct Setup {
+    app: App,
+    oracle: Addr,
+    escrow: Addr,
+}
+
+/// Escrow with two native milestones whose arbiter is the oracle
+fn setup() -> Setup {
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &Addr::unchecked(FUNDER), coins(1000, DENOM))
+            .unwrap();
+    });
+
+    let escrow_id = app.store_code(contract_escrow());
+    let escrow = app
+        .instantiate_contract(
+            escrow_id,
+            Addr::unchecked(OWNER),
+            &cw20_escrow_milestones::msg::InstantiateMsg::default(),
+            &[],
+            "escrow",
+            None,
+        )
+        .unwrap();
+
+    let oracle_id = app.store_code(contract_oracle());
+    let oracle = app
+        .instantiate_contract(
+            oracle_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg {
+                escrow: escrow.to_string(),
+            },
+            &[],
+            "oracle",
+            None,
+        )
+        .unwrap();
+
+    let milestone = |title: &str, amount: u128| CreateMilestoneMsg {
+        escrow_id: "audit".to_string(),
+        title: title.to_string(),
+        description: title.to_string(),
+        amount: GenericBalance {
+            native: coins(amount, DENOM),
+            cw20: vec![],
+        },
+        expires: None,
+        stream: None,
+    };
+    let create = EscrowExecuteMsg::Create(CreateMsg {
+        id: "audit".to_string(),
+        arbiter: oracle.to_string(),
+        co_arbiters: None,
+        threshold: None,
+        arbiter_kind: None,
+        recipient: Some(RECIPIENT.to_string()),
+        title: "audit".to_string(),
+        description: "paid when the oracle confirms each milestone".to_string(),
+        cw20_whitelist: None,
+        strict_whitelist: None,
+        crowdfunded: None,
+        stake: None,
+        milestones: vec![milestone("tests pass", 300), milestone("deployed", 700)],
+    });
+    app.execute_contract(
+        Addr::unchecked(FUNDER),
+        escrow.clone(),
+        &create,
+        &coins(1000, DENOM),
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(RECIPIENT),
+        escrow.clone(),
+        &EscrowExecuteMsg::AcceptEscrow {
+            id: "audit".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    // both milestones are delivered, waiting on the oracle to confirm them
+    for milestone_id in ["1", "2"] {
+        app.execute_contract(
+            Addr::unchecked(RECIPIENT),
+            escrow.clone(),
+            &EscrowExecuteMsg::SubmitMilestone {
+                id: "audit".to_string(),
+                milestone_id: milestone_id.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    Setup {
+        app,
+        oracle,
+        escrow,
+    }
+}
+
+impl Setup {
+    fn attest(
+        &mut self,
+        sender: &str,
+        milestone_id: &str,
+        passed: bool,
+    ) -> Result<(), ContractError> {
+        self.execute(
+            sender,
+            ExecuteMsg::Attest {
+                escrow_id: "audit".to_string(),
+                milestone_id: milestone_id.to_string(),
+                passed,
+            },
+        )
+    }
+
+    fn settle(&mut self, milestone_id: &str) -> Result<(), ContractError> {
+        self.execute(
+            "anyone",
+            ExecuteMsg::Settle {
+                escrow_id: "audit".to_string(),
+                milestone_id: milestone_id.to_string(),
+            },
+        )
+    }
+
+    fn execute(&mut self, sender: &str, msg: ExecuteMsg) -> Result<(), ContractError> {
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.oracle.clone(), &msg, &[])
+            .map(|_| ())
+            .map_err(|err| err.downcast().unwrap())
+    }
+
+    /// What the escrow has credited to the recipient for approved milestones
+    fn released(&self) -> u128 {
+        let claims: ClaimsResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.escrow,
+                &EscrowQueryMsg::Claims {
+                    address: RECIPIENT.to_string(),
+                },
+            )
+            .unwrap();
+        claims
+            .native_balance
+            .iter()
+            .map(|coin| coin.amount.u128())
+            .sum()
+    }
+}
+
+#[test]
+fn passing_attestation_releases_milestone() {
+    let mut setup = setup();
+
+    assert_eq!(
+        setup.settle("1").unwrap_err(),
+        ContractError::NotAttested {
+            escrow_id: "audit".to_string(),
+            milestone_id: "1".to_string()
+        }
+    );
+    assert_eq!(
+        setup.attest(RECIPIENT, "1", true).unwrap_err(),
+        ContractError::Unauthorized {}
+    );
+
+    setup.attest(OWNER, "1", true).unwrap();
+    let res: AttestationResponse = setup
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &setup.oracle,
+            &QueryMsg::Attestation {
+                escrow_id: "audit".to_string(),
+                milestone_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+    assert!(res.attestation.unwrap().passed);
+
+    setup.settle("1").unwrap();
+    assert_eq!(setup.released(), 300);
+
+    // settled attestations are final and pay out once
+    let settled = ContractError::AlreadySettled {
+        escrow_id: "audit".to_string(),
+        milestone_id: "1".to_string(),
+    };
+    assert_eq!(setup.settle("1").unwrap_err(), settled);
+    assert_eq!(setup.attest(OWNER, "1", false).unwrap_err(), settled);
+    assert_eq!(setup.released(), 300);
+}
+
+#[test]
+fn failing_attestation_can_be_corrected() {
+    let mut setup = setup();
+
+    setup.attest(OWNER, "2", false).unwrap();
+    assert_eq!(
+        setup.settle("2").unwrap_err(),
+        ContractError::NotPassed {
+            escrow_id: "audit".to_string(),
+            milestone_id: "2".to_string()
+        }
+    );
+    assert_eq!(setup.released(), 0);
+
+    setup.attest(OWNER, "2", true).unwrap();
+    setup.attest(OWNER, "1", false).unwrap();
+    setup.settle("2").unwrap();
+    assert_eq!(setup.released(), 700);
+
+    let res: AttestationsResponse = setup
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &setup.oracle,
+            &QueryMsg::Attestations {
+                escrow_id: "audit".to_string(),
+            },
+        )
+        .unwrap();
+    let summary: Vec<_> = res
+        .attestations
+        .iter()
+        .map(|(id, a)| (id.as_str(), a.passed, a.settled))
+        .collect();
+    assert_eq!(summary, vec![("1", false, false), ("2", true, true)]);
+}
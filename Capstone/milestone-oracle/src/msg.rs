@@ -0,0 +1,57 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+
+use crate::state::{Attestation, Config};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub escrow: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Records whether a milestone's conditions were met. Can be posted again to correct it
+    /// until it is settled. Only the owner can do this
+    Attest {
+        escrow_id: String,
+        milestone_id: String,
+        passed: bool,
+    },
+    /// Approves the milestone on the escrow if its attestation passed. Anyone can do this
+    Settle {
+        escrow_id: String,
+        milestone_id: String,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+
+    #[returns(AttestationResponse)]
+    Attestation {
+        escrow_id: String,
+        milestone_id: String,
+    },
+
+    /// Every attestation posted for an escrow
+    #[returns(AttestationsResponse)]
+    Attestations { escrow_id: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub config: Config,
+}
+
+#[cw_serde]
+pub struct AttestationResponse {
+    pub attestation: Option<Attestation>,
+}
+
+#[cw_serde]
+pub struct AttestationsResponse {
+    /// `(milestone_id, attestation)` pairs
+    pub attestations: Vec<(String, Attestation)>,
+}
@@ -0,0 +1,24 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    /// Only address that can post attestations
+    pub owner: Addr,
+    /// Escrow this contract is the arbiter of
+    pub escrow: Addr,
+}
+
+#[cw_serde]
+pub struct Attestation {
+    pub passed: bool,
+    /// Block height the attestation was last posted at
+    pub height: u64,
+    /// Set once a passing attestation has been relayed to the escrow
+    pub settled: bool,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+/// Attestations keyed by `(escrow_id, milestone_id)`
+pub const ATTESTATIONS: Map<(&str, &str), Attestation> = Map::new("attestations");
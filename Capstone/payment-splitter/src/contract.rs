@@ -0,0 +1,244 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response, StdResult, SubMsg,
+    Uint128,
+};
+use cosmwatch_balances::{send_tokens, GenericBalance};
+use cosmwatch_clients::EscrowClient;
+use cosmwatch_events::{attr, response};
+use cw2::set_contract_version;
+use cw20::{BalanceResponse, Cw20CoinVerified, Cw20QueryMsg, Cw20ReceiveMsg};
+
+use crate::error::ContractError;
+use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::state::{Config, Recipient, CONFIG};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:payment-splitter";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if msg.recipients.is_empty() {
+        return Err(ContractError::NoRecipients {});
+    }
+    let mut recipients: Vec<Recipient> = Vec::with_capacity(msg.recipients.len());
+    for (addr, weight) in msg.recipients {
+        let addr = deps.api.addr_validate(&addr)?;
+        if weight == 0 {
+            return Err(ContractError::ZeroWeight { addr: addr.into() });
+        }
+        if recipients.iter().any(|r| r.addr == addr) {
+            return Err(ContractError::DuplicateRecipient { addr: addr.into() });
+        }
+        recipients.push(Recipient { addr, weight });
+    }
+    let config = Config {
+        total_weight: recipients.iter().map(|r| r.weight).sum(),
+        recipients,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(response("instantiate").add_attribute("recipients", config.recipients.len().to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Distribute {
+            denoms,
+            cw20_tokens,
+        } => execute_distribute(deps, env, denoms, cw20_tokens),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, info, msg),
+        ExecuteMsg::AcceptEscrow { escrow, id } => execute_accept_escrow(deps, info, escrow, id),
+        ExecuteMsg::SubmitMilestone {
+            escrow,
+            id,
+            milestone_id,
+        } => execute_submit_milestone(deps, info, escrow, id, milestone_id),
+        ExecuteMsg::ClaimEscrow { escrow } => execute_claim_escrow(deps, escrow),
+    }
+}
+
+fn execute_distribute(
+    deps: DepsMut,
+    env: Env,
+    denoms: Vec<String>,
+    cw20_tokens: Vec<String>,
+) -> Result<Response, ContractError> {
+    let mut balance = GenericBalance::default();
+    for denom in denoms {
+        let coin = deps.querier.query_balance(&env.contract.address, denom)?;
+        if !coin.amount.is_zero() {
+            balance.native.push(coin);
+        }
+    }
+    for token in cw20_tokens {
+        let address = deps.api.addr_validate(&token)?;
+        let res: BalanceResponse = deps.querier.query_wasm_smart(
+            &address,
+            &Cw20QueryMsg::Balance {
+                address: env.contract.address.to_string(),
+            },
+        )?;
+        if !res.balance.is_zero() {
+            balance.cw20.push(Cw20CoinVerified {
+                address,
+                amount: res.balance,
+            });
+        }
+    }
+
+    split(deps, "distribute", &balance)
+}
+
+fn execute_receive(
+    deps: DepsMut,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // the cw20 contract calls us, so the sender is the token
+    let balance = GenericBalance {
+        native: vec![],
+        cw20: vec![Cw20CoinVerified {
+            address: info.sender,
+            amount: wrapper.amount,
+        }],
+    };
+
+    split(deps, "receive", &balance)
+}
+
+fn execute_accept_escrow(
+    deps: DepsMut,
+    info: MessageInfo,
+    escrow: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if !config.recipients.iter().any(|r| r.addr == info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // the escrow only takes acceptance from its recipient, this contract
+    let escrow = EscrowClient::new(deps.api.addr_validate(&escrow)?);
+    Ok(response("accept_escrow")
+        .add_attribute(attr::ESCROW_ID, &id)
+        .add_message(escrow.accept_escrow(id)?))
+}
+
+fn execute_submit_milestone(
+    deps: DepsMut,
+    info: MessageInfo,
+    escrow: String,
+    id: String,
+    milestone_id: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if !config.recipients.iter().any(|r| r.addr == info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let escrow = EscrowClient::new(deps.api.addr_validate(&escrow)?);
+    Ok(response("submit_milestone")
+        .add_attribute(attr::ESCROW_ID, &id)
+        .add_attribute(attr::MILESTONE_ID, &milestone_id)
+        .add_message(escrow.submit_milestone(id, milestone_id)?))
+}
+
+fn execute_claim_escrow(deps: DepsMut, escrow: String) -> Result<Response, ContractError> {
+    // the funds can only ever land here, so nobody needs to be trusted with this
+    let escrow = EscrowClient::new(deps.api.addr_validate(&escrow)?);
+    Ok(response("claim_escrow")
+        .add_attribute(attr::ESCROW, escrow.addr())
+        .add_message(escrow.claim()?))
+}
+
+fn split(deps: DepsMut, action: &str, balance: &GenericBalance) -> Result<Response, ContractError> {
+    if balance.is_empty() {
+        return Err(ContractError::NothingToDistribute {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut messages: Vec<SubMsg> = vec![];
+    for (recipient, share) in shares(&config, balance) {
+        messages.append(&mut send_tokens(&recipient, &share)?);
+    }
+
+    Ok(response(action).add_submessages(messages))
+}
+
+/// Each recipient's cut of `balance`, rounded down. The last recipient also gets whatever the
+/// rounding left over, so the whole balance is paid out
+fn shares(config: &Config, balance: &GenericBalance) -> Vec<(Addr, GenericBalance)> {
+    let mut left = balance.clone();
+    let last = config.recipients.len() - 1;
+    config
+        .recipients
+        .iter()
+        .enumerate()
+        .map(|(i, recipient)| {
+            let share = if i == last {
+                left.clone()
+            } else {
+                let cut =
+                    |amount: Uint128| amount.multiply_ratio(recipient.weight, config.total_weight);
+                let share = GenericBalance {
+                    native: balance
+                        .native
+                        .iter()
+                        .map(|coin| Coin {
+                            denom: coin.denom.clone(),
+                            amount: cut(coin.amount),
+                        })
+                        .collect(),
+                    cw20: balance
+                        .cw20
+                        .iter()
+                        .map(|coin| Cw20CoinVerified {
+                            address: coin.address.clone(),
+                            amount: cut(coin.amount),
+                        })
+                        .collect(),
+                };
+                for (left, paid) in left.native.iter_mut().zip(&share.native) {
+                    left.amount -= paid.amount;
+                }
+                for (left, paid) in left.cw20.iter_mut().zip(&share.cw20) {
+                    left.amount -= paid.amount;
+                }
+                share
+            };
+            (recipient.addr.clone(), without_zeros(share))
+        })
+        .filter(|(_, share)| !share.is_empty())
+        .collect()
+}
+
+fn without_zeros(mut balance: GenericBalance) -> GenericBalance {
+    balance.native.retain(|coin| !coin.amount.is_zero());
+    balance.cw20.retain(|coin| !coin.amount.is_zero());
+    balance
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&ConfigResponse {
+            config: CONFIG.load(deps.storage)?,
+        }),
+    }
+}
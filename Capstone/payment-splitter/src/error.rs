@@ -0,0 +1,23 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("At least one recipient is required")]
+    NoRecipients {},
+
+    #[error("Recipient {addr} has zero weight")]
+    ZeroWeight { addr: String },
+
+    #[error("Recipient {addr} is listed more than once")]
+    DuplicateRecipient { addr: String },
+
+    #[error("Nothing to distribute")]
+    NothingToDistribute {},
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+}
@@ -0,0 +1,382 @@
+#![cfg(test)]
+
+use cosmwasm_std::{coins, to_binary, Addr, Empty, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw20_escrow_milestones::msg::{
+    CreateMilestoneMsg, CreateMsg, ExecuteMsg as EscrowExecuteMsg, ReceiveMsg,
+};
+use cw20_escrow_milestones::state::GenericBalance;
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+
+use crate::msg::{ExecuteMsg, InstantiateMsg};
+use crate::ContractError;
+
+const FUNDER: &str = "funder";
+const ARBITER: &str = "arbiter";
+const DENOM: &str = "juno";
+
+fn contract_splitter() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    ))
+}
+
+fn contract_escrow() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            cw20_escrow_milestones::contract::execute,
+            cw20_escrow_milestones::contract::instantiate,
+            cw20_escrow_milestones::contract::query,
+        )
+        .with_reply(cw20_escrow_milestones::contract::reply),
+    )
+}
+
+fn contract_cw20() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    ))
+}
+
+struct Setup {
+    app: App,
+    splitter: Addr,
+    escrow: Addr,
+    token: Addr,
+}
+
+/// Splitter over `recipients`, an escrow and a cw20 token, with native and cw20 funds for the
+/// funder
+fn setup(recipients: &[(&str, u64)]) -> Setup {
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &Addr::unchecked(FUNDER), coins(1000, DENOM))
+            .unwrap();
+    });
+    let owner = Addr::unchecked("owner");
+
+    let splitter_id = app.store_code(contract_splitter());
+    let splitter = app
+        .instantiate_contract(
+            splitter_id,
+            owner.clone(),
+            &InstantiateMsg {
+                recipients: recipients
+                    .iter()
+                    .map(|(addr, weight)| (addr.to_string(), *weight))
+                    .collect(),
+            },
+            &[],
+            "splitter",
+            None,
+        )
+        .unwrap();
+
+    let escrow_id = app.store_code(contract_escrow());
+    let escrow = app
+        .instantiate_contract(
+            escrow_id,
+            owner.clone(),
+            &cw20_escrow_milestones::msg::InstantiateMsg::default(),
+            &[],
+            "escrow",
+            None,
+        )
+        .unwrap();
+
+    let cw20_id = app.store_code(contract_cw20());
+    let token = app
+        .instantiate_contract(
+            cw20_id,
+            owner,
+            &cw20_base::msg::InstantiateMsg {
+                name: "Cash Money".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 2,
+                initial_balances: vec![Cw20Coin {
+                    address: FUNDER.to_string(),
+                    amount: Uint128::new(1000),
+                }],
+                mint: None,
+                marketing: None,
+            },
+            &[],
+            "CASH",
+            None,
+        )
+        .unwrap();
+
+    Setup {
+        app,
+        splitter,
+        escrow,
+        token,
+    }
+}
+
+impl Setup {
+    fn distribute(&mut self) -> Result<(), ContractError> {
+        self.app
+            .execute_contract(
+                Addr::unchecked("anyone"),
+                self.splitter.clone(),
+                &ExecuteMsg::Distribute {
+                    denoms: vec![DENOM.to_string()],
+                    cw20_tokens: vec![self.token.to_string()],
+                },
+                &[],
+            )
+            .map(|_| ())
+            .map_err(|err| err.downcast().unwrap())
+    }
+
+    fn native_balance(&self, addr: &str) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(addr, DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    fn cw20_balance(&self, addr: &str) -> u128 {
+        let res: BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.token,
+                &Cw20QueryMsg::Balance {
+                    address: addr.to_string(),
+                },
+            )
+            .unwrap();
+        res.balance.u128()
+    }
+}
+
+fn milestone(title: &str, amount: GenericBalance) -> CreateMilestoneMsg {
+    CreateMilestoneMsg {
+        escrow_id: "team".to_string(),
+        title: title.to_string(),
+        description: title.to_string(),
+        amount,
+        expires: None,
+        stream: None,
+    }
+}
+
+fn create_msg(recipient: &Addr, milestones: Vec<CreateMilestoneMsg>) -> CreateMsg {
+    CreateMsg {
+        id: "team".to_string(),
+        arbiter: ARBITER.to_string(),
+        co_arbiters: None,
+        threshold: None,
+        arbiter_kind: None,
+        recipient: Some(recipient.to_string()),
+        title: "team".to_string(),
+        description: "paid out to the whole team".to_string(),
+        cw20_whitelist: None,
+        strict_whitelist: None,
+        crowdfunded: None,
+        stake: None,
+        milestones,
+    }
+}
+
+#[test]
+fn splits_escrow_payouts() {
+    let mut setup = setup(&[("alice", 50), ("bob", 30), ("carol", 20)]);
+    let splitter = setup.splitter.clone();
+    let escrow = setup.escrow.clone();
+    let token = setup.token.clone();
+
+    // one native and one cw20 milestone, both paid to the splitter
+    let native = milestone(
+        "native",
+        GenericBalance {
+            native: coins(1000, DENOM),
+            cw20: vec![],
+        },
+    );
+    setup
+        .app
+        .execute_contract(
+            Addr::unchecked(FUNDER),
+            escrow.clone(),
+            &EscrowExecuteMsg::Create(create_msg(&splitter, vec![native])),
+            &coins(1000, DENOM),
+        )
+        .unwrap();
+    let cw20 = milestone(
+        "cw20",
+        GenericBalance {
+            native: vec![],
+            cw20: vec![Cw20CoinVerified {
+                address: token.clone(),
+                amount: Uint128::new(101),
+            }],
+        },
+    );
+    let mut msg = create_msg(&splitter, vec![cw20]);
+    msg.id = "team_cw20".to_string();
+    setup
+        .app
+        .execute_contract(
+            Addr::unchecked(FUNDER),
+            token.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: escrow.to_string(),
+                amount: Uint128::new(101),
+                msg: to_binary(&ReceiveMsg::Create(msg)).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    // the splitter is the escrows' recipient, so one of its recipients accepts them through it
+    let accept = |id: &str| ExecuteMsg::AcceptEscrow {
+        escrow: escrow.to_string(),
+        id: id.to_string(),
+    };
+    let err = setup
+        .app
+        .execute_contract(
+            Addr::unchecked("mallory"),
+            splitter.clone(),
+            &accept("team"),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    for id in ["team", "team_cw20"] {
+        setup
+            .app
+            .execute_contract(Addr::unchecked("bob"), splitter.clone(), &accept(id), &[])
+            .unwrap();
+        let submit = ExecuteMsg::SubmitMilestone {
+            escrow: escrow.to_string(),
+            id: id.to_string(),
+            milestone_id: "1".to_string(),
+        };
+        setup
+            .app
+            .execute_contract(Addr::unchecked("carol"), splitter.clone(), &submit, &[])
+            .unwrap();
+    }
+
+    for id in ["team", "team_cw20"] {
+        setup
+            .app
+            .execute_contract(
+                Addr::unchecked(ARBITER),
+                escrow.clone(),
+                &EscrowExecuteMsg::ApproveMilestone {
+                    id: id.to_string(),
+                    milestone_id: "1".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+    // approvals are credited to the splitter, anyone can have it claim them
+    assert_eq!(setup.native_balance(splitter.as_str()), 0);
+    setup
+        .app
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            splitter.clone(),
+            &ExecuteMsg::ClaimEscrow {
+                escrow: escrow.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(setup.native_balance(splitter.as_str()), 1000);
+    assert_eq!(setup.cw20_balance(splitter.as_str()), 101);
+
+    setup.distribute().unwrap();
+    assert_eq!(setup.native_balance("alice"), 500);
+    assert_eq!(setup.native_balance("bob"), 300);
+    assert_eq!(setup.native_balance("carol"), 200);
+    // 50.5 and 30.3 round down, the last recipient takes the remainder
+    assert_eq!(setup.cw20_balance("alice"), 50);
+    assert_eq!(setup.cw20_balance("bob"), 30);
+    assert_eq!(setup.cw20_balance("carol"), 21);
+    assert_eq!(setup.native_balance(splitter.as_str()), 0);
+    assert_eq!(setup.cw20_balance(splitter.as_str()), 0);
+
+    assert_eq!(
+        setup.distribute().unwrap_err(),
+        ContractError::NothingToDistribute {}
+    );
+}
+
+#[test]
+fn splits_cw20_sent_directly() {
+    let mut setup = setup(&[("alice", 1), ("bob", 1), ("carol", 1)]);
+    let splitter = setup.splitter.clone();
+    let token = setup.token.clone();
+
+    setup
+        .app
+        .execute_contract(
+            Addr::unchecked(FUNDER),
+            token,
+            &Cw20ExecuteMsg::Send {
+                contract: splitter.to_string(),
+                amount: Uint128::new(10),
+                msg: to_binary(&Empty {}).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(setup.cw20_balance("alice"), 3);
+    assert_eq!(setup.cw20_balance("bob"), 3);
+    assert_eq!(setup.cw20_balance("carol"), 4);
+    assert_eq!(setup.cw20_balance(splitter.as_str()), 0);
+}
+
+#[test]
+fn rejects_invalid_recipients() {
+    let mut app = App::default();
+    let code_id = app.store_code(contract_splitter());
+    let mut instantiate = |recipients: &[(&str, u64)]| {
+        let msg = InstantiateMsg {
+            recipients: recipients
+                .iter()
+                .map(|(addr, weight)| (addr.to_string(), *weight))
+                .collect(),
+        };
+        app.instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &msg,
+            &[],
+            "splitter",
+            None,
+        )
+        .unwrap_err()
+        .downcast::<ContractError>()
+        .unwrap()
+    };
+
+    assert_eq!(instantiate(&[]), ContractError::NoRecipients {});
+    assert_eq!(
+        instantiate(&[("alice", 1), ("bob", 0)]),
+        ContractError::ZeroWeight {
+            addr: "bob".to_string()
+        }
+    );
+    assert_eq!(
+        instantiate(&[("alice", 1), ("alice", 2)]),
+        ContractError::DuplicateRecipient {
+            addr: "alice".to_string()
+        }
+    );
+}
@@ -0,0 +1,48 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cw20::Cw20ReceiveMsg;
+
+use crate::state::Config;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// `(address, weight)` pairs. Each recipient gets `weight / total weight` of every payout
+    pub recipients: Vec<(String, u64)>,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Splits this contract's whole balance of the given native denoms and cw20 tokens. Bank
+    /// sends and cw20 transfers, which is how the escrow pays out, do not call the contract, so
+    /// anyone can trigger the split afterwards
+    Distribute {
+        denoms: Vec<String>,
+        cw20_tokens: Vec<String>,
+    },
+    /// Splits cw20 tokens sent with `Send` straight away
+    Receive(Cw20ReceiveMsg),
+    /// Accepts escrow `id` on the `escrow` contract, which pays out to this contract. Any of the
+    /// recipients can do this
+    AcceptEscrow { escrow: String, id: String },
+    /// Submits milestone `milestone_id` of escrow `id` on the `escrow` contract for approval,
+    /// as its recipient. Any of the recipients can do this
+    SubmitMilestone {
+        escrow: String,
+        id: String,
+        milestone_id: String,
+    },
+    /// Collects what the `escrow` contract has credited to this contract for approved
+    /// milestones, ready to `Distribute`. Anyone can do this
+    ClaimEscrow { escrow: String },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub config: Config,
+}
@@ -0,0 +1,18 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_storage_plus::Item;
+
+#[cw_serde]
+pub struct Recipient {
+    pub addr: Addr,
+    pub weight: u64,
+}
+
+#[cw_serde]
+pub struct Config {
+    /// Fixed at instantiation, in the order given
+    pub recipients: Vec<Recipient>,
+    pub total_weight: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
@@ -0,0 +1,182 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+};
+use cosmwatch_clients::{EscrowClient, PollClient};
+use cosmwatch_events::{attr, response};
+use cw2::set_contract_version;
+use cw_utils::Expiration;
+
+use crate::error::ContractError;
+use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, ProposalResponse, QueryMsg};
+use crate::state::{Action, Config, Proposal, CONFIG, PROPOSALS};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:poll-escrow-adapter";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        owner: info.sender,
+        poll: deps.api.addr_validate(&msg.poll)?,
+        escrow: deps.api.addr_validate(&msg.escrow)?,
+        quorum: msg.quorum,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(response("instantiate")
+        .add_attribute(attr::POLL, config.poll)
+        .add_attribute(attr::ESCROW, config.escrow))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Propose {
+            poll_id,
+            escrow_id,
+            action,
+            pass_option,
+            voting_ends,
+        } => execute_propose(
+            deps,
+            env,
+            info,
+            poll_id,
+            escrow_id,
+            action,
+            pass_option,
+            voting_ends,
+        ),
+        ExecuteMsg::Execute { poll_id } => execute_execute(deps, env, poll_id),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_propose(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: String,
+    escrow_id: String,
+    action: Action,
+    pass_option: String,
+    voting_ends: Expiration,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if PROPOSALS.has(deps.storage, &poll_id) {
+        return Err(ContractError::ProposalExists { poll_id });
+    }
+    if voting_ends.is_expired(&env.block) {
+        return Err(ContractError::VotingEnded { poll_id });
+    }
+
+    let proposal = Proposal {
+        escrow_id,
+        action,
+        pass_option,
+        voting_ends,
+        executed: false,
+    };
+    PROPOSALS.save(deps.storage, &poll_id, &proposal)?;
+
+    Ok(response("propose")
+        .add_attribute(attr::POLL_ID, poll_id)
+        .add_attribute(attr::ESCROW_ID, proposal.escrow_id)
+        .add_attribute("voting_ends", proposal.voting_ends.to_string()))
+}
+
+fn execute_execute(deps: DepsMut, env: Env, poll_id: String) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut proposal = PROPOSALS.may_load(deps.storage, &poll_id)?.ok_or_else(|| {
+        ContractError::ProposalNotFound {
+            poll_id: poll_id.clone(),
+        }
+    })?;
+    if proposal.executed {
+        return Err(ContractError::AlreadyExecuted { poll_id });
+    }
+    if !proposal.voting_ends.is_expired(&env.block) {
+        return Err(ContractError::VotingOpen { poll_id });
+    }
+
+    let poll = PollClient::new(config.poll)
+        .poll(poll_id.clone())?
+        .fetch(&deps.querier)?
+        .poll
+        .ok_or_else(|| ContractError::PollNotFound {
+            poll_id: poll_id.clone(),
+        })?;
+
+    let votes = poll
+        .options
+        .iter()
+        .find(|(option, _)| option == &proposal.pass_option)
+        .map(|(_, votes)| *votes)
+        .ok_or_else(|| ContractError::OptionNotFound {
+            poll_id: poll_id.clone(),
+            option: proposal.pass_option.clone(),
+        })?;
+    let against = poll
+        .options
+        .iter()
+        .filter(|(option, _)| option != &proposal.pass_option)
+        .map(|(_, votes)| *votes)
+        .max()
+        .unwrap_or_default();
+    if votes < Uint128::from(config.quorum) || votes <= against {
+        return Err(ContractError::NotPassed {
+            votes,
+            against,
+            quorum: config.quorum,
+        });
+    }
+
+    proposal.executed = true;
+    PROPOSALS.save(deps.storage, &poll_id, &proposal)?;
+
+    // this contract is the escrow's arbiter, so the decision is sent in its name
+    let escrow = EscrowClient::new(config.escrow);
+    let (relayed, msg) = match &proposal.action {
+        Action::ApproveMilestone { milestone_id } => (
+            "approve_milestone",
+            escrow.approve_milestone(&proposal.escrow_id, milestone_id)?,
+        ),
+        Action::Refund {} => ("refund", escrow.refund(&proposal.escrow_id)?),
+    };
+
+    Ok(response("execute")
+        .add_attribute(attr::POLL_ID, poll_id)
+        .add_attribute(attr::ESCROW_ID, proposal.escrow_id)
+        .add_attribute("relayed", relayed)
+        .add_message(msg))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&ConfigResponse {
+            config: CONFIG.load(deps.storage)?,
+        }),
+        QueryMsg::Proposal { poll_id } => to_binary(&ProposalResponse {
+            proposal: PROPOSALS.may_load(deps.storage, &poll_id)?,
+        }),
+    }
+}
@@ -0,0 +1,41 @@
+use cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Poll {poll_id} already has a proposal")]
+    ProposalExists { poll_id: String },
+
+    #[error("No proposal for poll {poll_id}")]
+    ProposalNotFound { poll_id: String },
+
+    #[error("Proposal for poll {poll_id} was already executed")]
+    AlreadyExecuted { poll_id: String },
+
+    #[error("Voting on poll {poll_id} has not ended")]
+    VotingOpen { poll_id: String },
+
+    #[error("Voting on poll {poll_id} has already ended")]
+    VotingEnded { poll_id: String },
+
+    #[error("Poll {poll_id} not found")]
+    PollNotFound { poll_id: String },
+
+    #[error("Poll {poll_id} has no option {option}")]
+    OptionNotFound { poll_id: String, option: String },
+
+    #[error(
+        "Poll has not passed: {votes} votes for, {against} for the next option, quorum {quorum}"
+    )]
+    NotPassed {
+        votes: Uint128,
+        against: Uint128,
+        quorum: u64,
+    },
+}
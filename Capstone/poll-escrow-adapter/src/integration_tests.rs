@@ -0,0 +1,452 @@
+#![cfg(test)]
+
+use cosmwasm_std::{coins, Addr, Empty, Uint128};
+use cw20_escrow_milestones::msg::{CreateMilestoneMsg, CreateMsg, ExecuteMsg as EscrowExecuteMsg};
+use cw20_escrow_milestones::state::GenericBalance;
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_utils::Expiration;
+
+use crate::msg::{ExecuteMsg, InstantiateMsg, ProposalResponse, QueryMsg};
+use crate::state::Action;
+use crate::ContractError;
+
+const FUNDER: &str = "funder";
+const RECIPIENT: &str = "recipient";
+const DENOM: &str = "juno";
+/// Blocks a poll stays open after its proposal is registered
+const VOTING_BLOCKS: u64 = 10;
+
+fn contract_adapter() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    ))
+}
+
+fn contract_escrow() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            cw20_escrow_milestones::contract::execute,
+            cw20_escrow_milestones::contract::instantiate,
+            cw20_escrow_milestones::contract::query,
+        )
+        .with_reply(cw20_escrow_milestones::contract::reply),
+    )
+}
+
+fn contract_poll() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cosm_wasm_zero2_hero::contract::execute,
+        cosm_wasm_zero2_hero::contract::instantiate,
+        cosm_wasm_zero2_hero::contract::query,
+    ))
+}
+
+struct Setup {
+    app: App,
+    owner: Addr,
+    poll: Addr,
+    escrow: Addr,
+    adapter: Addr,
+}
+
+/// Poll, escrow and adapter, with a two-milestone escrow whose arbiter is the adapter
+fn setup(quorum: u64) -> Setup {
+    let owner = Addr::unchecked("owner");
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &Addr::unchecked(FUNDER), coins(1000, DENOM))
+            .unwrap();
+    });
+
+    let poll_id = app.store_code(contract_poll());
+    let poll = app
+        .instantiate_contract(
+            poll_id,
+            owner.clone(),
+            &cosm_wasm_zero2_hero::msg::InstantiateMsg { admin: None },
+            &[],
+            "poll",
+            None,
+        )
+        .unwrap();
+
+    let escrow_id = app.store_code(contract_escrow());
+    let escrow = app
+        .instantiate_contract(
+            escrow_id,
+            owner.clone(),
+            &cw20_escrow_milestones::msg::InstantiateMsg::default(),
+            &[],
+            "escrow",
+            None,
+        )
+        .unwrap();
+
+    let adapter_id = app.store_code(contract_adapter());
+    let adapter = app
+        .instantiate_contract(
+            adapter_id,
+            owner.clone(),
+            &InstantiateMsg {
+                poll: poll.to_string(),
+                escrow: escrow.to_string(),
+                quorum,
+            },
+            &[],
+            "adapter",
+            None,
+        )
+        .unwrap();
+
+    let milestone = |title: &str, amount: u128| CreateMilestoneMsg {
+        escrow_id: "grant".to_string(),
+        title: title.to_string(),
+        description: title.to_string(),
+        amount: GenericBalance {
+            native: coins(amount, DENOM),
+            cw20: vec![],
+        },
+        expires: None,
+        stream: None,
+    };
+    let create = EscrowExecuteMsg::Create(CreateMsg {
+        id: "grant".to_string(),
+        arbiter: adapter.to_string(),
+        co_arbiters: None,
+        threshold: None,
+        arbiter_kind: None,
+        recipient: Some(RECIPIENT.to_string()),
+        title: "grant".to_string(),
+        description: "released by community vote".to_string(),
+        cw20_whitelist: None,
+        strict_whitelist: None,
+        crowdfunded: None,
+        stake: None,
+        milestones: vec![milestone("design", 400), milestone("launch", 600)],
+    });
+    app.execute_contract(
+        Addr::unchecked(FUNDER),
+        escrow.clone(),
+        &create,
+        &coins(1000, DENOM),
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked(RECIPIENT),
+        escrow.clone(),
+        &EscrowExecuteMsg::AcceptEscrow {
+            id: "grant".to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    // both milestones are delivered, it is up to the vote to release them
+    for milestone_id in ["1", "2"] {
+        app.execute_contract(
+            Addr::unchecked(RECIPIENT),
+            escrow.clone(),
+            &EscrowExecuteMsg::SubmitMilestone {
+                id: "grant".to_string(),
+                milestone_id: milestone_id.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    Setup {
+        app,
+        owner,
+        poll,
+        escrow,
+        adapter,
+    }
+}
+
+impl Setup {
+    fn create_poll(&mut self, poll_id: &str, action: Action) {
+        self.app
+            .execute_contract(
+                self.owner.clone(),
+                self.poll.clone(),
+                &cosm_wasm_zero2_hero::msg::ExecuteMsg::CreatePoll {
+                    poll_id: poll_id.to_string(),
+                    question: format!("{:?}?", action),
+                    options: vec!["yes".to_string(), "no".to_string()],
+                },
+                &[],
+            )
+            .unwrap();
+        self.app
+            .execute_contract(
+                self.owner.clone(),
+                self.adapter.clone(),
+                &ExecuteMsg::Propose {
+                    poll_id: poll_id.to_string(),
+                    escrow_id: "grant".to_string(),
+                    action,
+                    pass_option: "yes".to_string(),
+                    voting_ends: Expiration::AtHeight(self.app.block_info().height + VOTING_BLOCKS),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    fn end_voting(&mut self) {
+        self.app.update_block(|block| block.height += VOTING_BLOCKS);
+    }
+
+    fn vote(&mut self, voter: &str, poll_id: &str, option: &str) {
+        self.app
+            .execute_contract(
+                Addr::unchecked(voter),
+                self.poll.clone(),
+                &cosm_wasm_zero2_hero::msg::ExecuteMsg::Vote {
+                    poll_id: poll_id.to_string(),
+                    vote: option.to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    fn execute(&mut self, poll_id: &str) -> Result<(), ContractError> {
+        self.app
+            .execute_contract(
+                Addr::unchecked("anyone"),
+                self.adapter.clone(),
+                &ExecuteMsg::Execute {
+                    poll_id: poll_id.to_string(),
+                },
+                &[],
+            )
+            .map(|_| ())
+            .map_err(|err| err.downcast().unwrap())
+    }
+
+    /// The recipient collects what approved milestones credited to it
+    fn claim(&mut self) {
+        self.app
+            .execute_contract(
+                Addr::unchecked(RECIPIENT),
+                self.escrow.clone(),
+                &EscrowExecuteMsg::Claim {},
+                &[],
+            )
+            .unwrap();
+    }
+
+    fn balance(&self, addr: &str) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(addr, DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    }
+}
+
+#[test]
+fn passing_poll_approves_milestone() {
+    let mut setup = setup(2);
+    setup.create_poll(
+        "release_design",
+        Action::ApproveMilestone {
+            milestone_id: "1".to_string(),
+        },
+    );
+
+    // votes are only counted once voting ends
+    assert_eq!(
+        setup.execute("release_design").unwrap_err(),
+        ContractError::VotingOpen {
+            poll_id: "release_design".to_string()
+        }
+    );
+    setup.end_voting();
+
+    // nobody has voted
+    assert_eq!(
+        setup.execute("release_design").unwrap_err(),
+        ContractError::NotPassed {
+            votes: Uint128::zero(),
+            against: Uint128::zero(),
+            quorum: 2
+        }
+    );
+
+    setup.vote("voter1", "release_design", "yes");
+    setup.vote("voter2", "release_design", "no");
+    setup.vote("voter3", "release_design", "yes");
+    setup.execute("release_design").unwrap();
+    setup.claim();
+    assert_eq!(setup.balance(RECIPIENT), 400);
+
+    let proposal: ProposalResponse = setup
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &setup.adapter,
+            &QueryMsg::Proposal {
+                poll_id: "release_design".to_string(),
+            },
+        )
+        .unwrap();
+    assert!(proposal.proposal.unwrap().executed);
+
+    // a proposal only executes once
+    assert_eq!(
+        setup.execute("release_design").unwrap_err(),
+        ContractError::AlreadyExecuted {
+            poll_id: "release_design".to_string()
+        }
+    );
+
+    // the last milestone closes the escrow
+    setup.create_poll(
+        "release_launch",
+        Action::ApproveMilestone {
+            milestone_id: "2".to_string(),
+        },
+    );
+    setup.vote("voter1", "release_launch", "yes");
+    setup.vote("voter2", "release_launch", "yes");
+    setup.end_voting();
+    setup.execute("release_launch").unwrap();
+    setup.claim();
+    assert_eq!(setup.balance(RECIPIENT), 1000);
+    assert_eq!(
+        setup
+            .app
+            .wrap()
+            .query_balance(&setup.escrow, DENOM)
+            .unwrap()
+            .amount
+            .u128(),
+        0
+    );
+}
+
+#[test]
+fn failing_poll_leaves_escrow_untouched() {
+    let mut setup = setup(1);
+    setup.create_poll(
+        "release_design",
+        Action::ApproveMilestone {
+            milestone_id: "1".to_string(),
+        },
+    );
+
+    // a tie does not pass
+    setup.vote("voter1", "release_design", "yes");
+    setup.vote("voter2", "release_design", "no");
+    setup.end_voting();
+    assert_eq!(
+        setup.execute("release_design").unwrap_err(),
+        ContractError::NotPassed {
+            votes: Uint128::one(),
+            against: Uint128::one(),
+            quorum: 1
+        }
+    );
+
+    setup.vote("voter3", "release_design", "no");
+    setup.execute("release_design").unwrap_err();
+    assert_eq!(setup.balance(RECIPIENT), 0);
+
+    // the arbiter role is the adapter's alone
+    let err = setup
+        .app
+        .execute_contract(
+            Addr::unchecked("voter1"),
+            setup.escrow.clone(),
+            &EscrowExecuteMsg::ApproveMilestone {
+                id: "grant".to_string(),
+                milestone_id: "1".to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<cw20_escrow_milestones::ContractError>()
+            .unwrap()
+            .to_string(),
+        "Unauthorized"
+    );
+}
+
+#[test]
+fn only_owner_proposes() {
+    let mut setup = setup(1);
+    let err = setup
+        .app
+        .execute_contract(
+            Addr::unchecked("voter1"),
+            setup.adapter.clone(),
+            &ExecuteMsg::Propose {
+                poll_id: "release_design".to_string(),
+                escrow_id: "grant".to_string(),
+                action: Action::Refund {},
+                pass_option: "yes".to_string(),
+                voting_ends: Expiration::Never {},
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    assert_eq!(
+        setup.execute("release_design").unwrap_err(),
+        ContractError::ProposalNotFound {
+            poll_id: "release_design".to_string()
+        }
+    );
+}
+
+#[test]
+fn passing_poll_refunds_escrow() {
+    let mut setup = setup(1);
+    setup.create_poll("cancel_grant", Action::Refund {});
+    setup.vote("voter1", "cancel_grant", "yes");
+    setup.end_voting();
+    setup.execute("cancel_grant").unwrap();
+
+    assert_eq!(setup.balance(FUNDER), 1000);
+    assert_eq!(setup.balance(RECIPIENT), 0);
+    let escrow = setup.escrow.clone();
+    assert_eq!(setup.balance(escrow.as_str()), 0);
+}
+
+#[test]
+fn proposals_need_open_voting() {
+    let mut setup = setup(1);
+    let voting_ends = Expiration::AtHeight(setup.app.block_info().height);
+    let err = setup
+        .app
+        .execute_contract(
+            setup.owner.clone(),
+            setup.adapter.clone(),
+            &ExecuteMsg::Propose {
+                poll_id: "cancel_grant".to_string(),
+                escrow_id: "grant".to_string(),
+                action: Action::Refund {},
+                pass_option: "yes".to_string(),
+                voting_ends,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::VotingEnded {
+            poll_id: "cancel_grant".to_string()
+        }
+    );
+}
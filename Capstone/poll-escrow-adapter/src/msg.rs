@@ -0,0 +1,48 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+
+use cw_utils::Expiration;
+
+use crate::state::{Action, Config, Proposal};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub poll: String,
+    pub escrow: String,
+    pub quorum: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Ties `poll_id` to an action on an escrow: if `pass_option` wins the poll once voting
+    /// ends, the action can be relayed. Only the owner can do this
+    Propose {
+        poll_id: String,
+        escrow_id: String,
+        action: Action,
+        pass_option: String,
+        voting_ends: Expiration,
+    },
+    /// Relays the action tied to `poll_id` if voting has ended and its passing option has
+    /// reached quorum with more votes than any other option. Anyone can do this
+    Execute { poll_id: String },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+
+    #[returns(ProposalResponse)]
+    Proposal { poll_id: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub config: Config,
+}
+
+#[cw_serde]
+pub struct ProposalResponse {
+    pub proposal: Option<Proposal>,
+}
@@ -0,0 +1,38 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+use cw_utils::Expiration;
+
+#[cw_serde]
+pub struct Config {
+    /// Can register proposals
+    pub owner: Addr,
+    /// Poll contract whose results decide the proposals
+    pub poll: Addr,
+    /// Escrow this contract is the arbiter of
+    pub escrow: Addr,
+    /// Votes the passing option needs before a proposal can pass
+    pub quorum: u64,
+}
+
+/// What the adapter tells the escrow to do once a poll passes
+#[cw_serde]
+pub enum Action {
+    ApproveMilestone { milestone_id: String },
+    Refund {},
+}
+
+/// Arbiter decision on an escrow, relayed once its poll passes
+#[cw_serde]
+pub struct Proposal {
+    pub escrow_id: String,
+    pub action: Action,
+    /// Poll option that passes the proposal
+    pub pass_option: String,
+    /// The poll contract has no closing time of its own, so votes are counted once this expires
+    pub voting_ends: Expiration,
+    pub executed: bool,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const PROPOSALS: Map<&str, Proposal> = Map::new("proposals");
@@ -0,0 +1,209 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Timestamp,
+    Uint128,
+};
+use cosmwatch_balances::{send_tokens, GenericBalance};
+use cosmwatch_clients::EscrowClient;
+use cosmwatch_events::{attr, response};
+use cw2::set_contract_version;
+use cw20::{BalanceResponse, Cw20CoinVerified, Cw20QueryMsg};
+
+use crate::error::ContractError;
+use crate::msg::{ClaimableResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::state::{Config, CLAIMED_CW20, CLAIMED_NATIVE, CONFIG};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:vesting-payout";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if msg.duration == 0 {
+        return Err(ContractError::ZeroDuration {});
+    }
+    let config = Config {
+        beneficiary: deps.api.addr_validate(&msg.beneficiary)?,
+        start: msg
+            .start_time
+            .map(Timestamp::from_seconds)
+            .unwrap_or(env.block.time),
+        duration: msg.duration,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(response("instantiate")
+        .add_attribute("beneficiary", config.beneficiary)
+        .add_attribute("start", config.start.seconds().to_string())
+        .add_attribute("duration", config.duration.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Claim {
+            denoms,
+            cw20_tokens,
+        } => execute_claim(deps, env, info, denoms, cw20_tokens),
+        ExecuteMsg::AcceptEscrow { escrow, id } => execute_accept_escrow(deps, info, escrow, id),
+        ExecuteMsg::SubmitMilestone {
+            escrow,
+            id,
+            milestone_id,
+        } => execute_submit_milestone(deps, info, escrow, id, milestone_id),
+        ExecuteMsg::ClaimEscrow { escrow } => execute_claim_escrow(deps, escrow),
+    }
+}
+
+fn execute_accept_escrow(
+    deps: DepsMut,
+    info: MessageInfo,
+    escrow: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.beneficiary {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // the escrow only takes acceptance from its recipient, this contract
+    let escrow = EscrowClient::new(deps.api.addr_validate(&escrow)?);
+    Ok(response("accept_escrow")
+        .add_attribute(attr::ESCROW_ID, &id)
+        .add_message(escrow.accept_escrow(id)?))
+}
+
+fn execute_submit_milestone(
+    deps: DepsMut,
+    info: MessageInfo,
+    escrow: String,
+    id: String,
+    milestone_id: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.beneficiary {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let escrow = EscrowClient::new(deps.api.addr_validate(&escrow)?);
+    Ok(response("submit_milestone")
+        .add_attribute(attr::ESCROW_ID, &id)
+        .add_attribute(attr::MILESTONE_ID, &milestone_id)
+        .add_message(escrow.submit_milestone(id, milestone_id)?))
+}
+
+fn execute_claim_escrow(deps: DepsMut, escrow: String) -> Result<Response, ContractError> {
+    // the funds can only ever land here, so nobody needs to be trusted with this
+    let escrow = EscrowClient::new(deps.api.addr_validate(&escrow)?);
+    Ok(response("claim_escrow")
+        .add_attribute(attr::ESCROW, escrow.addr())
+        .add_message(escrow.claim()?))
+}
+
+fn execute_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denoms: Vec<String>,
+    cw20_tokens: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.beneficiary {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let claimable = claimable(deps.as_ref(), &env, &config, denoms, cw20_tokens)?;
+    if claimable.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    for coin in &claimable.native {
+        CLAIMED_NATIVE.update(deps.storage, &coin.denom, |claimed| -> StdResult<_> {
+            Ok(claimed.unwrap_or_default() + coin.amount)
+        })?;
+    }
+    for coin in &claimable.cw20 {
+        CLAIMED_CW20.update(deps.storage, &coin.address, |claimed| -> StdResult<_> {
+            Ok(claimed.unwrap_or_default() + coin.amount)
+        })?;
+    }
+
+    Ok(response("claim")
+        .add_attribute("beneficiary", &config.beneficiary)
+        .add_submessages(send_tokens(&config.beneficiary, &claimable)?))
+}
+
+/// Unlocked but unclaimed amounts. The total ever received is the current balance plus what has
+/// already been claimed
+fn claimable(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    denoms: Vec<String>,
+    cw20_tokens: Vec<String>,
+) -> StdResult<GenericBalance> {
+    let unclaimed =
+        |held: Uint128, claimed: Uint128| config.vested(held + claimed, env.block.time) - claimed;
+
+    let mut claimable = GenericBalance::default();
+    for denom in denoms {
+        let held = deps
+            .querier
+            .query_balance(&env.contract.address, &denom)?
+            .amount;
+        let claimed = CLAIMED_NATIVE
+            .may_load(deps.storage, &denom)?
+            .unwrap_or_default();
+        let amount = unclaimed(held, claimed);
+        if !amount.is_zero() {
+            claimable.native.push(Coin { denom, amount });
+        }
+    }
+    for token in cw20_tokens {
+        let address = deps.api.addr_validate(&token)?;
+        let held: BalanceResponse = deps.querier.query_wasm_smart(
+            &address,
+            &Cw20QueryMsg::Balance {
+                address: env.contract.address.to_string(),
+            },
+        )?;
+        let claimed = CLAIMED_CW20
+            .may_load(deps.storage, &address)?
+            .unwrap_or_default();
+        let amount = unclaimed(held.balance, claimed);
+        if !amount.is_zero() {
+            claimable.cw20.push(Cw20CoinVerified { address, amount });
+        }
+    }
+    Ok(claimable)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&ConfigResponse {
+            config: CONFIG.load(deps.storage)?,
+        }),
+        QueryMsg::Claimable {
+            denoms,
+            cw20_tokens,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            to_binary(&ClaimableResponse {
+                claimable: claimable(deps, &env, &config, denoms, cw20_tokens)?,
+            })
+        }
+    }
+}
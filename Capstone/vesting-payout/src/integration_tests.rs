@@ -0,0 +1,356 @@
+#![cfg(test)]
+
+use cosmwasm_std::{coins, to_binary, Addr, Empty, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw20_escrow_milestones::msg::{
+    CreateMilestoneMsg, CreateMsg, ExecuteMsg as EscrowExecuteMsg, ReceiveMsg,
+};
+use cw20_escrow_milestones::state::GenericBalance;
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+
+use crate::msg::{ClaimableResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::ContractError;
+
+const FUNDER: &str = "funder";
+const ARBITER: &str = "arbiter";
+const BENEFICIARY: &str = "beneficiary";
+const DENOM: &str = "juno";
+/// Seconds until all received funds are unlocked
+const DURATION: u64 = 1000;
+
+fn contract_vesting() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    ))
+}
+
+fn contract_escrow() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            cw20_escrow_milestones::contract::execute,
+            cw20_escrow_milestones::contract::instantiate,
+            cw20_escrow_milestones::contract::query,
+        )
+        .with_reply(cw20_escrow_milestones::contract::reply),
+    )
+}
+
+fn contract_cw20() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    ))
+}
+
+struct Setup {
+    app: App,
+    vesting: Addr,
+    escrow: Addr,
+    token: Addr,
+}
+
+/// Vesting contract starting now, and an escrow paying into it with one native and one cw20
+/// milestone
+fn setup() -> Setup {
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &Addr::unchecked(FUNDER), coins(1000, DENOM))
+            .unwrap();
+    });
+    let owner = Addr::unchecked("owner");
+
+    let vesting_id = app.store_code(contract_vesting());
+    let vesting = app
+        .instantiate_contract(
+            vesting_id,
+            owner.clone(),
+            &InstantiateMsg {
+                beneficiary: BENEFICIARY.to_string(),
+                start_time: None,
+                duration: DURATION,
+            },
+            &[],
+            "vesting",
+            None,
+        )
+        .unwrap();
+
+    let escrow_id = app.store_code(contract_escrow());
+    let escrow = app
+        .instantiate_contract(
+            escrow_id,
+            owner.clone(),
+            &cw20_escrow_milestones::msg::InstantiateMsg::default(),
+            &[],
+            "escrow",
+            None,
+        )
+        .unwrap();
+
+    let cw20_id = app.store_code(contract_cw20());
+    let token = app
+        .instantiate_contract(
+            cw20_id,
+            owner,
+            &cw20_base::msg::InstantiateMsg {
+                name: "Cash Money".to_string(),
+                symbol: "CASH".to_string(),
+                decimals: 2,
+                initial_balances: vec![Cw20Coin {
+                    address: FUNDER.to_string(),
+                    amount: Uint128::new(400),
+                }],
+                mint: None,
+                marketing: None,
+            },
+            &[],
+            "CASH",
+            None,
+        )
+        .unwrap();
+
+    let milestone = |amount: GenericBalance| CreateMilestoneMsg {
+        escrow_id: "salary".to_string(),
+        title: "salary".to_string(),
+        description: "approved but vested".to_string(),
+        amount,
+        expires: None,
+        stream: None,
+    };
+    let create = |id: &str, milestone: CreateMilestoneMsg| CreateMsg {
+        id: id.to_string(),
+        arbiter: ARBITER.to_string(),
+        co_arbiters: None,
+        threshold: None,
+        arbiter_kind: None,
+        recipient: Some(vesting.to_string()),
+        title: id.to_string(),
+        description: "paid into vesting".to_string(),
+        cw20_whitelist: None,
+        strict_whitelist: None,
+        crowdfunded: None,
+        stake: None,
+        milestones: vec![milestone],
+    };
+
+    let native = milestone(GenericBalance {
+        native: coins(1000, DENOM),
+        cw20: vec![],
+    });
+    app.execute_contract(
+        Addr::unchecked(FUNDER),
+        escrow.clone(),
+        &EscrowExecuteMsg::Create(create("salary", native)),
+        &coins(1000, DENOM),
+    )
+    .unwrap();
+    let cw20 = milestone(GenericBalance {
+        native: vec![],
+        cw20: vec![Cw20CoinVerified {
+            address: token.clone(),
+            amount: Uint128::new(400),
+        }],
+    });
+    app.execute_contract(
+        Addr::unchecked(FUNDER),
+        token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: escrow.to_string(),
+            amount: Uint128::new(400),
+            msg: to_binary(&ReceiveMsg::Create(create("bonus", cw20))).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    Setup {
+        app,
+        vesting,
+        escrow,
+        token,
+    }
+}
+
+impl Setup {
+    /// The beneficiary accepts escrow `id`, which pays into the vesting contract
+    fn accept(&mut self, sender: &str, id: &str) -> Result<(), ContractError> {
+        let msg = ExecuteMsg::AcceptEscrow {
+            escrow: self.escrow.to_string(),
+            id: id.to_string(),
+        };
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.vesting.clone(), &msg, &[])
+            .map(|_| ())
+            .map_err(|err| err.downcast().unwrap())
+    }
+
+    /// The beneficiary submits escrow `id` through the vesting contract
+    fn submit(&mut self, sender: &str, id: &str) -> Result<(), ContractError> {
+        let msg = ExecuteMsg::SubmitMilestone {
+            escrow: self.escrow.to_string(),
+            id: id.to_string(),
+            milestone_id: "1".to_string(),
+        };
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.vesting.clone(), &msg, &[])
+            .map(|_| ())
+            .map_err(|err| err.downcast().unwrap())
+    }
+
+    /// The arbiter approves escrow `id`, then anyone has the vesting contract collect the payout
+    fn approve(&mut self, id: &str) {
+        self.app
+            .execute_contract(
+                Addr::unchecked(ARBITER),
+                self.escrow.clone(),
+                &EscrowExecuteMsg::ApproveMilestone {
+                    id: id.to_string(),
+                    milestone_id: "1".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+        self.app
+            .execute_contract(
+                Addr::unchecked("anyone"),
+                self.vesting.clone(),
+                &ExecuteMsg::ClaimEscrow {
+                    escrow: self.escrow.to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    fn wait(&mut self, seconds: u64) {
+        self.app
+            .update_block(|block| block.time = block.time.plus_seconds(seconds));
+    }
+
+    fn claim_msg(&self) -> ExecuteMsg {
+        ExecuteMsg::Claim {
+            denoms: vec![DENOM.to_string()],
+            cw20_tokens: vec![self.token.to_string()],
+        }
+    }
+
+    fn claim(&mut self, sender: &str) -> Result<(), ContractError> {
+        let msg = self.claim_msg();
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.vesting.clone(), &msg, &[])
+            .map(|_| ())
+            .map_err(|err| err.downcast().unwrap())
+    }
+
+    fn native_balance(&self, addr: &str) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(addr, DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    fn cw20_balance(&self, addr: &str) -> u128 {
+        let res: BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.token,
+                &Cw20QueryMsg::Balance {
+                    address: addr.to_string(),
+                },
+            )
+            .unwrap();
+        res.balance.u128()
+    }
+}
+
+#[test]
+fn approved_payouts_vest_linearly() {
+    let mut setup = setup();
+    assert_eq!(
+        setup.accept(ARBITER, "salary").unwrap_err(),
+        ContractError::Unauthorized {}
+    );
+    setup.accept(BENEFICIARY, "salary").unwrap();
+    setup.accept(BENEFICIARY, "bonus").unwrap();
+    assert_eq!(
+        setup.submit(ARBITER, "salary").unwrap_err(),
+        ContractError::Unauthorized {}
+    );
+    setup.submit(BENEFICIARY, "salary").unwrap();
+    setup.submit(BENEFICIARY, "bonus").unwrap();
+    setup.approve("salary");
+    assert_eq!(setup.native_balance(setup.vesting.as_str()), 1000);
+
+    // nothing has unlocked yet
+    assert_eq!(
+        setup.claim(BENEFICIARY).unwrap_err(),
+        ContractError::NothingToClaim {}
+    );
+
+    setup.wait(DURATION / 4);
+    assert_eq!(
+        setup.claim(ARBITER).unwrap_err(),
+        ContractError::Unauthorized {}
+    );
+    setup.claim(BENEFICIARY).unwrap();
+    assert_eq!(setup.native_balance(BENEFICIARY), 250);
+
+    // funds approved halfway through are already half unlocked
+    setup.wait(DURATION / 4);
+    setup.approve("bonus");
+    let res: ClaimableResponse = setup
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &setup.vesting,
+            &QueryMsg::Claimable {
+                denoms: vec![DENOM.to_string()],
+                cw20_tokens: vec![setup.token.to_string()],
+            },
+        )
+        .unwrap();
+    assert_eq!(res.claimable.native, coins(250, DENOM));
+    assert_eq!(res.claimable.cw20[0].amount, Uint128::new(200));
+    setup.claim(BENEFICIARY).unwrap();
+    assert_eq!(setup.native_balance(BENEFICIARY), 500);
+    assert_eq!(setup.cw20_balance(BENEFICIARY), 200);
+
+    // everything is unlocked once the duration has passed
+    setup.wait(DURATION);
+    setup.claim(BENEFICIARY).unwrap();
+    assert_eq!(setup.native_balance(BENEFICIARY), 1000);
+    assert_eq!(setup.cw20_balance(BENEFICIARY), 400);
+    assert_eq!(setup.native_balance(setup.vesting.as_str()), 0);
+    assert_eq!(setup.cw20_balance(setup.vesting.as_str()), 0);
+}
+
+#[test]
+fn rejects_zero_duration() {
+    let mut app = App::default();
+    let code_id = app.store_code(contract_vesting());
+    let err = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked("owner"),
+            &InstantiateMsg {
+                beneficiary: BENEFICIARY.to_string(),
+                start_time: None,
+                duration: 0,
+            },
+            &[],
+            "vesting",
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ZeroDuration {}
+    );
+}
@@ -0,0 +1,60 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwatch_balances::GenericBalance;
+
+use crate::state::Config;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub beneficiary: String,
+    /// Seconds since epoch, defaults to the instantiation block time
+    pub start_time: Option<u64>,
+    /// Seconds from the start until everything received is unlocked
+    pub duration: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Sends the beneficiary everything unlocked so far in the given native denoms and cw20
+    /// tokens. Only the beneficiary can do this
+    Claim {
+        denoms: Vec<String>,
+        cw20_tokens: Vec<String>,
+    },
+    /// Accepts escrow `id` on the `escrow` contract, which pays out into this contract. Only the
+    /// beneficiary can do this
+    AcceptEscrow { escrow: String, id: String },
+    /// Submits milestone `milestone_id` of escrow `id` on the `escrow` contract for approval,
+    /// as its recipient. Only the beneficiary can do this
+    SubmitMilestone {
+        escrow: String,
+        id: String,
+        milestone_id: String,
+    },
+    /// Collects what the `escrow` contract has credited to this contract for approved
+    /// milestones, which then vests like anything else received. Anyone can do this
+    ClaimEscrow { escrow: String },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+
+    /// What `Claim` with the same denoms and tokens would pay out now
+    #[returns(ClaimableResponse)]
+    Claimable {
+        denoms: Vec<String>,
+        cw20_tokens: Vec<String>,
+    },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub config: Config,
+}
+
+#[cw_serde]
+pub struct ClaimableResponse {
+    pub claimable: GenericBalance,
+}
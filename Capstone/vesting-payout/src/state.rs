@@ -0,0 +1,31 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Timestamp, Uint128};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    /// Only address that can claim
+    pub beneficiary: Addr,
+    pub start: Timestamp,
+    /// Seconds from `start` until everything received is unlocked
+    pub duration: u64,
+}
+
+impl Config {
+    /// Share of `total` unlocked at `now`. Funds received late vest as if they had been there from
+    /// the start, so everything is unlocked once the duration has passed
+    pub fn vested(&self, total: Uint128, now: Timestamp) -> Uint128 {
+        let elapsed = now.seconds().saturating_sub(self.start.seconds());
+        if elapsed >= self.duration {
+            total
+        } else {
+            total.multiply_ratio(elapsed, self.duration)
+        }
+    }
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+/// Amount already claimed per native denom
+pub const CLAIMED_NATIVE: Map<&str, Uint128> = Map::new("claimed_native");
+/// Amount already claimed per cw20 token
+pub const CLAIMED_CW20: Map<&Addr, Uint128> = Map::new("claimed_cw20");
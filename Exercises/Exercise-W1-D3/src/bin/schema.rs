@@ -1,11 +1,13 @@
 use cosmwasm_schema::write_api;
 
-use coding_session_1::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use coding_session_1::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
         query: QueryMsg,
+        sudo: SudoMsg,
+        migrate: MigrateMsg,
     }
 }
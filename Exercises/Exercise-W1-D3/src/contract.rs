@@ -1,117 +1,216 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult,
+    SubMsgResult, Uint128,
 };
+use cosmwatch_events::{attr, migrate_response, response};
+use cosmwatch_migrate::Migration;
 use cw2::set_contract_version;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, GetCountResponse, InstantiateMsg, QueryMsg};
-use crate::state::{State, STATE};
+use crate::msg::{
+    ExecuteMsg, GetCountResponse, InstantiateMsg, MigrateMsg, PeerStatsResponse, PrizeResponse,
+    QueryMsg, SudoMsg,
+};
+use crate::state::{PeerStats, Prize, State, OWNERSHIP, PAUSE, PEER, PEER_STATS, PRIZE, STATE};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:coding-session-1";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// reply id for the Increment submessage sent to the peer counter
+pub const INCREMENT_PEER_REPLY_ID: u64 = 1;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     let state = State {
         count: msg.count,
-        owner: info.sender.clone(),
+        end_height: msg.end_height,
+        end_time: msg.end_time,
     };
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    STATE.save(deps.storage, &state)?;
+    STATE.save(deps.storage, &state, env.block.height)?;
+    OWNERSHIP.initialize(deps.storage, &info.sender)?;
+    PEER_STATS.save(deps.storage, &PeerStats::default())?;
+    PRIZE.save(deps.storage, &Prize::default())?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
-        .add_attribute("owner", info.sender)
+        .add_attribute(attr::OWNER, info.sender)
         .add_attribute("count", msg.count.to_string()))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // Once expired the count is frozen for good
+    if STATE.load(deps.storage)?.is_expired(&env) {
+        return Err(ContractError::Expired {});
+    }
+
+    // Only the pause switch and ownership handover are available while paused
+    let is_access = matches!(
+        msg,
+        ExecuteMsg::Pause {}
+            | ExecuteMsg::Unpause {}
+            | ExecuteMsg::TransferOwnership { .. }
+            | ExecuteMsg::AcceptOwnership {}
+    );
+    if !is_access {
+        PAUSE.assert_not_paused(deps.storage)?;
+    }
+
     match msg {
-        ExecuteMsg::Increment {} => execute::increment(deps),
-        ExecuteMsg::Decrement {} => execute::decrement(deps, info),
-        ExecuteMsg::Reset { count } => execute::reset(deps, info, count),
-        ExecuteMsg::IncremementBy { count } => execute::incremement_by(deps, info, count),
-        ExecuteMsg::DecrementBy { count } => execute::decrement_by(deps, info, count),
+        ExecuteMsg::Increment {} => execute::increment(deps, env, info),
+        ExecuteMsg::Decrement {} => execute::decrement(deps, env, info),
+        ExecuteMsg::Reset { count } => execute::reset(deps, env, info, count),
+        ExecuteMsg::IncremementBy { count } => execute::incremement_by(deps, env, info, count),
+        ExecuteMsg::DecrementBy { count } => execute::decrement_by(deps, env, info, count),
         ExecuteMsg::ReflectFunds { amount } => execute::reflect_funds(deps, info, amount),
+        ExecuteMsg::Pause {} => Ok(PAUSE.set_paused(deps.storage, &OWNERSHIP, &info, true)?),
+        ExecuteMsg::Unpause {} => Ok(PAUSE.set_paused(deps.storage, &OWNERSHIP, &info, false)?),
+        ExecuteMsg::TransferOwnership { new_owner } => {
+            Ok(OWNERSHIP.transfer(deps.storage, deps.api, &info, &new_owner)?)
+        }
+        ExecuteMsg::AcceptOwnership {} => Ok(OWNERSHIP.accept(deps.storage, &info)?),
+        ExecuteMsg::SetPeer { peer } => execute::set_peer(deps, info, peer),
+        ExecuteMsg::IncrementPeer {} => execute::increment_peer(deps),
+        ExecuteMsg::FundPrize { target } => execute::fund_prize(deps, info, target),
     }
 }
 
 pub mod execute {
-    use cosmwasm_std::{BankMsg, CosmosMsg};
+    use cosmwasm_std::{BankMsg, CosmosMsg, SubMsg};
 
     use super::*;
 
-    pub fn increment(deps: DepsMut) -> Result<Response, ContractError> {
-        STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
-            state.count += 1;
-            Ok(state)
-        })?;
+    pub fn increment(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        let previous = state.count;
+        state.count += 1;
+        STATE.save(deps.storage, &state, env.block.height)?;
 
-        Ok(Response::new().add_attribute("action", "increment"))
+        let res = response("increment");
+        pay_prize(deps, res, &info, previous, state.count)
     }
 
-    pub fn reset(deps: DepsMut, info: MessageInfo, count: i32) -> Result<Response, ContractError> {
-        STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
-            if info.sender != state.owner {
-                return Err(ContractError::Unauthorized {});
-            }
-            state.count = count;
-            Ok(state)
-        })?;
-        Ok(Response::new().add_attribute("action", "reset"))
+    pub fn reset(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        count: i32,
+    ) -> Result<Response, ContractError> {
+        OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+        let mut state = STATE.load(deps.storage)?;
+        state.count = count;
+        STATE.save(deps.storage, &state, env.block.height)?;
+        Ok(response("reset"))
     }
 
-    pub fn decrement(deps: DepsMut, _info: MessageInfo) -> Result<Response, ContractError> {
-        STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
-            state.count -= 1;
-            Ok(state)
-        })?;
+    pub fn decrement(
+        deps: DepsMut,
+        env: Env,
+        _info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        state.count -= 1;
+        STATE.save(deps.storage, &state, env.block.height)?;
 
-        Ok(Response::new().add_attribute("action", "decrement"))
+        Ok(response("decrement"))
     }
 
     pub fn incremement_by(
         deps: DepsMut,
-        __info: MessageInfo,
+        env: Env,
+        info: MessageInfo,
         count: i32,
     ) -> Result<Response, ContractError> {
-        STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
-            state.count += count;
-            Ok(state)
-        })?;
+        let mut state = STATE.load(deps.storage)?;
+        let previous = state.count;
+        state.count += count;
+        STATE.save(deps.storage, &state, env.block.height)?;
+
+        let res = response("increment_by").add_attribute("incremented_by", count.to_string());
+        pay_prize(deps, res, &info, previous, state.count)
+    }
+
+    // Sends the prize pool to the sender if this increment crossed the target
+    fn pay_prize(
+        deps: DepsMut,
+        res: Response,
+        info: &MessageInfo,
+        previous: i32,
+        count: i32,
+    ) -> Result<Response, ContractError> {
+        let prize = PRIZE.load(deps.storage)?;
+        let reached = match prize.target {
+            Some(target) => previous < target && count >= target,
+            None => false,
+        };
+        if !reached || prize.pool.is_empty() {
+            return Ok(res);
+        }
+
+        PRIZE.save(deps.storage, &Prize::default())?;
+
+        let msg = BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: prize.pool,
+        };
+        Ok(res
+            .add_attribute("prize_winner", info.sender.as_str())
+            .add_message(msg))
+    }
+
+    pub fn fund_prize(
+        deps: DepsMut,
+        info: MessageInfo,
+        target: i32,
+    ) -> Result<Response, ContractError> {
+        OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+        if info.funds.is_empty() {
+            return Err(ContractError::NoFunds {});
+        }
+
+        // Add the sent funds to the pool, merging coins of the same denom
+        let mut prize = PRIZE.load(deps.storage)?;
+        for coin in info.funds {
+            match prize.pool.iter_mut().find(|c| c.denom == coin.denom) {
+                Some(existing) => existing.amount += coin.amount,
+                None => prize.pool.push(coin),
+            }
+        }
+        prize.target = Some(target);
+        PRIZE.save(deps.storage, &prize)?;
 
-        Ok(Response::new()
-            .add_attribute("action", "increment_by")
-            .add_attribute("incremented_by", count.to_string()))
+        Ok(response("fund_prize").add_attribute("target", target.to_string()))
     }
 
     pub fn decrement_by(
         deps: DepsMut,
+        env: Env,
         _info: MessageInfo,
         count: i32,
     ) -> Result<Response, ContractError> {
-        STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
-            state.count -= count;
-            Ok(state)
-        })?;
+        let mut state = STATE.load(deps.storage)?;
+        state.count -= count;
+        STATE.save(deps.storage, &state, env.block.height)?;
 
-        Ok(Response::new()
-            .add_attribute("action", "decrement_by")
-            .add_attribute("decremented_by", count.to_string()))
+        Ok(response("decrement_by").add_attribute("decremented_by", count.to_string()))
     }
     pub fn reflect_funds(
         _deps: DepsMut,
@@ -139,39 +238,183 @@ pub mod execute {
         // Craft msg and send funds back to sender
         let _cosmos_msg: CosmosMsg<BankMsg> = CosmosMsg::Bank(msg);
 
-        Ok(Response::new()
-            .add_attribute("action", "reflect_funds")
-            .add_attribute("amount", amount.to_string()))
+        Ok(response("reflect_funds").add_attribute(attr::AMOUNT, amount.to_string()))
+    }
+
+    pub fn set_peer(
+        deps: DepsMut,
+        info: MessageInfo,
+        peer: String,
+    ) -> Result<Response, ContractError> {
+        OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+        let peer = deps.api.addr_validate(&peer)?;
+        PEER.save(deps.storage, &peer)?;
+
+        Ok(response("set_peer").add_attribute("peer", peer))
+    }
+
+    pub fn increment_peer(deps: DepsMut) -> Result<Response, ContractError> {
+        let peer = PEER
+            .may_load(deps.storage)?
+            .ok_or(ContractError::PeerNotSet {})?;
+
+        // Every call is counted here, failures are counted when the reply comes back
+        PEER_STATS.update(deps.storage, |mut stats| -> StdResult<_> {
+            stats.calls += 1;
+            Ok(stats)
+        })?;
+
+        // A failing peer only triggers the reply, it does not revert this transaction
+        let msg = ExecuteMsg::increment().into_cosmos_msg(&peer)?;
+
+        Ok(response("increment_peer")
+            .add_attribute("peer", peer)
+            .add_submessage(SubMsg::reply_on_error(msg, INCREMENT_PEER_REPLY_ID)))
     }
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::Reset { count } => sudo::reset(deps, env, count),
+    }
+}
+
+pub mod sudo {
+    use super::*;
+
+    // Governance can reset the count regardless of owner, pause or expiration
+    pub fn reset(deps: DepsMut, env: Env, count: i32) -> Result<Response, ContractError> {
+        let mut state = STATE.load(deps.storage)?;
+        state.count = count;
+        STATE.save(deps.storage, &state, env.block.height)?;
+
+        Ok(response("sudo_reset").add_attribute("count", count.to_string()))
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        INCREMENT_PEER_REPLY_ID => reply::increment_peer(deps, msg.result),
+        id => Err(ContractError::UnknownReplyId { id }),
+    }
+}
+
+pub mod reply {
+    use super::*;
+
+    pub fn increment_peer(deps: DepsMut, result: SubMsgResult) -> Result<Response, ContractError> {
+        // Only errors are replied to, but be explicit about it
+        let error = match result {
+            SubMsgResult::Ok(_) => return Ok(Response::new()),
+            SubMsgResult::Err(error) => error,
+        };
+
+        PEER_STATS.update(deps.storage, |mut stats| -> StdResult<_> {
+            stats.failures += 1;
+            Ok(stats)
+        })?;
+
+        Ok(response("increment_peer_failed").add_attribute(attr::ERROR, error))
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // If state structure changes in a later version, register its transform here with
+    // `.step(version, transform)`
+    let migrated = Migration::new(CONTRACT_NAME, CONTRACT_VERSION).run(deps.storage)?;
+
+    Ok(migrate_response(migrated.from, migrated.to))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetCount {} => to_binary(&query::count(deps)?),
+        QueryMsg::GetCountAt { height } => to_binary(&query::count_at(deps, height)?),
+        QueryMsg::Paused {} => to_binary(&PAUSE.query(deps.storage)?),
+        QueryMsg::Ownership {} => to_binary(&OWNERSHIP.query(deps.storage)?),
+        QueryMsg::PeerStats {} => to_binary(&query::peer_stats(deps)?),
+        QueryMsg::GetCombinedCount { other_contract } => {
+            to_binary(&query::combined_count(deps, other_contract)?)
+        }
+        QueryMsg::Prize {} => to_binary(&query::prize(deps)?),
     }
 }
 
 pub mod query {
+    use cosmwasm_std::{OverflowError, OverflowOperation};
+
     use super::*;
 
     pub fn count(deps: Deps) -> StdResult<GetCountResponse> {
         let state = STATE.load(deps.storage)?;
         Ok(GetCountResponse { count: state.count })
     }
+
+    pub fn count_at(deps: Deps, height: u64) -> StdResult<GetCountResponse> {
+        // Returns the count as it was at the start of the given block
+        let state = STATE
+            .may_load_at_height(deps.storage, height)?
+            .ok_or_else(|| StdError::not_found("State"))?;
+        Ok(GetCountResponse { count: state.count })
+    }
+
+    pub fn peer_stats(deps: Deps) -> StdResult<PeerStatsResponse> {
+        let peer = PEER.may_load(deps.storage)?;
+        let stats = PEER_STATS.load(deps.storage)?;
+        Ok(PeerStatsResponse {
+            peer: peer.map(|addr| addr.into_string()),
+            successes: stats.calls - stats.failures,
+            failures: stats.failures,
+        })
+    }
+
+    pub fn combined_count(deps: Deps, other_contract: String) -> StdResult<GetCountResponse> {
+        let state = STATE.load(deps.storage)?;
+
+        // Ask the other counter for its own count via a smart query
+        let other_contract = deps.api.addr_validate(&other_contract)?;
+        let request = QueryMsg::get_count().into_query_request(other_contract)?;
+        let other: GetCountResponse = deps.querier.query(&request)?;
+
+        let count = state
+            .count
+            .checked_add(other.count)
+            .ok_or_else(|| OverflowError::new(OverflowOperation::Add, state.count, other.count))?;
+        Ok(GetCountResponse { count })
+    }
+
+    pub fn prize(deps: Deps) -> StdResult<PrizeResponse> {
+        let prize = PRIZE.load(deps.storage)?;
+        Ok(PrizeResponse {
+            target: prize.target,
+            pool: prize.pool,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary};
+    use cosmwasm_std::{coins, from_binary, Addr, BankMsg, CosmosMsg};
+    use cosmwatch_migrate::MigrateError;
+    use cw2::get_contract_version;
+
+    use crate::msg::{OwnershipResponse, PausedResponse};
 
     #[test]
     fn proper_initialization() {
         let mut deps = mock_dependencies();
 
-        let msg = InstantiateMsg { count: 17 };
+        let msg = InstantiateMsg {
+            count: 17,
+            end_height: None,
+            end_time: None,
+        };
         let info = mock_info("creator", &coins(1000, "earth"));
 
         // we can just call .unwrap() to assert this was a success
@@ -188,7 +431,11 @@ mod tests {
     fn increment() {
         let mut deps = mock_dependencies();
 
-        let msg = InstantiateMsg { count: 17 };
+        let msg = InstantiateMsg {
+            count: 17,
+            end_height: None,
+            end_time: None,
+        };
         let info = mock_info("creator", &coins(2, "token"));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -207,7 +454,11 @@ mod tests {
     fn decrement() {
         let mut deps = mock_dependencies();
 
-        let msg = InstantiateMsg { count: 17 };
+        let msg = InstantiateMsg {
+            count: 17,
+            end_height: None,
+            end_time: None,
+        };
         let info = mock_info("creator", &coins(2, "token"));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -224,7 +475,11 @@ mod tests {
     fn reset() {
         let mut deps = mock_dependencies();
 
-        let msg = InstantiateMsg { count: 17 };
+        let msg = InstantiateMsg {
+            count: 17,
+            end_height: None,
+            end_time: None,
+        };
         let info = mock_info("creator", &coins(2, "token"));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -253,7 +508,11 @@ mod tests {
         let mut deps = mock_dependencies();
 
         // Instantiate with 17
-        let msg = InstantiateMsg { count: 17 };
+        let msg = InstantiateMsg {
+            count: 17,
+            end_height: None,
+            end_time: None,
+        };
         let info = mock_info("creator", &coins(2, "token"));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -273,7 +532,11 @@ mod tests {
         let mut deps = mock_dependencies();
 
         // Instantiate with 17
-        let msg = InstantiateMsg { count: 17 };
+        let msg = InstantiateMsg {
+            count: 17,
+            end_height: None,
+            end_time: None,
+        };
         let info = mock_info("creator", &coins(1, "token"));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -287,4 +550,320 @@ mod tests {
         let value: GetCountResponse = from_binary(&res).unwrap();
         assert_eq!(10, value.count);
     }
+
+    #[test]
+    fn count_at_height() {
+        let mut deps = mock_dependencies();
+
+        // Instantiate with 17 at height 100
+        let mut env = mock_env();
+        env.block.height = 100;
+        let msg = InstantiateMsg {
+            count: 17,
+            end_height: None,
+            end_time: None,
+        };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Increment by 5 at height 200
+        env.block.height = 200;
+        let info = mock_info("anyone", &coins(2, "token"));
+        let msg = ExecuteMsg::IncremementBy { count: 5 };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Before the increment the count was still 17
+        let msg = QueryMsg::GetCountAt { height: 150 };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let value: GetCountResponse = from_binary(&res).unwrap();
+        assert_eq!(17, value.count);
+
+        // Snapshots reflect the state at the start of a block
+        let msg = QueryMsg::GetCountAt { height: 200 };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let value: GetCountResponse = from_binary(&res).unwrap();
+        assert_eq!(17, value.count);
+
+        let msg = QueryMsg::GetCountAt { height: 201 };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let value: GetCountResponse = from_binary(&res).unwrap();
+        assert_eq!(22, value.count);
+
+        // Nothing was stored before instantiation
+        let msg = QueryMsg::GetCountAt { height: 50 };
+        let err = query(deps.as_ref(), env, msg).unwrap_err();
+        assert!(matches!(err, StdError::NotFound { .. }));
+    }
+
+    #[test]
+    fn pause() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            count: 17,
+            end_height: None,
+            end_time: None,
+        };
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Only the owner can pause
+        let unauth_info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), mock_env(), unauth_info, ExecuteMsg::Pause {});
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let auth_info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, ExecuteMsg::Pause {}).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Paused {}).unwrap();
+        let value: PausedResponse = from_binary(&res).unwrap();
+        assert!(value.paused);
+
+        // Mutations are rejected while paused
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Increment {});
+        match res {
+            Err(ContractError::ContractPaused {}) => {}
+            _ => panic!("Must return contract paused error"),
+        }
+
+        // Unpausing allows mutations again
+        let auth_info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, ExecuteMsg::Unpause {}).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Increment {}).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetCount {}).unwrap();
+        let value: GetCountResponse = from_binary(&res).unwrap();
+        assert_eq!(18, value.count);
+    }
+
+    #[test]
+    fn ownership_handover() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            count: 17,
+            end_height: None,
+            end_time: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Ownership is offered while paused and only moves once accepted
+        let auth_info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, ExecuteMsg::Pause {}).unwrap();
+        let auth_info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::TransferOwnership {
+            new_owner: "successor".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::AcceptOwnership {},
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let info = mock_info("successor", &[]);
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::AcceptOwnership {},
+        )
+        .unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Ownership {}).unwrap();
+        let value: OwnershipResponse = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("successor"), value.owner);
+        assert_eq!(None, value.pending_owner);
+
+        // The old owner lost control, the new one can unpause
+        let info = mock_info("creator", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Unpause {});
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let info = mock_info("successor", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Unpause {}).unwrap();
+    }
+
+    #[test]
+    fn prize() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            count: 17,
+            end_height: None,
+            end_time: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Only the owner can fund the prize
+        let unauth_info = mock_info("anyone", &coins(100, "token"));
+        let msg = ExecuteMsg::FundPrize { target: 20 };
+        let res = execute(deps.as_mut(), mock_env(), unauth_info, msg);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+
+        let auth_info = mock_info("creator", &coins(100, "token"));
+        let msg = ExecuteMsg::FundPrize { target: 20 };
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Prize {}).unwrap();
+        let value: PrizeResponse = from_binary(&res).unwrap();
+        assert_eq!(Some(20), value.target);
+        assert_eq!(coins(100, "token"), value.pool);
+
+        // Falling short of the target pays nothing
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::IncremementBy { count: 2 };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // Reaching the target pays the pool to the sender
+        let info = mock_info("winner", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Increment {}).unwrap();
+        assert_eq!(1, res.messages.len());
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "winner".to_string(),
+                amount: coins(100, "token"),
+            })
+        );
+
+        // The pool is emptied once paid out
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Prize {}).unwrap();
+        let value: PrizeResponse = from_binary(&res).unwrap();
+        assert_eq!(None, value.target);
+        assert!(value.pool.is_empty());
+    }
+
+    #[test]
+    fn expired() {
+        let mut deps = mock_dependencies();
+
+        let mut env = mock_env();
+        let msg = InstantiateMsg {
+            count: 17,
+            end_height: Some(env.block.height + 10),
+            end_time: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Mutations work until the end height
+        env.block.height += 10;
+        let info = mock_info("anyone", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Increment {}).unwrap();
+
+        // After the end height everything is rejected, even for the owner
+        env.block.height += 1;
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Increment {});
+        match res {
+            Err(ContractError::Expired {}) => {}
+            _ => panic!("Must return expired error"),
+        }
+
+        let auth_info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Reset { count: 0 };
+        let res = execute(deps.as_mut(), env.clone(), auth_info, msg);
+        match res {
+            Err(ContractError::Expired {}) => {}
+            _ => panic!("Must return expired error"),
+        }
+
+        // The final value remains queryable
+        let res = query(deps.as_ref(), env, QueryMsg::GetCount {}).unwrap();
+        let value: GetCountResponse = from_binary(&res).unwrap();
+        assert_eq!(18, value.count);
+    }
+
+    #[test]
+    fn sudo_reset() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            count: 17,
+            end_height: None,
+            end_time: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Governance can reset even while the owner has paused the counter
+        let auth_info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), auth_info, ExecuteMsg::Pause {}).unwrap();
+
+        let msg = SudoMsg::Reset { count: 3 };
+        let _res = sudo(deps.as_mut(), mock_env(), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetCount {}).unwrap();
+        let value: GetCountResponse = from_binary(&res).unwrap();
+        assert_eq!(3, value.count);
+    }
+
+    #[test]
+    fn migrate_from_older_version() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            count: 17,
+            end_height: None,
+            end_time: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.1").unwrap();
+
+        let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(CONTRACT_VERSION, version.version);
+
+        // The count carries over
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetCount {}).unwrap();
+        let value: GetCountResponse = from_binary(&res).unwrap();
+        assert_eq!(17, value.count);
+    }
+
+    #[test]
+    fn migrate_rejects_downgrade_and_other_contracts() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            count: 17,
+            end_height: None,
+            end_time: None,
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(
+            matches!(err, ContractError::Migrate(MigrateError::CannotDowngrade { stored, .. }) if stored == "99.0.0")
+        );
+
+        set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.0.1").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(
+            matches!(err, ContractError::Migrate(MigrateError::InvalidContract { contract }) if contract == "crates.io:cw20-base")
+        );
+    }
 }
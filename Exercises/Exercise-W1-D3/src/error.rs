@@ -1,4 +1,6 @@
 use cosmwasm_std::StdError;
+use cosmwatch_access::AccessError;
+use cosmwatch_migrate::MigrateError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,9 +11,38 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("{0}")]
+    Migrate(#[from] MigrateError),
+
     #[error("Funds mismatch")]
     FundsMismatch {},
 
     #[error("No funds provided")]
     NoFunds {},
+
+    #[error("Contract is paused")]
+    ContractPaused {},
+
+    #[error("No ownership transfer is pending")]
+    NoPendingOwner {},
+
+    #[error("Counter is expired")]
+    Expired {},
+
+    #[error("Peer contract is not set")]
+    PeerNotSet {},
+
+    #[error("Unknown reply id: {id}")]
+    UnknownReplyId { id: u64 },
+}
+
+impl From<AccessError> for ContractError {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::Std(err) => ContractError::Std(err),
+            AccessError::Unauthorized {} => ContractError::Unauthorized {},
+            AccessError::ContractPaused {} => ContractError::ContractPaused {},
+            AccessError::NoPendingOwner {} => ContractError::NoPendingOwner {},
+        }
+    }
 }
@@ -2,7 +2,8 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{
-    to_binary, Addr, CosmosMsg, CustomQuery, Querier, QuerierWrapper, StdResult, WasmMsg, WasmQuery,
+    to_binary, Addr, CosmosMsg, CustomQuery, Empty, Querier, QuerierWrapper, QueryRequest,
+    StdResult, WasmMsg, WasmQuery,
 };
 
 use crate::msg::{ExecuteMsg, GetCountResponse, QueryMsg};
@@ -44,3 +45,59 @@ impl CwTemplateContract {
         Ok(res)
     }
 }
+
+/// Typed constructors so other contracts (built with the `library` feature) can drive a counter
+/// without hand-building json, e.g. `ExecuteMsg::increment_by(5).into_cosmos_msg(addr)`
+impl ExecuteMsg {
+    pub fn increment() -> Self {
+        ExecuteMsg::Increment {}
+    }
+
+    pub fn decrement() -> Self {
+        ExecuteMsg::Decrement {}
+    }
+
+    pub fn increment_by(count: i32) -> Self {
+        ExecuteMsg::IncremementBy { count }
+    }
+
+    pub fn decrement_by(count: i32) -> Self {
+        ExecuteMsg::DecrementBy { count }
+    }
+
+    pub fn reset(count: i32) -> Self {
+        ExecuteMsg::Reset { count }
+    }
+
+    /// Wraps the message in a `WasmMsg::Execute` targeting the given counter
+    pub fn into_cosmos_msg(self, contract_addr: impl Into<String>) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: to_binary(&self)?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+impl QueryMsg {
+    pub fn get_count() -> Self {
+        QueryMsg::GetCount {}
+    }
+
+    pub fn get_count_at(height: u64) -> Self {
+        QueryMsg::GetCountAt { height }
+    }
+
+    /// Wraps the query in a `WasmQuery::Smart` targeting the given counter
+    pub fn into_query_request(
+        self,
+        contract_addr: impl Into<String>,
+    ) -> StdResult<QueryRequest<Empty>> {
+        Ok(WasmQuery::Smart {
+            contract_addr: contract_addr.into(),
+            msg: to_binary(&self)?,
+        }
+        .into())
+    }
+}
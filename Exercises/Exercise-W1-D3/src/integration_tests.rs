@@ -10,7 +10,8 @@ mod tests {
             crate::contract::execute,
             crate::contract::instantiate,
             crate::contract::query,
-        );
+        )
+        .with_reply(crate::contract::reply);
         Box::new(contract)
     }
 
@@ -38,7 +39,11 @@ mod tests {
         let mut app = mock_app();
         let cw_template_id = app.store_code(contract_template());
 
-        let msg = InstantiateMsg { count: 1i32 };
+        let msg = InstantiateMsg {
+            count: 1i32,
+            end_height: None,
+            end_time: None,
+        };
         let cw_template_contract_addr = app
             .instantiate_contract(
                 cw_template_id,
@@ -57,7 +62,7 @@ mod tests {
 
     mod count {
         use super::*;
-        use crate::msg::ExecuteMsg;
+        use crate::msg::{ExecuteMsg, GetCountResponse, QueryMsg};
 
         #[test]
         fn count() {
@@ -67,5 +72,141 @@ mod tests {
             let cosmos_msg = cw_template_contract.call(msg).unwrap();
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
         }
+
+        #[test]
+        fn typed_helpers() {
+            let (mut app, cw_template_contract) = proper_instantiate();
+
+            let cosmos_msg = ExecuteMsg::increment_by(5)
+                .into_cosmos_msg(cw_template_contract.addr())
+                .unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let request = QueryMsg::get_count()
+                .into_query_request(cw_template_contract.addr())
+                .unwrap();
+            let res: GetCountResponse = app.wrap().query(&request).unwrap();
+            assert_eq!(6, res.count);
+        }
+
+        #[test]
+        fn combined_count() {
+            let (mut app, cw_template_contract) = proper_instantiate();
+
+            let cw_template_id = app.store_code(contract_template());
+            let other_contract_addr = app
+                .instantiate_contract(
+                    cw_template_id,
+                    Addr::unchecked(ADMIN),
+                    &InstantiateMsg {
+                        count: 41i32,
+                        end_height: None,
+                        end_time: None,
+                    },
+                    &[],
+                    "other",
+                    None,
+                )
+                .unwrap();
+
+            let res: GetCountResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    cw_template_contract.addr(),
+                    &QueryMsg::GetCombinedCount {
+                        other_contract: other_contract_addr.to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(42, res.count);
+        }
+    }
+
+    mod peer {
+        use super::*;
+        use crate::msg::{ExecuteMsg, GetCountResponse, PeerStatsResponse, QueryMsg};
+
+        fn instantiate_peer(app: &mut App) -> CwTemplateContract {
+            let cw_template_id = app.store_code(contract_template());
+            let addr = app
+                .instantiate_contract(
+                    cw_template_id,
+                    Addr::unchecked(ADMIN),
+                    &InstantiateMsg {
+                        count: 10i32,
+                        end_height: None,
+                        end_time: None,
+                    },
+                    &[],
+                    "peer",
+                    None,
+                )
+                .unwrap();
+            CwTemplateContract(addr)
+        }
+
+        fn peer_stats(app: &App, contract: &CwTemplateContract) -> PeerStatsResponse {
+            app.wrap()
+                .query_wasm_smart(contract.addr(), &QueryMsg::PeerStats {})
+                .unwrap()
+        }
+
+        #[test]
+        fn increment_peer_records_outcomes() {
+            let (mut app, cw_template_contract) = proper_instantiate();
+            let peer_contract = instantiate_peer(&mut app);
+
+            let msg = ExecuteMsg::SetPeer {
+                peer: peer_contract.addr().to_string(),
+            };
+            let cosmos_msg = cw_template_contract.call(msg).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            // Successful call increments the peer
+            let cosmos_msg = cw_template_contract
+                .call(ExecuteMsg::IncrementPeer {})
+                .unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let count: GetCountResponse = app
+                .wrap()
+                .query_wasm_smart(peer_contract.addr(), &QueryMsg::GetCount {})
+                .unwrap();
+            assert_eq!(11, count.count);
+
+            let stats = peer_stats(&app, &cw_template_contract);
+            assert_eq!(Some(peer_contract.addr().to_string()), stats.peer);
+            assert_eq!(1, stats.successes);
+            assert_eq!(0, stats.failures);
+
+            // A paused peer rejects the increment, which is recorded instead of failing the tx
+            let cosmos_msg = peer_contract.call(ExecuteMsg::Pause {}).unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let cosmos_msg = cw_template_contract
+                .call(ExecuteMsg::IncrementPeer {})
+                .unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let count: GetCountResponse = app
+                .wrap()
+                .query_wasm_smart(peer_contract.addr(), &QueryMsg::GetCount {})
+                .unwrap();
+            assert_eq!(11, count.count);
+
+            let stats = peer_stats(&app, &cw_template_contract);
+            assert_eq!(1, stats.successes);
+            assert_eq!(1, stats.failures);
+        }
+
+        #[test]
+        fn increment_peer_requires_peer() {
+            let (mut app, cw_template_contract) = proper_instantiate();
+
+            let cosmos_msg = cw_template_contract
+                .call(ExecuteMsg::IncrementPeer {})
+                .unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap_err();
+        }
     }
 }
@@ -1,11 +1,21 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Coin, Uint128};
+
+pub use cosmwatch_access::{OwnershipResponse, PausedResponse};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub count: i32,
+    /// When end height set and block height exceeds this value, the count is frozen.
+    pub end_height: Option<u64>,
+    /// When end time (in seconds since epoch 00:00:00 UTC on 1 January 1970) is set and
+    /// block time exceeds this value, the count is frozen.
+    pub end_time: Option<u64>,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     Increment {},
@@ -14,6 +24,19 @@ pub enum ExecuteMsg {
     IncremementBy { count: i32 },
     DecrementBy { count: i32 },
     ReflectFunds { amount: Uint128 },
+    Pause {},
+    Unpause {},
+    TransferOwnership { new_owner: String },
+    AcceptOwnership {},
+    SetPeer { peer: String },
+    IncrementPeer {},
+    FundPrize { target: i32 },
+}
+
+/// Messages only chain governance can send
+#[cw_serde]
+pub enum SudoMsg {
+    Reset { count: i32 },
 }
 
 #[cw_serde]
@@ -22,6 +45,24 @@ pub enum QueryMsg {
     // GetCount returns the current count as a json-encoded number
     #[returns(GetCountResponse)]
     GetCount {},
+    // GetCountAt returns the count as it was at the start of the given block height
+    #[returns(GetCountResponse)]
+    GetCountAt { height: u64 },
+    // Paused returns whether mutations are currently blocked
+    #[returns(PausedResponse)]
+    Paused {},
+    // Ownership returns the owner and the address ownership was offered to, if any
+    #[returns(OwnershipResponse)]
+    Ownership {},
+    // PeerStats returns the configured peer and the outcome of calls made to it
+    #[returns(PeerStatsResponse)]
+    PeerStats {},
+    // GetCombinedCount returns this count plus the count of another counter contract
+    #[returns(GetCountResponse)]
+    GetCombinedCount { other_contract: String },
+    // Prize returns the target count and the pool paid to whoever reaches it
+    #[returns(PrizeResponse)]
+    Prize {},
 }
 
 // We define a custom struct for each query response
@@ -29,3 +70,16 @@ pub enum QueryMsg {
 pub struct GetCountResponse {
     pub count: i32,
 }
+
+#[cw_serde]
+pub struct PeerStatsResponse {
+    pub peer: Option<String>,
+    pub successes: u64,
+    pub failures: u64,
+}
+
+#[cw_serde]
+pub struct PrizeResponse {
+    pub target: Option<i32>,
+    pub pool: Vec<Coin>,
+}
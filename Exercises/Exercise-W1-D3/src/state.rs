@@ -1,13 +1,64 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Coin, Env, Timestamp};
+use cosmwatch_access::{Ownable, Pausable};
+use cw_storage_plus::{Item, SnapshotItem, Strategy};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct State {
     pub count: i32,
-    pub owner: Addr,
+    /// When end height set and block height exceeds this value, the count is frozen.
+    pub end_height: Option<u64>,
+    /// When end time (in seconds since epoch 00:00:00 UTC on 1 January 1970) is set and
+    /// block time exceeds this value, the count is frozen.
+    pub end_time: Option<u64>,
 }
 
-pub const STATE: Item<State> = Item::new("state");
+impl State {
+    pub fn is_expired(&self, env: &Env) -> bool {
+        (if let Some(end_height) = self.end_height {
+            env.block.height > end_height
+        } else {
+            false
+        }) || (if let Some(end_time) = self.end_time {
+            env.block.time > Timestamp::from_seconds(end_time)
+        } else {
+            false
+        })
+    }
+}
+
+// Every change is checkpointed so the state can be loaded as it was at any past height
+pub const STATE: SnapshotItem<State> = SnapshotItem::new(
+    "state",
+    "state__checkpoints",
+    "state__changelog",
+    Strategy::EveryBlock,
+);
+
+// The owner can reset the count, pause, set the peer and fund the prize
+pub const OWNERSHIP: Ownable = Ownable::new("owner", "pending_owner");
+
+// While paused, the owner has to unpause before the count can be changed again
+pub const PAUSE: Pausable = Pausable::new("paused");
+
+// Counter contract that IncrementPeer calls into
+pub const PEER: Item<Addr> = Item::new("peer");
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct PeerStats {
+    pub calls: u64,
+    pub failures: u64,
+}
+
+pub const PEER_STATS: Item<PeerStats> = Item::new("peer_stats");
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct Prize {
+    // The increment that reaches this count wins the pool
+    pub target: Option<i32>,
+    pub pool: Vec<Coin>,
+}
+
+pub const PRIZE: Item<Prize> = Item::new("prize");
@@ -1,27 +1,35 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+    to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order,
+    Response, StdResult, Storage,
 };
-// use cw2::set_contract_version;
+use cosmwatch_events::{migrate_response, response};
+use cosmwatch_migrate::Migration;
+use cw2::set_contract_version;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, QueryTotalForwardedResponse,
+    SenderStatsResponse,
+};
+use crate::state::{Config, CONFIG, SENDER_FORWARDS, SENDER_TOTALS, TOKENS_SENT};
 
-/*
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:sender-receiver-code-challenge";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
-*/
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     _msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    Ok(Response::new().add_attribute("action", "instantiate"))
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    CONFIG.save(deps.storage, &Config { owner: info.sender })?;
+
+    Ok(response("instantiate"))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -46,28 +54,271 @@ fn forward_tokens(
 ) -> Result<Response, ContractError> {
     let validated_addr = deps.api.addr_validate(&forward_to_addr)?.to_string();
 
-    // If funds are zero, throw an error to the sender - technically unecessary, since the chain will not let you send a
-    // message w/zero funds
-    if info.funds[0].amount == Uint128::new(0) {
-        ContractError::ZeroFunds {};
-    }
+    validate_funds(&info.funds)?;
+    record_forward(deps.storage, &info.sender, &info.funds)?;
 
-    // Create send msg using validated forward_to address and funds included in the request
+    // Create send msg using validated forward_to address and every coin included in the request
     let msg = BankMsg::Send {
         to_address: validated_addr,
         amount: info.funds,
     };
 
     // New response with action and
-    Ok(Response::new()
-        .add_attribute("action", "forward_tokens")
-        .add_message(CosmosMsg::Bank(msg)))
+    Ok(response("forward_tokens").add_message(CosmosMsg::Bank(msg)))
+}
+
+/// Checks the attached funds before anything is forwarded
+fn validate_funds(funds: &[Coin]) -> Result<(), ContractError> {
+    // Check if funds are empty before we access
+    if funds.is_empty() {
+        return Err(ContractError::ZeroFunds {});
+    }
+
+    // If any coin is zero, throw an error to the sender - technically unecessary, since the chain will not let you send a
+    // message w/zero funds
+    if funds.iter().any(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::ZeroFunds {});
+    }
+
+    Ok(())
+}
+
+/// Adds the forwarded funds to the running totals, overall and for the sender
+fn record_forward(storage: &mut dyn Storage, sender: &Addr, funds: &[Coin]) -> StdResult<()> {
+    for coin in funds {
+        TOKENS_SENT.update(storage, &coin.denom, |total| -> StdResult<_> {
+            Ok(total.unwrap_or_default() + coin.amount)
+        })?;
+        SENDER_TOTALS.update(storage, (sender, &coin.denom), |total| -> StdResult<_> {
+            Ok(total.unwrap_or_default() + coin.amount)
+        })?;
+    }
+    SENDER_FORWARDS.update(storage, sender, |forwards| -> StdResult<_> {
+        Ok(forwards.unwrap_or_default() + 1)
+    })?;
+    Ok(())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(_deps: Deps, _env: Env, _msg: QueryMsg) -> StdResult<Binary> {
-    unimplemented!()
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // If state structure changes in a later version, register its transform here with
+    // `.step(version, transform)`
+    let migrated = Migration::new(CONTRACT_NAME, CONTRACT_VERSION).run(deps.storage)?;
+
+    Ok(migrate_response(migrated.from, migrated.to))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::QueryTotalForwarded {} => to_binary(&query_total_forwarded(deps)?),
+        QueryMsg::SenderStats { sender } => to_binary(&query_sender_stats(deps, sender)?),
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: config.owner,
+    })
+}
+
+fn query_total_forwarded(deps: Deps) -> StdResult<QueryTotalForwardedResponse> {
+    let amounts = TOKENS_SENT
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin { denom, amount })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(QueryTotalForwardedResponse { amounts })
+}
+
+fn query_sender_stats(deps: Deps, sender: String) -> StdResult<SenderStatsResponse> {
+    let sender = deps.api.addr_validate(&sender)?;
+    let forwards = SENDER_FORWARDS
+        .may_load(deps.storage, &sender)?
+        .unwrap_or_default();
+    let amounts = SENDER_TOTALS
+        .prefix(&sender)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin { denom, amount })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SenderStatsResponse {
+        sender: sender.into_string(),
+        forwards,
+        amounts,
+    })
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coin, from_binary};
+    use cosmwatch_migrate::MigrateError;
+    use cw2::get_contract_version;
+
+    #[test]
+    fn forwards_every_coin() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg {}).unwrap();
+
+        let funds = vec![coin(100, "ujuno"), coin(25, "uosmo")];
+        let info = mock_info("sender", &funds);
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: "recipient".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: funds,
+            })
+        );
+    }
+
+    #[test]
+    fn queries_track_forwards() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg {}).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("creator"), value.owner);
+
+        for (sender, funds) in [
+            ("sender1", vec![coin(100, "ujuno"), coin(25, "uosmo")]),
+            ("sender2", vec![coin(50, "ujuno")]),
+            ("sender1", vec![coin(10, "ujuno")]),
+        ] {
+            let info = mock_info(sender, &funds);
+            let msg = ExecuteMsg::ForwardTokens {
+                forward_to_addr: "recipient".to_string(),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::QueryTotalForwarded {}).unwrap();
+        let value: QueryTotalForwardedResponse = from_binary(&res).unwrap();
+        assert_eq!(vec![coin(160, "ujuno"), coin(25, "uosmo")], value.amounts);
+
+        let msg = QueryMsg::SenderStats {
+            sender: "sender1".to_string(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: SenderStatsResponse = from_binary(&res).unwrap();
+        assert_eq!(2, value.forwards);
+        assert_eq!(vec![coin(110, "ujuno"), coin(25, "uosmo")], value.amounts);
+
+        // Unknown senders have forwarded nothing
+        let msg = QueryMsg::SenderStats {
+            sender: "nobody".to_string(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: SenderStatsResponse = from_binary(&res).unwrap();
+        assert_eq!(0, value.forwards);
+        assert!(value.amounts.is_empty());
+    }
+
+    #[test]
+    fn rejects_missing_funds() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg {}).unwrap();
+
+        let info = mock_info("sender", &[]);
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: "recipient".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::ZeroFunds {}));
+    }
+
+    #[test]
+    fn rejects_zero_coin_in_funds() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg {}).unwrap();
+
+        let info = mock_info("sender", &[coin(100, "ujuno"), coin(0, "uosmo")]);
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: "recipient".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::ZeroFunds {}));
+    }
+
+    #[test]
+    fn rejects_invalid_recipient() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg {}).unwrap();
+
+        let info = mock_info("sender", &[coin(100, "ujuno")]);
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: "".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Std(_)));
+    }
+
+    #[test]
+    fn instantiate_sets_contract_version() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg {}).unwrap();
+
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(CONTRACT_NAME, version.contract);
+        assert_eq!(CONTRACT_VERSION, version.version);
+    }
+
+    #[test]
+    fn migrate_from_older_version() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg {}).unwrap();
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.1").unwrap();
+
+        let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(CONTRACT_VERSION, version.version);
+    }
+
+    #[test]
+    fn migrate_rejects_downgrade_and_other_contracts() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg {}).unwrap();
+
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(
+            matches!(err, ContractError::Migrate(MigrateError::CannotDowngrade { stored, .. }) if stored == "99.0.0")
+        );
+
+        set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.0.1").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(
+            matches!(err, ContractError::Migrate(MigrateError::InvalidContract { contract }) if contract == "crates.io:cw20-base")
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use cosmwasm_std::StdError;
+use cosmwatch_migrate::MigrateError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,6 +10,9 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("{0}")]
+    Migrate(#[from] MigrateError),
+
     #[error("Cannot send zero funds")]
     ZeroFunds {},
     // Add any other custom errors you like here.
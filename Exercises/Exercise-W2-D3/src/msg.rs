@@ -1,8 +1,12 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Coin};
 
 #[cw_serde]
 pub struct InstantiateMsg {}
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     ForwardTokens { forward_to_addr: String },
@@ -10,4 +14,33 @@ pub enum ExecuteMsg {
 
 #[cw_serde]
 #[derive(QueryResponses)]
-pub enum QueryMsg {}
+pub enum QueryMsg {
+    /// Returns the address that instantiated the forwarder
+    #[returns(ConfigResponse)]
+    Config {},
+
+    /// Returns the lifetime amount forwarded per denom
+    #[returns(QueryTotalForwardedResponse)]
+    QueryTotalForwarded {},
+
+    /// Returns how many forwards the given sender made and the amounts they forwarded
+    #[returns(SenderStatsResponse)]
+    SenderStats { sender: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+}
+
+#[cw_serde]
+pub struct QueryTotalForwardedResponse {
+    pub amounts: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct SenderStatsResponse {
+    pub sender: String,
+    pub forwards: u64,
+    pub amounts: Vec<Coin>,
+}
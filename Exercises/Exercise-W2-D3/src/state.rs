@@ -1,4 +1,21 @@
-use cosmwasm_std::Coin;
-use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-pub const TOKENS_SENT: Item<Coin> = Item::new("tokens_sent");
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    pub owner: Addr,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// Lifetime amount forwarded per denom
+pub const TOKENS_SENT: Map<&str, Uint128> = Map::new("tokens_sent");
+
+// Number of forwards made by each sender
+pub const SENDER_FORWARDS: Map<&Addr, u64> = Map::new("sender_forwards");
+
+// Lifetime amount per denom forwarded by each sender
+pub const SENDER_TOTALS: Map<(&Addr, &str), Uint128> = Map::new("sender_totals");
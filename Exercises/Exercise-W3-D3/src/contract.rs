@@ -1,29 +1,97 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
-    Uint128,
+    to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Empty, Env, Event,
+    MessageInfo, Order, Reply, Response, StdResult, Storage, SubMsg, SubMsgResult, Timestamp,
+    Uint128, WasmMsg,
 };
-// use cw2::set_contract_version;
+use cosmwatch_balances::add_coin;
+use cosmwatch_events::{attr, event, migrate_response, response, transfer_event};
+use cosmwatch_migrate::Migration;
+use cw2::set_contract_version;
+use cw_storage_plus::Bound;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, QueryTotalForwardedResponse};
-use crate::state::TOKENS_SENT;
+use crate::msg::{
+    AccruedFeesResponse, AliasResponse, AliasesResponse, AllowedDenomsResponse, BurnedResponse,
+    ClaimableResponse, ConfigResponse, DestinationsResponse, ExecuteMsg, FailedTransferResponse,
+    FailedTransfersResponse, FeeExemptResponse, ForwardResponse, ForwardedBySenderResponse,
+    HistoryResponse, InstantiateMsg, LockResponse, LocksResponse, MaxAmountsResponse, MigrateMsg,
+    MinAmountsResponse, PoolExecuteMsg, QueryMsg, QueryTotalForwardedResponse,
+    ReceivedByAddressResponse, RecurringForwardsResponse, RecurringResponse, SenderTotalResponse,
+    StatsResponse, TopSendersResponse,
+};
+use crate::state::{
+    Config, ForwardRecord, Lock, Payout, PendingSwap, Recurring, SwapRoute, ACCRUED_FEES, ALIASES,
+    BURNED, CLAIMABLE, CONFIG, DESTINATIONS, FAILED_TRANSFERS, FEE_EXEMPT, FORWARD_COUNT, HISTORY,
+    HISTORY_BY_RECIPIENT, LEGACY_CONFIG, LOCKS, LOCKS_BY_RECIPIENT, LOCK_COUNT, MAX_AMOUNTS,
+    MIN_AMOUNTS, OWNERSHIP, PAUSE, PAYOUTS, PAYOUT_COUNT, PENDING_SWAP, RECIPIENT_TOTALS,
+    RECURRING, RECURRING_BY_DUE, RECURRING_COUNT, SENDERS_BY_AMOUNT, SENDER_TOTALS, STATS,
+    SWAP_ROUTE, TOKENS_SENT,
+};
+
+// fees are expressed in basis points of the forwarded amount
+const MAX_FEE_BPS: u64 = 10_000;
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+// reply id of the swap submessage, payouts are replied to under their ids starting at 1
+const SWAP_REPLY_ID: u64 = 0;
 
-/*
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:sender-receiver-code-challenge";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
-*/
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
-    _msg: InstantiateMsg,
+    info: MessageInfo,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    Ok(Response::new().add_attribute("action", "instantiate"))
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if msg.fee_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidFee {});
+    }
+    if msg.fee_bps + msg.burn_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidBurnShare {});
+    }
+
+    // Drop duplicates so RemoveDenom only ever has one entry to remove
+    let mut allowed_denoms: Vec<String> = vec![];
+    for denom in msg.denoms {
+        if !allowed_denoms.contains(&denom) {
+            allowed_denoms.push(denom);
+        }
+    }
+
+    let fixed_destination = msg
+        .forward_to_addr
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    OWNERSHIP.initialize(deps.storage, &info.sender)?;
+    let config = Config {
+        fee_bps: msg.fee_bps,
+        burn_bps: msg.burn_bps,
+        allowed_denoms,
+        enforce_destinations: false,
+        fixed_destination,
+        pull_payments: msg.pull_payments,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    for max in msg.max_amounts {
+        MAX_AMOUNTS.save(deps.storage, &max.denom, &max.amount)?;
+    }
+    for min in msg.min_amounts {
+        MIN_AMOUNTS.save(deps.storage, &min.denom, &min.amount)?;
+    }
+
+    Ok(response("instantiate"))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -33,75 +101,3807 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // Admin operations stay available while paused, forwarding does not
+    let is_forward = matches!(
+        msg,
+        ExecuteMsg::ForwardTokens { .. }
+            | ExecuteMsg::ForwardSplit { .. }
+            | ExecuteMsg::ForwardBatch { .. }
+            | ExecuteMsg::ForwardToContract { .. }
+            | ExecuteMsg::ForwardSwapped { .. }
+            | ExecuteMsg::ForwardLocked { .. }
+            | ExecuteMsg::Claim { .. }
+            | ExecuteMsg::RegisterRecurring { .. }
+            | ExecuteMsg::TriggerDue {}
+    );
+    if is_forward {
+        PAUSE.assert_not_paused(deps.storage)?;
+    }
+
     match msg {
         ExecuteMsg::ForwardTokens {
             forward_to_addr,
+            forward_to_alias,
+            amount,
+            memo,
+        } => forward_tokens(
+            deps,
+            env,
+            info,
+            forward_to_addr,
+            forward_to_alias,
+            amount,
+            memo,
+        ),
+        ExecuteMsg::ForwardSplit { recipients } => forward_split(deps, env, info, recipients),
+        ExecuteMsg::ForwardToContract { contract_addr, msg } => {
+            forward_to_contract(deps, env, info, contract_addr, msg)
+        }
+        ExecuteMsg::ForwardSwapped {
+            forward_to_addr,
+            min_receive,
+        } => forward_swapped(deps, env, info, forward_to_addr, min_receive),
+        ExecuteMsg::ForwardBatch { transfers } => forward_batch(deps, env, info, transfers),
+        ExecuteMsg::ForwardLocked { to, release_time } => {
+            forward_locked(deps, env, info, to, release_time)
+        }
+        ExecuteMsg::Claim { id } => claim(deps, env, info, id),
+        ExecuteMsg::CancelLock { id } => cancel_lock(deps, env, info, id),
+        ExecuteMsg::RegisterRecurring {
+            to,
             amount,
-        } => forward_tokens(deps, env, info, forward_to_addr, amount),
+            interval,
+        } => register_recurring(deps, env, info, to, amount, interval),
+        ExecuteMsg::FundRecurring { id } => fund_recurring(deps, info, id),
+        ExecuteMsg::CancelRecurring { id } => cancel_recurring(deps, info, id),
+        ExecuteMsg::TriggerDue {} => trigger_due(deps, env),
+        ExecuteMsg::RetryFailedTransfer { id } => retry_failed_transfer(deps, info, id),
+        ExecuteMsg::ReclaimFailedTransfer { id } => reclaim_failed_transfer(deps, info, id),
+        ExecuteMsg::SetSwapRoute { pool, ask_denom } => set_swap_route(deps, info, pool, ask_denom),
+        ExecuteMsg::RemoveSwapRoute {} => remove_swap_route(deps, info),
+        ExecuteMsg::TransferOwnership { new_owner } => {
+            Ok(OWNERSHIP.transfer(deps.storage, deps.api, &info, &new_owner)?)
+        }
+        ExecuteMsg::AcceptOwnership {} => Ok(OWNERSHIP.accept(deps.storage, &info)?),
+        ExecuteMsg::Pause {} => Ok(PAUSE.set_paused(deps.storage, &OWNERSHIP, &info, true)?),
+        ExecuteMsg::Unpause {} => Ok(PAUSE.set_paused(deps.storage, &OWNERSHIP, &info, false)?),
+        ExecuteMsg::WithdrawFees {} => withdraw_fees(deps, info),
+        ExecuteMsg::Withdraw {} => withdraw(deps, info),
+        ExecuteMsg::AddFeeExempt { addr } => add_fee_exempt(deps, info, addr),
+        ExecuteMsg::RemoveFeeExempt { addr } => remove_fee_exempt(deps, info, addr),
+        ExecuteMsg::AddDenom { denom } => add_denom(deps, info, denom),
+        ExecuteMsg::RemoveDenom { denom } => remove_denom(deps, info, denom),
+        ExecuteMsg::SetMaxAmount { denom, amount } => set_max_amount(deps, info, denom, amount),
+        ExecuteMsg::SetMinAmount { denom, amount } => set_min_amount(deps, info, denom, amount),
+        ExecuteMsg::AddDestination { addr } => add_destination(deps, info, addr),
+        ExecuteMsg::RemoveDestination { addr } => remove_destination(deps, info, addr),
+        ExecuteMsg::SetEnforceDestinations { enforce } => {
+            set_enforce_destinations(deps, info, enforce)
+        }
+        ExecuteMsg::SetPullPayments { enabled } => set_pull_payments(deps, info, enabled),
+        ExecuteMsg::SetAlias { alias, addr } => set_alias(deps, info, alias, addr),
+        ExecuteMsg::RemoveAlias { alias } => remove_alias(deps, info, alias),
     }
 }
 
 fn forward_tokens(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    forward_to_addr: String,
+    forward_to_addr: Option<String>,
+    forward_to_alias: Option<String>,
     amount: Uint128,
+    memo: Option<String>,
 ) -> Result<Response, ContractError> {
-    let validated_addr = deps.api.addr_validate(&forward_to_addr)?.to_string();
+    let config = CONFIG.load(deps.storage)?;
+    // Aliases are resolved now, so forwards follow the owner re-pointing an alias
+    let validated_addr = match (&config.fixed_destination, forward_to_addr, forward_to_alias) {
+        (Some(fixed), None, None) => fixed.clone(),
+        (Some(fixed), _, _) => {
+            return Err(ContractError::DestinationFixed {
+                addr: fixed.to_string(),
+            })
+        }
+        (None, Some(addr), None) => deps.api.addr_validate(&addr)?,
+        (None, None, Some(alias)) => ALIASES
+            .may_load(deps.storage, &alias)?
+            .ok_or(ContractError::UnknownAlias { alias })?,
+        _ => return Err(ContractError::InvalidDestination {}),
+    };
+    check_destination(deps.storage, &config, &validated_addr)?;
 
-    // Check if funds are empty before we access
-    if info.funds.is_empty() {
-        ContractError::ZeroFunds {};
+    let Coin { denom, amount } = validate_funds(deps.storage, &config, &info.funds, Some(amount))?;
+
+    // Retain the fee, burn the burn share and forward the rest
+    let (fee, burned, forwarded) =
+        record_forward(deps.storage, &config, &info.sender, &denom, amount)?;
+
+    let forwarded_coin = Coin::new(forwarded.u128(), &denom);
+    let mut event = forward_event(
+        event::FORWARD,
+        &info.sender,
+        &validated_addr,
+        &forwarded_coin,
+        fee,
+    );
+    if let Some(memo) = memo {
+        event = event.add_attribute(attr::MEMO, memo);
     }
 
+    // New response with action and
+    let res = response("forward_tokens")
+        .add_attribute(attr::FEE, fee)
+        .add_attribute("burned", burned)
+        .add_event(event)
+        .add_messages(burn_msg(vec![Coin::new(burned.u128(), &denom)]));
+
+    // Nothing is left to send when the fee and burn share take the whole amount
+    if forwarded.is_zero() {
+        return Ok(res);
+    }
+
+    // Create send msg using validated forward_to address and the funds left after the fee
+    let coins = vec![forwarded_coin];
+    let msg = payout(deps.storage, &env, &info.sender, &validated_addr, coins)?;
+
+    Ok(res.add_submessages(msg))
+}
+
+fn forward_split(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipients: Vec<(String, u64)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let Coin { denom, amount } = validate_funds(deps.storage, &config, &info.funds, None)?;
+
+    if recipients.is_empty() {
+        return Err(ContractError::NoRecipients {});
+    }
+    let mut total_weight: u128 = 0;
+    let mut validated = Vec::with_capacity(recipients.len());
+    for (addr, weight) in recipients {
+        if weight == 0 {
+            return Err(ContractError::ZeroWeight { addr });
+        }
+        let addr = deps.api.addr_validate(&addr)?;
+        check_destination(deps.storage, &config, &addr)?;
+        total_weight += u128::from(weight);
+        validated.push((addr, weight));
+    }
+
+    let (fee, burned, forwarded) =
+        record_forward(deps.storage, &config, &info.sender, &denom, amount)?;
+
+    // Each recipient gets its share rounded down, the first one also takes the dust
+    let mut shares: Vec<Uint128> = validated
+        .iter()
+        .map(|(_, weight)| forwarded.multiply_ratio(*weight, total_weight))
+        .collect();
+    let dust = forwarded - shares.iter().copied().sum::<Uint128>();
+    shares[0] += dust;
+
+    // The whole fee is reported on the first recipient's event, like the dust
+    let mut events: Vec<Event> = Vec::with_capacity(validated.len());
+    let mut msgs: Vec<SubMsg> = Vec::with_capacity(validated.len());
+    for ((addr, _), share) in validated.into_iter().zip(shares) {
+        if share.is_zero() {
+            continue;
+        }
+        let coin = Coin::new(share.u128(), denom.clone());
+        let event_fee = if events.is_empty() {
+            fee
+        } else {
+            Uint128::zero()
+        };
+        events.push(forward_event(
+            event::FORWARD,
+            &info.sender,
+            &addr,
+            &coin,
+            event_fee,
+        ));
+        msgs.extend(payout(deps.storage, &env, &info.sender, &addr, vec![coin])?);
+    }
+
+    Ok(response("forward_split")
+        .add_attribute(attr::FEE, fee)
+        .add_attribute("burned", burned)
+        .add_events(events)
+        .add_messages(burn_msg(vec![Coin::new(burned.u128(), &denom)]))
+        .add_submessages(msgs))
+}
+
+/// Checks the attached funds are a single non-zero coin in an allowed denom and within its limits,
+/// matching `expected` when the message names an amount. Every forward goes through here
+/// before touching state.
+fn validate_funds(
+    storage: &dyn Storage,
+    config: &Config,
+    funds: &[Coin],
+    expected: Option<Uint128>,
+) -> Result<Coin, ContractError> {
+    let coin = validate_coin(config, funds, expected)?;
+    check_amount_limits(storage, &coin)?;
+    Ok(coin)
+}
+
+/// Same as `validate_funds` without the limits, for deposits that are paid out in smaller forwards
+fn validate_coin(
+    config: &Config,
+    funds: &[Coin],
+    expected: Option<Uint128>,
+) -> Result<Coin, ContractError> {
+    // Check if funds are empty before we access
+    let coin = match funds {
+        [] => return Err(ContractError::ZeroFunds {}),
+        [coin] => coin,
+        // Rejected outright rather than validating the first coin and ignoring the rest
+        _ => return Err(ContractError::MoreThanOneToken {}),
+    };
+
     // If funds are zero, throw an error to the sender - technically unecessary, since the chain will not let you send a
     // message w/zero funds
-    if info.funds[0].amount == Uint128::zero() {
-        ContractError::ZeroFunds {};
+    if coin.amount.is_zero() {
+        return Err(ContractError::ZeroFunds {});
     }
 
     // Compare provided amount with funds amount
-    if info.funds[0].amount != amount {
-        ContractError::AmountMismatch {};
+    if let Some(expected) = expected {
+        if coin.amount != expected {
+            return Err(ContractError::AmountMismatch {});
+        }
+    }
+
+    // Ensure we are only sending tokens in one of the configured denoms
+    if !config.allowed_denoms.contains(&coin.denom) {
+        return Err(ContractError::DenomNotAllowed {
+            denom: coin.denom.clone(),
+        });
+    }
+
+    Ok(coin.clone())
+}
+
+fn check_amount_limits(storage: &dyn Storage, coin: &Coin) -> Result<(), ContractError> {
+    // Ensure the forward is not dust for its denom
+    if let Some(min) = MIN_AMOUNTS.may_load(storage, &coin.denom)? {
+        if coin.amount < min {
+            return Err(ContractError::BelowMinimum {
+                denom: coin.denom.clone(),
+                amount: coin.amount,
+                min,
+            });
+        }
+    }
+
+    // Ensure the forward is not larger than the cap for its denom
+    if let Some(max) = MAX_AMOUNTS.may_load(storage, &coin.denom)? {
+        if coin.amount > max {
+            return Err(ContractError::AboveMaxAmount {
+                denom: coin.denom.clone(),
+                amount: coin.amount,
+                max,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn forward_to_contract(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract_addr: String,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let contract_addr = deps.api.addr_validate(&contract_addr)?;
+    let config = CONFIG.load(deps.storage)?;
+    check_destination(deps.storage, &config, &contract_addr)?;
+
+    let Coin { denom, amount } = validate_funds(deps.storage, &config, &info.funds, None)?;
+    let (fee, burned, forwarded) =
+        record_forward(deps.storage, &config, &info.sender, &denom, amount)?;
+    let burn = burn_msg(vec![Coin::new(burned.u128(), &denom)]);
+
+    let forwarded = Coin::new(forwarded.u128(), denom);
+    let event = forward_event(
+        event::FORWARD_TO_CONTRACT,
+        &info.sender,
+        &contract_addr,
+        &forwarded,
+        fee,
+    );
+    let funds = if forwarded.amount.is_zero() {
+        vec![]
+    } else {
+        vec![forwarded]
+    };
+    record_history(deps.storage, &env, &info.sender, &contract_addr, &funds)?;
+
+    // Not wrapped like bank payouts: if the contract rejects the call, the whole forward fails
+    // and the sender keeps their funds
+    let msg = WasmMsg::Execute {
+        contract_addr: contract_addr.into_string(),
+        msg,
+        funds,
+    };
+
+    Ok(response("forward_to_contract")
+        .add_attribute(attr::FEE, fee)
+        .add_attribute("burned", burned)
+        .add_event(event)
+        .add_messages(burn)
+        .add_message(msg))
+}
+
+fn forward_swapped(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    forward_to_addr: String,
+    min_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let recipient = deps.api.addr_validate(&forward_to_addr)?;
+    let config = CONFIG.load(deps.storage)?;
+    check_destination(deps.storage, &config, &recipient)?;
+    let route = SWAP_ROUTE
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoSwapRoute {})?;
+
+    // Fees and the burn share are taken in the offered denom, and the totals count what the
+    // sender put in
+    let Coin { denom, amount } = validate_funds(deps.storage, &config, &info.funds, None)?;
+    let (fee, burned, offered) =
+        record_forward(deps.storage, &config, &info.sender, &denom, amount)?;
+    let burn = burn_msg(vec![Coin::new(burned.u128(), &denom)]);
+    if offered.is_zero() {
+        return Err(ContractError::ZeroFunds {});
+    }
+
+    let balance_before = deps
+        .querier
+        .query_balance(&env.contract.address, &route.ask_denom)?
+        .amount;
+    PENDING_SWAP.save(
+        deps.storage,
+        &PendingSwap {
+            sender: info.sender,
+            recipient,
+            offered: Coin::new(offered.u128(), &denom),
+            fee,
+            balance_before,
+        },
+    )?;
+
+    let msg = WasmMsg::Execute {
+        contract_addr: route.pool.into_string(),
+        msg: to_binary(&PoolExecuteMsg::Swap {
+            ask_denom: route.ask_denom,
+            min_receive,
+        })?,
+        funds: vec![Coin::new(offered.u128(), denom)],
+    };
+
+    // A failed swap fails the whole forward, the proceeds are forwarded in the reply
+    Ok(response("forward_swapped")
+        .add_attribute(attr::FEE, fee)
+        .add_attribute("burned", burned)
+        .add_messages(burn)
+        .add_submessage(SubMsg::reply_on_success(msg, SWAP_REPLY_ID)))
+}
+
+fn forward_batch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    transfers: Vec<(String, Coin)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if transfers.is_empty() {
+        return Err(ContractError::NoRecipients {});
+    }
+
+    // Every transfer is held to the same rules as a single forward
+    let mut required: Vec<Coin> = vec![];
+    let mut validated = Vec::with_capacity(transfers.len());
+    for (addr, coin) in transfers {
+        let addr = deps.api.addr_validate(&addr)?;
+        check_destination(deps.storage, &config, &addr)?;
+        let coin = validate_coin(&config, &[coin], None)?;
+        check_amount_limits(deps.storage, &coin)?;
+        add_coin(&mut required, &coin);
+        validated.push((addr, coin));
+    }
+
+    // The funds must pay for the transfers exactly, nothing may be left behind in the contract
+    let mut funds: Vec<Coin> = vec![];
+    for coin in info.funds.iter() {
+        add_coin(&mut funds, coin);
+    }
+    required.sort_by(|a, b| a.denom.cmp(&b.denom));
+    funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+    if required != funds {
+        return Err(ContractError::AmountMismatch {});
+    }
+
+    // Merge the forwards per recipient, in the order recipients first appear
+    let mut fees: Vec<Coin> = vec![];
+    let mut burns: Vec<Coin> = vec![];
+    let mut sends: Vec<(Addr, Vec<Coin>)> = vec![];
+    // One event per transfer, even when sends to a recipient are merged
+    let mut events: Vec<Event> = Vec::with_capacity(validated.len());
+    for (addr, coin) in validated {
+        let (fee, burned, forwarded) = record_forward(
+            deps.storage,
+            &config,
+            &info.sender,
+            &coin.denom,
+            coin.amount,
+        )?;
+        add_coin(&mut fees, &Coin::new(fee.u128(), coin.denom.clone()));
+        add_coin(&mut burns, &Coin::new(burned.u128(), coin.denom.clone()));
+        let forwarded = Coin::new(forwarded.u128(), coin.denom);
+        events.push(forward_event(
+            event::FORWARD,
+            &info.sender,
+            &addr,
+            &forwarded,
+            fee,
+        ));
+        if forwarded.amount.is_zero() {
+            continue;
+        }
+
+        match sends.iter_mut().find(|(recipient, _)| *recipient == addr) {
+            Some((_, coins)) => add_coin(coins, &forwarded),
+            None => sends.push((addr, vec![forwarded])),
+        }
+    }
+
+    let mut msgs: Vec<SubMsg> = Vec::with_capacity(sends.len());
+    for (addr, coins) in sends {
+        msgs.extend(payout(deps.storage, &env, &info.sender, &addr, coins)?);
+    }
+
+    let fees: Vec<String> = fees.iter().map(Coin::to_string).collect();
+    Ok(response("forward_batch")
+        .add_attribute(attr::FEE, fees.join(","))
+        .add_events(events)
+        .add_messages(burn_msg(burns))
+        .add_submessages(msgs))
+}
+
+fn forward_locked(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    to: String,
+    release_time: Timestamp,
+) -> Result<Response, ContractError> {
+    let recipient = deps.api.addr_validate(&to)?;
+    let config = CONFIG.load(deps.storage)?;
+    check_destination(deps.storage, &config, &recipient)?;
+
+    let coin = validate_funds(deps.storage, &config, &info.funds, None)?;
+
+    if release_time <= env.block.time {
+        return Err(ContractError::ReleaseTimeInPast {});
+    }
+
+    // Nothing is recorded as forwarded until the lock is claimed
+    let id = LOCK_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    LOCK_COUNT.save(deps.storage, &id)?;
+    let lock = Lock {
+        sender: info.sender,
+        recipient,
+        coin,
+        release_time,
+    };
+    LOCKS.save(deps.storage, id, &lock)?;
+    LOCKS_BY_RECIPIENT.save(deps.storage, (&lock.recipient, id), &Empty {})?;
+
+    Ok(response("forward_locked")
+        .add_attribute(attr::ID, id.to_string())
+        .add_attribute("release_time", release_time.to_string()))
+}
+
+fn claim(deps: DepsMut, env: Env, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let lock = LOCKS.load(deps.storage, id)?;
+    if info.sender != lock.recipient {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.time < lock.release_time {
+        return Err(ContractError::NotReleased {
+            id,
+            release_time: lock.release_time,
+        });
+    }
+    remove_lock(deps.storage, id, &lock);
+
+    // Fees are taken when the funds are actually forwarded
+    let config = CONFIG.load(deps.storage)?;
+    let Coin { denom, amount } = lock.coin;
+    let (fee, burned, forwarded) =
+        record_forward(deps.storage, &config, &lock.sender, &denom, amount)?;
+
+    let res = response("claim")
+        .add_attribute(attr::ID, id.to_string())
+        .add_attribute(attr::FEE, fee)
+        .add_attribute("burned", burned)
+        .add_messages(burn_msg(vec![Coin::new(burned.u128(), &denom)]));
+
+    let forwarded = Coin::new(forwarded.u128(), denom);
+    let res = res.add_event(forward_event(
+        event::FORWARD,
+        &lock.sender,
+        &lock.recipient,
+        &forwarded,
+        fee,
+    ));
+    if forwarded.amount.is_zero() {
+        return Ok(res);
+    }
+
+    let coins = vec![forwarded];
+    let msg = payout(deps.storage, &env, &lock.sender, &lock.recipient, coins)?;
+
+    Ok(res.add_submessages(msg))
+}
+
+fn cancel_lock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let lock = LOCKS.load(deps.storage, id)?;
+    if info.sender != lock.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.time >= lock.release_time {
+        return Err(ContractError::AlreadyReleased { id });
+    }
+    remove_lock(deps.storage, id, &lock);
+
+    // The sender gets back everything they locked
+    let msg = BankMsg::Send {
+        to_address: lock.sender.into_string(),
+        amount: vec![lock.coin],
+    };
+
+    Ok(response("cancel_lock")
+        .add_attribute(attr::ID, id.to_string())
+        .add_message(CosmosMsg::Bank(msg)))
+}
+
+fn remove_lock(storage: &mut dyn Storage, id: u64, lock: &Lock) {
+    LOCKS.remove(storage, id);
+    LOCKS_BY_RECIPIENT.remove(storage, (&lock.recipient, id));
+}
+
+fn register_recurring(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    to: String,
+    amount: Uint128,
+    interval: u64,
+) -> Result<Response, ContractError> {
+    let recipient = deps.api.addr_validate(&to)?;
+    let config = CONFIG.load(deps.storage)?;
+    check_destination(deps.storage, &config, &recipient)?;
+
+    // The prepayment can cover many forwards, so only each forward is held to the cap
+    let prepaid = validate_coin(&config, &info.funds, None)?;
+    if amount.is_zero() {
+        return Err(ContractError::ZeroFunds {});
+    }
+    let amount = Coin {
+        denom: prepaid.denom,
+        amount,
+    };
+    check_amount_limits(deps.storage, &amount)?;
+    if interval == 0 {
+        return Err(ContractError::ZeroInterval {});
+    }
+
+    let id = RECURRING_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    RECURRING_COUNT.save(deps.storage, &id)?;
+    let recurring = Recurring {
+        sender: info.sender,
+        recipient,
+        amount,
+        interval,
+        next_time: env.block.time,
+        balance: prepaid.amount,
+    };
+    RECURRING.save(deps.storage, id, &recurring)?;
+    RECURRING_BY_DUE.save(deps.storage, (recurring.next_time.nanos(), id), &Empty {})?;
+
+    Ok(response("register_recurring").add_attribute(attr::ID, id.to_string()))
+}
+
+fn fund_recurring(deps: DepsMut, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let mut recurring = RECURRING.load(deps.storage, id)?;
+
+    let coin = match info.funds.as_slice() {
+        [] => return Err(ContractError::ZeroFunds {}),
+        [coin] => coin,
+        _ => return Err(ContractError::MoreThanOneToken {}),
+    };
+    if coin.denom != recurring.amount.denom {
+        return Err(ContractError::DenomMismatch {
+            expected: recurring.amount.denom,
+        });
     }
 
-    // Ensure we are not sending tokens other than uluna
-    if info.funds[0].denom != "uluna" {
-        ContractError::DenomMismatch {};
+    recurring.balance += coin.amount;
+    RECURRING.save(deps.storage, id, &recurring)?;
+
+    Ok(response("fund_recurring")
+        .add_attribute(attr::ID, id.to_string())
+        .add_attribute("balance", recurring.balance))
+}
+
+fn cancel_recurring(deps: DepsMut, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let recurring = RECURRING.load(deps.storage, id)?;
+    if info.sender != recurring.sender {
+        return Err(ContractError::Unauthorized {});
     }
+    RECURRING.remove(deps.storage, id);
+    RECURRING_BY_DUE.remove(deps.storage, (recurring.next_time.nanos(), id));
 
-    // Ensure only 1 type of token is being sent
-    if info.funds.len() > 1 {
-        ContractError::MoreThanOneToken {};
+    let res = response("cancel_recurring").add_attribute(attr::ID, id.to_string());
+
+    if recurring.balance.is_zero() {
+        return Ok(res);
     }
 
-    // Create send msg using validated forward_to address and funds included in the request
     let msg = BankMsg::Send {
-        to_address: validated_addr,
-        amount: info.funds,
+        to_address: recurring.sender.into_string(),
+        amount: vec![Coin::new(recurring.balance.u128(), recurring.amount.denom)],
     };
 
-    // New response with action and
-    Ok(Response::new()
-        .add_attribute("action", "forward_tokens")
+    Ok(res.add_message(CosmosMsg::Bank(msg)))
+}
+
+fn trigger_due(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let max = Bound::inclusive((env.block.time.nanos(), u64::MAX));
+    let due = RECURRING_BY_DUE
+        .keys(deps.storage, None, Some(max), Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut burns: Vec<Coin> = vec![];
+    let mut events: Vec<Event> = vec![];
+    let mut msgs: Vec<SubMsg> = vec![];
+    let mut forwards = 0;
+    for (next_time, id) in due {
+        let mut recurring = RECURRING.load(deps.storage, id)?;
+        // Stays due until it is funded again or cancelled
+        if recurring.balance < recurring.amount.amount {
+            continue;
+        }
+
+        // Missed intervals are caught up one forward per trigger
+        recurring.balance -= recurring.amount.amount;
+        recurring.next_time = recurring.next_time.plus_seconds(recurring.interval);
+        RECURRING.save(deps.storage, id, &recurring)?;
+        RECURRING_BY_DUE.remove(deps.storage, (next_time, id));
+        RECURRING_BY_DUE.save(deps.storage, (recurring.next_time.nanos(), id), &Empty {})?;
+
+        let Coin { denom, amount } = recurring.amount;
+        let (fee, burned, forwarded) =
+            record_forward(deps.storage, &config, &recurring.sender, &denom, amount)?;
+        add_coin(&mut burns, &Coin::new(burned.u128(), denom.clone()));
+        let forwarded = Coin::new(forwarded.u128(), denom);
+        events.push(forward_event(
+            event::FORWARD,
+            &recurring.sender,
+            &recurring.recipient,
+            &forwarded,
+            fee,
+        ));
+        if forwarded.amount.is_zero() {
+            continue;
+        }
+
+        let coins = vec![forwarded];
+        let msg = payout(
+            deps.storage,
+            &env,
+            &recurring.sender,
+            &recurring.recipient,
+            coins,
+        )?;
+        msgs.extend(msg);
+        forwards += 1;
+    }
+
+    Ok(response("trigger_due")
+        .add_attribute("forwards", forwards.to_string())
+        .add_events(events)
+        .add_messages(burn_msg(burns))
+        .add_submessages(msgs))
+}
+
+fn retry_failed_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    // Only the sender's own failed transfers can be found under its address
+    let transfer = FAILED_TRANSFERS.load(deps.storage, (&info.sender, id))?;
+    FAILED_TRANSFERS.remove(deps.storage, (&info.sender, id));
+
+    // It is already in the history, so only a new payout is needed
+    let msg = payout_msg(deps.storage, transfer)?;
+
+    Ok(response("retry_failed_transfer")
+        .add_attribute(attr::ID, id.to_string())
+        .add_submessage(msg))
+}
+
+fn reclaim_failed_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let transfer = FAILED_TRANSFERS.load(deps.storage, (&info.sender, id))?;
+    FAILED_TRANSFERS.remove(deps.storage, (&info.sender, id));
+
+    // The recipient never got these, so they no longer count as forwarded
+    for coin in transfer.coins.iter() {
+        TOKENS_SENT.update(deps.storage, &coin.denom, |total| -> StdResult<_> {
+            Ok(total.unwrap_or_default().checked_sub(coin.amount)?)
+        })?;
+        let key = (&transfer.recipient, coin.denom.as_str());
+        RECIPIENT_TOTALS.update(deps.storage, key, |total| -> StdResult<_> {
+            Ok(total.unwrap_or_default().checked_sub(coin.amount)?)
+        })?;
+    }
+
+    let msg = BankMsg::Send {
+        to_address: transfer.sender.into_string(),
+        amount: transfer.coins,
+    };
+
+    Ok(response("reclaim_failed_transfer")
+        .add_attribute(attr::ID, id.to_string())
         .add_message(CosmosMsg::Bank(msg)))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::QueryTotalForwarded {} => to_binary(&query_total_forwarded(deps)?),
+fn set_swap_route(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool: String,
+    ask_denom: String,
+) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+
+    let route = SwapRoute {
+        pool: deps.api.addr_validate(&pool)?,
+        ask_denom,
+    };
+    SWAP_ROUTE.save(deps.storage, &route)?;
+
+    Ok(response("set_swap_route")
+        .add_attribute("pool", route.pool)
+        .add_attribute("ask_denom", route.ask_denom))
+}
+
+fn remove_swap_route(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+
+    SWAP_ROUTE.remove(deps.storage);
+
+    Ok(response("remove_swap_route"))
+}
+
+fn withdraw_fees(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+
+    let fees = accrued_fees(deps.storage)?;
+    if fees.is_empty() {
+        return Err(ContractError::NoFees {});
     }
+    for fee in fees.iter() {
+        ACCRUED_FEES.remove(deps.storage, &fee.denom);
+    }
+
+    let msg = BankMsg::Send {
+        to_address: OWNERSHIP.owner(deps.storage)?.to_string(),
+        amount: fees,
+    };
+
+    Ok(response("withdraw_fees").add_message(CosmosMsg::Bank(msg)))
 }
 
-fn query_total_forwarded(deps: Deps) -> StdResult<QueryTotalForwardedResponse> {
-    let tokens_sent = TOKENS_SENT.load(deps.storage)?;
+fn withdraw(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let amount = claimable(deps.storage, &info.sender)?;
+    if amount.is_empty() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+    for coin in amount.iter() {
+        CLAIMABLE.remove(deps.storage, (&info.sender, &coin.denom));
+    }
 
-    Ok(QueryTotalForwardedResponse {
-        amount: tokens_sent.amount,
-    })
+    // Sent directly: if the recipient can't take the funds, the withdrawal fails and the balance
+    // stays claimable
+    let msg = BankMsg::Send {
+        to_address: info.sender.to_string(),
+        amount,
+    };
+
+    Ok(response("withdraw").add_message(CosmosMsg::Bank(msg)))
 }
 
-#[cfg(test)]
-mod tests {}
+fn claimable(storage: &dyn Storage, address: &Addr) -> StdResult<Vec<Coin>> {
+    CLAIMABLE
+        .prefix(address)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin { denom, amount })
+        })
+        .collect()
+}
+
+fn add_fee_exempt(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+
+    let addr = deps.api.addr_validate(&addr)?;
+    FEE_EXEMPT.save(deps.storage, &addr, &Empty {})?;
+
+    Ok(response("add_fee_exempt").add_attribute("addr", addr))
+}
+
+fn remove_fee_exempt(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+
+    let addr = deps.api.addr_validate(&addr)?;
+    if !FEE_EXEMPT.has(deps.storage, &addr) {
+        return Err(ContractError::NotFeeExempt {
+            addr: addr.into_string(),
+        });
+    }
+    FEE_EXEMPT.remove(deps.storage, &addr);
+
+    Ok(response("remove_fee_exempt").add_attribute("addr", addr))
+}
+
+fn add_denom(deps: DepsMut, info: MessageInfo, denom: String) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    if config.allowed_denoms.contains(&denom) {
+        return Err(ContractError::DenomAlreadyAllowed { denom });
+    }
+
+    config.allowed_denoms.push(denom.clone());
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(response("add_denom").add_attribute(attr::DENOM, denom))
+}
+
+fn remove_denom(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    if !config.allowed_denoms.contains(&denom) {
+        return Err(ContractError::DenomNotAllowed { denom });
+    }
+
+    config.allowed_denoms.retain(|d| d != &denom);
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(response("remove_denom").add_attribute(attr::DENOM, denom))
+}
+
+fn set_max_amount(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+
+    let res = response("set_max_amount").add_attribute(attr::DENOM, &denom);
+    match amount {
+        Some(amount) => {
+            MAX_AMOUNTS.save(deps.storage, &denom, &amount)?;
+            Ok(res.add_attribute("max_amount", amount))
+        }
+        None => {
+            MAX_AMOUNTS.remove(deps.storage, &denom);
+            Ok(res.add_attribute("max_amount", "none"))
+        }
+    }
+}
+
+fn set_min_amount(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+
+    let res = response("set_min_amount").add_attribute(attr::DENOM, &denom);
+    match amount {
+        Some(amount) => {
+            MIN_AMOUNTS.save(deps.storage, &denom, &amount)?;
+            Ok(res.add_attribute("min_amount", amount))
+        }
+        None => {
+            MIN_AMOUNTS.remove(deps.storage, &denom);
+            Ok(res.add_attribute("min_amount", "none"))
+        }
+    }
+}
+
+fn add_destination(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+
+    let addr = deps.api.addr_validate(&addr)?;
+    DESTINATIONS.save(deps.storage, &addr, &Empty {})?;
+
+    Ok(response("add_destination").add_attribute("addr", addr))
+}
+
+fn set_alias(
+    deps: DepsMut,
+    info: MessageInfo,
+    alias: String,
+    addr: String,
+) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+
+    let addr = deps.api.addr_validate(&addr)?;
+    ALIASES.save(deps.storage, &alias, &addr)?;
+
+    Ok(response("set_alias")
+        .add_attribute("alias", alias)
+        .add_attribute("addr", addr))
+}
+
+fn remove_alias(
+    deps: DepsMut,
+    info: MessageInfo,
+    alias: String,
+) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+
+    if !ALIASES.has(deps.storage, &alias) {
+        return Err(ContractError::UnknownAlias { alias });
+    }
+    ALIASES.remove(deps.storage, &alias);
+
+    Ok(response("remove_alias").add_attribute("alias", alias))
+}
+
+fn remove_destination(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+
+    let addr = deps.api.addr_validate(&addr)?;
+    if !DESTINATIONS.has(deps.storage, &addr) {
+        return Err(ContractError::DestinationNotAllowed {
+            addr: addr.into_string(),
+        });
+    }
+    DESTINATIONS.remove(deps.storage, &addr);
+
+    Ok(response("remove_destination").add_attribute("addr", addr))
+}
+
+fn set_enforce_destinations(
+    deps: DepsMut,
+    info: MessageInfo,
+    enforce: bool,
+) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+
+    config.enforce_destinations = enforce;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(response("set_enforce_destinations").add_attribute("enforce", enforce.to_string()))
+}
+
+fn set_pull_payments(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    OWNERSHIP.assert_owner(deps.storage, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+
+    config.pull_payments = enabled;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(response("set_pull_payments").add_attribute("enabled", enabled.to_string()))
+}
+
+fn check_destination(
+    storage: &dyn Storage,
+    config: &Config,
+    addr: &Addr,
+) -> Result<(), ContractError> {
+    if config.enforce_destinations && !DESTINATIONS.has(storage, addr) {
+        return Err(ContractError::DestinationNotAllowed {
+            addr: addr.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn accrued_fees(storage: &dyn Storage) -> StdResult<Vec<Coin>> {
+    ACCRUED_FEES
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin { denom, amount })
+        })
+        .collect()
+}
+
+/// Retains the fee, unless the sender is exempt, takes the burn share and records the forward in
+/// the running totals. Returns the fee, the amount to burn and the amount left to forward.
+fn record_forward(
+    storage: &mut dyn Storage,
+    config: &Config,
+    sender: &Addr,
+    denom: &str,
+    amount: Uint128,
+) -> StdResult<(Uint128, Uint128, Uint128)> {
+    let fee = if FEE_EXEMPT.has(storage, sender) {
+        Uint128::zero()
+    } else {
+        amount.multiply_ratio(config.fee_bps, MAX_FEE_BPS)
+    };
+    let burned = amount.multiply_ratio(config.burn_bps, MAX_FEE_BPS);
+    let forwarded = amount - fee - burned;
+    if !fee.is_zero() {
+        ACCRUED_FEES.update(storage, denom, |fees| -> StdResult<_> {
+            Ok(fees.unwrap_or_default() + fee)
+        })?;
+    }
+    if !burned.is_zero() {
+        BURNED.update(storage, denom, |total| -> StdResult<_> {
+            Ok(total.unwrap_or_default() + burned)
+        })?;
+    }
+
+    // Add the forwarded amount to the running total
+    TOKENS_SENT.update(storage, denom, |total| -> StdResult<_> {
+        Ok(total.unwrap_or_default() + forwarded)
+    })?;
+    add_sender_total(storage, sender, denom, amount)?;
+
+    Ok((fee, burned, forwarded))
+}
+
+/// Event of type `ty` for a forward of `coin` to `recipient`, emitted as `wasm-<ty>` so indexers
+/// can follow forwards without parsing bank events. `fee` is in the denom the sender paid in
+fn forward_event(ty: &str, sender: &Addr, recipient: &Addr, coin: &Coin, fee: Uint128) -> Event {
+    transfer_event(ty, sender, recipient, coin).add_attribute(attr::FEE, fee)
+}
+
+/// Burns the given coins, leaving out those with nothing to burn
+fn burn_msg(coins: Vec<Coin>) -> Option<BankMsg> {
+    let amount: Vec<Coin> = coins.into_iter().filter(|c| !c.amount.is_zero()).collect();
+    if amount.is_empty() {
+        return None;
+    }
+    Some(BankMsg::Burn { amount })
+}
+
+fn record_history(
+    storage: &mut dyn Storage,
+    env: &Env,
+    sender: &Addr,
+    recipient: &Addr,
+    coins: &[Coin],
+) -> StdResult<u64> {
+    let id = FORWARD_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    FORWARD_COUNT.save(storage, &id)?;
+
+    let record = ForwardRecord {
+        sender: sender.clone(),
+        recipient: recipient.clone(),
+        coins: coins.to_vec(),
+        height: env.block.height,
+        time: env.block.time,
+    };
+    HISTORY.save(storage, id, &record)?;
+
+    let is_new_recipient = HISTORY_BY_RECIPIENT
+        .prefix(recipient)
+        .keys(storage, None, None, Order::Ascending)
+        .next()
+        .is_none();
+    if is_new_recipient {
+        let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+        stats.unique_recipients += 1;
+        STATS.save(storage, &stats)?;
+    }
+    HISTORY_BY_RECIPIENT.save(storage, (recipient, id), &Empty {})?;
+
+    for coin in coins {
+        RECIPIENT_TOTALS.update(storage, (recipient, &coin.denom), |total| -> StdResult<_> {
+            Ok(total.unwrap_or_default() + coin.amount)
+        })?;
+    }
+
+    Ok(id)
+}
+
+/// Records a forward to `recipient` in the history and returns the send for it, or credits it
+/// to the recipient's claimable balance while pull payments are on
+fn payout(
+    storage: &mut dyn Storage,
+    env: &Env,
+    sender: &Addr,
+    recipient: &Addr,
+    coins: Vec<Coin>,
+) -> StdResult<Option<SubMsg>> {
+    record_history(storage, env, sender, recipient, &coins)?;
+
+    if CONFIG.load(storage)?.pull_payments {
+        for coin in coins.iter() {
+            CLAIMABLE.update(storage, (recipient, &coin.denom), |total| -> StdResult<_> {
+                Ok(total.unwrap_or_default() + coin.amount)
+            })?;
+        }
+        return Ok(None);
+    }
+
+    let msg = payout_msg(
+        storage,
+        Payout {
+            sender: sender.clone(),
+            recipient: recipient.clone(),
+            coins,
+        },
+    )?;
+    Ok(Some(msg))
+}
+
+/// Wraps the send in a submessage, so a recipient that can't be paid only parks the funds in
+/// FAILED_TRANSFERS instead of failing the whole transaction
+fn payout_msg(storage: &mut dyn Storage, payout: Payout) -> StdResult<SubMsg> {
+    let id = PAYOUT_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    PAYOUT_COUNT.save(storage, &id)?;
+    PAYOUTS.save(storage, id, &payout)?;
+
+    let msg = BankMsg::Send {
+        to_address: payout.recipient.into_string(),
+        amount: payout.coins,
+    };
+    Ok(SubMsg::reply_always(msg, id))
+}
+
+fn add_sender_total(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    denom: &str,
+    amount: Uint128,
+) -> StdResult<()> {
+    let is_new_sender = SENDER_TOTALS
+        .prefix(sender)
+        .keys(storage, None, None, Order::Ascending)
+        .next()
+        .is_none();
+    if is_new_sender {
+        let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+        stats.unique_senders += 1;
+        STATS.save(storage, &stats)?;
+    }
+
+    let previous = SENDER_TOTALS
+        .may_load(storage, (sender, denom))?
+        .unwrap_or_default();
+    let total = previous + amount;
+    SENDER_TOTALS.save(storage, (sender, denom), &total)?;
+
+    // Move the sender to its new position in the ordering
+    SENDERS_BY_AMOUNT.remove(storage, (denom, previous.u128(), sender));
+    SENDERS_BY_AMOUNT.save(storage, (denom, total.u128(), sender), &Empty {})
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        SWAP_REPLY_ID => reply_swap(deps, env),
+        id => reply_payout(deps, id, msg.result),
+    }
+}
+
+fn reply_swap(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    // Only successful swaps are replied to
+    let swap = PENDING_SWAP.load(deps.storage)?;
+    PENDING_SWAP.remove(deps.storage);
+    let route = SWAP_ROUTE.load(deps.storage)?;
+
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &route.ask_denom)?
+        .amount;
+    let proceeds = balance.checked_sub(swap.balance_before).unwrap_or_default();
+    if proceeds.is_zero() {
+        return Err(ContractError::NoSwapProceeds {});
+    }
+
+    let proceeds = Coin::new(proceeds.u128(), route.ask_denom);
+    let event = forward_event(
+        event::FORWARD_SWAPPED,
+        &swap.sender,
+        &swap.recipient,
+        &proceeds,
+        swap.fee,
+    )
+    .add_attribute("offer_amount", swap.offered.amount)
+    .add_attribute("offer_denom", swap.offered.denom);
+    let msg = payout(
+        deps.storage,
+        &env,
+        &swap.sender,
+        &swap.recipient,
+        vec![proceeds.clone()],
+    )?;
+
+    Ok(response("forward_swap_proceeds")
+        .add_attribute("proceeds", proceeds.amount)
+        .add_event(event)
+        .add_submessages(msg))
+}
+
+fn reply_payout(deps: DepsMut, id: u64, result: SubMsgResult) -> Result<Response, ContractError> {
+    // Every other submessage is a payout, replied to under its payout id
+    let payout = PAYOUTS
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::UnknownReplyId { id })?;
+    PAYOUTS.remove(deps.storage, id);
+
+    let error = match result {
+        SubMsgResult::Ok(_) => return Ok(Response::new()),
+        SubMsgResult::Err(error) => error,
+    };
+
+    FAILED_TRANSFERS.save(deps.storage, (&payout.sender, id), &payout)?;
+
+    Ok(response("payout_failed")
+        .add_attribute(attr::ID, id.to_string())
+        .add_attribute(attr::ERROR, error))
+}
+
+// The owner moved from Config to OWNERSHIP in 0.2.0
+fn move_owner_out_of_config(storage: &mut dyn Storage) -> StdResult<()> {
+    if let Some(owner) = LEGACY_CONFIG.load(storage)?.owner {
+        OWNERSHIP.initialize(storage, &owner)?;
+        // Saving again drops the owner from the stored Config
+        let config = CONFIG.load(storage)?;
+        CONFIG.save(storage, &config)?;
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // If state structure changes in a later version, register its transform here with
+    // `.step(version, transform)`
+    let migrated = Migration::new(CONTRACT_NAME, CONTRACT_VERSION)
+        .step("0.2.0", move_owner_out_of_config)
+        .run(deps.storage)?;
+
+    Ok(migrate_response(migrated.from, migrated.to))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::QueryTotalForwarded {} => to_binary(&query_total_forwarded(deps)?),
+        QueryMsg::Stats {} => to_binary(&query_stats(deps)?),
+        QueryMsg::ForwardedBySender { sender } => {
+            to_binary(&query_forwarded_by_sender(deps, sender)?)
+        }
+        QueryMsg::ReceivedByAddress { address } => {
+            to_binary(&query_received_by_address(deps, address)?)
+        }
+        QueryMsg::TopSenders {
+            denom,
+            start_after,
+            limit,
+        } => to_binary(&query_top_senders(deps, denom, start_after, limit)?),
+        QueryMsg::Claimable { address } => to_binary(&query_claimable(deps, address)?),
+        QueryMsg::AccruedFees {} => to_binary(&query_accrued_fees(deps)?),
+        QueryMsg::Burned {} => to_binary(&query_burned(deps)?),
+        QueryMsg::Ownership {} => to_binary(&OWNERSHIP.query(deps.storage)?),
+        QueryMsg::Paused {} => to_binary(&PAUSE.query(deps.storage)?),
+        QueryMsg::FeeExempt { start_after, limit } => {
+            to_binary(&query_fee_exempt(deps, start_after, limit)?)
+        }
+        QueryMsg::AllowedDenoms {} => to_binary(&query_allowed_denoms(deps)?),
+        QueryMsg::MaxAmounts {} => to_binary(&query_max_amounts(deps)?),
+        QueryMsg::MinAmounts {} => to_binary(&query_min_amounts(deps)?),
+        QueryMsg::Destinations { start_after, limit } => {
+            to_binary(&query_destinations(deps, start_after, limit)?)
+        }
+        QueryMsg::Alias { alias } => to_binary(&query_alias(deps, alias)?),
+        QueryMsg::Aliases { start_after, limit } => {
+            to_binary(&query_aliases(deps, start_after, limit)?)
+        }
+        QueryMsg::Lock { id } => to_binary(&query_lock(deps, id)?),
+        QueryMsg::Locks {
+            recipient,
+            start_after,
+            limit,
+        } => to_binary(&query_locks(deps, recipient, start_after, limit)?),
+        QueryMsg::Recurring { id } => to_binary(&query_recurring(deps, id)?),
+        QueryMsg::RecurringForwards { start_after, limit } => {
+            to_binary(&query_recurring_forwards(deps, start_after, limit)?)
+        }
+        QueryMsg::FailedTransfers {
+            sender,
+            start_after,
+            limit,
+        } => to_binary(&query_failed_transfers(deps, sender, start_after, limit)?),
+        QueryMsg::History {
+            recipient,
+            start_after,
+            limit,
+        } => to_binary(&query_history(deps, recipient, start_after, limit)?),
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let ownership = OWNERSHIP.query(deps.storage)?;
+
+    Ok(ConfigResponse {
+        owner: ownership.owner,
+        pending_owner: ownership.pending_owner,
+        fee_bps: config.fee_bps,
+        burn_bps: config.burn_bps,
+        allowed_denoms: config.allowed_denoms,
+        max_amounts: query_max_amounts(deps)?.max_amounts,
+        min_amounts: query_min_amounts(deps)?.min_amounts,
+        enforce_destinations: config.enforce_destinations,
+        fixed_destination: config.fixed_destination,
+        pull_payments: config.pull_payments,
+        swap_route: SWAP_ROUTE.may_load(deps.storage)?,
+        paused: PAUSE.is_paused(deps.storage)?,
+    })
+}
+
+fn query_total_forwarded(deps: Deps) -> StdResult<QueryTotalForwardedResponse> {
+    let amounts = TOKENS_SENT
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin { denom, amount })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(QueryTotalForwardedResponse { amounts })
+}
+
+fn query_stats(deps: Deps) -> StdResult<StatsResponse> {
+    // Every forward gets a history id, so the last id is the number of forwards
+    let stats = STATS.may_load(deps.storage)?.unwrap_or_default();
+
+    Ok(StatsResponse {
+        total_forwards: FORWARD_COUNT.may_load(deps.storage)?.unwrap_or_default(),
+        unique_senders: stats.unique_senders,
+        unique_recipients: stats.unique_recipients,
+        volume: query_total_forwarded(deps)?.amounts,
+    })
+}
+
+fn query_forwarded_by_sender(deps: Deps, sender: String) -> StdResult<ForwardedBySenderResponse> {
+    let sender = deps.api.addr_validate(&sender)?;
+    let amounts = SENDER_TOTALS
+        .prefix(&sender)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin { denom, amount })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ForwardedBySenderResponse {
+        sender: sender.into_string(),
+        amounts,
+    })
+}
+
+fn query_received_by_address(deps: Deps, address: String) -> StdResult<ReceivedByAddressResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let amounts = RECIPIENT_TOTALS
+        .prefix(&address)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin { denom, amount })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ReceivedByAddressResponse {
+        address: address.into_string(),
+        amounts,
+    })
+}
+
+fn query_top_senders(
+    deps: Deps,
+    denom: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TopSendersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    // Resume below the last sender of the previous page
+    let start_after = match start_after {
+        Some(sender) => {
+            let sender = deps.api.addr_validate(&sender)?;
+            let amount = SENDER_TOTALS
+                .may_load(deps.storage, (&sender, &denom))?
+                .unwrap_or_default();
+            Some((amount.u128(), sender))
+        }
+        None => None,
+    };
+    let max = start_after
+        .as_ref()
+        .map(|(amount, sender)| Bound::exclusive((*amount, sender)));
+
+    let senders = SENDERS_BY_AMOUNT
+        .sub_prefix(&denom)
+        .keys(deps.storage, None, max, Order::Descending)
+        .take(limit)
+        .map(|key| {
+            let (amount, sender) = key?;
+            Ok(SenderTotalResponse {
+                sender: sender.into_string(),
+                amount: Uint128::new(amount),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(TopSendersResponse { senders })
+}
+
+fn query_claimable(deps: Deps, address: String) -> StdResult<ClaimableResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    Ok(ClaimableResponse {
+        amounts: claimable(deps.storage, &address)?,
+        address: address.into_string(),
+    })
+}
+
+fn query_accrued_fees(deps: Deps) -> StdResult<AccruedFeesResponse> {
+    Ok(AccruedFeesResponse {
+        fees: accrued_fees(deps.storage)?,
+    })
+}
+
+fn query_burned(deps: Deps) -> StdResult<BurnedResponse> {
+    let burned = BURNED
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin { denom, amount })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(BurnedResponse { burned })
+}
+
+fn query_fee_exempt(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<FeeExemptResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let min = start_after.as_ref().map(Bound::exclusive);
+
+    let addrs = FEE_EXEMPT
+        .keys(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|addr| addr.map(Addr::into_string))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(FeeExemptResponse { addrs })
+}
+
+fn query_allowed_denoms(deps: Deps) -> StdResult<AllowedDenomsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(AllowedDenomsResponse {
+        denoms: config.allowed_denoms,
+    })
+}
+
+fn query_max_amounts(deps: Deps) -> StdResult<MaxAmountsResponse> {
+    let max_amounts = MAX_AMOUNTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin { denom, amount })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(MaxAmountsResponse { max_amounts })
+}
+
+fn query_min_amounts(deps: Deps) -> StdResult<MinAmountsResponse> {
+    let min_amounts = MIN_AMOUNTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Coin { denom, amount })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(MinAmountsResponse { min_amounts })
+}
+
+fn query_destinations(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<DestinationsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let min = start_after.as_ref().map(Bound::exclusive);
+
+    let destinations = DESTINATIONS
+        .keys(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|addr| addr.map(Addr::into_string))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(DestinationsResponse {
+        enforced: config.enforce_destinations,
+        destinations,
+    })
+}
+
+fn query_alias(deps: Deps, alias: String) -> StdResult<AliasResponse> {
+    let addr = ALIASES.load(deps.storage, &alias)?;
+    Ok(AliasResponse { alias, addr })
+}
+
+fn query_aliases(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AliasesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.as_deref().map(Bound::exclusive);
+
+    let aliases = ALIASES
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (alias, addr) = item?;
+            Ok(AliasResponse { alias, addr })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AliasesResponse { aliases })
+}
+
+fn lock_response(id: u64, lock: Lock) -> LockResponse {
+    LockResponse {
+        id,
+        sender: lock.sender,
+        recipient: lock.recipient,
+        coin: lock.coin,
+        release_time: lock.release_time,
+    }
+}
+
+fn query_lock(deps: Deps, id: u64) -> StdResult<LockResponse> {
+    let lock = LOCKS.load(deps.storage, id)?;
+    Ok(lock_response(id, lock))
+}
+
+fn query_locks(
+    deps: Deps,
+    recipient: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<LocksResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(Bound::exclusive);
+
+    let ids = match recipient {
+        Some(recipient) => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            LOCKS_BY_RECIPIENT
+                .prefix(&recipient)
+                .keys(deps.storage, min, None, Order::Ascending)
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?
+        }
+        None => LOCKS
+            .keys(deps.storage, min, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?,
+    };
+
+    let locks = ids
+        .into_iter()
+        .map(|id| {
+            let lock = LOCKS.load(deps.storage, id)?;
+            Ok(lock_response(id, lock))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(LocksResponse { locks })
+}
+
+fn recurring_response(id: u64, recurring: Recurring) -> RecurringResponse {
+    RecurringResponse {
+        id,
+        sender: recurring.sender,
+        recipient: recurring.recipient,
+        amount: recurring.amount,
+        interval: recurring.interval,
+        next_time: recurring.next_time,
+        balance: recurring.balance,
+    }
+}
+
+fn query_recurring(deps: Deps, id: u64) -> StdResult<RecurringResponse> {
+    let recurring = RECURRING.load(deps.storage, id)?;
+    Ok(recurring_response(id, recurring))
+}
+
+fn query_recurring_forwards(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<RecurringForwardsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(Bound::exclusive);
+
+    let recurring = RECURRING
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (id, recurring) = item?;
+            Ok(recurring_response(id, recurring))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RecurringForwardsResponse { recurring })
+}
+
+fn query_failed_transfers(
+    deps: Deps,
+    sender: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<FailedTransfersResponse> {
+    let sender = deps.api.addr_validate(&sender)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(Bound::exclusive);
+
+    let transfers = FAILED_TRANSFERS
+        .prefix(&sender)
+        .range(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (id, transfer) = item?;
+            Ok(FailedTransferResponse {
+                id,
+                recipient: transfer.recipient,
+                coins: transfer.coins,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(FailedTransfersResponse { transfers })
+}
+
+fn query_history(
+    deps: Deps,
+    recipient: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<HistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(Bound::exclusive);
+
+    let ids = match recipient {
+        Some(recipient) => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            HISTORY_BY_RECIPIENT
+                .prefix(&recipient)
+                .keys(deps.storage, min, None, Order::Ascending)
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?
+        }
+        None => HISTORY
+            .keys(deps.storage, min, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?,
+    };
+
+    let forwards = ids
+        .into_iter()
+        .map(|id| {
+            let record = HISTORY.load(deps.storage, id)?;
+            Ok(ForwardResponse {
+                id,
+                sender: record.sender,
+                recipient: record.recipient,
+                coins: record.coins,
+                height: record.height,
+                time: record.time,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(HistoryResponse { forwards })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::{OwnershipResponse, PausedResponse};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{coins, from_binary, ReplyOn, StdError, SubMsgResponse};
+    use cosmwatch_migrate::MigrateError;
+    use cw2::get_contract_version;
+
+    fn instantiate_msg(fee_bps: u64) -> InstantiateMsg {
+        InstantiateMsg {
+            fee_bps,
+            burn_bps: 0,
+            denoms: vec!["uluna".to_string()],
+            max_amounts: vec![],
+            min_amounts: vec![],
+            forward_to_addr: None,
+            pull_payments: false,
+        }
+    }
+
+    #[test]
+    fn total_forwarded_starts_at_zero() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::QueryTotalForwarded {}).unwrap();
+        let value: QueryTotalForwardedResponse = from_binary(&res).unwrap();
+        assert!(value.amounts.is_empty());
+    }
+
+    #[test]
+    fn total_forwarded_accumulates() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        // Forward twice, from different senders to different recipients
+        let info = mock_info("sender1", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: Some("recipient1".to_string()),
+            forward_to_alias: None,
+            amount: Uint128::new(100),
+            memo: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        let info = mock_info("sender2", &coins(250, "uluna"));
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: Some("recipient2".to_string()),
+            forward_to_alias: None,
+            amount: Uint128::new(250),
+            memo: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::QueryTotalForwarded {}).unwrap();
+        let value: QueryTotalForwardedResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(350, "uluna"), value.amounts);
+    }
+
+    #[test]
+    fn forwarded_by_sender() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        // sender1 forwards 100 + 50, sender2 forwards 120, sender3 forwards 10
+        for (sender, amount) in [
+            ("sender1", 100u128),
+            ("sender2", 120),
+            ("sender1", 50),
+            ("sender3", 10),
+        ] {
+            let info = mock_info(sender, &coins(amount, "uluna"));
+            let msg = ExecuteMsg::ForwardTokens {
+                forward_to_addr: Some("recipient".to_string()),
+                forward_to_alias: None,
+                amount: Uint128::new(amount),
+                memo: None,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let msg = QueryMsg::ForwardedBySender {
+            sender: "sender1".to_string(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ForwardedBySenderResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(150, "uluna"), value.amounts);
+
+        // Unknown senders have forwarded nothing
+        let msg = QueryMsg::ForwardedBySender {
+            sender: "nobody".to_string(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ForwardedBySenderResponse = from_binary(&res).unwrap();
+        assert!(value.amounts.is_empty());
+
+        // Top senders are listed largest first, one page at a time
+        let msg = QueryMsg::TopSenders {
+            denom: "uluna".to_string(),
+            start_after: None,
+            limit: Some(2),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: TopSendersResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.senders,
+            vec![
+                SenderTotalResponse {
+                    sender: "sender1".to_string(),
+                    amount: Uint128::new(150),
+                },
+                SenderTotalResponse {
+                    sender: "sender2".to_string(),
+                    amount: Uint128::new(120),
+                },
+            ]
+        );
+
+        let msg = QueryMsg::TopSenders {
+            denom: "uluna".to_string(),
+            start_after: Some("sender2".to_string()),
+            limit: Some(2),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: TopSendersResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.senders,
+            vec![SenderTotalResponse {
+                sender: "sender3".to_string(),
+                amount: Uint128::new(10),
+            }]
+        );
+    }
+
+    #[test]
+    fn received_by_address() {
+        let mut deps = mock_dependencies();
+
+        // 1% fee
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(100)).unwrap();
+
+        let info = mock_info("sender1", &coins(1000, "uluna"));
+        let _res = execute(deps.as_mut(), mock_env(), info, forward_msg(1000)).unwrap();
+
+        let info = mock_info("sender2", &coins(500, "uluna"));
+        let msg = ExecuteMsg::ForwardSplit {
+            recipients: vec![("recipient".to_string(), 1), ("other".to_string(), 1)],
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Only what was delivered counts, not the fees
+        let msg = QueryMsg::ReceivedByAddress {
+            address: "recipient".to_string(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ReceivedByAddressResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(990 + 248, "uluna"), value.amounts);
+
+        let msg = QueryMsg::ReceivedByAddress {
+            address: "nobody".to_string(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ReceivedByAddressResponse = from_binary(&res).unwrap();
+        assert!(value.amounts.is_empty());
+    }
+
+    #[test]
+    fn forwards_emit_events() {
+        let mut deps = mock_dependencies();
+
+        // 1% fee
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(100)).unwrap();
+
+        let info = mock_info("sender", &coins(1000, "uluna"));
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: Some("recipient".to_string()),
+            forward_to_alias: None,
+            amount: Uint128::new(1000),
+            memo: Some("invoice 42".to_string()),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("forward").add_attributes(vec![
+                ("sender", "sender"),
+                ("recipient", "recipient"),
+                ("amount", "990"),
+                ("denom", "uluna"),
+                ("fee", "10"),
+                ("memo", "invoice 42"),
+            ])]
+        );
+
+        // Split forwards get one event per recipient, the fee is reported once
+        let info = mock_info("sender", &coins(1000, "uluna"));
+        let msg = ExecuteMsg::ForwardSplit {
+            recipients: vec![("alice".to_string(), 1), ("bob".to_string(), 1)],
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let fees: Vec<_> = res
+            .events
+            .iter()
+            .map(|e| {
+                (
+                    e.ty.as_str(),
+                    e.attributes[1].value.as_str(),
+                    e.attributes[4].value.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            fees,
+            vec![("forward", "alice", "10"), ("forward", "bob", "0")]
+        );
+
+        // Contract calls have their own event type
+        let info = mock_info("sender", &coins(1000, "uluna"));
+        let msg = ExecuteMsg::ForwardToContract {
+            contract_addr: "vault".to_string(),
+            msg: Binary::from(b"{}".to_vec()),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!("forward_to_contract", res.events[0].ty);
+    }
+
+    #[test]
+    fn invalid_fee() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let msg = instantiate_msg(10_001);
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFee {}));
+    }
+
+    #[test]
+    fn burn_share_is_burned() {
+        let mut deps = mock_dependencies();
+
+        // Fee and burn share can't take more than everything
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            burn_bps: 9_001,
+            ..instantiate_msg(1_000)
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidBurnShare {}));
+
+        // 1% fee, 5% burned
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            burn_bps: 500,
+            ..instantiate_msg(100)
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("sender", &coins(1000, "uluna"));
+        let res = execute(deps.as_mut(), mock_env(), info, forward_msg(1000)).unwrap();
+        let msgs: Vec<_> = res.messages.into_iter().map(|m| m.msg).collect();
+        assert_eq!(
+            msgs,
+            vec![
+                CosmosMsg::Bank(BankMsg::Burn {
+                    amount: coins(50, "uluna"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "recipient".to_string(),
+                    amount: coins(940, "uluna"),
+                }),
+            ]
+        );
+
+        // Fee exempt senders only skip the fee
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::AddFeeExempt {
+            addr: "sender".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("sender", &coins(200, "uluna"));
+        let res = execute(deps.as_mut(), mock_env(), info, forward_msg(200)).unwrap();
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: coins(190, "uluna"),
+            })
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Burned {}).unwrap();
+        let value: BurnedResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(60, "uluna"), value.burned);
+
+        // Burned tokens are not counted as forwarded
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::QueryTotalForwarded {}).unwrap();
+        let value: QueryTotalForwardedResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(1130, "uluna"), value.amounts);
+    }
+
+    #[test]
+    fn fees_are_retained_and_withdrawn() {
+        let mut deps = mock_dependencies();
+
+        // 2.5% fee
+        let info = mock_info("creator", &[]);
+        let msg = instantiate_msg(250);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("sender", &coins(1000, "uluna"));
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: Some("recipient".to_string()),
+            forward_to_alias: None,
+            amount: Uint128::new(1000),
+            memo: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: coins(975, "uluna"),
+            })
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::AccruedFees {}).unwrap();
+        let value: AccruedFeesResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(25, "uluna"), value.fees);
+
+        // Only the owner can withdraw
+        let info = mock_info("sender", &[]);
+        let err =
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::WithdrawFees {}).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("creator", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::WithdrawFees {}).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".to_string(),
+                amount: coins(25, "uluna"),
+            })
+        );
+
+        // Nothing left after withdrawing
+        let info = mock_info("creator", &[]);
+        let err =
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::WithdrawFees {}).unwrap_err();
+        assert!(matches!(err, ContractError::NoFees {}));
+    }
+
+    #[test]
+    fn disallowed_denom_is_rejected() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        let info = mock_info("sender", &coins(100, "ujuno"));
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: Some("recipient".to_string()),
+            forward_to_alias: None,
+            amount: Uint128::new(100),
+            memo: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::DenomNotAllowed { denom } if denom == "ujuno"));
+    }
+
+    #[test]
+    fn add_and_remove_denoms() {
+        let mut deps = mock_dependencies();
+
+        // Duplicates are dropped on instantiate
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            fee_bps: 0,
+            burn_bps: 0,
+            denoms: vec!["uluna".to_string(), "uluna".to_string()],
+            max_amounts: vec![],
+            min_amounts: vec![],
+            forward_to_addr: None,
+            pull_payments: false,
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::AllowedDenoms {}).unwrap();
+        let value: AllowedDenomsResponse = from_binary(&res).unwrap();
+        assert_eq!(vec!["uluna".to_string()], value.denoms);
+
+        // Only the owner can change the denoms
+        let info = mock_info("sender", &[]);
+        let msg = ExecuteMsg::AddDenom {
+            denom: "ujuno".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::AddDenom {
+            denom: "ujuno".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::AddDenom {
+            denom: "ujuno".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::DenomAlreadyAllowed { denom } if denom == "ujuno"));
+
+        // Totals are kept per denom
+        for (denom, amount) in [("uluna", 100u128), ("ujuno", 40)] {
+            let info = mock_info("sender", &coins(amount, denom));
+            let msg = ExecuteMsg::ForwardTokens {
+                forward_to_addr: Some("recipient".to_string()),
+                forward_to_alias: None,
+                amount: Uint128::new(amount),
+                memo: None,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::QueryTotalForwarded {}).unwrap();
+        let value: QueryTotalForwardedResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![Coin::new(40, "ujuno"), Coin::new(100, "uluna")],
+            value.amounts
+        );
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::RemoveDenom {
+            denom: "uluna".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::AllowedDenoms {}).unwrap();
+        let value: AllowedDenomsResponse = from_binary(&res).unwrap();
+        assert_eq!(vec!["ujuno".to_string()], value.denoms);
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: Some("recipient".to_string()),
+            forward_to_alias: None,
+            amount: Uint128::new(100),
+            memo: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::DenomNotAllowed { denom } if denom == "uluna"));
+    }
+
+    #[test]
+    fn forward_split_by_weight() {
+        let mut deps = mock_dependencies();
+
+        // 1% fee
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(100)).unwrap();
+
+        // 990 left after the fee, split 1:2:4 gives 141 + 282 + 565 with 2 dust for alice
+        let info = mock_info("sender", &coins(1000, "uluna"));
+        let msg = ExecuteMsg::ForwardSplit {
+            recipients: vec![
+                ("alice".to_string(), 1),
+                ("bob".to_string(), 2),
+                ("carol".to_string(), 4),
+            ],
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let sends: Vec<_> = res.messages.into_iter().map(|m| m.msg).collect();
+        assert_eq!(
+            sends,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "alice".to_string(),
+                    amount: coins(143, "uluna"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "bob".to_string(),
+                    amount: coins(282, "uluna"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "carol".to_string(),
+                    amount: coins(565, "uluna"),
+                }),
+            ]
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::QueryTotalForwarded {}).unwrap();
+        let value: QueryTotalForwardedResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(990, "uluna"), value.amounts);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::AccruedFees {}).unwrap();
+        let value: AccruedFeesResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(10, "uluna"), value.fees);
+    }
+
+    #[test]
+    fn forward_split_invalid_recipients() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardSplit { recipients: vec![] };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoRecipients {}));
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardSplit {
+            recipients: vec![("alice".to_string(), 1), ("bob".to_string(), 0)],
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::ZeroWeight { addr } if addr == "bob"));
+    }
+
+    #[test]
+    fn forward_to_alias() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        // Only the owner registers aliases
+        let msg = ExecuteMsg::SetAlias {
+            alias: "treasury".to_string(),
+            addr: "vault".to_string(),
+        };
+        let info = mock_info("sender", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = QueryMsg::Alias {
+            alias: "treasury".to_string(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: AliasResponse = from_binary(&res).unwrap();
+        assert_eq!(Addr::unchecked("vault"), value.addr);
+
+        let alias_msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: None,
+            forward_to_alias: Some("treasury".to_string()),
+            amount: Uint128::new(100),
+            memo: None,
+        };
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let res = execute(deps.as_mut(), mock_env(), info, alias_msg.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "vault".to_string(),
+                amount: coins(100, "uluna"),
+            })
+        );
+
+        // Re-pointing the alias changes where later forwards go
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::SetAlias {
+            alias: "treasury".to_string(),
+            addr: "new_vault".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let res = execute(deps.as_mut(), mock_env(), info, alias_msg.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "new_vault".to_string(),
+                amount: coins(100, "uluna"),
+            })
+        );
+
+        let msg = QueryMsg::Aliases {
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: AliasesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.aliases,
+            vec![AliasResponse {
+                alias: "treasury".to_string(),
+                addr: Addr::unchecked("new_vault"),
+            }]
+        );
+
+        // Exactly one of address and alias must be given
+        for (forward_to_addr, forward_to_alias) in [
+            (None, None),
+            (Some("recipient".to_string()), Some("treasury".to_string())),
+        ] {
+            let info = mock_info("sender", &coins(100, "uluna"));
+            let msg = ExecuteMsg::ForwardTokens {
+                forward_to_addr,
+                forward_to_alias,
+                amount: Uint128::new(100),
+                memo: None,
+            };
+            let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidDestination {}));
+        }
+
+        // Removed aliases no longer resolve
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::RemoveAlias {
+            alias: "treasury".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let err = execute(deps.as_mut(), mock_env(), info, alias_msg).unwrap_err();
+        assert!(matches!(err, ContractError::UnknownAlias { alias } if alias == "treasury"));
+    }
+
+    #[test]
+    fn fixed_destination() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            forward_to_addr: Some("charity".to_string()),
+            ..instantiate_msg(0)
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: None,
+            forward_to_alias: None,
+            amount: Uint128::new(100),
+            memo: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "charity".to_string(),
+                amount: coins(100, "uluna"),
+            })
+        );
+
+        // No other destination can be named
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let err = execute(deps.as_mut(), mock_env(), info, forward_msg(100)).unwrap_err();
+        assert!(matches!(err, ContractError::DestinationFixed { addr } if addr == "charity"));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(Some(Addr::unchecked("charity")), value.fixed_destination);
+    }
+
+    #[test]
+    fn pull_payments_are_withdrawn() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            pull_payments: true,
+            ..instantiate_msg(0)
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Nothing is sent, the recipient is credited instead
+        for amount in [100, 50] {
+            let info = mock_info("sender", &coins(amount, "uluna"));
+            let res = execute(deps.as_mut(), mock_env(), info, forward_msg(amount)).unwrap();
+            assert!(res.messages.is_empty());
+        }
+
+        let msg = QueryMsg::Claimable {
+            address: "recipient".to_string(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ClaimableResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(150, "uluna"), value.amounts);
+
+        let info = mock_info("recipient", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Withdraw {}).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: coins(150, "uluna"),
+            })
+        );
+
+        let info = mock_info("recipient", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Withdraw {}).unwrap_err();
+        assert!(matches!(err, ContractError::NothingToWithdraw {}));
+
+        // Only the owner can switch back to pushing payouts
+        let msg = ExecuteMsg::SetPullPayments { enabled: false };
+        let info = mock_info("sender", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let res = execute(deps.as_mut(), mock_env(), info, forward_msg(100)).unwrap();
+        assert_eq!(1, res.messages.len());
+    }
+
+    #[test]
+    fn destination_allowlist() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        // Only the owner manages the allowlist
+        let info = mock_info("sender", &[]);
+        let msg = ExecuteMsg::SetEnforceDestinations { enforce: true };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        for msg in [
+            ExecuteMsg::AddDestination {
+                addr: "charity".to_string(),
+            },
+            ExecuteMsg::SetEnforceDestinations { enforce: true },
+        ] {
+            let info = mock_info("creator", &[]);
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let msg = QueryMsg::Destinations {
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: DestinationsResponse = from_binary(&res).unwrap();
+        assert!(value.enforced);
+        assert_eq!(vec!["charity".to_string()], value.destinations);
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: Some("charity".to_string()),
+            forward_to_alias: None,
+            amount: Uint128::new(100),
+            memo: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: Some("attacker".to_string()),
+            forward_to_alias: None,
+            amount: Uint128::new(100),
+            memo: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::DestinationNotAllowed { addr } if addr == "attacker"));
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardSplit {
+            recipients: vec![("charity".to_string(), 1), ("attacker".to_string(), 1)],
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::DestinationNotAllowed { addr } if addr == "attacker"));
+
+        // Anyone can be paid again once enforcement is off
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::SetEnforceDestinations { enforce: false };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: Some("attacker".to_string()),
+            forward_to_alias: None,
+            amount: Uint128::new(100),
+            memo: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn multi_coin_sends_are_rejected() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            fee_bps: 0,
+            burn_bps: 0,
+            denoms: vec!["ujuno".to_string(), "uluna".to_string()],
+            max_amounts: vec![],
+            min_amounts: vec![],
+            forward_to_addr: None,
+            pull_payments: false,
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Both coins are allowed and the first matches `amount`, but the send is still rejected
+        let funds = vec![Coin::new(100, "uluna"), Coin::new(50, "ujuno")];
+        let info = mock_info("sender", &funds);
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: Some("recipient".to_string()),
+            forward_to_alias: None,
+            amount: Uint128::new(100),
+            memo: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::MoreThanOneToken {}));
+
+        let info = mock_info("sender", &funds);
+        let msg = ExecuteMsg::ForwardSplit {
+            recipients: vec![("alice".to_string(), 1)],
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::MoreThanOneToken {}));
+
+        // Nothing was recorded
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::QueryTotalForwarded {}).unwrap();
+        let value: QueryTotalForwardedResponse = from_binary(&res).unwrap();
+        assert!(value.amounts.is_empty());
+    }
+
+    fn forward_msg(amount: u128) -> ExecuteMsg {
+        ExecuteMsg::ForwardTokens {
+            forward_to_addr: Some("recipient".to_string()),
+            forward_to_alias: None,
+            amount: Uint128::new(amount),
+            memo: None,
+        }
+    }
+
+    #[test]
+    fn rejects_missing_funds() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        let info = mock_info("sender", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, forward_msg(100)).unwrap_err();
+        assert!(matches!(err, ContractError::ZeroFunds {}));
+    }
+
+    #[test]
+    fn rejects_zero_funds() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        let info = mock_info("sender", &coins(0, "uluna"));
+        let err = execute(deps.as_mut(), mock_env(), info, forward_msg(0)).unwrap_err();
+        assert!(matches!(err, ContractError::ZeroFunds {}));
+    }
+
+    #[test]
+    fn rejects_amount_mismatch() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let err = execute(deps.as_mut(), mock_env(), info, forward_msg(99)).unwrap_err();
+        assert!(matches!(err, ContractError::AmountMismatch {}));
+    }
+
+    #[test]
+    fn rejects_invalid_recipient() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr: Some("".to_string()),
+            forward_to_alias: None,
+            amount: Uint128::new(100),
+            memo: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Std(_)));
+    }
+
+    #[test]
+    fn history_is_paginated_and_filtered() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        for (recipient, amount) in [("alice", 10u128), ("bob", 20), ("alice", 30)] {
+            let info = mock_info("sender", &coins(amount, "uluna"));
+            let msg = ExecuteMsg::ForwardTokens {
+                forward_to_addr: Some(recipient.to_string()),
+                forward_to_alias: None,
+                amount: Uint128::new(amount),
+                memo: None,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let msg = QueryMsg::History {
+            recipient: None,
+            start_after: None,
+            limit: Some(2),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: HistoryResponse = from_binary(&res).unwrap();
+        let env = mock_env();
+        assert_eq!(
+            value.forwards,
+            vec![
+                ForwardResponse {
+                    id: 1,
+                    sender: Addr::unchecked("sender"),
+                    recipient: Addr::unchecked("alice"),
+                    coins: coins(10, "uluna"),
+                    height: env.block.height,
+                    time: env.block.time,
+                },
+                ForwardResponse {
+                    id: 2,
+                    sender: Addr::unchecked("sender"),
+                    recipient: Addr::unchecked("bob"),
+                    coins: coins(20, "uluna"),
+                    height: env.block.height,
+                    time: env.block.time,
+                },
+            ]
+        );
+
+        let msg = QueryMsg::History {
+            recipient: None,
+            start_after: Some(2),
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: HistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![3],
+            value.forwards.iter().map(|f| f.id).collect::<Vec<_>>()
+        );
+
+        let msg = QueryMsg::History {
+            recipient: Some("alice".to_string()),
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: HistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![1, 3],
+            value.forwards.iter().map(|f| f.id).collect::<Vec<_>>()
+        );
+
+        let msg = QueryMsg::History {
+            recipient: Some("alice".to_string()),
+            start_after: Some(1),
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: HistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![3],
+            value.forwards.iter().map(|f| f.id).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn max_amount_per_forward() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            fee_bps: 0,
+            burn_bps: 0,
+            denoms: vec!["uluna".to_string()],
+            max_amounts: coins(100, "uluna"),
+            min_amounts: vec![],
+            forward_to_addr: None,
+            pull_payments: false,
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let _res = execute(deps.as_mut(), mock_env(), info, forward_msg(100)).unwrap();
+
+        let info = mock_info("sender", &coins(101, "uluna"));
+        let err = execute(deps.as_mut(), mock_env(), info, forward_msg(101)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::AboveMaxAmount { denom, amount, max }
+                if denom == "uluna" && amount == Uint128::new(101) && max == Uint128::new(100)
+        ));
+
+        // Only the owner can change the cap
+        let msg = ExecuteMsg::SetMaxAmount {
+            denom: "uluna".to_string(),
+            amount: Some(Uint128::new(500)),
+        };
+        let info = mock_info("sender", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::MaxAmounts {}).unwrap();
+        let value: MaxAmountsResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(500, "uluna"), value.max_amounts);
+
+        let info = mock_info("sender", &coins(101, "uluna"));
+        let _res = execute(deps.as_mut(), mock_env(), info, forward_msg(101)).unwrap();
+
+        // Removing the cap lets any amount through
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::SetMaxAmount {
+            denom: "uluna".to_string(),
+            amount: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("sender", &coins(10_000, "uluna"));
+        let _res = execute(deps.as_mut(), mock_env(), info, forward_msg(10_000)).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::MaxAmounts {}).unwrap();
+        let value: MaxAmountsResponse = from_binary(&res).unwrap();
+        assert!(value.max_amounts.is_empty());
+    }
+
+    #[test]
+    fn min_amount_per_forward() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            fee_bps: 0,
+            burn_bps: 0,
+            denoms: vec!["uluna".to_string()],
+            max_amounts: vec![],
+            min_amounts: coins(100, "uluna"),
+            forward_to_addr: None,
+            pull_payments: false,
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let _res = execute(deps.as_mut(), mock_env(), info, forward_msg(100)).unwrap();
+
+        let info = mock_info("sender", &coins(99, "uluna"));
+        let err = execute(deps.as_mut(), mock_env(), info, forward_msg(99)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::BelowMinimum { denom, amount, min }
+                if denom == "uluna" && amount == Uint128::new(99) && min == Uint128::new(100)
+        ));
+
+        // Only the owner can change the minimum
+        let msg = ExecuteMsg::SetMinAmount {
+            denom: "uluna".to_string(),
+            amount: Some(Uint128::new(10)),
+        };
+        let info = mock_info("sender", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::MinAmounts {}).unwrap();
+        let value: MinAmountsResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(10, "uluna"), value.min_amounts);
+
+        let info = mock_info("sender", &coins(99, "uluna"));
+        let _res = execute(deps.as_mut(), mock_env(), info, forward_msg(99)).unwrap();
+
+        // Removing the minimum lets any amount through
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::SetMinAmount {
+            denom: "uluna".to_string(),
+            amount: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("sender", &coins(1, "uluna"));
+        let _res = execute(deps.as_mut(), mock_env(), info, forward_msg(1)).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::MinAmounts {}).unwrap();
+        let value: MinAmountsResponse = from_binary(&res).unwrap();
+        assert!(value.min_amounts.is_empty());
+    }
+
+    #[test]
+    fn pause_blocks_forwarding() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        // Only the owner can pause
+        let info = mock_info("sender", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Pause {}).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Pause {}).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Paused {}).unwrap();
+        let value: PausedResponse = from_binary(&res).unwrap();
+        assert!(value.paused);
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let err = execute(deps.as_mut(), mock_env(), info, forward_msg(100)).unwrap_err();
+        assert!(matches!(err, ContractError::ContractPaused {}));
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardSplit {
+            recipients: vec![("alice".to_string(), 1)],
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::ContractPaused {}));
+
+        // Admin operations still work while paused
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::AddDenom {
+            denom: "ujuno".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Unpause {}).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let _res = execute(deps.as_mut(), mock_env(), info, forward_msg(100)).unwrap();
+    }
+
+    #[test]
+    fn locked_forward_is_claimed_after_release() {
+        let mut deps = mock_dependencies();
+
+        // 1% fee, taken on claim
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(100)).unwrap();
+
+        let mut env = mock_env();
+        let release_time = env.block.time.plus_seconds(60);
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardLocked {
+            to: "alice".to_string(),
+            release_time: env.block.time,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::ReleaseTimeInPast {}));
+
+        let msg = ExecuteMsg::ForwardLocked {
+            to: "alice".to_string(),
+            release_time,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert!(res.messages.is_empty());
+
+        let msg = QueryMsg::Locks {
+            recipient: Some("alice".to_string()),
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let value: LocksResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.locks,
+            vec![LockResponse {
+                id: 1,
+                sender: Addr::unchecked("sender"),
+                recipient: Addr::unchecked("alice"),
+                coin: Coin::new(100, "uluna"),
+                release_time,
+            }]
+        );
+
+        // Too early to claim
+        let info = mock_info("alice", &[]);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::Claim { id: 1 },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotReleased { id: 1, .. }));
+
+        env.block.time = release_time;
+
+        // Only the recipient can claim
+        let info = mock_info("bob", &[]);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::Claim { id: 1 },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // Nor can the sender cancel once released
+        let info = mock_info("sender", &[]);
+        let msg = ExecuteMsg::CancelLock { id: 1 };
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyReleased { id: 1 }));
+
+        let info = mock_info("alice", &[]);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::Claim { id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(99, "uluna"),
+            })
+        );
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::QueryTotalForwarded {}).unwrap();
+        let value: QueryTotalForwardedResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(99, "uluna"), value.amounts);
+
+        // The lock is gone once claimed
+        let err = query(deps.as_ref(), env.clone(), QueryMsg::Lock { id: 1 }).unwrap_err();
+        assert!(matches!(err, StdError::NotFound { .. }));
+        let info = mock_info("alice", &[]);
+        let err = execute(deps.as_mut(), env, info, ExecuteMsg::Claim { id: 1 }).unwrap_err();
+        assert!(matches!(err, ContractError::Std(StdError::NotFound { .. })));
+    }
+
+    #[test]
+    fn locked_forward_is_cancelled_before_release() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(100)).unwrap();
+
+        let env = mock_env();
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardLocked {
+            to: "alice".to_string(),
+            release_time: env.block.time.plus_seconds(60),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Only the sender can cancel
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::CancelLock { id: 1 };
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // The full amount is returned, no fee is taken
+        let info = mock_info("sender", &[]);
+        let msg = ExecuteMsg::CancelLock { id: 1 };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "sender".to_string(),
+                amount: coins(100, "uluna"),
+            })
+        );
+
+        let msg = QueryMsg::Locks {
+            recipient: None,
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), env, msg).unwrap();
+        let value: LocksResponse = from_binary(&res).unwrap();
+        assert!(value.locks.is_empty());
+    }
+
+    #[test]
+    fn recurring_forwards() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        let mut env = mock_env();
+        let start = env.block.time;
+
+        let info = mock_info("sender", &coins(250, "uluna"));
+        let msg = ExecuteMsg::RegisterRecurring {
+            to: "alice".to_string(),
+            amount: Uint128::new(100),
+            interval: 0,
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::ZeroInterval {}));
+
+        let msg = ExecuteMsg::RegisterRecurring {
+            to: "alice".to_string(),
+            amount: Uint128::new(100),
+            interval: 60,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // The first forward is due straight away
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::TriggerDue {}).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(100, "uluna"),
+            })
+        );
+
+        // Nothing more until the interval has passed
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::TriggerDue {}).unwrap();
+        assert!(res.messages.is_empty());
+
+        env.block.time = start.plus_seconds(60);
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::TriggerDue {}).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        // 50 left is not enough for another forward
+        env.block.time = start.plus_seconds(120);
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::TriggerDue {}).unwrap();
+        assert!(res.messages.is_empty());
+
+        let info = mock_info("sender", &coins(10, "ujuno"));
+        let msg = ExecuteMsg::FundRecurring { id: 1 };
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::DenomMismatch { expected } if expected == "uluna"));
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::FundRecurring { id: 1 };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::TriggerDue {}).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Recurring { id: 1 }).unwrap();
+        let value: RecurringResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::new(50), value.balance);
+        assert_eq!(start.plus_seconds(180), value.next_time);
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::QueryTotalForwarded {}).unwrap();
+        let value: QueryTotalForwardedResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(300, "uluna"), value.amounts);
+
+        // Only the sender can cancel, and gets the rest of the balance back
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::CancelRecurring { id: 1 };
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("sender", &[]);
+        let msg = ExecuteMsg::CancelRecurring { id: 1 };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "sender".to_string(),
+                amount: coins(50, "uluna"),
+            })
+        );
+
+        let msg = QueryMsg::RecurringForwards {
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), env, msg).unwrap();
+        let value: RecurringForwardsResponse = from_binary(&res).unwrap();
+        assert!(value.recurring.is_empty());
+    }
+
+    #[test]
+    fn two_step_ownership_transfer() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::AcceptOwnership {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NoPendingOwner {}));
+
+        // Only the owner can offer ownership
+        let msg = ExecuteMsg::TransferOwnership {
+            new_owner: "new_owner".to_string(),
+        };
+        let info = mock_info("anyone", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // The creator stays in charge until the offer is accepted
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Ownership {}).unwrap();
+        let value: OwnershipResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value,
+            OwnershipResponse {
+                owner: Addr::unchecked("creator"),
+                pending_owner: Some(Addr::unchecked("new_owner")),
+            }
+        );
+
+        let info = mock_info("anyone", &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::AcceptOwnership {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("new_owner", &[]);
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::AcceptOwnership {},
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Ownership {}).unwrap();
+        let value: OwnershipResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value,
+            OwnershipResponse {
+                owner: Addr::unchecked("new_owner"),
+                pending_owner: None,
+            }
+        );
+
+        // Admin operations follow the new owner
+        let info = mock_info("creator", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Pause {}).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("new_owner", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Pause {}).unwrap();
+    }
+
+    #[test]
+    fn failed_payouts_are_parked() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let res = execute(deps.as_mut(), mock_env(), info, forward_msg(100)).unwrap();
+        assert_eq!(ReplyOn::Always, res.messages[0].reply_on);
+        let id = res.messages[0].id;
+
+        let failed = Reply {
+            id,
+            result: SubMsgResult::Err("cannot receive funds".to_string()),
+        };
+        let _res = reply(deps.as_mut(), mock_env(), failed.clone()).unwrap();
+
+        // The reply for a payout only comes back once
+        let err = reply(deps.as_mut(), mock_env(), failed).unwrap_err();
+        assert!(matches!(err, ContractError::UnknownReplyId { id: unknown } if unknown == id));
+
+        let msg = QueryMsg::FailedTransfers {
+            sender: "sender".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg.clone()).unwrap();
+        let value: FailedTransfersResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.transfers,
+            vec![FailedTransferResponse {
+                id,
+                recipient: Addr::unchecked("recipient"),
+                coins: coins(100, "uluna"),
+            }]
+        );
+
+        // Nobody else can touch the failed transfer
+        let info = mock_info("recipient", &[]);
+        let msg_retry = ExecuteMsg::RetryFailedTransfer { id };
+        let err = execute(deps.as_mut(), mock_env(), info, msg_retry.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Std(StdError::NotFound { .. })));
+
+        // A retry is sent as a new payout, and succeeds this time
+        let info = mock_info("sender", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg_retry).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: coins(100, "uluna"),
+            })
+        );
+        let succeeded = Reply {
+            id: res.messages[0].id,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        let _res = reply(deps.as_mut(), mock_env(), succeeded).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: FailedTransfersResponse = from_binary(&res).unwrap();
+        assert!(value.transfers.is_empty());
+    }
+
+    #[test]
+    fn failed_payouts_can_be_reclaimed() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let res = execute(deps.as_mut(), mock_env(), info, forward_msg(100)).unwrap();
+        let id = res.messages[0].id;
+        let failed = Reply {
+            id,
+            result: SubMsgResult::Err("cannot receive funds".to_string()),
+        };
+        let _res = reply(deps.as_mut(), mock_env(), failed).unwrap();
+
+        let info = mock_info("sender", &[]);
+        let msg = ExecuteMsg::ReclaimFailedTransfer { id };
+        let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "sender".to_string(),
+                amount: coins(100, "uluna"),
+            })
+        );
+
+        // Reclaimed funds were never forwarded
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::QueryTotalForwarded {}).unwrap();
+        let value: QueryTotalForwardedResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(0, "uluna"), value.amounts);
+
+        let msg = QueryMsg::ReceivedByAddress {
+            address: "recipient".to_string(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ReceivedByAddressResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(0, "uluna"), value.amounts);
+
+        let msg = ExecuteMsg::ReclaimFailedTransfer { id };
+        let info = mock_info("sender", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Std(StdError::NotFound { .. })));
+    }
+
+    #[test]
+    fn forward_batch_merges_sends_per_recipient() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            fee_bps: 100,
+            burn_bps: 0,
+            denoms: vec!["ujuno".to_string(), "uluna".to_string()],
+            max_amounts: vec![],
+            min_amounts: vec![],
+            forward_to_addr: None,
+            pull_payments: false,
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let transfers = vec![
+            ("alice".to_string(), Coin::new(100, "uluna")),
+            ("bob".to_string(), Coin::new(200, "uluna")),
+            ("alice".to_string(), Coin::new(300, "uluna")),
+            ("alice".to_string(), Coin::new(1000, "ujuno")),
+        ];
+
+        // Too little, too much and missing or extra denoms are all rejected
+        for funds in [
+            vec![Coin::new(500, "uluna"), Coin::new(1000, "ujuno")],
+            vec![Coin::new(700, "uluna"), Coin::new(1000, "ujuno")],
+            vec![Coin::new(600, "uluna")],
+            vec![
+                Coin::new(600, "uluna"),
+                Coin::new(1000, "ujuno"),
+                Coin::new(1, "uatom"),
+            ],
+        ] {
+            let info = mock_info("sender", &funds);
+            let msg = ExecuteMsg::ForwardBatch {
+                transfers: transfers.clone(),
+            };
+            let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert!(matches!(err, ContractError::AmountMismatch {}));
+        }
+
+        let funds = vec![Coin::new(1000, "ujuno"), Coin::new(600, "uluna")];
+        let info = mock_info("sender", &funds);
+        let msg = ExecuteMsg::ForwardBatch { transfers };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let sends: Vec<_> = res.messages.into_iter().map(|m| m.msg).collect();
+        assert_eq!(
+            sends,
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "alice".to_string(),
+                    amount: vec![Coin::new(396, "uluna"), Coin::new(990, "ujuno")],
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "bob".to_string(),
+                    amount: coins(198, "uluna"),
+                }),
+            ]
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::AccruedFees {}).unwrap();
+        let value: AccruedFeesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![Coin::new(10, "ujuno"), Coin::new(6, "uluna")],
+            value.fees
+        );
+    }
+
+    #[test]
+    fn forward_batch_invalid_transfers() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardBatch { transfers: vec![] };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoRecipients {}));
+
+        let info = mock_info("sender", &coins(100, "ujuno"));
+        let msg = ExecuteMsg::ForwardBatch {
+            transfers: vec![("alice".to_string(), Coin::new(100, "ujuno"))],
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::DenomNotAllowed { denom } if denom == "ujuno"));
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let msg = ExecuteMsg::ForwardBatch {
+            transfers: vec![
+                ("alice".to_string(), Coin::new(100, "uluna")),
+                ("bob".to_string(), Coin::new(0, "uluna")),
+            ],
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::ZeroFunds {}));
+    }
+
+    #[test]
+    fn fee_exempt_senders() {
+        let mut deps = mock_dependencies();
+
+        // 10% fee
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(1000)).unwrap();
+
+        // Only the owner manages the exemptions
+        let msg = ExecuteMsg::AddFeeExempt {
+            addr: "treasury".to_string(),
+        };
+        let info = mock_info("treasury", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = QueryMsg::FeeExempt {
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: FeeExemptResponse = from_binary(&res).unwrap();
+        assert_eq!(vec!["treasury".to_string()], value.addrs);
+
+        // The treasury forwards everything, others still pay the fee
+        let info = mock_info("treasury", &coins(100, "uluna"));
+        let res = execute(deps.as_mut(), mock_env(), info, forward_msg(100)).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: coins(100, "uluna"),
+            })
+        );
+
+        let info = mock_info("sender", &coins(100, "uluna"));
+        let res = execute(deps.as_mut(), mock_env(), info, forward_msg(100)).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: coins(90, "uluna"),
+            })
+        );
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::RemoveFeeExempt {
+            addr: "treasury".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::NotFeeExempt { addr } if addr == "treasury"));
+
+        let info = mock_info("treasury", &coins(100, "uluna"));
+        let res = execute(deps.as_mut(), mock_env(), info, forward_msg(100)).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: coins(90, "uluna"),
+            })
+        );
+    }
+
+    #[test]
+    fn config_query() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            fee_bps: 250,
+            burn_bps: 0,
+            denoms: vec!["uluna".to_string()],
+            max_amounts: coins(1000, "uluna"),
+            min_amounts: vec![],
+            forward_to_addr: None,
+            pull_payments: false,
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Pause {}).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value,
+            ConfigResponse {
+                owner: Addr::unchecked("creator"),
+                pending_owner: None,
+                fee_bps: 250,
+                burn_bps: 0,
+                allowed_denoms: vec!["uluna".to_string()],
+                max_amounts: coins(1000, "uluna"),
+                min_amounts: vec![],
+                enforce_destinations: false,
+                fixed_destination: None,
+                pull_payments: false,
+                swap_route: None,
+                paused: true,
+            }
+        );
+    }
+
+    #[test]
+    fn instantiate_sets_contract_version() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(CONTRACT_NAME, version.contract);
+        assert_eq!(CONTRACT_VERSION, version.version);
+    }
+
+    #[test]
+    fn migrate_from_older_version() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.1").unwrap();
+
+        let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(CONTRACT_VERSION, version.version);
+    }
+
+    #[test]
+    fn migrate_moves_owner_out_of_config() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        // lay the state out as 0.1.0 stored it, with the owner inside Config
+        let config = deps.storage.get(b"config").unwrap();
+        let legacy = [br#"{"owner":"old_owner","#.as_slice(), &config[1..]].concat();
+        deps.storage.set(b"config", &legacy);
+        deps.storage.remove(b"owner");
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.1.0").unwrap();
+
+        let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(
+            OWNERSHIP.owner(deps.as_ref().storage).unwrap(),
+            Addr::unchecked("old_owner")
+        );
+        assert_eq!(
+            LEGACY_CONFIG.load(deps.as_ref().storage).unwrap().owner,
+            None
+        );
+
+        // the migrated owner administers the forwarder
+        let info = mock_info("old_owner", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Pause {}).unwrap();
+        assert!(PAUSE.is_paused(deps.as_ref().storage).unwrap());
+    }
+
+    #[test]
+    fn migrate_rejects_downgrade_and_other_contracts() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(0)).unwrap();
+
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(
+            matches!(err, ContractError::Migrate(MigrateError::CannotDowngrade { stored, .. }) if stored == "99.0.0")
+        );
+
+        set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.0.1").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert!(
+            matches!(err, ContractError::Migrate(MigrateError::InvalidContract { contract }) if contract == "crates.io:cw20-base")
+        );
+    }
+
+    #[test]
+    fn forward_to_contract() {
+        let mut deps = mock_dependencies();
+
+        // 1% fee
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(100)).unwrap();
+
+        let payload = to_binary(&"create").unwrap();
+        let info = mock_info("sender", &coins(1000, "uluna"));
+        let msg = ExecuteMsg::ForwardToContract {
+            contract_addr: "escrow".to_string(),
+            msg: payload.clone(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "escrow".to_string(),
+                msg: payload,
+                funds: coins(990, "uluna"),
+            })
+        );
+        assert_eq!(ReplyOn::Never, res.messages[0].reply_on);
+
+        let msg = QueryMsg::History {
+            recipient: Some("escrow".to_string()),
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: HistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(990, "uluna"), value.forwards[0].coins);
+    }
+
+    #[test]
+    fn forward_swapped() {
+        let mut deps = mock_dependencies();
+
+        // 1% fee, taken before the swap
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg(100)).unwrap();
+
+        let msg = ExecuteMsg::ForwardSwapped {
+            forward_to_addr: "recipient".to_string(),
+            min_receive: Some(Uint128::new(450)),
+        };
+        let info = mock_info("sender", &coins(1000, "uluna"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NoSwapRoute {}));
+
+        let info = mock_info("creator", &[]);
+        let route = ExecuteMsg::SetSwapRoute {
+            pool: "pool".to_string(),
+            ask_denom: "uusdc".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, route).unwrap();
+
+        // The contract already holds some of the ask denom, only the increase is forwarded
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(30, "uusdc"));
+
+        let info = mock_info("sender", &coins(1000, "uluna"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "pool".to_string(),
+                msg: to_binary(&PoolExecuteMsg::Swap {
+                    ask_denom: "uusdc".to_string(),
+                    min_receive: Some(Uint128::new(450)),
+                })
+                .unwrap(),
+                funds: coins(990, "uluna"),
+            })
+        );
+        assert_eq!(SWAP_REPLY_ID, res.messages[0].id);
+        assert_eq!(ReplyOn::Success, res.messages[0].reply_on);
+
+        // The pool pays out 495uusdc
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(525, "uusdc"));
+        let swapped = Reply {
+            id: SWAP_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        let res = reply(deps.as_mut(), mock_env(), swapped).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: coins(495, "uusdc"),
+            })
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::AccruedFees {}).unwrap();
+        let value: AccruedFeesResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(10, "uluna"), value.fees);
+    }
+
+    #[test]
+    fn stats() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            fee_bps: 0,
+            burn_bps: 0,
+            denoms: vec!["ujuno".to_string(), "uluna".to_string()],
+            max_amounts: vec![],
+            min_amounts: vec![],
+            forward_to_addr: None,
+            pull_payments: false,
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value,
+            StatsResponse {
+                total_forwards: 0,
+                unique_senders: 0,
+                unique_recipients: 0,
+                volume: vec![],
+            }
+        );
+
+        for (sender, recipient, amount, denom) in [
+            ("sender1", "alice", 100u128, "uluna"),
+            ("sender1", "bob", 10, "ujuno"),
+            ("sender2", "alice", 50, "uluna"),
+        ] {
+            let info = mock_info(sender, &coins(amount, denom));
+            let msg = ExecuteMsg::ForwardTokens {
+                forward_to_addr: Some(recipient.to_string()),
+                forward_to_alias: None,
+                amount: Uint128::new(amount),
+                memo: None,
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value,
+            StatsResponse {
+                total_forwards: 3,
+                unique_senders: 2,
+                unique_recipients: 2,
+                volume: vec![Coin::new(10, "ujuno"), Coin::new(150, "uluna")],
+            }
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Timestamp, Uint128};
+use cosmwatch_access::AccessError;
+use cosmwatch_migrate::MigrateError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,15 +11,109 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("{0}")]
+    Migrate(#[from] MigrateError),
+
+    #[error("Forwarding is paused")]
+    ContractPaused {},
+
     #[error("Cannot send zero funds")]
     ZeroFunds {},
 
-    #[error("Denom mismatch. Expected 'uluna'")]
-    DenomMismatch {},
+    #[error("Denom '{denom}' is not accepted")]
+    DenomNotAllowed { denom: String },
+
+    #[error("Denom '{denom}' is already accepted")]
+    DenomAlreadyAllowed { denom: String },
 
     #[error("Amount mismatch. Please check the amount sent and try again.")]
     AmountMismatch {},
 
+    #[error("Cannot forward {amount}{denom} at once, the maximum is {max}{denom}")]
+    AboveMaxAmount {
+        denom: String,
+        amount: Uint128,
+        max: Uint128,
+    },
+
+    #[error("Cannot forward {amount}{denom}, the minimum is {min}{denom}")]
+    BelowMinimum {
+        denom: String,
+        amount: Uint128,
+        min: Uint128,
+    },
+
     #[error("More than one token provided")]
     MoreThanOneToken {},
+
+    #[error("Fee must be at most 10000 basis points")]
+    InvalidFee {},
+
+    #[error("Fee and burn share together must be at most 10000 basis points")]
+    InvalidBurnShare {},
+
+    #[error("No fees to withdraw")]
+    NoFees {},
+
+    #[error("At least one recipient is required")]
+    NoRecipients {},
+
+    #[error("Recipient '{addr}' has a zero weight")]
+    ZeroWeight { addr: String },
+
+    #[error("Address '{addr}' is not an allowed destination")]
+    DestinationNotAllowed { addr: String },
+
+    #[error("Exactly one of forward_to_addr and forward_to_alias must be set")]
+    InvalidDestination {},
+
+    #[error("Forwards always go to {addr}, no destination can be given")]
+    DestinationFixed { addr: String },
+
+    #[error("Nothing to withdraw")]
+    NothingToWithdraw {},
+
+    #[error("No destination is registered under alias '{alias}'")]
+    UnknownAlias { alias: String },
+
+    #[error("Release time must be in the future")]
+    ReleaseTimeInPast {},
+
+    #[error("Lock {id} is not released until {release_time}")]
+    NotReleased { id: u64, release_time: Timestamp },
+
+    #[error("Lock {id} has already been released")]
+    AlreadyReleased { id: u64 },
+
+    #[error("Interval must be at least one second")]
+    ZeroInterval {},
+
+    #[error("Funds must be sent in {expected}")]
+    DenomMismatch { expected: String },
+
+    #[error("No ownership transfer is pending")]
+    NoPendingOwner {},
+
+    #[error("Unknown reply id: {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("Address '{addr}' is not fee exempt")]
+    NotFeeExempt { addr: String },
+
+    #[error("No swap route is configured")]
+    NoSwapRoute {},
+
+    #[error("The swap paid out nothing")]
+    NoSwapProceeds {},
+}
+
+impl From<AccessError> for ContractError {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::Std(err) => ContractError::Std(err),
+            AccessError::Unauthorized {} => ContractError::Unauthorized {},
+            AccessError::ContractPaused {} => ContractError::ContractPaused {},
+            AccessError::NoPendingOwner {} => ContractError::NoPendingOwner {},
+        }
+    }
 }
@@ -0,0 +1,80 @@
+use cw_orch::interface;
+use cw_orch::prelude::*;
+
+use crate::contract::{execute, instantiate, migrate, query, reply};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+
+/// cw-orchestrator interface for the forwarder, usable against `Mock` in tests or a `Daemon` on
+/// a live chain
+#[interface(InstantiateMsg, ExecuteMsg, QueryMsg, MigrateMsg)]
+pub struct Forwarder;
+
+impl<Chain: CwEnv> Uploadable for Forwarder<Chain> {
+    /// Optimized wasm from `artifacts/`, as produced by rust-optimizer
+    fn wasm(&self) -> WasmPath {
+        ArtifactsDir::new(concat!(env!("CARGO_MANIFEST_DIR"), "/artifacts"))
+            .find_wasm_path("sender_receiver_code_challenge")
+            .unwrap()
+    }
+
+    fn wrapper(&self) -> Box<dyn MockContract<Empty>> {
+        Box::new(
+            ContractWrapper::new_with_empty(execute, instantiate, query)
+                .with_reply(reply)
+                .with_migrate(migrate),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coins, Uint128};
+
+    use crate::msg::{ExecuteMsgFns, QueryMsgFns};
+
+    #[test]
+    fn forwards_on_a_mock_chain() {
+        let sender = Addr::unchecked("sender");
+        let mock = Mock::new(&sender);
+        mock.set_balance(&sender, coins(1000, "uluna")).unwrap();
+
+        let forwarder = Forwarder::new("forwarder", mock.clone());
+        forwarder.upload().unwrap();
+        forwarder
+            .instantiate(
+                &InstantiateMsg {
+                    fee_bps: 0,
+                    burn_bps: 0,
+                    denoms: vec!["uluna".to_string()],
+                    max_amounts: vec![],
+                    min_amounts: vec![],
+                    forward_to_addr: None,
+                    pull_payments: false,
+                },
+                None,
+                None,
+            )
+            .unwrap();
+
+        forwarder
+            .forward_tokens(
+                Uint128::new(400),
+                Some("recipient".to_string()),
+                None,
+                None,
+                &coins(400, "uluna"),
+            )
+            .unwrap();
+
+        assert_eq!(
+            mock.query_balance(&Addr::unchecked("recipient"), "uluna")
+                .unwrap(),
+            Uint128::new(400)
+        );
+        assert_eq!(
+            forwarder.query_total_forwarded().unwrap().amounts,
+            coins(400, "uluna")
+        );
+    }
+}
@@ -1,6 +1,8 @@
 pub mod contract;
 mod error;
 pub mod helpers;
+#[cfg(feature = "interface")]
+pub mod interface;
 pub mod msg;
 pub mod state;
 
@@ -1,25 +1,443 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Addr, Binary, Coin, Timestamp, Uint128};
+
+use crate::state::SwapRoute;
+
+pub use cosmwatch_access::{OwnershipResponse, PausedResponse};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Share of each forward retained by the contract, in basis points (100 = 1%)
+    pub fee_bps: u64,
+    /// Share of each forward burned, in basis points. Applies to fee exempt senders too
+    pub burn_bps: u64,
+    /// Denoms the forwarder accepts, e.g. ["uluna"] or ["ujuno", "uosmo"]
+    pub denoms: Vec<String>,
+    /// Largest amount a single forward may carry, per denom. Unlisted denoms are uncapped
+    pub max_amounts: Vec<Coin>,
+    /// Smallest amount a single forward may carry, per denom. Unlisted denoms have no minimum
+    pub min_amounts: Vec<Coin>,
+    /// Pins ForwardTokens to this address for the lifetime of the contract, so it can be handed
+    /// out as a donation address
+    pub forward_to_addr: Option<String>,
+    /// Credits payouts to their recipients to be withdrawn, instead of sending them
+    pub pull_payments: bool,
+}
 
 #[cw_serde]
-pub struct InstantiateMsg {}
+pub struct MigrateMsg {}
 
+/// Execute message the swap route's pool is expected to accept. The offered funds are attached
+/// and the proceeds are sent back to the forwarder
 #[cw_serde]
+pub enum PoolExecuteMsg {
+    Swap {
+        ask_denom: String,
+        min_receive: Option<Uint128>,
+    },
+}
+
+#[cw_serde]
+#[cfg_attr(feature = "interface", derive(cw_orch::ExecuteFns))]
 pub enum ExecuteMsg {
+    /// Forwards the attached funds, less fees, to `forward_to_addr` or to the address registered
+    /// under `forward_to_alias`. Exactly one of the two must be set, unless the contract was
+    /// instantiated with a fixed destination, in which case neither may be
+    #[cfg_attr(feature = "interface", payable)]
     ForwardTokens {
+        forward_to_addr: Option<String>,
+        forward_to_alias: Option<String>,
+        amount: Uint128,
+        /// Free text passed on in the forward event, e.g. a donation reference
+        memo: Option<String>,
+    },
+    /// Splits the attached funds between the recipients in proportion to their weights.
+    /// Any rounding dust goes to the first recipient.
+    #[cfg_attr(feature = "interface", payable)]
+    ForwardSplit { recipients: Vec<(String, u64)> },
+    /// Executes `msg` on `contract_addr` with the attached funds, less fees
+    #[cfg_attr(feature = "interface", payable)]
+    ForwardToContract { contract_addr: String, msg: Binary },
+    /// Swaps the attached funds, less fees, through the swap route and forwards the proceeds.
+    /// Fails unless the pool pays out at least `min_receive`
+    #[cfg_attr(feature = "interface", payable)]
+    ForwardSwapped {
         forward_to_addr: String,
+        min_receive: Option<Uint128>,
+    },
+    /// Pays each transfer from the attached funds, which must add up to exactly the transfers per
+    /// denom. Transfers to the same address are merged into one send
+    #[cfg_attr(feature = "interface", payable)]
+    ForwardBatch { transfers: Vec<(String, Coin)> },
+    /// Holds the attached funds until `release_time`, after which `to` can claim them
+    #[cfg_attr(feature = "interface", payable)]
+    ForwardLocked { to: String, release_time: Timestamp },
+    /// Sends the funds of a released lock to its recipient, less fees. Only the recipient can do this
+    Claim { id: u64 },
+    /// Returns the funds of a lock to its sender before release. Only the sender can do this
+    CancelLock { id: u64 },
+    /// Forwards `amount` to `to` every `interval` seconds, paid from the attached funds.
+    /// The first forward is due straight away
+    #[cfg_attr(feature = "interface", payable)]
+    RegisterRecurring {
+        to: String,
         amount: Uint128,
+        interval: u64,
     },
+    /// Adds the attached funds to the prepaid balance of a recurring forward
+    #[cfg_attr(feature = "interface", payable)]
+    FundRecurring { id: u64 },
+    /// Stops a recurring forward and returns what is left of its balance. Only the sender can do this
+    CancelRecurring { id: u64 },
+    /// Makes one forward for every recurring forward that is due and funded. Anyone can call this
+    TriggerDue {},
+    /// Sends the sender everything credited to it while pull payments were on
+    Withdraw {},
+    /// Sends a failed transfer to its recipient again. Only the original sender can do this
+    RetryFailedTransfer { id: u64 },
+    /// Returns a failed transfer to the original sender. Only the original sender can do this
+    ReclaimFailedTransfer { id: u64 },
+    /// Routes ForwardSwapped through `pool`, which pays out `ask_denom`. Only the owner can do this
+    SetSwapRoute { pool: String, ask_denom: String },
+    /// Turns ForwardSwapped off. Only the owner can do this
+    RemoveSwapRoute {},
+    /// Offers ownership to `new_owner`, replacing any earlier offer. Only the owner can do this
+    TransferOwnership { new_owner: String },
+    /// Takes over ownership. Only the address ownership was offered to can do this
+    AcceptOwnership {},
+    /// Stops all forwarding until unpaused. Only the owner can do this
+    Pause {},
+    /// Resumes forwarding. Only the owner can do this
+    Unpause {},
+    /// Sends all accrued fees to the owner
+    WithdrawFees {},
+    /// Lets the given sender forward without paying the fee. Only the owner can do this
+    AddFeeExempt { addr: String },
+    /// Makes the given sender pay the fee again. Only the owner can do this
+    RemoveFeeExempt { addr: String },
+    /// Starts accepting the given denom. Only the owner can do this
+    AddDenom { denom: String },
+    /// Stops accepting the given denom. Only the owner can do this
+    RemoveDenom { denom: String },
+    /// Caps the amount of `denom` a single forward may carry, or removes the cap when `amount`
+    /// is not set. Only the owner can do this
+    SetMaxAmount {
+        denom: String,
+        amount: Option<Uint128>,
+    },
+    /// Rejects forwards of `denom` smaller than `amount`, or removes the minimum when `amount`
+    /// is not set. Only the owner can do this
+    SetMinAmount {
+        denom: String,
+        amount: Option<Uint128>,
+    },
+    /// Allows tokens to be forwarded to the given address. Only the owner can do this
+    AddDestination { addr: String },
+    /// Removes the given address from the allowlist. Only the owner can do this
+    RemoveDestination { addr: String },
+    /// Turns the destination allowlist on or off. Only the owner can do this
+    SetEnforceDestinations { enforce: bool },
+    /// Turns pull payments on or off. Balances credited so far stay withdrawable.
+    /// Only the owner can do this
+    SetPullPayments { enabled: bool },
+    /// Registers `alias` as a name for `addr`, replacing any earlier address. Only the owner can do this
+    SetAlias { alias: String, addr: String },
+    /// Removes the given alias. Only the owner can do this
+    RemoveAlias { alias: String },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
+#[cfg_attr(feature = "interface", derive(cw_orch::QueryFns))]
 pub enum QueryMsg {
+    /// Returns the owner, fee, accepted denoms, caps and pause status
+    #[returns(ConfigResponse)]
+    Config {},
+
     #[returns(QueryTotalForwardedResponse)]
     QueryTotalForwarded {},
+
+    /// Returns the number of forwards, unique senders and recipients, and volume per denom
+    #[returns(StatsResponse)]
+    Stats {},
+
+    /// Returns the lifetime amounts forwarded by the given sender
+    #[returns(ForwardedBySenderResponse)]
+    ForwardedBySender { sender: String },
+
+    /// Returns the lifetime amounts delivered to the given recipient, after fees
+    #[returns(ReceivedByAddressResponse)]
+    ReceivedByAddress { address: String },
+
+    /// Lists senders by lifetime amount of the given denom forwarded, largest first.
+    /// `start_after` is the last sender of the previous page.
+    #[returns(TopSendersResponse)]
+    TopSenders {
+        denom: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns what the given address can withdraw
+    #[returns(ClaimableResponse)]
+    Claimable { address: String },
+
+    /// Returns the fees retained so far and not yet withdrawn
+    #[returns(AccruedFeesResponse)]
+    AccruedFees {},
+
+    /// Returns the lifetime amounts burned per denom
+    #[returns(BurnedResponse)]
+    Burned {},
+
+    /// Returns the owner and the address ownership was offered to, if any
+    #[returns(OwnershipResponse)]
+    Ownership {},
+
+    /// Returns whether forwarding is paused
+    #[returns(PausedResponse)]
+    Paused {},
+
+    /// Lists the senders that forward without paying the fee.
+    /// `start_after` is the last address of the previous page.
+    #[returns(FeeExemptResponse)]
+    FeeExempt {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the denoms the forwarder accepts
+    #[returns(AllowedDenomsResponse)]
+    AllowedDenoms {},
+
+    /// Returns the per-forward caps of every capped denom
+    #[returns(MaxAmountsResponse)]
+    MaxAmounts {},
+
+    /// Returns the per-forward minimums of every denom that has one
+    #[returns(MinAmountsResponse)]
+    MinAmounts {},
+
+    /// Lists the allowlisted destinations and whether the allowlist is enforced
+    #[returns(DestinationsResponse)]
+    Destinations {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the address registered under the given alias
+    #[returns(AliasResponse)]
+    Alias { alias: String },
+
+    /// Lists the registered aliases by name. `start_after` is the last alias of the previous page.
+    #[returns(AliasesResponse)]
+    Aliases {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns a lock that has not been claimed or cancelled yet
+    #[returns(LockResponse)]
+    Lock { id: u64 },
+
+    /// Lists open locks, oldest first, optionally only those for `recipient`.
+    /// `start_after` is the last id of the previous page.
+    #[returns(LocksResponse)]
+    Locks {
+        recipient: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Returns a recurring forward that has not been cancelled
+    #[returns(RecurringResponse)]
+    Recurring { id: u64 },
+
+    /// Lists recurring forwards by id. `start_after` is the last id of the previous page.
+    #[returns(RecurringForwardsResponse)]
+    RecurringForwards {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Lists the transfers of `sender` that its recipients could not be sent.
+    /// `start_after` is the last id of the previous page.
+    #[returns(FailedTransfersResponse)]
+    FailedTransfers {
+        sender: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Lists past forwards, oldest first, optionally only those paid to `recipient`.
+    /// `start_after` is the last id of the previous page.
+    #[returns(HistoryResponse)]
+    History {
+        recipient: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub pending_owner: Option<Addr>,
+    pub fee_bps: u64,
+    pub burn_bps: u64,
+    pub allowed_denoms: Vec<String>,
+    pub max_amounts: Vec<Coin>,
+    pub min_amounts: Vec<Coin>,
+    pub enforce_destinations: bool,
+    pub fixed_destination: Option<Addr>,
+    pub pull_payments: bool,
+    pub swap_route: Option<SwapRoute>,
+    pub paused: bool,
 }
 
 #[cw_serde]
 pub struct QueryTotalForwardedResponse {
+    pub amounts: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct StatsResponse {
+    pub total_forwards: u64,
+    pub unique_senders: u64,
+    pub unique_recipients: u64,
+    /// Same as QueryTotalForwarded
+    pub volume: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct ForwardedBySenderResponse {
+    pub sender: String,
+    pub amounts: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct ReceivedByAddressResponse {
+    pub address: String,
+    pub amounts: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct SenderTotalResponse {
+    pub sender: String,
     pub amount: Uint128,
 }
+
+#[cw_serde]
+pub struct TopSendersResponse {
+    pub senders: Vec<SenderTotalResponse>,
+}
+
+#[cw_serde]
+pub struct ClaimableResponse {
+    pub address: String,
+    pub amounts: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct AccruedFeesResponse {
+    pub fees: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct BurnedResponse {
+    pub burned: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct FeeExemptResponse {
+    pub addrs: Vec<String>,
+}
+
+#[cw_serde]
+pub struct AllowedDenomsResponse {
+    pub denoms: Vec<String>,
+}
+
+#[cw_serde]
+pub struct MaxAmountsResponse {
+    pub max_amounts: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct MinAmountsResponse {
+    pub min_amounts: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct DestinationsResponse {
+    pub enforced: bool,
+    pub destinations: Vec<String>,
+}
+
+#[cw_serde]
+pub struct AliasResponse {
+    pub alias: String,
+    pub addr: Addr,
+}
+
+#[cw_serde]
+pub struct AliasesResponse {
+    pub aliases: Vec<AliasResponse>,
+}
+
+#[cw_serde]
+pub struct ForwardResponse {
+    pub id: u64,
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub coins: Vec<Coin>,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+#[cw_serde]
+pub struct HistoryResponse {
+    pub forwards: Vec<ForwardResponse>,
+}
+
+#[cw_serde]
+pub struct LockResponse {
+    pub id: u64,
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub coin: Coin,
+    pub release_time: Timestamp,
+}
+
+#[cw_serde]
+pub struct LocksResponse {
+    pub locks: Vec<LockResponse>,
+}
+
+#[cw_serde]
+pub struct RecurringResponse {
+    pub id: u64,
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub amount: Coin,
+    pub interval: u64,
+    pub next_time: Timestamp,
+    pub balance: Uint128,
+}
+
+#[cw_serde]
+pub struct RecurringForwardsResponse {
+    pub recurring: Vec<RecurringResponse>,
+}
+
+#[cw_serde]
+pub struct FailedTransferResponse {
+    pub id: u64,
+    pub recipient: Addr,
+    pub coins: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct FailedTransfersResponse {
+    pub transfers: Vec<FailedTransferResponse>,
+}
@@ -1,4 +1,187 @@
-use cosmwasm_std::Coin;
-use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-pub const TOKENS_SENT: Item<Coin> = Item::new("tokens_sent");
+use cosmwasm_std::{Addr, Coin, Empty, Timestamp, Uint128};
+use cosmwatch_access::{Ownable, Pausable};
+use cw_storage_plus::{Item, Map};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    // Share of each forward retained by the contract, in basis points
+    pub fee_bps: u64,
+    // Share of each forward burned, in basis points. Fee exempt senders still burn this share
+    pub burn_bps: u64,
+    // Only these denoms can be forwarded
+    pub allowed_denoms: Vec<String>,
+    // When set, tokens can only be forwarded to addresses in DESTINATIONS
+    pub enforce_destinations: bool,
+    // When set, ForwardTokens always pays this address. Can't be changed after instantiation
+    pub fixed_destination: Option<Addr>,
+    // When set, payouts are credited to CLAIMABLE instead of sent, and recipients withdraw them
+    pub pull_payments: bool,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// Config as stored up to 0.1.0, when it also held the owner. Only read when migrating
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct LegacyConfig {
+    pub owner: Option<Addr>,
+}
+
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
+// DEX pool incoming funds can be swapped through before forwarding, and the denom it pays out
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SwapRoute {
+    pub pool: Addr,
+    pub ask_denom: String,
+}
+
+pub const SWAP_ROUTE: Item<SwapRoute> = Item::new("swap_route");
+
+// The owner administers the forwarder. Ownership offers keep the key they were stored under
+// before the owner moved out of Config
+pub const OWNERSHIP: Ownable = Ownable::new("owner", "pending_owner");
+
+// While set, nothing can be forwarded
+pub const PAUSE: Pausable = Pausable::new("paused");
+
+// Running total per denom of what was forwarded on to recipients, after fees
+pub const TOKENS_SENT: Map<&str, Uint128> = Map::new("tokens_sent");
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct Stats {
+    // Addresses that ever forwarded through, or were paid by, the forwarder
+    pub unique_senders: u64,
+    pub unique_recipients: u64,
+}
+
+pub const STATS: Item<Stats> = Item::new("stats");
+
+// Lifetime amount per denom sent through the forwarder by each sender, before fees
+pub const SENDER_TOTALS: Map<(&Addr, &str), Uint128> = Map::new("sender_totals");
+
+// Lifetime amount per denom delivered to each recipient, after fees
+pub const RECIPIENT_TOTALS: Map<(&Addr, &str), Uint128> = Map::new("recipient_totals");
+
+// Senders ordered by their lifetime amount per denom, kept in sync with SENDER_TOTALS for the
+// top senders listing
+pub const SENDERS_BY_AMOUNT: Map<(&str, u128, &Addr), Empty> = Map::new("senders_by_amount");
+
+// Largest amount per denom a single forward may carry. Denoms without an entry are uncapped
+pub const MAX_AMOUNTS: Map<&str, Uint128> = Map::new("max_amounts");
+
+// Smallest amount per denom a single forward may carry, so dust is not forwarded. Denoms without
+// an entry have no minimum
+pub const MIN_AMOUNTS: Map<&str, Uint128> = Map::new("min_amounts");
+
+// Senders that forward without paying the fee
+pub const FEE_EXEMPT: Map<&Addr, Empty> = Map::new("fee_exempt");
+
+// Fees retained per denom, until the owner withdraws them
+pub const ACCRUED_FEES: Map<&str, Uint128> = Map::new("accrued_fees");
+
+// Lifetime amount burned per denom
+pub const BURNED: Map<&str, Uint128> = Map::new("burned");
+
+// Addresses tokens may be forwarded to while the allowlist is enforced
+pub const DESTINATIONS: Map<&Addr, Empty> = Map::new("destinations");
+
+// Human-readable names for destinations, e.g. "treasury", resolved when a forward is executed
+pub const ALIASES: Map<&str, Addr> = Map::new("aliases");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ForwardRecord {
+    pub sender: Addr,
+    pub recipient: Addr,
+    // What the recipient was sent, after fees
+    pub coins: Vec<Coin>,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+// Id of the last recorded forward, ids start at 1
+pub const FORWARD_COUNT: Item<u64> = Item::new("forward_count");
+
+// Every forward made, by id
+pub const HISTORY: Map<u64, ForwardRecord> = Map::new("history");
+
+// Forward ids grouped by recipient, kept in sync with HISTORY for the per-recipient listing
+pub const HISTORY_BY_RECIPIENT: Map<(&Addr, u64), Empty> = Map::new("history_by_recipient");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Lock {
+    pub sender: Addr,
+    pub recipient: Addr,
+    // What the sender locked, before fees
+    pub coin: Coin,
+    pub release_time: Timestamp,
+}
+
+// Id of the last lock created, ids start at 1
+pub const LOCK_COUNT: Item<u64> = Item::new("lock_count");
+
+// Funds held until their release time, removed once claimed or cancelled
+pub const LOCKS: Map<u64, Lock> = Map::new("locks");
+
+// Lock ids grouped by recipient, kept in sync with LOCKS for the per-recipient listing
+pub const LOCKS_BY_RECIPIENT: Map<(&Addr, u64), Empty> = Map::new("locks_by_recipient");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Recurring {
+    pub sender: Addr,
+    pub recipient: Addr,
+    // Forwarded every interval, before fees
+    pub amount: Coin,
+    // Seconds between forwards
+    pub interval: u64,
+    pub next_time: Timestamp,
+    // What is left of the sender's prepayment, in the denom of `amount`
+    pub balance: Uint128,
+}
+
+// Id of the last recurring forward registered, ids start at 1
+pub const RECURRING_COUNT: Item<u64> = Item::new("recurring_count");
+
+// Recurring forwards, removed once cancelled
+pub const RECURRING: Map<u64, Recurring> = Map::new("recurring");
+
+// Recurring forward ids ordered by the time their next forward is due, in nanoseconds, kept in
+// sync with RECURRING so TriggerDue only visits the forwards that are due
+pub const RECURRING_BY_DUE: Map<(u64, u64), Empty> = Map::new("recurring_by_due");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Payout {
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub coins: Vec<Coin>,
+}
+
+// Id of the last payout sent, ids start at 1 and double as the reply id of the payout
+pub const PAYOUT_COUNT: Item<u64> = Item::new("payout_count");
+
+// Payouts waiting for their reply, removed as soon as it comes back
+pub const PAYOUTS: Map<u64, Payout> = Map::new("payouts");
+
+// Payouts the recipient could not be sent, by original sender and payout id, until the sender
+// retries or reclaims them. They are still counted in TOKENS_SENT until reclaimed
+// Amount per denom each recipient can withdraw, credited instead of sent while pull payments
+// are on
+pub const CLAIMABLE: Map<(&Addr, &str), Uint128> = Map::new("claimable");
+
+pub const FAILED_TRANSFERS: Map<(&Addr, u64), Payout> = Map::new("failed_transfers");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PendingSwap {
+    pub sender: Addr,
+    pub recipient: Addr,
+    // What went into the pool and the fee taken from it, for the forward event
+    pub offered: Coin,
+    pub fee: Uint128,
+    // The contract's balance of the ask denom before the swap, the proceeds are what it grew by
+    pub balance_before: Uint128,
+}
+
+// Swap in flight, from the ForwardSwapped execute until its reply
+pub const PENDING_SWAP: Item<PendingSwap> = Item::new("pending_swap");
@@ -4,6 +4,7 @@ use cosmwasm_std::{
     to_binary, Addr, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
     SubMsg, Uint128, WasmMsg,
 };
+use cosmwatch_events::response;
 use cw2::set_contract_version;
 use cw20::{Cw20ExecuteMsg, MinterResponse};
 use cw20_base::msg::InstantiateMsg as CW20InstantiateMsg;
@@ -164,8 +165,7 @@ fn reply_init_cw20(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, Con
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    Ok(Response::new()
-        .add_attribute("action", "migrate")
+    Ok(response("migrate")
         .add_attribute("new_name", CONTRACT_NAME)
         .add_attribute("new_version", CONTRACT_VERSION))
 }
@@ -0,0 +1,385 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coins, to_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Uint128,
+};
+use cosmwatch_events::{attr, migrate_response, response};
+use cosmwatch_migrate::Migration;
+use cw2::set_contract_version;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ClaimableResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+};
+use crate::state::{ClaimWindow, Config, CLAIMS, CONFIG};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:faucet";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    if msg.window == 0 {
+        return Err(ContractError::ZeroWindow {});
+    }
+
+    let config = Config {
+        owner: info.sender,
+        denom: msg.denom,
+        limit: msg.limit,
+        window: msg.window,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(response("instantiate")
+        .add_attribute(attr::OWNER, config.owner)
+        .add_attribute(attr::DENOM, config.denom)
+        .add_attribute("limit", config.limit))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Claim { amount } => execute_claim(deps, env, info, amount),
+        ExecuteMsg::UpdateConfig { limit, window } => {
+            execute_update_config(deps, info, limit, window)
+        }
+        ExecuteMsg::Withdraw { amount } => execute_withdraw(deps, env, info, amount),
+    }
+}
+
+fn execute_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroClaim {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let stored = CLAIMS.may_load(deps.storage, &info.sender)?;
+    let mut window = ClaimWindow::current(stored, &config, env.block.time);
+
+    let remaining = config.limit.saturating_sub(window.claimed);
+    if amount > remaining {
+        return Err(ContractError::LimitReached {
+            remaining,
+            resets_at: window.start.plus_seconds(config.window).seconds(),
+        });
+    }
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.denom)?
+        .amount;
+    if amount > balance {
+        return Err(ContractError::FaucetEmpty { balance });
+    }
+
+    window.claimed += amount;
+    CLAIMS.save(deps.storage, &info.sender, &window)?;
+
+    Ok(response("claim")
+        .add_attribute(attr::RECIPIENT, info.sender.as_str())
+        .add_attribute(attr::AMOUNT, amount)
+        .add_message(BankMsg::Send {
+            to_address: info.sender.into_string(),
+            amount: coins(amount.u128(), config.denom),
+        }))
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<Uint128>,
+    window: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if window == Some(0) {
+        return Err(ContractError::ZeroWindow {});
+    }
+
+    config.limit = limit.unwrap_or(config.limit);
+    config.window = window.unwrap_or(config.window);
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(response("update_config")
+        .add_attribute("limit", config.limit)
+        .add_attribute("window", config.window.to_string()))
+}
+
+fn execute_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.denom)?
+        .amount;
+    if amount > balance {
+        return Err(ContractError::FaucetEmpty { balance });
+    }
+
+    Ok(response("withdraw")
+        .add_attribute(attr::AMOUNT, amount)
+        .add_message(BankMsg::Send {
+            to_address: config.owner.into_string(),
+            amount: coins(amount.u128(), config.denom),
+        }))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let migrated = Migration::new(CONTRACT_NAME, CONTRACT_VERSION).run(deps.storage)?;
+
+    Ok(migrate_response(migrated.from, migrated.to))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&ConfigResponse {
+            config: CONFIG.load(deps.storage)?,
+        }),
+        QueryMsg::Claimable { address } => to_binary(&query_claimable(deps, env, address)?),
+    }
+}
+
+fn query_claimable(deps: Deps, env: Env, address: String) -> StdResult<ClaimableResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let address = deps.api.addr_validate(&address)?;
+    let stored = CLAIMS.may_load(deps.storage, &address)?;
+    let window = ClaimWindow::current(stored, &config, env.block.time);
+
+    Ok(ClaimableResponse {
+        amount: config.limit.saturating_sub(window.claimed),
+        resets_at: window.start.plus_seconds(config.window).seconds(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
+    };
+    use cosmwasm_std::{from_binary, CosmosMsg};
+
+    const DENOM: &str = "ujunox";
+
+    fn instantiate_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            denom: DENOM.to_string(),
+            limit: Uint128::new(100),
+            window: 3600,
+        }
+    }
+
+    fn claimable(deps: Deps, env: Env, address: &str) -> ClaimableResponse {
+        let res = query(
+            deps,
+            env,
+            QueryMsg::Claimable {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+        from_binary(&res).unwrap()
+    }
+
+    #[test]
+    fn claims_are_capped_per_window() {
+        let mut deps = mock_dependencies_with_balance(&coins(1000, DENOM));
+        let mut env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("owner", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("student", &[]),
+            ExecuteMsg::Claim {
+                amount: Uint128::new(60),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "student".to_string(),
+                amount: coins(60, DENOM),
+            })
+        );
+
+        // only what is left of the limit can be claimed until the window ends
+        let resets_at = env.block.time.plus_seconds(3600).seconds();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("student", &[]),
+            ExecuteMsg::Claim {
+                amount: Uint128::new(50),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::LimitReached {
+                remaining: Uint128::new(40),
+                resets_at
+            }
+        );
+        assert_eq!(
+            claimable(deps.as_ref(), env.clone(), "student"),
+            ClaimableResponse {
+                amount: Uint128::new(40),
+                resets_at
+            }
+        );
+
+        // other addresses have their own limit
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("other", &[]),
+            ExecuteMsg::Claim {
+                amount: Uint128::new(100),
+            },
+        )
+        .unwrap();
+
+        // a new window starts the limit over
+        env.block.time = env.block.time.plus_seconds(3600);
+        assert_eq!(
+            claimable(deps.as_ref(), env.clone(), "student").amount,
+            Uint128::new(100)
+        );
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("student", &[]),
+            ExecuteMsg::Claim {
+                amount: Uint128::new(100),
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn empty_faucet_and_owner_actions() {
+        let mut deps = mock_dependencies_with_balance(&coins(30, DENOM));
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            instantiate_msg(),
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("student", &[]),
+            ExecuteMsg::Claim {
+                amount: Uint128::new(50),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::FaucetEmpty {
+                balance: Uint128::new(30)
+            }
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("student", &[]),
+            ExecuteMsg::Withdraw {
+                amount: Uint128::new(30),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Withdraw {
+                amount: Uint128::new(30),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "owner".to_string(),
+                amount: coins(30, DENOM),
+            })
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::UpdateConfig {
+                limit: None,
+                window: Some(0),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ZeroWindow {});
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::UpdateConfig {
+                limit: Some(Uint128::new(10)),
+                window: None,
+            },
+        )
+        .unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let config: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(config.config.limit, Uint128::new(10));
+        assert_eq!(config.config.window, 3600);
+    }
+
+    #[test]
+    fn rejects_zero_window() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            window: 0,
+            ..instantiate_msg()
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::ZeroWindow {});
+    }
+}
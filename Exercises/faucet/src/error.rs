@@ -0,0 +1,27 @@
+use cosmwasm_std::{StdError, Uint128};
+use cosmwatch_migrate::MigrateError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Migrate(#[from] MigrateError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Window must be longer than zero seconds")]
+    ZeroWindow {},
+
+    #[error("Cannot claim zero tokens")]
+    ZeroClaim {},
+
+    #[error("Claim limit reached: {remaining} left until {resets_at}")]
+    LimitReached { remaining: Uint128, resets_at: u64 },
+
+    #[error("Faucet only holds {balance}")]
+    FaucetEmpty { balance: Uint128 },
+}
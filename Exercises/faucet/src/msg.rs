@@ -0,0 +1,52 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Uint128;
+
+use crate::state::Config;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub denom: String,
+    /// Most an address can claim in one window
+    pub limit: Uint128,
+    /// Window length in seconds, counted from an address's first claim in the window
+    pub window: u64,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Sends `amount` to the sender, if it stays within their limit for the current window
+    Claim { amount: Uint128 },
+    /// Owner only
+    UpdateConfig {
+        limit: Option<Uint128>,
+        window: Option<u64>,
+    },
+    /// Sends `amount` of the faucet's balance back to the owner. Owner only
+    Withdraw { amount: Uint128 },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+
+    /// How much `address` can claim right now
+    #[returns(ClaimableResponse)]
+    Claimable { address: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub config: Config,
+}
+
+#[cw_serde]
+pub struct ClaimableResponse {
+    pub amount: Uint128,
+    /// Seconds since epoch when the address's limit resets
+    pub resets_at: u64,
+}
@@ -0,0 +1,39 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Timestamp, Uint128};
+use cw_storage_plus::{Item, Map};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    pub owner: Addr,
+    pub denom: String,
+    // Most an address can claim in one window
+    pub limit: Uint128,
+    // Window length in seconds
+    pub window: u64,
+}
+
+// Claims made by an address in its current window
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ClaimWindow {
+    pub start: Timestamp,
+    pub claimed: Uint128,
+}
+
+impl ClaimWindow {
+    // Window in effect at `now`: the stored one, or a fresh one if it has run out
+    pub fn current(stored: Option<ClaimWindow>, config: &Config, now: Timestamp) -> ClaimWindow {
+        match stored {
+            Some(window) if now < window.start.plus_seconds(config.window) => window,
+            _ => ClaimWindow {
+                start: now,
+                claimed: Uint128::zero(),
+            },
+        }
+    }
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+pub const CLAIMS: Map<&Addr, ClaimWindow> = Map::new("claims");
@@ -0,0 +1,252 @@
+//! Ownership and pause switches, so every contract gates owner-only messages, hands over
+//! ownership and pauses the same way. Contracts keep their own `ContractError` and convert
+//! [`AccessError`] into it.
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Api, MessageInfo, Response, StdError, StdResult, Storage};
+use cosmwatch_events::{attr, response};
+use cw_storage_plus::Item;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum AccessError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Contract is paused")]
+    ContractPaused {},
+
+    #[error("No ownership transfer is pending")]
+    NoPendingOwner {},
+}
+
+#[cw_serde]
+pub struct OwnershipResponse {
+    pub owner: Addr,
+    pub pending_owner: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct PausedResponse {
+    pub paused: bool,
+}
+
+/// Contract owner, handed over in two steps: the owner offers ownership, then the new owner
+/// accepts, so a typo can't lock the owner out
+pub struct Ownable<'a> {
+    owner: Item<'a, Addr>,
+    pending_owner: Item<'a, Addr>,
+}
+
+impl<'a> Ownable<'a> {
+    pub const fn new(owner_key: &'a str, pending_owner_key: &'a str) -> Self {
+        Ownable {
+            owner: Item::new(owner_key),
+            pending_owner: Item::new(pending_owner_key),
+        }
+    }
+
+    pub fn initialize(&self, storage: &mut dyn Storage, owner: &Addr) -> StdResult<()> {
+        self.owner.save(storage, owner)
+    }
+
+    pub fn is_initialized(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self.owner.may_load(storage)?.is_some())
+    }
+
+    pub fn owner(&self, storage: &dyn Storage) -> StdResult<Addr> {
+        self.owner.load(storage)
+    }
+
+    pub fn assert_owner(&self, storage: &dyn Storage, sender: &Addr) -> Result<(), AccessError> {
+        if *sender != self.owner(storage)? {
+            return Err(AccessError::Unauthorized {});
+        }
+        Ok(())
+    }
+
+    /// Offers ownership to `new_owner`, replacing any earlier offer. Owner only
+    pub fn transfer(
+        &self,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        info: &MessageInfo,
+        new_owner: &str,
+    ) -> Result<Response, AccessError> {
+        self.assert_owner(storage, &info.sender)?;
+
+        let new_owner = api.addr_validate(new_owner)?;
+        self.pending_owner.save(storage, &new_owner)?;
+
+        Ok(response("transfer_ownership").add_attribute(attr::PENDING_OWNER, new_owner))
+    }
+
+    /// Makes the sender the owner, if ownership was offered to it
+    pub fn accept(
+        &self,
+        storage: &mut dyn Storage,
+        info: &MessageInfo,
+    ) -> Result<Response, AccessError> {
+        let pending_owner = self
+            .pending_owner
+            .may_load(storage)?
+            .ok_or(AccessError::NoPendingOwner {})?;
+        if info.sender != pending_owner {
+            return Err(AccessError::Unauthorized {});
+        }
+
+        self.owner.save(storage, &pending_owner)?;
+        self.pending_owner.remove(storage);
+
+        Ok(response("accept_ownership").add_attribute(attr::OWNER, pending_owner))
+    }
+
+    pub fn query(&self, storage: &dyn Storage) -> StdResult<OwnershipResponse> {
+        Ok(OwnershipResponse {
+            owner: self.owner(storage)?,
+            pending_owner: self.pending_owner.may_load(storage)?,
+        })
+    }
+}
+
+/// Pause switch the owner flips. What a pause blocks is up to each contract, which checks
+/// [`Pausable::assert_not_paused`] before the messages it covers
+pub struct Pausable<'a> {
+    paused: Item<'a, bool>,
+}
+
+impl<'a> Pausable<'a> {
+    pub const fn new(key: &'a str) -> Self {
+        Pausable {
+            paused: Item::new(key),
+        }
+    }
+
+    /// Contracts that never saved the switch count as unpaused
+    pub fn is_paused(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self.paused.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn assert_not_paused(&self, storage: &dyn Storage) -> Result<(), AccessError> {
+        if self.is_paused(storage)? {
+            return Err(AccessError::ContractPaused {});
+        }
+        Ok(())
+    }
+
+    /// Pauses or unpauses. Only the owner in `ownable` can do this
+    pub fn set_paused(
+        &self,
+        storage: &mut dyn Storage,
+        ownable: &Ownable,
+        info: &MessageInfo,
+        paused: bool,
+    ) -> Result<Response, AccessError> {
+        ownable.assert_owner(storage, &info.sender)?;
+        self.paused.save(storage, &paused)?;
+
+        let action = if paused { "pause" } else { "unpause" };
+        Ok(response(action))
+    }
+
+    pub fn query(&self, storage: &dyn Storage) -> StdResult<PausedResponse> {
+        Ok(PausedResponse {
+            paused: self.is_paused(storage)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_info};
+
+    const OWNERSHIP: Ownable = Ownable::new("owner", "pending_owner");
+    const PAUSE: Pausable = Pausable::new("paused");
+
+    #[test]
+    fn ownership_moves_once_accepted() {
+        let mut deps = mock_dependencies();
+        let api = deps.api;
+        OWNERSHIP
+            .initialize(deps.as_mut().storage, &Addr::unchecked("owner"))
+            .unwrap();
+
+        let err = OWNERSHIP
+            .transfer(
+                deps.as_mut().storage,
+                &api,
+                &mock_info("other", &[]),
+                "other",
+            )
+            .unwrap_err();
+        assert_eq!(err, AccessError::Unauthorized {});
+        let err = OWNERSHIP
+            .accept(deps.as_mut().storage, &mock_info("next", &[]))
+            .unwrap_err();
+        assert_eq!(err, AccessError::NoPendingOwner {});
+
+        OWNERSHIP
+            .transfer(
+                deps.as_mut().storage,
+                &api,
+                &mock_info("owner", &[]),
+                "next",
+            )
+            .unwrap();
+        // the current owner keeps control until the offer is accepted
+        OWNERSHIP
+            .assert_owner(deps.as_ref().storage, &Addr::unchecked("owner"))
+            .unwrap();
+        let err = OWNERSHIP
+            .accept(deps.as_mut().storage, &mock_info("other", &[]))
+            .unwrap_err();
+        assert_eq!(err, AccessError::Unauthorized {});
+
+        OWNERSHIP
+            .accept(deps.as_mut().storage, &mock_info("next", &[]))
+            .unwrap();
+        assert_eq!(
+            OWNERSHIP.query(deps.as_ref().storage).unwrap(),
+            OwnershipResponse {
+                owner: Addr::unchecked("next"),
+                pending_owner: None,
+            }
+        );
+    }
+
+    #[test]
+    fn only_owner_pauses() {
+        let mut deps = mock_dependencies();
+        OWNERSHIP
+            .initialize(deps.as_mut().storage, &Addr::unchecked("owner"))
+            .unwrap();
+        assert!(!PAUSE.is_paused(deps.as_ref().storage).unwrap());
+
+        let err = PAUSE
+            .set_paused(
+                deps.as_mut().storage,
+                &OWNERSHIP,
+                &mock_info("other", &[]),
+                true,
+            )
+            .unwrap_err();
+        assert_eq!(err, AccessError::Unauthorized {});
+
+        PAUSE
+            .set_paused(
+                deps.as_mut().storage,
+                &OWNERSHIP,
+                &mock_info("owner", &[]),
+                true,
+            )
+            .unwrap();
+        assert_eq!(
+            PAUSE.assert_not_paused(deps.as_ref().storage).unwrap_err(),
+            AccessError::ContractPaused {}
+        );
+    }
+}
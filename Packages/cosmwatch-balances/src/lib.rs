@@ -0,0 +1,255 @@
+//! Native and cw20 balance helpers shared by the escrow and the forwarders, so the balance math
+//! lives in one place.
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_binary, Addr, BankMsg, Coin, StdResult, SubMsg, Uint128, WasmMsg};
+use cw20::{Balance, Cw20CoinVerified, Cw20ExecuteMsg};
+
+#[cw_serde]
+#[derive(Default)]
+pub struct GenericBalance {
+    pub native: Vec<Coin>,
+    pub cw20: Vec<Cw20CoinVerified>,
+}
+
+impl GenericBalance {
+    pub fn is_empty(&self) -> bool {
+        self.native.is_empty() && self.cw20.is_empty()
+    }
+
+    pub fn add_tokens(&mut self, add: Balance) {
+        match add {
+            Balance::Native(balance) => {
+                for token in balance.0 {
+                    add_coin(&mut self.native, &token);
+                }
+            }
+            Balance::Cw20(token) => {
+                match self
+                    .cw20
+                    .iter_mut()
+                    .find(|exist| exist.address == token.address)
+                {
+                    Some(exist) => exist.amount += token.amount,
+                    None => self.cw20.push(token),
+                }
+            }
+        };
+    }
+
+    /// Adds every native coin and cw20 token in `other`
+    pub fn add_balance(&mut self, other: &GenericBalance) {
+        for coin in &other.native {
+            add_coin(&mut self.native, coin);
+        }
+        for token in &other.cw20 {
+            self.add_tokens(Balance::Cw20(token.clone()));
+        }
+    }
+
+    /// Holds at least every native coin and cw20 token in `other`
+    pub fn covers(&self, other: &GenericBalance) -> bool {
+        self.clone().sub_balance(other).is_ok()
+    }
+
+    /// Takes every native coin and cw20 token in `other` out, dropping the ones that reach
+    /// zero. Fails without changes if any is short
+    pub fn sub_balance(&mut self, other: &GenericBalance) -> StdResult<()> {
+        let mut native = self.native.clone();
+        for coin in &other.native {
+            let held = native
+                .iter_mut()
+                .find(|exist| exist.denom == coin.denom)
+                .map(|exist| &mut exist.amount);
+            sub_amount(held, coin.amount)?;
+        }
+        let mut cw20 = self.cw20.clone();
+        for token in &other.cw20 {
+            let held = cw20
+                .iter_mut()
+                .find(|exist| exist.address == token.address)
+                .map(|exist| &mut exist.amount);
+            sub_amount(held, token.amount)?;
+        }
+        native.retain(|coin| !coin.amount.is_zero());
+        cw20.retain(|token| !token.amount.is_zero());
+        self.native = native;
+        self.cw20 = cw20;
+        Ok(())
+    }
+}
+
+fn sub_amount(held: Option<&mut Uint128>, amount: Uint128) -> StdResult<()> {
+    // a token that isn't held at all counts as a zero balance
+    let mut zero = Uint128::zero();
+    let held = held.unwrap_or(&mut zero);
+    *held = held.checked_sub(amount)?;
+    Ok(())
+}
+
+/// Adds `coin` to the coin of the same denom in `coins`, or appends it if there is none
+pub fn add_coin(coins: &mut Vec<Coin>, coin: &Coin) {
+    match coins.iter_mut().find(|exist| exist.denom == coin.denom) {
+        Some(exist) => exist.amount += coin.amount,
+        None => coins.push(coin.clone()),
+    }
+}
+
+/// Sends the whole balance to `to`: one bank send for the native coins and a transfer per cw20
+pub fn send_tokens(to: &Addr, balance: &GenericBalance) -> StdResult<Vec<SubMsg>> {
+    let native_balance = &balance.native;
+    let mut msgs: Vec<SubMsg> = if native_balance.is_empty() {
+        vec![]
+    } else {
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: to.into(),
+            amount: native_balance.to_vec(),
+        })]
+    };
+
+    let cw20_balance = &balance.cw20;
+    let cw20_msgs: StdResult<Vec<_>> = cw20_balance
+        .iter()
+        .map(|c| {
+            let msg = Cw20ExecuteMsg::Transfer {
+                recipient: to.into(),
+                amount: c.amount,
+            };
+            let exec = SubMsg::new(WasmMsg::Execute {
+                contract_addr: c.address.to_string(),
+                msg: to_binary(&msg)?,
+                funds: vec![],
+            });
+            Ok(exec)
+        })
+        .collect();
+    msgs.append(&mut cw20_msgs?);
+    Ok(msgs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coin, coins};
+    use cw_utils::NativeBalance;
+
+    fn cw20(address: &str, amount: u128) -> Cw20CoinVerified {
+        Cw20CoinVerified {
+            address: Addr::unchecked(address),
+            amount: Uint128::new(amount),
+        }
+    }
+
+    #[test]
+    fn add_tokens_merges_by_denom_and_address() {
+        let mut balance = GenericBalance::default();
+        assert!(balance.is_empty());
+
+        balance.add_tokens(Balance::Native(NativeBalance(vec![
+            coin(100, "ujuno"),
+            coin(5, "uosmo"),
+        ])));
+        balance.add_tokens(Balance::Native(NativeBalance(coins(50, "ujuno"))));
+        balance.add_tokens(Balance::Cw20(cw20("token1", 10)));
+        balance.add_tokens(Balance::Cw20(cw20("token2", 20)));
+        balance.add_tokens(Balance::Cw20(cw20("token1", 30)));
+
+        assert_eq!(
+            balance,
+            GenericBalance {
+                native: vec![coin(150, "ujuno"), coin(5, "uosmo")],
+                cw20: vec![cw20("token1", 40), cw20("token2", 20)],
+            }
+        );
+        assert!(!balance.is_empty());
+    }
+
+    #[test]
+    fn sub_balance_drops_emptied_tokens() {
+        let mut balance = GenericBalance {
+            native: vec![coin(150, "ujuno"), coin(5, "uosmo")],
+            cw20: vec![cw20("token1", 40)],
+        };
+        let payout = GenericBalance {
+            native: vec![coin(50, "ujuno"), coin(5, "uosmo")],
+            cw20: vec![cw20("token1", 40)],
+        };
+        balance.sub_balance(&payout).unwrap();
+        assert_eq!(
+            balance,
+            GenericBalance {
+                native: vec![coin(100, "ujuno")],
+                cw20: vec![],
+            }
+        );
+
+        // short on one token, nothing is taken
+        let too_much = GenericBalance {
+            native: vec![coin(10, "ujuno"), coin(1, "uosmo")],
+            cw20: vec![],
+        };
+        assert!(!balance.covers(&too_much));
+        balance.sub_balance(&too_much).unwrap_err();
+        assert_eq!(balance.native, vec![coin(100, "ujuno")]);
+        assert!(balance.covers(&GenericBalance::default()));
+
+        balance.add_balance(&payout);
+        assert_eq!(
+            balance,
+            GenericBalance {
+                native: vec![coin(150, "ujuno"), coin(5, "uosmo")],
+                cw20: vec![cw20("token1", 40)],
+            }
+        );
+    }
+
+    #[test]
+    fn add_coin_appends_new_denoms() {
+        let mut coins = vec![];
+        add_coin(&mut coins, &coin(1, "uluna"));
+        add_coin(&mut coins, &coin(2, "ujuno"));
+        add_coin(&mut coins, &coin(3, "uluna"));
+        assert_eq!(vec![coin(4, "uluna"), coin(2, "ujuno")], coins);
+    }
+
+    #[test]
+    fn send_tokens_sends_native_and_cw20() {
+        let to = Addr::unchecked("recipient");
+
+        let msgs = send_tokens(&to, &GenericBalance::default()).unwrap();
+        assert!(msgs.is_empty());
+
+        let balance = GenericBalance {
+            native: vec![coin(100, "ujuno")],
+            cw20: vec![cw20("token1", 40), cw20("token2", 20)],
+        };
+        let msgs = send_tokens(&to, &balance).unwrap();
+        assert_eq!(
+            msgs,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: "recipient".to_string(),
+                    amount: vec![coin(100, "ujuno")],
+                }),
+                SubMsg::new(WasmMsg::Execute {
+                    contract_addr: "token1".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: "recipient".to_string(),
+                        amount: Uint128::new(40),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+                SubMsg::new(WasmMsg::Execute {
+                    contract_addr: "token2".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: "recipient".to_string(),
+                        amount: Uint128::new(20),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ]
+        );
+    }
+}
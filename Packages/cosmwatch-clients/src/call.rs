@@ -0,0 +1,90 @@
+use std::marker::PhantomData;
+
+use cosmwasm_std::{
+    to_binary, Coin, CosmosMsg, Empty, QuerierWrapper, QueryRequest, StdResult, WasmMsg, WasmQuery,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// An execute message ready to be dispatched from a contract or a transaction
+#[derive(Clone, Debug, PartialEq)]
+pub struct Exec {
+    msg: CosmosMsg,
+}
+
+impl Exec {
+    pub(crate) fn new(msg: CosmosMsg) -> Self {
+        Exec { msg }
+    }
+
+    pub(crate) fn wasm<M: Serialize>(
+        contract_addr: &str,
+        msg: &M,
+        funds: Vec<Coin>,
+    ) -> StdResult<Self> {
+        Ok(Exec::new(
+            WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(msg)?,
+                funds,
+            }
+            .into(),
+        ))
+    }
+
+    pub fn msg(self) -> CosmosMsg {
+        self.msg
+    }
+
+    /// Executes the message on `app` as `sender`
+    #[cfg(feature = "multitest")]
+    pub fn run(
+        self,
+        app: &mut cw_multi_test::App,
+        sender: &str,
+    ) -> anyhow::Result<cw_multi_test::AppResponse> {
+        use cw_multi_test::Executor;
+
+        app.execute(cosmwasm_std::Addr::unchecked(sender), self.msg)
+    }
+}
+
+impl From<Exec> for CosmosMsg {
+    fn from(exec: Exec) -> Self {
+        exec.msg
+    }
+}
+
+/// A smart query that decodes into `T`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query<T> {
+    request: QueryRequest<Empty>,
+    response: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> Query<T> {
+    pub(crate) fn wasm<M: Serialize>(contract_addr: &str, msg: &M) -> StdResult<Self> {
+        Ok(Query {
+            request: WasmQuery::Smart {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(msg)?,
+            }
+            .into(),
+            response: PhantomData,
+        })
+    }
+
+    pub fn request(self) -> QueryRequest<Empty> {
+        self.request
+    }
+
+    pub fn fetch(&self, querier: &QuerierWrapper) -> StdResult<T> {
+        querier.query(&self.request)
+    }
+
+    /// Runs the query against `app`
+    #[cfg(feature = "multitest")]
+    pub fn run(&self, app: &cw_multi_test::App) -> StdResult<T> {
+        self.fetch(&app.wrap())
+    }
+}
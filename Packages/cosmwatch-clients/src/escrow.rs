@@ -0,0 +1,520 @@
+use cosmwasm_std::{Addr, Coin, Decimal, StdResult, Uint128};
+use cw20_escrow_milestones::helpers::EscrowContract;
+use cw20_escrow_milestones::msg::{
+    ClaimsResponse, ContributionsResponse, CreateMilestoneMsg, CreateMsg, DisputesResponse,
+    EscrowDetailsResponse, EscrowHistoryResponse, EscrowStatus, FundingStatusResponse,
+    ListEscrowsResponse, ListMilestonesResponse, MilestoneApprovalsResponse, QueryMsg,
+};
+use cw20_escrow_milestones::state::{GenericBalance, Milestone};
+use cw_utils::Expiration;
+
+use crate::{Exec, Query};
+
+/// Client for the milestone escrow
+#[derive(Clone, Debug, PartialEq)]
+pub struct EscrowClient {
+    contract: EscrowContract,
+}
+
+impl EscrowClient {
+    pub fn new(addr: impl Into<String>) -> Self {
+        EscrowClient {
+            contract: EscrowContract(Addr::unchecked(addr)),
+        }
+    }
+
+    pub fn addr(&self) -> Addr {
+        self.contract.addr()
+    }
+
+    /// Creates an escrow funded with the native `funds` attached
+    pub fn create(&self, msg: CreateMsg, funds: Vec<Coin>) -> StdResult<Exec> {
+        self.contract.create(msg, funds).map(Exec::new)
+    }
+
+    /// Creates an escrow funded by sending `amount` of the cw20 `token`
+    pub fn create_with_cw20(
+        &self,
+        token: impl Into<String>,
+        amount: impl Into<Uint128>,
+        msg: CreateMsg,
+    ) -> StdResult<Exec> {
+        self.contract
+            .create_with_cw20(&Addr::unchecked(token), amount.into(), msg)
+            .map(Exec::new)
+    }
+
+    /// Adds a milestone funded with the native `funds` attached
+    pub fn create_milestone(&self, msg: CreateMilestoneMsg, funds: Vec<Coin>) -> StdResult<Exec> {
+        self.contract.create_milestone(msg, funds).map(Exec::new)
+    }
+
+    /// Adds a milestone funded by sending `amount` of the cw20 `token`
+    pub fn create_milestone_with_cw20(
+        &self,
+        token: impl Into<String>,
+        amount: impl Into<Uint128>,
+        msg: CreateMilestoneMsg,
+    ) -> StdResult<Exec> {
+        self.contract
+            .create_milestone_with_cw20(&Addr::unchecked(token), amount.into(), msg)
+            .map(Exec::new)
+    }
+
+    /// Changes a milestone the recipient hasn't submitted yet, keeping what is left `None`
+    pub fn update_milestone(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+        title: Option<String>,
+        description: Option<String>,
+        amount: Option<GenericBalance>,
+    ) -> StdResult<Exec> {
+        self.contract
+            .update_milestone(id, milestone_id, title, description, amount)
+            .map(Exec::new)
+    }
+
+    /// Deletes a milestone the recipient hasn't submitted yet
+    pub fn remove_milestone(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<Exec> {
+        self.contract
+            .remove_milestone(id, milestone_id)
+            .map(Exec::new)
+    }
+
+    /// Adds the native `funds` attached to an escrow that isn't fully funded yet
+    pub fn top_up(&self, id: impl Into<String>, funds: Vec<Coin>) -> StdResult<Exec> {
+        self.contract.top_up(id, funds).map(Exec::new)
+    }
+
+    /// Adds `amount` of the cw20 `token` to an escrow that isn't fully funded yet
+    pub fn top_up_with_cw20(
+        &self,
+        token: impl Into<String>,
+        amount: impl Into<Uint128>,
+        id: impl Into<String>,
+    ) -> StdResult<Exec> {
+        self.contract
+            .top_up_with_cw20(&Addr::unchecked(token), amount.into(), id)
+            .map(Exec::new)
+    }
+
+    /// Adds `amount` of the cw20 `token` to an escrow for one of its milestones
+    pub fn top_up_milestone_with_cw20(
+        &self,
+        token: impl Into<String>,
+        amount: impl Into<Uint128>,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<Exec> {
+        self.contract
+            .top_up_milestone_with_cw20(&Addr::unchecked(token), amount.into(), id, milestone_id)
+            .map(Exec::new)
+    }
+
+    pub fn set_recipient(
+        &self,
+        id: impl Into<String>,
+        recipient: impl Into<String>,
+    ) -> StdResult<Exec> {
+        self.contract.set_recipient(id, recipient).map(Exec::new)
+    }
+
+    /// Whitelists the cw20 tokens in `add` and takes those in `remove` off, as the arbiter
+    pub fn update_cw20_whitelist(
+        &self,
+        id: impl Into<String>,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> StdResult<Exec> {
+        self.contract
+            .update_cw20_whitelist(id, add, remove)
+            .map(Exec::new)
+    }
+
+    /// Accepts the escrow as its recipient, so its milestones can be approved
+    pub fn accept_escrow(&self, id: impl Into<String>) -> StdResult<Exec> {
+        self.contract.accept_escrow(id).map(Exec::new)
+    }
+
+    /// Offers the arbiter role to `new_arbiter`, who takes it over with `accept_arbiter`
+    pub fn transfer_arbiter(
+        &self,
+        id: impl Into<String>,
+        new_arbiter: impl Into<String>,
+    ) -> StdResult<Exec> {
+        self.contract
+            .transfer_arbiter(id, new_arbiter)
+            .map(Exec::new)
+    }
+
+    pub fn accept_arbiter(&self, id: impl Into<String>) -> StdResult<Exec> {
+        self.contract.accept_arbiter(id).map(Exec::new)
+    }
+
+    /// Submits a milestone as delivered, for the arbiters to approve
+    pub fn submit_milestone(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<Exec> {
+        self.contract
+            .submit_milestone(id, milestone_id)
+            .map(Exec::new)
+    }
+
+    pub fn approve_milestone(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<Exec> {
+        self.contract
+            .approve_milestone(id, milestone_id)
+            .map(Exec::new)
+    }
+
+    /// Approves several submitted milestones in one go, failing if any of them can't be
+    pub fn approve_milestones(
+        &self,
+        id: impl Into<String>,
+        milestone_ids: Vec<String>,
+    ) -> StdResult<Exec> {
+        self.contract
+            .approve_milestones(id, milestone_ids)
+            .map(Exec::new)
+    }
+
+    pub fn extend_milestone(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+        expires: Expiration,
+    ) -> StdResult<Exec> {
+        self.contract
+            .extend_milestone(id, milestone_id, expires)
+            .map(Exec::new)
+    }
+
+    /// Freezes a pending milestone until the arbiter resolves the dispute
+    pub fn raise_dispute(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> StdResult<Exec> {
+        self.contract
+            .raise_dispute(id, milestone_id, reason)
+            .map(Exec::new)
+    }
+
+    /// Settles a disputed milestone, `recipient_share` to the recipient and the rest to the
+    /// source
+    pub fn resolve_dispute(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+        recipient_share: Decimal,
+    ) -> StdResult<Exec> {
+        self.contract
+            .resolve_dispute(id, milestone_id, recipient_share)
+            .map(Exec::new)
+    }
+
+    /// Cancels a milestone, as its arbiter or as one of source and recipient agreeing to
+    pub fn cancel_milestone(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<Exec> {
+        self.contract
+            .cancel_milestone(id, milestone_id)
+            .map(Exec::new)
+    }
+
+    /// Approves a milestone for a cw3 arbiter, with a passed proposal carrying the approval
+    pub fn approve_by_proposal(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+        proposal_id: u64,
+    ) -> StdResult<Exec> {
+        self.contract
+            .approve_by_proposal(id, milestone_id, proposal_id)
+            .map(Exec::new)
+    }
+
+    /// Refunds an escrow for a cw3 arbiter, with a passed proposal carrying the refund
+    pub fn refund_by_proposal(&self, id: impl Into<String>, proposal_id: u64) -> StdResult<Exec> {
+        self.contract
+            .refund_by_proposal(id, proposal_id)
+            .map(Exec::new)
+    }
+
+    pub fn refund(&self, id: impl Into<String>) -> StdResult<Exec> {
+        self.contract.refund(id).map(Exec::new)
+    }
+
+    /// Sends a milestone's funds back to the source once its own deadline passed
+    pub fn refund_milestone(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<Exec> {
+        self.contract
+            .refund_milestone(id, milestone_id)
+            .map(Exec::new)
+    }
+
+    /// Refunds up to `limit` expired escrows to their sources
+    pub fn process_expired(&self, limit: Option<u32>) -> StdResult<Exec> {
+        self.contract.process_expired(limit).map(Exec::new)
+    }
+
+    /// Undelegates a staked escrow's funds and credits the yield they earned
+    pub fn unstake(&self, id: impl Into<String>) -> StdResult<Exec> {
+        self.contract.unstake(id).map(Exec::new)
+    }
+
+    /// Collects everything approved milestones have credited to the sender
+    pub fn claim(&self) -> StdResult<Exec> {
+        self.contract.claim().map(Exec::new)
+    }
+
+    pub fn list(&self, status: Option<EscrowStatus>) -> StdResult<Query<ListEscrowsResponse>> {
+        self.query(&QueryMsg::List { status })
+    }
+
+    pub fn list_by_arbiter(
+        &self,
+        arbiter: impl Into<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Query<ListEscrowsResponse>> {
+        self.query(&QueryMsg::ListByArbiter {
+            arbiter: arbiter.into(),
+            start_after,
+            limit,
+        })
+    }
+
+    pub fn list_by_recipient(
+        &self,
+        recipient: impl Into<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Query<ListEscrowsResponse>> {
+        self.query(&QueryMsg::ListByRecipient {
+            recipient: recipient.into(),
+            start_after,
+            limit,
+        })
+    }
+
+    pub fn list_by_source(
+        &self,
+        source: impl Into<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Query<ListEscrowsResponse>> {
+        self.query(&QueryMsg::ListBySource {
+            source: source.into(),
+            start_after,
+            limit,
+        })
+    }
+
+    pub fn escrow_details(&self, id: impl Into<String>) -> StdResult<Query<EscrowDetailsResponse>> {
+        self.query(&QueryMsg::EscrowDetails { id: id.into() })
+    }
+
+    pub fn funding_status(&self, id: impl Into<String>) -> StdResult<Query<FundingStatusResponse>> {
+        self.query(&QueryMsg::FundingStatus { id: id.into() })
+    }
+
+    pub fn claims(&self, address: impl Into<String>) -> StdResult<Query<ClaimsResponse>> {
+        self.query(&QueryMsg::Claims {
+            address: address.into(),
+        })
+    }
+
+    pub fn disputes(
+        &self,
+        id: impl Into<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Query<DisputesResponse>> {
+        self.query(&QueryMsg::Disputes {
+            id: id.into(),
+            start_after,
+            limit,
+        })
+    }
+
+    pub fn escrow_history(
+        &self,
+        id: impl Into<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Query<EscrowHistoryResponse>> {
+        self.query(&QueryMsg::EscrowHistory {
+            id: id.into(),
+            start_after,
+            limit,
+        })
+    }
+
+    pub fn contributions(
+        &self,
+        id: impl Into<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Query<ContributionsResponse>> {
+        self.query(&QueryMsg::Contributions {
+            id: id.into(),
+            start_after,
+            limit,
+        })
+    }
+
+    pub fn milestone_approvals(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<Query<MilestoneApprovalsResponse>> {
+        self.query(&QueryMsg::MilestoneApprovals {
+            id: id.into(),
+            milestone_id: milestone_id.into(),
+        })
+    }
+
+    pub fn milestone_details(
+        &self,
+        id: impl Into<String>,
+        milestone_id: impl Into<String>,
+    ) -> StdResult<Query<Milestone>> {
+        self.query(&QueryMsg::MilestoneDetails {
+            id: id.into(),
+            milestone_id: milestone_id.into(),
+        })
+    }
+
+    pub fn list_milestones(
+        &self,
+        id: impl Into<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Query<ListMilestonesResponse>> {
+        self.query(&QueryMsg::ListMilestones {
+            id: id.into(),
+            start_after,
+            limit,
+        })
+    }
+
+    fn query<T: serde::de::DeserializeOwned>(&self, msg: &QueryMsg) -> StdResult<Query<T>> {
+        Query::wasm(self.addr().as_str(), msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coins, Empty};
+    use cw20_escrow_milestones::state::GenericBalance;
+    use cw_multi_test::{App, ContractWrapper, Executor};
+
+    #[test]
+    fn drives_an_escrow_on_multi_test() {
+        let mut app = App::new(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked("funder"), coins(500, "juno"))
+                .unwrap();
+        });
+        let code_id = app.store_code(Box::new(
+            ContractWrapper::<_, _, _, _, _, _, Empty>::new(
+                cw20_escrow_milestones::contract::execute,
+                cw20_escrow_milestones::contract::instantiate,
+                cw20_escrow_milestones::contract::query,
+            )
+            .with_reply(cw20_escrow_milestones::contract::reply),
+        ));
+        let addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked("owner"),
+                &cw20_escrow_milestones::msg::InstantiateMsg::default(),
+                &[],
+                "escrow",
+                None,
+            )
+            .unwrap();
+        let escrow = EscrowClient::new(addr);
+
+        let create = CreateMsg {
+            id: "demo".to_string(),
+            arbiter: "arbiter".to_string(),
+            co_arbiters: None,
+            threshold: None,
+            arbiter_kind: None,
+            recipient: Some("recipient".to_string()),
+            title: "demo".to_string(),
+            description: "demo".to_string(),
+            cw20_whitelist: None,
+            strict_whitelist: None,
+            crowdfunded: None,
+            stake: None,
+            milestones: vec![CreateMilestoneMsg {
+                escrow_id: "demo".to_string(),
+                title: "only".to_string(),
+                description: "only".to_string(),
+                amount: GenericBalance {
+                    native: coins(500, "juno"),
+                    cw20: vec![],
+                },
+                expires: None,
+                stream: None,
+            }],
+        };
+        let msg = escrow.create(create, coins(500, "juno")).unwrap();
+        app.execute(Addr::unchecked("funder"), msg.msg()).unwrap();
+
+        let details = escrow
+            .escrow_details("demo")
+            .unwrap()
+            .fetch(&app.wrap())
+            .unwrap();
+        assert_eq!(details.arbiter, "arbiter");
+
+        let msg = escrow.accept_escrow("demo").unwrap();
+        app.execute(Addr::unchecked("recipient"), msg.into())
+            .unwrap();
+        let msg = escrow.submit_milestone("demo", "1").unwrap();
+        app.execute(Addr::unchecked("recipient"), msg.into())
+            .unwrap();
+        let msg = escrow.approve_milestone("demo", "1").unwrap();
+        app.execute(Addr::unchecked("arbiter"), msg.into()).unwrap();
+        let claims = escrow
+            .claims("recipient")
+            .unwrap()
+            .fetch(&app.wrap())
+            .unwrap();
+        assert_eq!(claims.native_balance, coins(500, "juno"));
+        let msg = escrow.claim().unwrap();
+        app.execute(Addr::unchecked("recipient"), msg.into())
+            .unwrap();
+        let balance = app.wrap().query_balance("recipient", "juno").unwrap();
+        assert_eq!(balance.amount.u128(), 500);
+        assert!(escrow
+            .list(None)
+            .unwrap()
+            .fetch(&app.wrap())
+            .unwrap()
+            .escrows
+            .is_empty());
+    }
+}
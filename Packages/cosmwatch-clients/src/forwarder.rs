@@ -0,0 +1,174 @@
+use cosmwasm_std::{Addr, Coin, StdResult};
+use sender_receiver_code_challenge::msg::{
+    AccruedFeesResponse, ClaimableResponse, ConfigResponse, ExecuteMsg, ForwardedBySenderResponse,
+    QueryMsg, QueryTotalForwardedResponse, StatsResponse,
+};
+use serde::de::DeserializeOwned;
+
+use crate::{Exec, Query};
+
+/// Client for the token forwarder. The common calls have their own builders, anything else can
+/// go through `execute` and `query`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForwarderClient {
+    addr: Addr,
+}
+
+impl ForwarderClient {
+    pub fn new(addr: impl Into<String>) -> Self {
+        ForwarderClient {
+            addr: Addr::unchecked(addr),
+        }
+    }
+
+    pub fn addr(&self) -> Addr {
+        self.addr.clone()
+    }
+
+    pub fn execute(&self, msg: &ExecuteMsg, funds: Vec<Coin>) -> StdResult<Exec> {
+        Exec::wasm(self.addr.as_str(), msg, funds)
+    }
+
+    pub fn query<T: DeserializeOwned>(&self, msg: &QueryMsg) -> StdResult<Query<T>> {
+        Query::wasm(self.addr.as_str(), msg)
+    }
+
+    /// Forwards `funds`, less fees, to `to`
+    pub fn forward_tokens(&self, to: impl Into<String>, funds: Coin) -> StdResult<Exec> {
+        self.forward(Some(to.into()), None, funds)
+    }
+
+    /// Forwards `funds`, less fees, to the address registered under `alias`
+    pub fn forward_to_alias(&self, alias: impl Into<String>, funds: Coin) -> StdResult<Exec> {
+        self.forward(None, Some(alias.into()), funds)
+    }
+
+    fn forward(
+        &self,
+        forward_to_addr: Option<String>,
+        forward_to_alias: Option<String>,
+        funds: Coin,
+    ) -> StdResult<Exec> {
+        let msg = ExecuteMsg::ForwardTokens {
+            forward_to_addr,
+            forward_to_alias,
+            amount: funds.amount,
+            memo: None,
+        };
+        self.execute(&msg, vec![funds])
+    }
+
+    /// Splits `funds` between the recipients in proportion to their weights
+    pub fn forward_split(&self, recipients: Vec<(String, u64)>, funds: Coin) -> StdResult<Exec> {
+        self.execute(&ExecuteMsg::ForwardSplit { recipients }, vec![funds])
+    }
+
+    pub fn claim(&self, id: u64) -> StdResult<Exec> {
+        self.execute(&ExecuteMsg::Claim { id }, vec![])
+    }
+
+    pub fn withdraw(&self) -> StdResult<Exec> {
+        self.execute(&ExecuteMsg::Withdraw {}, vec![])
+    }
+
+    pub fn withdraw_fees(&self) -> StdResult<Exec> {
+        self.execute(&ExecuteMsg::WithdrawFees {}, vec![])
+    }
+
+    pub fn config(&self) -> StdResult<Query<ConfigResponse>> {
+        self.query(&QueryMsg::Config {})
+    }
+
+    pub fn total_forwarded(&self) -> StdResult<Query<QueryTotalForwardedResponse>> {
+        self.query(&QueryMsg::QueryTotalForwarded {})
+    }
+
+    pub fn stats(&self) -> StdResult<Query<StatsResponse>> {
+        self.query(&QueryMsg::Stats {})
+    }
+
+    pub fn forwarded_by_sender(
+        &self,
+        sender: impl Into<String>,
+    ) -> StdResult<Query<ForwardedBySenderResponse>> {
+        self.query(&QueryMsg::ForwardedBySender {
+            sender: sender.into(),
+        })
+    }
+
+    pub fn claimable(&self, address: impl Into<String>) -> StdResult<Query<ClaimableResponse>> {
+        self.query(&QueryMsg::Claimable {
+            address: address.into(),
+        })
+    }
+
+    pub fn accrued_fees(&self) -> StdResult<Query<AccruedFeesResponse>> {
+        self.query(&QueryMsg::AccruedFees {})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coin, coins, Empty};
+    use cw_multi_test::{App, ContractWrapper, Executor};
+    use sender_receiver_code_challenge::msg::InstantiateMsg;
+
+    #[test]
+    fn forwards_on_multi_test() {
+        let mut app = App::new(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked("sender"), coins(1000, "uluna"))
+                .unwrap();
+        });
+        let code_id = app.store_code(Box::new(
+            ContractWrapper::<_, _, _, _, _, _, Empty>::new(
+                sender_receiver_code_challenge::contract::execute,
+                sender_receiver_code_challenge::contract::instantiate,
+                sender_receiver_code_challenge::contract::query,
+            )
+            .with_reply(sender_receiver_code_challenge::contract::reply),
+        ));
+        let msg = InstantiateMsg {
+            fee_bps: 100,
+            burn_bps: 0,
+            denoms: vec!["uluna".to_string()],
+            max_amounts: vec![],
+            min_amounts: vec![],
+            forward_to_addr: None,
+            pull_payments: false,
+        };
+        let addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked("owner"),
+                &msg,
+                &[],
+                "forwarder",
+                None,
+            )
+            .unwrap();
+        let forwarder = ForwarderClient::new(addr);
+
+        let msg = forwarder
+            .forward_tokens("recipient", coin(1000, "uluna"))
+            .unwrap();
+        app.execute(Addr::unchecked("sender"), msg.msg()).unwrap();
+
+        let balance = app.wrap().query_balance("recipient", "uluna").unwrap();
+        assert_eq!(balance.amount.u128(), 990);
+        let fees = forwarder
+            .accrued_fees()
+            .unwrap()
+            .fetch(&app.wrap())
+            .unwrap();
+        assert_eq!(fees.fees, coins(10, "uluna"));
+        let total = forwarder
+            .total_forwarded()
+            .unwrap()
+            .fetch(&app.wrap())
+            .unwrap();
+        assert_eq!(total.amounts, coins(990, "uluna"));
+    }
+}
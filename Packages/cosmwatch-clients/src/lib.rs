@@ -0,0 +1,18 @@
+//! Typed clients for the CosmWatch contracts. Each client wraps a contract address and builds
+//! `CosmosMsg` and `QueryRequest` values, so backend services and other contracts don't have to
+//! assemble the JSON themselves. With the `multitest` feature the same values can be run against
+//! a cw-multi-test `App`.
+//!
+//! ```ignore
+//! let msg = EscrowClient::new(addr).approve_milestone("demo", "1")?.msg();
+//! ```
+
+mod call;
+mod escrow;
+mod forwarder;
+mod poll;
+
+pub use call::{Exec, Query};
+pub use escrow::EscrowClient;
+pub use forwarder::ForwarderClient;
+pub use poll::PollClient;
@@ -0,0 +1,152 @@
+use cosm_wasm_zero2_hero::msg::{
+    AllPollsResponse, ConfigResponse, ExecuteMsg, PollResponse, QueryMsg, VoteResponse,
+};
+use cosmwasm_std::{Addr, StdResult};
+use serde::de::DeserializeOwned;
+
+use crate::{Exec, Query};
+
+/// Client for the ZeroToHero poll contract
+#[derive(Clone, Debug, PartialEq)]
+pub struct PollClient {
+    addr: Addr,
+}
+
+impl PollClient {
+    pub fn new(addr: impl Into<String>) -> Self {
+        PollClient {
+            addr: Addr::unchecked(addr),
+        }
+    }
+
+    pub fn addr(&self) -> Addr {
+        self.addr.clone()
+    }
+
+    pub fn create_poll(
+        &self,
+        poll_id: impl Into<String>,
+        question: impl Into<String>,
+        options: Vec<String>,
+    ) -> StdResult<Exec> {
+        self.execute(&ExecuteMsg::CreatePoll {
+            poll_id: poll_id.into(),
+            question: question.into(),
+            options,
+        })
+    }
+
+    /// Poll where each vote counts the voter's stake in `staking` when the poll was created
+    pub fn create_weighted_poll(
+        &self,
+        poll_id: impl Into<String>,
+        question: impl Into<String>,
+        options: Vec<String>,
+        staking: impl Into<String>,
+    ) -> StdResult<Exec> {
+        self.execute(&ExecuteMsg::CreateWeightedPoll {
+            poll_id: poll_id.into(),
+            question: question.into(),
+            options,
+            staking: staking.into(),
+        })
+    }
+
+    pub fn vote(&self, poll_id: impl Into<String>, vote: impl Into<String>) -> StdResult<Exec> {
+        self.execute(&ExecuteMsg::Vote {
+            poll_id: poll_id.into(),
+            vote: vote.into(),
+        })
+    }
+
+    pub fn config(&self) -> StdResult<Query<ConfigResponse>> {
+        self.query(&QueryMsg::Config)
+    }
+
+    pub fn all_polls(&self) -> StdResult<Query<AllPollsResponse>> {
+        self.query(&QueryMsg::AllPolls)
+    }
+
+    pub fn poll(&self, poll_id: impl Into<String>) -> StdResult<Query<PollResponse>> {
+        self.query(&QueryMsg::Poll {
+            poll_id: poll_id.into(),
+        })
+    }
+
+    pub fn ballot(
+        &self,
+        poll_id: impl Into<String>,
+        address: impl Into<String>,
+    ) -> StdResult<Query<VoteResponse>> {
+        self.query(&QueryMsg::Vote {
+            poll_id: poll_id.into(),
+            address: address.into(),
+        })
+    }
+
+    fn execute(&self, msg: &ExecuteMsg) -> StdResult<Exec> {
+        Exec::wasm(self.addr.as_str(), msg, vec![])
+    }
+
+    fn query<T: DeserializeOwned>(&self, msg: &QueryMsg) -> StdResult<Query<T>> {
+        Query::wasm(self.addr.as_str(), msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosm_wasm_zero2_hero::msg::InstantiateMsg;
+    use cosmwasm_std::Empty;
+    use cw_multi_test::{App, ContractWrapper, Executor};
+
+    #[test]
+    fn votes_on_multi_test() {
+        let mut app = App::default();
+        let code_id = app.store_code(Box::new(ContractWrapper::<_, _, _, _, _, _, Empty>::new(
+            cosm_wasm_zero2_hero::contract::execute,
+            cosm_wasm_zero2_hero::contract::instantiate,
+            cosm_wasm_zero2_hero::contract::query,
+        )));
+        let addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked("admin"),
+                &InstantiateMsg { admin: None },
+                &[],
+                "poll",
+                None,
+            )
+            .unwrap();
+        let poll = PollClient::new(addr);
+
+        let msg = poll
+            .create_poll(
+                "poll_1",
+                "Ship it?",
+                vec!["yes".to_string(), "no".to_string()],
+            )
+            .unwrap();
+        app.execute(Addr::unchecked("creator"), msg.msg()).unwrap();
+        let msg = poll.vote("poll_1", "no").unwrap();
+        app.execute(Addr::unchecked("voter"), msg.msg()).unwrap();
+
+        let res = poll
+            .ballot("poll_1", "voter")
+            .unwrap()
+            .fetch(&app.wrap())
+            .unwrap();
+        assert_eq!(res.vote.unwrap().option, "no");
+        let res = poll.all_polls().unwrap().fetch(&app.wrap()).unwrap();
+        assert_eq!(res.polls.len(), 1);
+        assert_eq!(
+            poll.config()
+                .unwrap()
+                .fetch(&app.wrap())
+                .unwrap()
+                .config
+                .admin,
+            "admin"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Event types and attribute keys shared by every CosmWatch contract, so indexers read the same
+//! keys whichever contract emitted them. Attributes only one contract knows about keep their own
+//! keys alongside these.
+
+use cosmwasm_std::{Addr, Coin, Event, Response};
+
+/// Attribute keys
+pub mod attr {
+    /// Name of the handled message, set on every response
+    pub const ACTION: &str = "action";
+    pub const ID: &str = "id";
+    pub const AMOUNT: &str = "amount";
+    pub const DENOM: &str = "denom";
+    pub const SENDER: &str = "sender";
+    pub const RECIPIENT: &str = "recipient";
+    pub const OWNER: &str = "owner";
+    pub const PENDING_OWNER: &str = "pending_owner";
+    pub const ADMIN: &str = "admin";
+    pub const FEE: &str = "fee";
+    pub const MEMO: &str = "memo";
+    pub const ERROR: &str = "error";
+
+    /// cw20 transfer keys, kept as the cw20 spec names them
+    pub const FROM: &str = "from";
+    pub const TO: &str = "to";
+    pub const BY: &str = "by";
+
+    pub const FROM_VERSION: &str = "from_version";
+    pub const TO_VERSION: &str = "to_version";
+
+    pub const ESCROW: &str = "escrow";
+    pub const ESCROW_ID: &str = "escrow_id";
+    pub const MILESTONE_ID: &str = "milestone_id";
+    pub const POLL: &str = "poll";
+    pub const POLL_ID: &str = "poll_id";
+}
+
+/// Custom event types, which the chain emits as `wasm-<type>`
+pub mod event {
+    /// Tokens forwarded to a recipient
+    pub const FORWARD: &str = "forward";
+    /// Tokens swapped on the way to a recipient
+    pub const FORWARD_SWAPPED: &str = "forward_swapped";
+    /// Tokens forwarded into a contract call
+    pub const FORWARD_TO_CONTRACT: &str = "forward_to_contract";
+}
+
+/// Response for the message `action`
+pub fn response<T>(action: &str) -> Response<T> {
+    Response::new().add_attribute(attr::ACTION, action)
+}
+
+/// Response for a migration between two contract versions
+pub fn migrate_response<T>(from: impl Into<String>, to: impl Into<String>) -> Response<T> {
+    response("migrate")
+        .add_attribute(attr::FROM_VERSION, from)
+        .add_attribute(attr::TO_VERSION, to)
+}
+
+/// Event of type `ty` for `coin` moving from `sender` to `recipient`
+pub fn transfer_event(ty: &str, sender: &Addr, recipient: &Addr, coin: &Coin) -> Event {
+    Event::new(ty)
+        .add_attribute(attr::SENDER, sender)
+        .add_attribute(attr::RECIPIENT, recipient)
+        .add_attribute(attr::AMOUNT, coin.amount)
+        .add_attribute(attr::DENOM, &coin.denom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coin, Attribute};
+
+    #[test]
+    fn responses_lead_with_action() {
+        let res: Response = migrate_response("0.1.0", "0.2.0");
+        assert_eq!(
+            res.attributes,
+            vec![
+                Attribute::new("action", "migrate"),
+                Attribute::new("from_version", "0.1.0"),
+                Attribute::new("to_version", "0.2.0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn transfer_event_carries_coin() {
+        let event = transfer_event(
+            event::FORWARD,
+            &Addr::unchecked("alice"),
+            &Addr::unchecked("bob"),
+            &coin(10, "uluna"),
+        );
+        assert_eq!(event.ty, "forward");
+        assert_eq!(
+            event.attributes,
+            vec![
+                Attribute::new("sender", "alice"),
+                Attribute::new("recipient", "bob"),
+                Attribute::new("amount", "10"),
+                Attribute::new("denom", "uluna"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! cw2 version checks and versioned state transforms, so every contract's `migrate` follows
+//! the same rules: the stored contract name must match, downgrades are rejected, and each
+//! transform runs once when migrating from a version older than the one that introduced it.
+
+use cosmwasm_std::{StdError, StdResult, Storage};
+use cw2::{get_contract_version, set_contract_version};
+use semver::Version;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MigrateError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    SemVer(String),
+
+    #[error("Cannot migrate from contract {contract}")]
+    InvalidContract { contract: String },
+
+    #[error("Cannot migrate from version {stored} to older version {current}")]
+    CannotDowngrade { stored: String, current: String },
+}
+
+impl From<semver::Error> for MigrateError {
+    fn from(err: semver::Error) -> Self {
+        MigrateError::SemVer(err.to_string())
+    }
+}
+
+/// A state transform run by [`Migration::run`]
+pub type Transform = fn(&mut dyn Storage) -> StdResult<()>;
+
+/// The versions a successful migration moved between
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Migrated {
+    pub from: String,
+    pub to: String,
+}
+
+/// Checks the stored cw2 info belongs to `name` and is not newer than `version`, returning the
+/// stored version
+pub fn ensure_from(
+    storage: &dyn Storage,
+    name: &str,
+    version: &str,
+) -> Result<Version, MigrateError> {
+    let stored = get_contract_version(storage)?;
+    if stored.contract != name {
+        return Err(MigrateError::InvalidContract {
+            contract: stored.contract,
+        });
+    }
+
+    let current: Version = version.parse()?;
+    let stored_version: Version = stored.version.parse()?;
+    if stored_version > current {
+        return Err(MigrateError::CannotDowngrade {
+            stored: stored.version,
+            current: version.to_string(),
+        });
+    }
+    Ok(stored_version)
+}
+
+/// Migration from any earlier version of a contract to `version`
+pub struct Migration {
+    name: &'static str,
+    version: &'static str,
+    steps: Vec<(&'static str, Transform)>,
+}
+
+impl Migration {
+    pub fn new(name: &'static str, version: &'static str) -> Self {
+        Migration {
+            name,
+            version,
+            steps: vec![],
+        }
+    }
+
+    /// Runs `transform` when migrating from a version older than `introduced_in`. Steps run in
+    /// version order, whatever order they are added in.
+    pub fn step(mut self, introduced_in: &'static str, transform: Transform) -> Self {
+        self.steps.push((introduced_in, transform));
+        self
+    }
+
+    /// Checks the stored version, applies the pending transforms and stores the new version
+    pub fn run(&self, storage: &mut dyn Storage) -> Result<Migrated, MigrateError> {
+        let stored = ensure_from(storage, self.name, self.version)?;
+
+        let mut steps = self
+            .steps
+            .iter()
+            .map(|(version, transform)| Ok((version.parse::<Version>()?, transform)))
+            .collect::<Result<Vec<_>, MigrateError>>()?;
+        steps.sort_by(|a, b| a.0.cmp(&b.0));
+        for (version, transform) in steps {
+            if stored < version {
+                transform(storage)?;
+            }
+        }
+
+        set_contract_version(storage, self.name, self.version)?;
+        Ok(Migrated {
+            from: stored.to_string(),
+            to: self.version.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+
+    const NAME: &str = "crates.io:test-contract";
+
+    fn append(storage: &mut dyn Storage, tag: &[u8]) -> StdResult<()> {
+        let mut log = storage.get(b"log").unwrap_or_default();
+        log.extend_from_slice(tag);
+        storage.set(b"log", &log);
+        Ok(())
+    }
+
+    fn storage_at(version: &str) -> MockStorage {
+        let mut storage = MockStorage::new();
+        set_contract_version(&mut storage, NAME, version).unwrap();
+        storage
+    }
+
+    fn migration() -> Migration {
+        Migration::new(NAME, "0.3.0")
+            .step("0.3.0", |storage| append(storage, b"c"))
+            .step("0.2.0", |storage| append(storage, b"b"))
+    }
+
+    #[test]
+    fn runs_pending_steps_in_order() {
+        let mut storage = storage_at("0.1.0");
+        let migrated = migration().run(&mut storage).unwrap();
+        assert_eq!(
+            migrated,
+            Migrated {
+                from: "0.1.0".to_string(),
+                to: "0.3.0".to_string()
+            }
+        );
+        assert_eq!(storage.get(b"log").unwrap(), b"bc");
+        assert_eq!(get_contract_version(&storage).unwrap().version, "0.3.0");
+
+        // steps already applied are skipped
+        let mut storage = storage_at("0.2.0");
+        migration().run(&mut storage).unwrap();
+        assert_eq!(storage.get(b"log").unwrap(), b"c");
+
+        let mut storage = storage_at("0.3.0");
+        migration().run(&mut storage).unwrap();
+        assert_eq!(storage.get(b"log"), None);
+    }
+
+    #[test]
+    fn rejects_downgrade_and_other_contracts() {
+        let mut storage = storage_at("0.4.0");
+        assert_eq!(
+            migration().run(&mut storage).unwrap_err(),
+            MigrateError::CannotDowngrade {
+                stored: "0.4.0".to_string(),
+                current: "0.3.0".to_string()
+            }
+        );
+
+        let mut storage = MockStorage::new();
+        set_contract_version(&mut storage, "crates.io:other", "0.1.0").unwrap();
+        assert_eq!(
+            migration().run(&mut storage).unwrap_err(),
+            MigrateError::InvalidContract {
+                contract: "crates.io:other".to_string()
+            }
+        );
+
+        let mut storage = storage_at("not-a-version");
+        assert!(matches!(
+            migration().run(&mut storage).unwrap_err(),
+            MigrateError::SemVer(_)
+        ));
+    }
+}
@@ -5,8 +5,8 @@ use cosmwasm_schema::{export_schema_with_title, remove_schemas, schema_for};
 
 use cosm_wasm_zero2_hero::config::{Ballot, Config, Poll};
 use cosm_wasm_zero2_hero::msg::{
-    AllPollsResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg,
-    VoteResponse,
+    AllPollsResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PollResponse,
+    QueryMsg, VoteResponse,
 };
 
 fn main() {
@@ -20,6 +20,7 @@ fn main() {
     export_schema_with_title(&schema_for!(ExecuteMsg), &out_dir, "ExecuteMsg");
     export_schema_with_title(&schema_for!(InstantiateMsg), &out_dir, "InstantiateMsg");
     export_schema_with_title(&schema_for!(QueryMsg), &out_dir, "QueryMsg");
+    export_schema_with_title(&schema_for!(MigrateMsg), &out_dir, "MigrateMsg");
 
     // Export schema for message responses
     export_schema_with_title(&schema_for!(AllPollsResponse), &out_dir, "AllPollsResponse");
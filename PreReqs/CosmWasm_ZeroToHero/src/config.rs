@@ -1,7 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Uint128};
+use cosmwatch_access::{Ownable, Pausable};
 use cw_storage_plus::{Item, Map};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -11,9 +12,30 @@ pub struct Config {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Poll {
+    pub creator: Addr,
+    pub question: String,
+    pub options: Vec<(String, Uint128)>,
+    // Set for stake-weighted polls, where option counts are summed stake rather than voters
+    #[serde(default)]
+    pub weighting: Option<Weighting>,
+}
+
+// A poll as stored up to 0.2.0, when option counts were u64
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyPoll {
     pub creator: Addr,
     pub question: String,
     pub options: Vec<(String, u64)>,
+    #[serde(default)]
+    pub weighting: Option<Weighting>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Weighting {
+    // Staking contract answering `StakedBalanceAtHeight`
+    pub staking: Addr,
+    // Voting power is each voter's stake at this height, so staking after creation doesn't count
+    pub height: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -21,6 +43,15 @@ pub struct Ballot {
     pub option: String,
 }
 
-pub const CONFIG: Item<Config> = Item::new("config");
+// The admin, which can pause voting and hand the contract over
+pub const OWNERSHIP: Ownable = Ownable::new("owner", "pending_owner");
+
+// While set, polls can't be created or voted on
+pub const PAUSE: Pausable = Pausable::new("paused");
+
+// Where the admin was stored up to 0.1.0. Only read when migrating
+pub const LEGACY_CONFIG: Item<Config> = Item::new("config");
 pub const POLLS: Map<String, Poll> = Map::new("polls");
+// The same polls, read with the 0.2.0 layout. Only read when migrating
+pub const LEGACY_POLLS: Map<String, LegacyPoll> = Map::new("polls");
 pub const BALLOTS: Map<(Addr, String), Ballot> = Map::new("ballots");
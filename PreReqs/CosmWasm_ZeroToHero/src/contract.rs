@@ -1,16 +1,22 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult,
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
+    Storage, Uint128,
 };
+use cosmwatch_events::{attr, migrate_response, response};
+use cosmwatch_migrate::Migration;
 use cw2::set_contract_version;
 
-use crate::config::{Ballot, Config, Poll, BALLOTS, CONFIG, POLLS};
+use crate::config::{
+    Ballot, Config, Poll, Weighting, BALLOTS, LEGACY_CONFIG, LEGACY_POLLS, OWNERSHIP, PAUSE, POLLS,
+};
 use crate::error::ContractError;
 use crate::msg::{
-    AllPollsResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg,
-    VoteResponse,
+    AllPollsResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PollResponse,
+    QueryMsg, VoteResponse,
 };
+use crate::staking::staked_balance_at_height;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:cosm-wasm-zero2-hero";
@@ -29,13 +35,8 @@ pub fn instantiate(
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     let admin = msg.admin.unwrap_or(info.sender.to_string());
     let validated_admin = deps.api.addr_validate(&admin)?;
-    let config = Config {
-        admin: validated_admin.clone(),
-    };
-    CONFIG.save(deps.storage, &config)?;
-    Ok(Response::new()
-        .add_attribute("action", "instantiate")
-        .add_attribute("admin", validated_admin.to_string()))
+    OWNERSHIP.initialize(deps.storage, &validated_admin)?;
+    Ok(response("instantiate").add_attribute(attr::ADMIN, validated_admin.to_string()))
 }
 
 /*
@@ -48,13 +49,43 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // The admin can still unpause and hand over the contract while paused
+    let is_access = matches!(
+        msg,
+        ExecuteMsg::Pause {}
+            | ExecuteMsg::Unpause {}
+            | ExecuteMsg::TransferOwnership { .. }
+            | ExecuteMsg::AcceptOwnership {}
+    );
+    if !is_access {
+        PAUSE.assert_not_paused(deps.storage)?;
+    }
+
     match msg {
         ExecuteMsg::CreatePoll {
             poll_id,
             question,
             options,
-        } => execute_create_poll(deps, env, info, poll_id, question, options),
+        } => execute_create_poll(deps, env, info, poll_id, question, options, None),
+        ExecuteMsg::CreateWeightedPoll {
+            poll_id,
+            question,
+            options,
+            staking,
+        } => {
+            let weighting = Weighting {
+                staking: deps.api.addr_validate(&staking)?,
+                height: env.block.height,
+            };
+            execute_create_poll(deps, env, info, poll_id, question, options, Some(weighting))
+        }
         ExecuteMsg::Vote { poll_id, vote } => execute_vote(deps, env, info, poll_id, vote),
+        ExecuteMsg::Pause {} => Ok(PAUSE.set_paused(deps.storage, &OWNERSHIP, &info, true)?),
+        ExecuteMsg::Unpause {} => Ok(PAUSE.set_paused(deps.storage, &OWNERSHIP, &info, false)?),
+        ExecuteMsg::TransferOwnership { new_owner } => {
+            Ok(OWNERSHIP.transfer(deps.storage, deps.api, &info, &new_owner)?)
+        }
+        ExecuteMsg::AcceptOwnership {} => Ok(OWNERSHIP.accept(deps.storage, &info)?),
     }
 }
 
@@ -65,6 +96,7 @@ fn execute_create_poll(
     poll_id: String,
     question: String,
     options: Vec<String>,
+    weighting: Option<Weighting>,
 ) -> Result<Response, ContractError> {
     // Ensure there are no more than 10 options
     if options.len() > 10 {
@@ -72,9 +104,9 @@ fn execute_create_poll(
     }
 
     // Loop over options and add to options vector
-    let mut opts: Vec<(String, u64)> = vec![];
+    let mut opts: Vec<(String, Uint128)> = vec![];
     for opt in options {
-        opts.push((opt, 0));
+        opts.push((opt, Uint128::zero()));
     }
 
     // Create poll and save it to config (aka state)
@@ -82,11 +114,11 @@ fn execute_create_poll(
         creator: info.sender,
         question,
         options: opts,
+        weighting,
     };
     POLLS.save(deps.storage, poll_id, &poll)?;
 
-    Ok(Response::new()
-        .add_attribute("action", "create_poll")
+    Ok(response("create_poll")
         .add_attribute("creator", &poll.creator)
         .add_attribute("question", &poll.question)
         .add_attribute(
@@ -113,6 +145,20 @@ fn execute_vote(
     match poll {
         // If poll found, update ballot with vote
         Some(mut poll) => {
+            // One vote per address, or the voter's stake for weighted polls
+            let weight = match &poll.weighting {
+                Some(weighting) => staked_balance_at_height(
+                    &deps.querier,
+                    &weighting.staking,
+                    &info.sender,
+                    weighting.height,
+                )?,
+                None => Uint128::one(),
+            };
+            if weight.is_zero() {
+                return Err(ContractError::NoVotingPower {});
+            }
+
             BALLOTS.update(
                 deps.storage,
                 (info.sender, poll_id.clone()),
@@ -124,7 +170,8 @@ fn execute_vote(
                                 .iter()
                                 .position(|option| option.0 == ballot.option)
                                 .unwrap();
-                            poll.options[position_of_old_vote].1 -= 1;
+                            let tally = &mut poll.options[position_of_old_vote].1;
+                            *tally = tally.checked_sub(weight)?;
                             Ok(Ballot {
                                 option: vote.clone(),
                             })
@@ -142,13 +189,13 @@ fn execute_vote(
                 return Err(ContractError::Unauthorized {});
             }
             let position = position.unwrap();
-            poll.options[position].1 += 1;
+            let tally = &mut poll.options[position].1;
+            *tally = tally.checked_add(weight).map_err(StdError::from)?;
 
             // Save to state
             POLLS.save(deps.storage, poll_id, &poll)?;
-            Ok(Response::new()
-                .add_attribute("action", "vote")
-                .add_attribute("poll", poll.question)
+            Ok(response("vote")
+                .add_attribute(attr::POLL, poll.question)
                 .add_attribute("vote", vote))
         }
         // If poll not found, return a PollNotFound error
@@ -156,6 +203,51 @@ fn execute_vote(
     }
 }
 
+/*
+** MIGRATE
+*/
+// The admin moved from Config to OWNERSHIP in 0.2.0
+fn move_admin_to_ownership(storage: &mut dyn Storage) -> StdResult<()> {
+    if let Some(config) = LEGACY_CONFIG.may_load(storage)? {
+        OWNERSHIP.initialize(storage, &config.admin)?;
+        LEGACY_CONFIG.remove(storage);
+    }
+    Ok(())
+}
+
+// Option counts became Uint128 in 0.3.0, so summed stake can't overflow them
+fn widen_poll_tallies(storage: &mut dyn Storage) -> StdResult<()> {
+    let legacy = LEGACY_POLLS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (poll_id, legacy) in legacy {
+        let poll = Poll {
+            creator: legacy.creator,
+            question: legacy.question,
+            options: legacy
+                .options
+                .into_iter()
+                .map(|(option, count)| (option, Uint128::from(count)))
+                .collect(),
+            weighting: legacy.weighting,
+        };
+        POLLS.save(storage, poll_id, &poll)?;
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // If state structure changes in a later version, register its transform here with
+    // `.step(version, transform)`
+    let migrated = Migration::new(CONTRACT_NAME, CONTRACT_VERSION)
+        .step("0.2.0", move_admin_to_ownership)
+        .step("0.3.0", widen_poll_tallies)
+        .run(deps.storage)?;
+
+    Ok(migrate_response(migrated.from, migrated.to))
+}
+
 /*
 ** QUERY
 */
@@ -163,6 +255,8 @@ fn execute_vote(
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config => to_binary(&query_config(deps)?),
+        QueryMsg::Paused => to_binary(&PAUSE.query(deps.storage)?),
+        QueryMsg::Ownership => to_binary(&OWNERSHIP.query(deps.storage)?),
         QueryMsg::AllPolls => to_binary(&query_all_polls(deps)?),
         QueryMsg::Poll { poll_id } => to_binary(&query_poll(deps, poll_id)?),
         QueryMsg::Vote { poll_id, address } => to_binary(&query_vote(deps, poll_id, address)?),
@@ -170,7 +264,9 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
 }
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
-    let config = CONFIG.load(deps.storage)?;
+    let config = Config {
+        admin: OWNERSHIP.owner(deps.storage)?,
+    };
 
     Ok(ConfigResponse { config })
 }
@@ -202,14 +298,17 @@ pub fn query_vote(deps: Deps, poll_id: String, address: String) -> StdResult<Vot
 */
 #[cfg(test)]
 mod tests {
-    use crate::contract::{execute, instantiate};
+    use crate::config::{Config, LegacyPoll, LEGACY_CONFIG, LEGACY_POLLS};
+    use crate::contract::{execute, instantiate, migrate, CONTRACT_NAME, CONTRACT_VERSION};
     use crate::msg::{
-        AllPollsResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg,
-        VoteResponse,
+        AllPollsResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PausedResponse,
+        PollResponse, QueryMsg, VoteResponse,
     };
     use crate::ContractError;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{attr, from_binary};
+    use cosmwasm_std::{attr, from_binary, Addr, Uint128};
+    use cosmwatch_migrate::MigrateError;
+    use cw2::{get_contract_version, set_contract_version};
 
     use super::query;
 
@@ -571,4 +670,148 @@ mod tests {
 
         assert_eq!(res.config.admin.to_string(), ADDR1.to_string());
     }
+
+    #[test]
+    fn test_migrate() {
+        // Define mock dependencies, env, and info
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        // Instantiate, then pretend the stored code is an older version
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.1").unwrap();
+
+        // Migrate and assert the version was bumped
+        let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "migrate"),
+                attr("from_version", "0.0.1"),
+                attr("to_version", CONTRACT_VERSION)
+            ]
+        );
+        let version = get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(version.version, CONTRACT_VERSION);
+
+        // Migrating back to an older version is rejected
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), env, MigrateMsg {}).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Migrate(MigrateError::CannotDowngrade { .. })
+        ));
+    }
+
+    #[test]
+    fn test_migrate_moves_admin_to_ownership() {
+        // Define mock dependencies and env
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        // Lay the state out as 0.1.0 stored it, with the admin in Config
+        let config = Config {
+            admin: Addr::unchecked(ADDR2),
+        };
+        LEGACY_CONFIG.save(deps.as_mut().storage, &config).unwrap();
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.1.0").unwrap();
+
+        // Migrate and assert the admin was carried over
+        let _res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        let bin = query(deps.as_ref(), env, QueryMsg::Config).unwrap();
+        let res: ConfigResponse = from_binary(&bin).unwrap();
+        assert_eq!(res.config.admin, ADDR2);
+        assert!(LEGACY_CONFIG
+            .may_load(deps.as_ref().storage)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_migrate_widens_poll_tallies() {
+        // Define mock dependencies and env
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        // Lay a poll out as 0.2.0 stored it, with u64 option counts
+        let legacy = LegacyPoll {
+            creator: Addr::unchecked(ADDR1),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec![("Cosmos Hub".to_string(), 3), ("Juno".to_string(), 0)],
+            weighting: None,
+        };
+        LEGACY_POLLS
+            .save(deps.as_mut().storage, "some_id".to_string(), &legacy)
+            .unwrap();
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.2.0").unwrap();
+
+        // Migrate and assert the counts were carried over
+        let _res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        let msg = QueryMsg::Poll {
+            poll_id: "some_id".to_string(),
+        };
+        let bin = query(deps.as_ref(), env, msg).unwrap();
+        let res: PollResponse = from_binary(&bin).unwrap();
+        assert_eq!(
+            res.poll.unwrap().options,
+            vec![
+                ("Cosmos Hub".to_string(), Uint128::new(3)),
+                ("Juno".to_string(), Uint128::zero())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pause() {
+        // Define mock dependencies, env, and info
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(ADDR1, &[]);
+
+        // Define message to instantiate contract and call instantiate
+        let msg = InstantiateMsg { admin: None };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Only the admin can pause
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADDR2, &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::Paused).unwrap();
+        let res: PausedResponse = from_binary(&bin).unwrap();
+        assert!(res.paused);
+
+        // Polls can't be created while paused
+        let msg = ExecuteMsg::CreatePoll {
+            poll_id: "some_id".to_string(),
+            question: "What's your favourite Cosmos coin?".to_string(),
+            options: vec!["Cosmos Hub".to_string(), "Juno".to_string()],
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::ContractPaused {}));
+
+        // Unpause and create the poll
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    }
 }
@@ -1,4 +1,6 @@
 use cosmwasm_std::StdError;
+use cosmwatch_access::AccessError;
+use cosmwatch_migrate::MigrateError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,12 +8,35 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Migrate(#[from] MigrateError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Contract is paused")]
+    ContractPaused {},
+
+    #[error("No ownership transfer is pending")]
+    NoPendingOwner {},
+
     #[error("Too many poll options")]
     TooManyOptions {},
 
     #[error("Poll not found")]
     PollNotFound {},
+
+    #[error("No stake at the poll's snapshot height")]
+    NoVotingPower {},
+}
+
+impl From<AccessError> for ContractError {
+    fn from(err: AccessError) -> Self {
+        match err {
+            AccessError::Std(err) => ContractError::Std(err),
+            AccessError::Unauthorized {} => ContractError::Unauthorized {},
+            AccessError::ContractPaused {} => ContractError::ContractPaused {},
+            AccessError::NoPendingOwner {} => ContractError::NoPendingOwner {},
+        }
+    }
 }
@@ -0,0 +1,62 @@
+use cw_orch::interface;
+use cw_orch::prelude::*;
+
+use crate::contract::{execute, instantiate, migrate, query};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+
+/// cw-orchestrator interface for the poll contract, usable against `Mock` in tests or a `Daemon`
+/// on a live chain. Queries go through `query` since `QueryMsg` has unit variants
+#[interface(InstantiateMsg, ExecuteMsg, QueryMsg, MigrateMsg)]
+pub struct PollContract;
+
+impl<Chain: CwEnv> Uploadable for PollContract<Chain> {
+    /// Optimized wasm from `artifacts/`, as produced by rust-optimizer
+    fn wasm(&self) -> WasmPath {
+        ArtifactsDir::new(concat!(env!("CARGO_MANIFEST_DIR"), "/artifacts"))
+            .find_wasm_path("cosm_wasm_zero2_hero")
+            .unwrap()
+    }
+
+    fn wrapper(&self) -> Box<dyn MockContract<Empty>> {
+        Box::new(ContractWrapper::new_with_empty(execute, instantiate, query).with_migrate(migrate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cosmwasm_std::Uint128;
+
+    use crate::msg::{ExecuteMsgFns, PollResponse};
+
+    #[test]
+    fn votes_on_a_mock_chain() {
+        let voter = Addr::unchecked("voter");
+        let poll = PollContract::new("poll", Mock::new(&voter));
+        poll.upload().unwrap();
+        poll.instantiate(&InstantiateMsg { admin: None }, None, None)
+            .unwrap();
+
+        poll.create_poll(
+            vec!["yes".to_string(), "no".to_string()],
+            "poll_1".to_string(),
+            "Ship it?".to_string(),
+        )
+        .unwrap();
+        poll.vote("poll_1".to_string(), "yes".to_string()).unwrap();
+
+        let res: PollResponse = poll
+            .query(&QueryMsg::Poll {
+                poll_id: "poll_1".to_string(),
+            })
+            .unwrap();
+        assert_eq!(
+            res.poll.unwrap().options,
+            vec![
+                ("yes".to_string(), Uint128::one()),
+                ("no".to_string(), Uint128::zero())
+            ]
+        );
+    }
+}
@@ -1,6 +1,9 @@
 pub mod config;
 pub mod contract;
 mod error;
+#[cfg(feature = "interface")]
+pub mod interface;
 pub mod msg;
+pub mod staking;
 
 pub use crate::error::ContractError;
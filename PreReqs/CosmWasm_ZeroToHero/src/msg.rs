@@ -2,22 +2,41 @@ use cosmwasm_schema::cw_serde;
 
 use crate::config::{Ballot, Config, Poll};
 
+pub use cosmwatch_access::{OwnershipResponse, PausedResponse};
+
 #[cw_serde]
 pub struct InstantiateMsg {
     pub admin: Option<String>,
 }
 
 #[cw_serde]
+pub struct MigrateMsg {}
+
+#[cw_serde]
+#[cfg_attr(feature = "interface", derive(cw_orch::ExecuteFns))]
 pub enum ExecuteMsg {
     CreatePoll {
         poll_id: String,
         question: String,
         options: Vec<String>,
     },
+    // Like CreatePoll, but each vote counts the voter's stake in `staking` when the poll was created
+    CreateWeightedPoll {
+        poll_id: String,
+        question: String,
+        options: Vec<String>,
+        staking: String,
+    },
     Vote {
         poll_id: String,
         vote: String,
     },
+    Pause {},
+    Unpause {},
+    TransferOwnership {
+        new_owner: String,
+    },
+    AcceptOwnership {},
 }
 
 #[cw_serde]
@@ -26,6 +45,8 @@ pub enum QueryMsg {
     Poll { poll_id: String },
     Vote { poll_id: String, address: String },
     Config,
+    Paused,
+    Ownership,
 }
 
 #[cw_serde]
@@ -0,0 +1,33 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, QuerierWrapper, StdResult, Uint128};
+
+// The part of the staking contract's query interface that weighted polls rely on
+#[cw_serde]
+pub enum StakingQueryMsg {
+    StakedBalanceAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+}
+
+#[cw_serde]
+pub struct StakedBalanceAtHeightResponse {
+    pub balance: Uint128,
+    pub height: u64,
+}
+
+pub fn staked_balance_at_height(
+    querier: &QuerierWrapper,
+    staking: &Addr,
+    address: &Addr,
+    height: u64,
+) -> StdResult<Uint128> {
+    let res: StakedBalanceAtHeightResponse = querier.query_wasm_smart(
+        staking,
+        &StakingQueryMsg::StakedBalanceAtHeight {
+            address: address.to_string(),
+            height: Some(height),
+        },
+    )?;
+    Ok(res.balance)
+}